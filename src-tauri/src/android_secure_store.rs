@@ -0,0 +1,233 @@
+// Android keeps sync provider auth in EncryptedSharedPreferences behind the
+// `SyncProviderSecureStore` Kotlin class. JNI calls have to run on the activity's thread, so each
+// call is scheduled through the main webview and waited on with a deadline.
+
+use std::{sync::mpsc, time::Duration};
+use tauri::Manager;
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    secure_store::SecureStoreBackend,
+};
+
+const ANDROID_SYNC_PROVIDER_SECURE_STORE_CLASS: &str =
+    "com.solutionsstudio.solostack.SyncProviderSecureStore";
+const ANDROID_SECURE_STORE_TIMEOUT_MS: u64 = 5_000;
+
+pub(crate) struct AndroidSecureStore {
+    app_handle: tauri::AppHandle,
+}
+
+impl AndroidSecureStore {
+    pub(crate) fn new(app_handle: tauri::AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+fn run_android_secure_store_call<T, F>(
+    app_handle: &tauri::AppHandle,
+    operation: F,
+) -> Result<T, SoloStackError>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut jni::JNIEnv<'_>, &jni::objects::JObject<'_>) -> Result<T, SoloStackError>
+        + Send
+        + 'static,
+{
+    let window = app_handle.get_webview_window("main").ok_or_else(|| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::SecureStoreUnavailable,
+            "android secure store needs the main webview",
+        )
+    })?;
+    let (sender, receiver) = mpsc::channel::<Result<T, SoloStackError>>();
+    window
+        .with_webview(move |webview| {
+            webview.jni_handle().exec(move |env, activity, _webview| {
+                let result = operation(env, activity);
+                let _ = sender.send(result);
+            });
+        })
+        .map_err(|error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::SecureStoreUnavailable,
+                format!("schedule android secure store call failed: {error}"),
+            )
+        })?;
+
+    receiver
+        .recv_timeout(Duration::from_millis(ANDROID_SECURE_STORE_TIMEOUT_MS))
+        .map_err(|_| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::SecureStoreTimeout,
+                "android secure store call timed out",
+            )
+        })?
+}
+
+fn find_android_secure_store_class<'a>(
+    env: &mut jni::JNIEnv<'a>,
+    activity: &jni::objects::JObject<'_>,
+) -> Result<jni::objects::JClass<'a>, SoloStackError> {
+    let unavailable = |error: String| {
+        SoloStackError::with_detail(SoloStackErrorCode::SecureStoreUnavailable, error)
+    };
+    let class_name = env
+        .new_string(ANDROID_SYNC_PROVIDER_SECURE_STORE_CLASS)
+        .map_err(|error| unavailable(format!("create secure store class name failed: {error}")))?;
+    let class_value = env
+        .call_method(
+            activity,
+            "getAppClass",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[(&class_name).into()],
+        )
+        .map_err(|error| unavailable(format!("resolve secure store class failed: {error}")))?;
+    let class_object = class_value.l().map_err(|error| {
+        unavailable(format!("resolve secure store class object failed: {error}"))
+    })?;
+    Ok(jni::objects::JClass::from(class_object))
+}
+
+fn read_auth_from_android_secure_store(
+    env: &mut jni::JNIEnv<'_>,
+    activity: &jni::objects::JObject<'_>,
+    provider: &str,
+) -> Result<Option<String>, SoloStackError> {
+    let failed = |error: String| {
+        SoloStackError::with_detail(SoloStackErrorCode::SecureStoreReadFailed, error)
+    };
+    let class = find_android_secure_store_class(env, activity)?;
+    let provider_value = env
+        .new_string(provider)
+        .map_err(|error| failed(format!("create provider string failed: {error}")))?;
+    let auth_value = env
+        .call_static_method(
+            class,
+            "get",
+            "(Landroid/app/Activity;Ljava/lang/String;)Ljava/lang/String;",
+            &[activity.into(), (&provider_value).into()],
+        )
+        .map_err(|error| failed(format!("android secure store get failed: {error}")))?;
+    let auth_object = auth_value
+        .l()
+        .map_err(|error| failed(format!("android secure store get payload failed: {error}")))?;
+    let is_null = env
+        .is_same_object(&auth_object, jni::objects::JObject::null())
+        .map_err(|error| failed(format!("android secure store null check failed: {error}")))?;
+    if is_null {
+        return Ok(None);
+    }
+
+    let auth_string = jni::objects::JString::from(auth_object);
+    let auth_text: String = env
+        .get_string(&auth_string)
+        .map_err(|error| failed(format!("android secure store decode failed: {error}")))?
+        .into();
+    let normalized = auth_text.trim().to_string();
+    if normalized.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(normalized))
+    }
+}
+
+fn write_auth_to_android_secure_store(
+    env: &mut jni::JNIEnv<'_>,
+    activity: &jni::objects::JObject<'_>,
+    provider: &str,
+    auth: &str,
+) -> Result<(), SoloStackError> {
+    let failed = |error: String| {
+        SoloStackError::with_detail(SoloStackErrorCode::SecureStoreWriteFailed, error)
+    };
+    let class = find_android_secure_store_class(env, activity)?;
+    let provider_value = env
+        .new_string(provider)
+        .map_err(|error| failed(format!("create provider string failed: {error}")))?;
+    let auth_value = env
+        .new_string(auth)
+        .map_err(|error| failed(format!("create auth string failed: {error}")))?;
+    let result = env
+        .call_static_method(
+            class,
+            "set",
+            "(Landroid/app/Activity;Ljava/lang/String;Ljava/lang/String;)Z",
+            &[
+                activity.into(),
+                (&provider_value).into(),
+                (&auth_value).into(),
+            ],
+        )
+        .map_err(|error| failed(format!("android secure store set failed: {error}")))?;
+    let stored = result
+        .z()
+        .map_err(|error| failed(format!("android secure store set status failed: {error}")))?;
+    if stored {
+        Ok(())
+    } else {
+        Err(failed(
+            "android secure store rejected auth write".to_string(),
+        ))
+    }
+}
+
+fn delete_auth_from_android_secure_store(
+    env: &mut jni::JNIEnv<'_>,
+    activity: &jni::objects::JObject<'_>,
+    provider: &str,
+) -> Result<(), SoloStackError> {
+    let failed = |error: String| {
+        SoloStackError::with_detail(SoloStackErrorCode::SecureStoreDeleteFailed, error)
+    };
+    let class = find_android_secure_store_class(env, activity)?;
+    let provider_value = env
+        .new_string(provider)
+        .map_err(|error| failed(format!("create provider string failed: {error}")))?;
+    let result = env
+        .call_static_method(
+            class,
+            "delete",
+            "(Landroid/app/Activity;Ljava/lang/String;)Z",
+            &[activity.into(), (&provider_value).into()],
+        )
+        .map_err(|error| failed(format!("android secure store delete failed: {error}")))?;
+    let deleted = result.z().map_err(|error| {
+        failed(format!(
+            "android secure store delete status failed: {error}"
+        ))
+    })?;
+    if deleted {
+        Ok(())
+    } else {
+        Err(failed("android secure store rejected delete".to_string()))
+    }
+}
+
+impl SecureStoreBackend for AndroidSecureStore {
+    fn name(&self) -> &'static str {
+        "android_encrypted_shared_prefs"
+    }
+
+    fn read(&self, key: &str) -> Result<Option<String>, SoloStackError> {
+        let key = key.to_string();
+        run_android_secure_store_call(&self.app_handle, move |env, activity| {
+            read_auth_from_android_secure_store(env, activity, &key)
+        })
+    }
+
+    fn write(&self, key: &str, value: &str) -> Result<(), SoloStackError> {
+        let key = key.to_string();
+        let value = value.to_string();
+        run_android_secure_store_call(&self.app_handle, move |env, activity| {
+            write_auth_to_android_secure_store(env, activity, &key, &value)
+        })
+    }
+
+    fn delete(&self, key: &str) -> Result<(), SoloStackError> {
+        let key = key.to_string();
+        run_android_secure_store_call(&self.app_handle, move |env, activity| {
+            delete_auth_from_android_secure_store(env, activity, &key)
+        })
+    }
+}
@@ -1,3 +1,893 @@
-// Database configuration constants.
-// The actual database is managed by tauri-plugin-sql from the frontend.
-// This module is reserved for future Rust-side database utilities.
+// Rust-owned schema for `solostack.db`.
+// Migrations run once at startup, before the webview opens the database through
+// tauri-plugin-sql, so a broken frontend build can never leave the schema half-applied.
+// Every migration runs in its own transaction together with its `schema_migrations` row.
+
+use rusqlite::{
+    backup::{Backup, StepResult},
+    Connection, OpenFlags, Transaction,
+};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::Duration,
+};
+
+use crate::error::{SoloStackError, SoloStackErrorCode};
+
+const SCHEMA_MIGRATIONS_TABLE_SQL: &str = "
+    CREATE TABLE IF NOT EXISTS schema_migrations (
+      version INTEGER PRIMARY KEY,
+      name TEXT NOT NULL,
+      applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+    )
+";
+
+// The webview keeps its own tauri-plugin-sql pool open on the same file.
+const DATABASE_BUSY_TIMEOUT_MS: u64 = 5_000;
+const BACKUP_PAGES_PER_STEP: std::os::raw::c_int = 256;
+const BACKUP_STEP_PAUSE_MS: u64 = 10;
+
+struct SchemaMigration {
+    version: i64,
+    name: &'static str,
+    apply: fn(&Transaction<'_>) -> rusqlite::Result<()>,
+}
+
+const SCHEMA_MIGRATIONS: &[SchemaMigration] = &[
+    SchemaMigration {
+        version: 1,
+        name: "create_base_tables",
+        apply: create_base_tables,
+    },
+    SchemaMigration {
+        version: 2,
+        name: "backfill_sync_columns",
+        apply: backfill_sync_columns,
+    },
+    SchemaMigration {
+        version: 3,
+        name: "create_task_indexes_and_sync_checkpoint",
+        apply: create_task_indexes_and_sync_checkpoint,
+    },
+];
+
+#[derive(Clone, Serialize)]
+pub(crate) struct AppliedSchemaMigration {
+    version: i64,
+    name: String,
+}
+
+#[derive(Clone, Serialize, Default)]
+pub(crate) struct SchemaMigrationReport {
+    pub(crate) database_path: Option<String>,
+    pub(crate) previous_version: i64,
+    pub(crate) current_version: i64,
+    pub(crate) target_version: i64,
+    pub(crate) applied_migrations: Vec<AppliedSchemaMigration>,
+    pub(crate) migration_error: Option<String>,
+}
+
+pub(crate) fn latest_schema_version() -> i64 {
+    SCHEMA_MIGRATIONS
+        .last()
+        .map(|migration| migration.version)
+        .unwrap_or(0)
+}
+
+pub(crate) fn open_database(database_path: &Path) -> Result<Connection, String> {
+    let connection = Connection::open(database_path)
+        .map_err(|error| format!("open database failed: {error}"))?;
+    connection
+        .busy_timeout(Duration::from_millis(DATABASE_BUSY_TIMEOUT_MS))
+        .map_err(|error| format!("set database busy timeout failed: {error}"))?;
+    connection
+        .pragma_update(None, "foreign_keys", "ON")
+        .map_err(|error| format!("enable foreign keys failed: {error}"))?;
+    Ok(connection)
+}
+
+// Reads the source through SQLite itself, so pages still sitting in the legacy `-wal` file
+// are folded into one consistent destination file even if the legacy app is running.
+// `on_progress` receives bytes copied and total bytes after every step.
+pub(crate) fn backup_database(
+    source_path: &Path,
+    destination_path: &Path,
+    on_progress: &mut dyn FnMut(u64, u64),
+) -> Result<i64, String> {
+    let source_connection =
+        Connection::open_with_flags(source_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|error| format!("open legacy database read-only failed: {error}"))?;
+    let page_size: i64 = source_connection
+        .query_row("PRAGMA page_size", [], |row| row.get(0))
+        .map_err(|error| format!("read legacy page size failed: {error}"))?;
+    let page_size = u64::try_from(page_size).unwrap_or_default();
+    let mut destination_connection = Connection::open(destination_path)
+        .map_err(|error| format!("open backup destination failed: {error}"))?;
+
+    let page_count = {
+        let backup = Backup::new(&source_connection, &mut destination_connection)
+            .map_err(|error| format!("start online backup failed: {error}"))?;
+        loop {
+            let step_result = backup
+                .step(BACKUP_PAGES_PER_STEP)
+                .map_err(|error| format!("online backup failed: {error}"))?;
+            let progress = backup.progress();
+            let total_pages = u64::try_from(progress.pagecount).unwrap_or_default();
+            let remaining_pages = u64::try_from(progress.remaining).unwrap_or_default();
+            on_progress(
+                total_pages.saturating_sub(remaining_pages) * page_size,
+                total_pages * page_size,
+            );
+            match step_result {
+                StepResult::Done => break,
+                // Busy/Locked mean the legacy app is writing; pausing lets it finish.
+                _ => thread::sleep(Duration::from_millis(BACKUP_STEP_PAUSE_MS)),
+            }
+        }
+        i64::from(backup.progress().pagecount)
+    };
+
+    let destination_page_count: i64 = destination_connection
+        .query_row("PRAGMA page_count", [], |row| row.get(0))
+        .map_err(|error| format!("read backup page count failed: {error}"))?;
+    if destination_page_count != page_count {
+        return Err(format!(
+            "backup page count mismatch: expected {page_count}, found {destination_page_count}"
+        ));
+    }
+
+    destination_connection
+        .close()
+        .map_err(|(_, error)| format!("close backup destination failed: {error}"))?;
+    Ok(page_count)
+}
+
+pub(crate) fn run_integrity_check(database_path: &Path) -> Result<(), String> {
+    let connection =
+        Connection::open_with_flags(database_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|error| format!("open database for integrity check failed: {error}"))?;
+    let mut statement = connection
+        .prepare("PRAGMA integrity_check")
+        .map_err(|error| format!("prepare integrity check failed: {error}"))?;
+    let findings = statement
+        .query_map([], |row| row.get::<_, String>(0))
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(|error| format!("run integrity check failed: {error}"))?;
+    if findings.len() == 1 && findings[0] == "ok" {
+        return Ok(());
+    }
+    Err(format!("integrity check failed: {}", findings.join("; ")))
+}
+
+// Only set once the schema migrations succeeded, so Rust commands never write into a
+// database whose schema they do not own yet. Startup migrations run on a background thread,
+// so the path is filled in after `setup` has returned.
+#[derive(Default)]
+pub(crate) struct DatabaseState {
+    database_path: Mutex<Option<PathBuf>>,
+}
+
+impl DatabaseState {
+    pub(crate) fn mark_ready(&self, database_path: PathBuf) {
+        if let Ok(mut guard) = self.database_path.lock() {
+            *guard = Some(database_path);
+        }
+    }
+
+    pub(crate) fn open(&self) -> Result<Connection, SoloStackError> {
+        let database_path = self
+            .database_path
+            .lock()
+            .map_err(|_| {
+                SoloStackError::with_detail(
+                    SoloStackErrorCode::Internal,
+                    "database state lock is poisoned",
+                )
+            })?
+            .clone()
+            .ok_or_else(|| {
+                SoloStackError::with_detail(
+                    SoloStackErrorCode::DatabaseUnavailable,
+                    "schema migration did not complete",
+                )
+            })?;
+        open_database(&database_path).map_err(|error| {
+            SoloStackError::with_detail(SoloStackErrorCode::DatabaseUnavailable, error)
+        })
+    }
+}
+
+// Test fixture: a migrated temp database behind a ready `DatabaseState`. Dropping it removes
+// the database file and its WAL sidecars.
+#[cfg(test)]
+pub(crate) struct TestDatabase {
+    state: DatabaseState,
+    database_path: PathBuf,
+}
+
+#[cfg(test)]
+impl TestDatabase {
+    // A temp path with no database yet; the state stays unready until `migrated`.
+    pub(crate) fn unmigrated() -> Self {
+        Self {
+            state: DatabaseState::default(),
+            database_path: std::env::temp_dir()
+                .join(format!("solostack-test-{}.db", uuid::Uuid::new_v4())),
+        }
+    }
+
+    pub(crate) fn migrated() -> Self {
+        let database = Self::unmigrated();
+        let report = run_schema_migrations(&database.database_path);
+        assert_eq!(report.migration_error, None);
+        database.state.mark_ready(database.database_path.clone());
+        database
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.database_path
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TestDatabase {
+    type Target = DatabaseState;
+
+    fn deref(&self) -> &DatabaseState {
+        &self.state
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.database_path);
+        for suffix in ["-wal", "-shm"] {
+            let _ =
+                std::fs::remove_file(format!("{}{suffix}", self.database_path.to_string_lossy()));
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn count_rows(state: &DatabaseState, sql: &str, params: impl rusqlite::Params) -> i64 {
+    state
+        .open()
+        .unwrap()
+        .query_row(sql, params, |row| row.get(0))
+        .unwrap()
+}
+
+fn read_current_schema_version(connection: &Connection) -> Result<i64, String> {
+    connection
+        .query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|error| format!("read schema version failed: {error}"))
+}
+
+fn apply_schema_migration(
+    connection: &mut Connection,
+    migration: &SchemaMigration,
+) -> Result<(), String> {
+    let transaction = connection
+        .transaction()
+        .map_err(|error| format!("begin migration {} failed: {error}", migration.version))?;
+    (migration.apply)(&transaction).map_err(|error| {
+        format!(
+            "apply migration {} ({}) failed: {error}",
+            migration.version, migration.name
+        )
+    })?;
+    transaction
+        .execute(
+            "INSERT INTO schema_migrations (version, name) VALUES (?1, ?2)",
+            (migration.version, migration.name),
+        )
+        .map_err(|error| format!("record migration {} failed: {error}", migration.version))?;
+    transaction
+        .commit()
+        .map_err(|error| format!("commit migration {} failed: {error}", migration.version))
+}
+
+pub(crate) fn run_schema_migrations(database_path: &Path) -> SchemaMigrationReport {
+    let mut report = SchemaMigrationReport {
+        database_path: Some(database_path.to_string_lossy().to_string()),
+        target_version: latest_schema_version(),
+        ..SchemaMigrationReport::default()
+    };
+
+    let mut connection = match open_database(database_path) {
+        Ok(connection) => connection,
+        Err(error) => {
+            report.migration_error = Some(error);
+            return report;
+        }
+    };
+
+    if let Err(error) = connection.execute_batch(SCHEMA_MIGRATIONS_TABLE_SQL) {
+        report.migration_error = Some(format!("create schema_migrations table failed: {error}"));
+        return report;
+    }
+
+    report.previous_version = match read_current_schema_version(&connection) {
+        Ok(version) => version,
+        Err(error) => {
+            report.migration_error = Some(error);
+            return report;
+        }
+    };
+    report.current_version = report.previous_version;
+
+    if report.previous_version > report.target_version {
+        report.migration_error = Some(format!(
+            "database schema version {} is newer than supported version {}",
+            report.previous_version, report.target_version
+        ));
+        return report;
+    }
+
+    for migration in SCHEMA_MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > report.previous_version)
+    {
+        if let Err(error) = apply_schema_migration(&mut connection, migration) {
+            report.migration_error = Some(error);
+            return report;
+        }
+        report.current_version = migration.version;
+        report.applied_migrations.push(AppliedSchemaMigration {
+            version: migration.version,
+            name: migration.name.to_string(),
+        });
+    }
+
+    report
+}
+
+fn table_has_column(
+    transaction: &Transaction<'_>,
+    table_name: &str,
+    column_name: &str,
+) -> rusqlite::Result<bool> {
+    let mut statement = transaction.prepare(&format!("PRAGMA table_info({table_name})"))?;
+    let column_names = statement.query_map([], |row| row.get::<_, String>(1))?;
+    for existing_column_name in column_names {
+        if existing_column_name? == column_name {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn add_column_if_missing(
+    transaction: &Transaction<'_>,
+    table_name: &str,
+    column_name: &str,
+    column_definition: &str,
+) -> rusqlite::Result<()> {
+    if table_has_column(transaction, table_name, column_name)? {
+        return Ok(());
+    }
+    transaction.execute_batch(&format!(
+        "ALTER TABLE {table_name} ADD COLUMN {column_name} {column_definition}"
+    ))
+}
+
+fn create_base_tables(transaction: &Transaction<'_>) -> rusqlite::Result<()> {
+    transaction.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS settings (
+          key TEXT PRIMARY KEY,
+          value TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS projects (
+          id TEXT PRIMARY KEY,
+          name TEXT NOT NULL UNIQUE,
+          description TEXT,
+          color TEXT,
+          status TEXT NOT NULL DEFAULT 'ACTIVE' CHECK(status IN ('ACTIVE', 'COMPLETED', 'ARCHIVED')),
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          sync_version INTEGER NOT NULL DEFAULT 1,
+          updated_by_device TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_projects_status_updated_at
+        ON projects(status, updated_at DESC);
+
+        CREATE TABLE IF NOT EXISTS tasks (
+          id TEXT PRIMARY KEY,
+          title TEXT NOT NULL,
+          description TEXT,
+          notes_markdown TEXT,
+          project_id TEXT,
+          status TEXT NOT NULL CHECK(status IN ('TODO', 'DOING', 'DONE', 'ARCHIVED')),
+          priority TEXT NOT NULL CHECK(priority IN ('URGENT', 'NORMAL', 'LOW')),
+          is_important BOOLEAN DEFAULT 0,
+          due_at DATETIME,
+          remind_at DATETIME,
+          recurrence TEXT NOT NULL DEFAULT 'NONE' CHECK(recurrence IN ('NONE', 'DAILY', 'WEEKLY', 'MONTHLY')),
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          sync_version INTEGER NOT NULL DEFAULT 1,
+          updated_by_device TEXT,
+          FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE SET NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS sessions (
+          id TEXT PRIMARY KEY,
+          task_id TEXT,
+          duration_minutes INTEGER NOT NULL,
+          completed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          FOREIGN KEY(task_id) REFERENCES tasks(id)
+        );
+
+        CREATE TABLE IF NOT EXISTS task_changelogs (
+          id TEXT PRIMARY KEY,
+          task_id TEXT NOT NULL,
+          action TEXT NOT NULL CHECK(action IN ('CREATED', 'UPDATED', 'STATUS_CHANGED')),
+          field_name TEXT,
+          old_value TEXT,
+          new_value TEXT,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_task_changelogs_task_created_at
+        ON task_changelogs(task_id, created_at DESC);
+
+        CREATE TABLE IF NOT EXISTS task_templates (
+          id TEXT PRIMARY KEY,
+          name TEXT NOT NULL UNIQUE,
+          title_template TEXT,
+          description TEXT,
+          priority TEXT NOT NULL CHECK(priority IN ('URGENT', 'NORMAL', 'LOW')),
+          is_important BOOLEAN DEFAULT 0,
+          due_offset_minutes INTEGER,
+          remind_offset_minutes INTEGER,
+          recurrence TEXT NOT NULL DEFAULT 'NONE' CHECK(recurrence IN ('NONE', 'DAILY', 'WEEKLY', 'MONTHLY')),
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          sync_version INTEGER NOT NULL DEFAULT 1,
+          updated_by_device TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_task_templates_updated_at
+        ON task_templates(updated_at DESC);
+
+        CREATE TABLE IF NOT EXISTS task_subtasks (
+          id TEXT PRIMARY KEY,
+          task_id TEXT NOT NULL,
+          title TEXT NOT NULL,
+          is_done BOOLEAN DEFAULT 0,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          sync_version INTEGER NOT NULL DEFAULT 1,
+          updated_by_device TEXT,
+          FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_task_subtasks_task_created_at
+        ON task_subtasks(task_id, created_at ASC);
+
+        CREATE TABLE IF NOT EXISTS sync_checkpoints (
+          id INTEGER PRIMARY KEY CHECK(id = 1),
+          last_sync_cursor TEXT,
+          last_synced_at DATETIME,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS sync_outbox (
+          id TEXT PRIMARY KEY,
+          entity_type TEXT NOT NULL CHECK(entity_type IN ('PROJECT', 'TASK', 'TASK_SUBTASK', 'TASK_TEMPLATE', 'SETTING')),
+          entity_id TEXT NOT NULL,
+          operation TEXT NOT NULL CHECK(operation IN ('UPSERT', 'DELETE')),
+          payload_json TEXT,
+          idempotency_key TEXT NOT NULL UNIQUE,
+          attempts INTEGER NOT NULL DEFAULT 0,
+          last_error TEXT,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_sync_outbox_created_at
+        ON sync_outbox(created_at ASC);
+
+        CREATE TABLE IF NOT EXISTS deleted_records (
+          id TEXT PRIMARY KEY,
+          entity_type TEXT NOT NULL CHECK(entity_type IN ('PROJECT', 'TASK', 'TASK_SUBTASK', 'TASK_TEMPLATE', 'SETTING')),
+          entity_id TEXT NOT NULL,
+          deleted_at DATETIME NOT NULL,
+          deleted_by_device TEXT,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          UNIQUE(entity_type, entity_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_deleted_records_deleted_at
+        ON deleted_records(deleted_at DESC);
+
+        CREATE TABLE IF NOT EXISTS sync_conflicts (
+          id TEXT PRIMARY KEY,
+          incoming_idempotency_key TEXT NOT NULL UNIQUE,
+          entity_type TEXT NOT NULL CHECK(entity_type IN ('PROJECT', 'TASK', 'TASK_SUBTASK', 'TASK_TEMPLATE', 'SETTING')),
+          entity_id TEXT NOT NULL,
+          operation TEXT NOT NULL CHECK(operation IN ('UPSERT', 'DELETE')),
+          conflict_type TEXT NOT NULL CHECK(conflict_type IN ('field_conflict', 'delete_vs_update', 'notes_collision', 'validation_error')),
+          reason_code TEXT NOT NULL,
+          message TEXT NOT NULL,
+          local_payload_json TEXT,
+          remote_payload_json TEXT,
+          base_payload_json TEXT,
+          status TEXT NOT NULL DEFAULT 'open' CHECK(status IN ('open', 'resolved', 'ignored')),
+          resolution_strategy TEXT CHECK(resolution_strategy IN ('keep_local', 'keep_remote', 'manual_merge', 'retry')),
+          resolution_payload_json TEXT,
+          resolved_by_device TEXT,
+          detected_at DATETIME NOT NULL,
+          resolved_at DATETIME,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_sync_conflicts_status_detected_at
+        ON sync_conflicts(status, detected_at DESC);
+
+        CREATE INDEX IF NOT EXISTS idx_sync_conflicts_entity
+        ON sync_conflicts(entity_type, entity_id, detected_at DESC);
+
+        CREATE TABLE IF NOT EXISTS sync_conflict_events (
+          id TEXT PRIMARY KEY,
+          conflict_id TEXT NOT NULL,
+          event_type TEXT NOT NULL CHECK(event_type IN ('detected', 'resolved', 'ignored', 'retried', 'exported')),
+          event_payload_json TEXT,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          FOREIGN KEY(conflict_id) REFERENCES sync_conflicts(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_sync_conflict_events_conflict_created_at
+        ON sync_conflict_events(conflict_id, created_at DESC);
+        ",
+    )
+}
+
+// Databases created by early builds predate these columns; `CREATE TABLE IF NOT EXISTS`
+// in the base migration leaves such tables untouched, so the columns are added here.
+fn backfill_sync_columns(transaction: &Transaction<'_>) -> rusqlite::Result<()> {
+    add_column_if_missing(
+        transaction,
+        "projects",
+        "sync_version",
+        "INTEGER NOT NULL DEFAULT 1",
+    )?;
+    add_column_if_missing(transaction, "projects", "updated_by_device", "TEXT")?;
+
+    add_column_if_missing(transaction, "tasks", "due_at", "DATETIME")?;
+    add_column_if_missing(transaction, "tasks", "remind_at", "DATETIME")?;
+    add_column_if_missing(
+        transaction,
+        "tasks",
+        "recurrence",
+        "TEXT NOT NULL DEFAULT 'NONE' CHECK(recurrence IN ('NONE', 'DAILY', 'WEEKLY', 'MONTHLY'))",
+    )?;
+    add_column_if_missing(transaction, "tasks", "project_id", "TEXT")?;
+    add_column_if_missing(transaction, "tasks", "notes_markdown", "TEXT")?;
+    add_column_if_missing(
+        transaction,
+        "tasks",
+        "sync_version",
+        "INTEGER NOT NULL DEFAULT 1",
+    )?;
+    add_column_if_missing(transaction, "tasks", "updated_by_device", "TEXT")?;
+
+    add_column_if_missing(
+        transaction,
+        "task_templates",
+        "due_offset_minutes",
+        "INTEGER",
+    )?;
+    add_column_if_missing(
+        transaction,
+        "task_templates",
+        "remind_offset_minutes",
+        "INTEGER",
+    )?;
+    add_column_if_missing(
+        transaction,
+        "task_templates",
+        "sync_version",
+        "INTEGER NOT NULL DEFAULT 1",
+    )?;
+    add_column_if_missing(transaction, "task_templates", "updated_by_device", "TEXT")?;
+
+    add_column_if_missing(
+        transaction,
+        "task_subtasks",
+        "sync_version",
+        "INTEGER NOT NULL DEFAULT 1",
+    )?;
+    add_column_if_missing(transaction, "task_subtasks", "updated_by_device", "TEXT")
+}
+
+fn create_task_indexes_and_sync_checkpoint(transaction: &Transaction<'_>) -> rusqlite::Result<()> {
+    transaction.execute_batch(
+        "
+        CREATE INDEX IF NOT EXISTS idx_tasks_due_at
+        ON tasks(due_at);
+
+        CREATE INDEX IF NOT EXISTS idx_tasks_project_id
+        ON tasks(project_id);
+
+        INSERT OR IGNORE INTO sync_checkpoints (id, last_sync_cursor, last_synced_at, updated_at)
+        VALUES (1, NULL, NULL, CURRENT_TIMESTAMP);
+        ",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The webview database tests start from this snapshot instead of creating the schema
+    // themselves.
+    const SCHEMA_SNAPSHOT_PATH: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../src/test/solostack-schema.sql"
+    );
+    const SCHEMA_SNAPSHOT_HEADER: &str =
+        "-- Generated from the schema migrations in src-tauri/src/db.rs.\n\
+        -- Refresh with SOLOSTACK_UPDATE_SCHEMA_SNAPSHOT=1 cargo test schema_snapshot.\n";
+    const WEBVIEW_DATABASE_MODULE_PATH: &str =
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../src/lib/database.ts");
+
+    fn schema_version(database: &TestDatabase) -> i64 {
+        read_current_schema_version(&open_database(database.path()).unwrap()).unwrap()
+    }
+
+    fn column_names(database: &TestDatabase, table_name: &str) -> Vec<String> {
+        let connection = open_database(database.path()).unwrap();
+        let mut statement = connection
+            .prepare(&format!("PRAGMA table_info({table_name})"))
+            .unwrap();
+        let names = statement
+            .query_map([], |row| row.get(1))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<String>>>()
+            .unwrap();
+        names
+    }
+
+    fn schema_snapshot(database: &TestDatabase) -> String {
+        let connection = open_database(database.path()).unwrap();
+        let mut statement = connection
+            .prepare(
+                "SELECT sql FROM sqlite_master
+                  WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+                  ORDER BY type DESC, name",
+            )
+            .unwrap();
+        let mut snapshot = SCHEMA_SNAPSHOT_HEADER.to_string();
+        for sql in statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .unwrap()
+        {
+            snapshot.push_str(&format!("\n{};\n", sql.unwrap()));
+        }
+        snapshot.push('\n');
+        for migration in SCHEMA_MIGRATIONS {
+            snapshot.push_str(&format!(
+                "INSERT INTO schema_migrations (version, name) VALUES ({}, '{}');\n",
+                migration.version, migration.name
+            ));
+        }
+        snapshot
+    }
+
+    #[test]
+    fn fresh_database_migrates_to_the_latest_version() {
+        let database = TestDatabase::unmigrated();
+        let report = run_schema_migrations(database.path());
+        assert_eq!(report.migration_error, None);
+        assert_eq!(report.previous_version, 0);
+        assert_eq!(report.current_version, latest_schema_version());
+        assert_eq!(
+            report
+                .applied_migrations
+                .iter()
+                .map(|migration| migration.version)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(schema_version(&database), latest_schema_version());
+        database.mark_ready(database.path().to_path_buf());
+        assert_eq!(
+            count_rows(&database, "SELECT COUNT(*) FROM sync_checkpoints", []),
+            1
+        );
+    }
+
+    #[test]
+    fn second_run_is_a_no_op() {
+        let database = TestDatabase::migrated();
+        let report = run_schema_migrations(database.path());
+        assert_eq!(report.migration_error, None);
+        assert_eq!(report.previous_version, latest_schema_version());
+        assert_eq!(report.current_version, latest_schema_version());
+        assert!(report.applied_migrations.is_empty());
+        assert_eq!(
+            count_rows(&database, "SELECT COUNT(*) FROM schema_migrations", []),
+            SCHEMA_MIGRATIONS.len() as i64
+        );
+    }
+
+    #[test]
+    fn refuses_a_schema_newer_than_this_build() {
+        let database = TestDatabase::migrated();
+        let newer_version = latest_schema_version() + 1;
+        open_database(database.path())
+            .unwrap()
+            .execute(
+                "INSERT INTO schema_migrations (version, name) VALUES (?1, 'from_a_newer_build')",
+                [newer_version],
+            )
+            .unwrap();
+
+        let report = run_schema_migrations(database.path());
+        assert_eq!(
+            report.migration_error,
+            Some(format!(
+                "database schema version {newer_version} is newer than supported version {}",
+                latest_schema_version()
+            ))
+        );
+        assert_eq!(report.current_version, newer_version);
+        assert!(report.applied_migrations.is_empty());
+    }
+
+    #[test]
+    fn backfills_sync_columns_on_a_database_from_an_early_build() {
+        let database = TestDatabase::unmigrated();
+        open_database(database.path())
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE projects (
+                   id TEXT PRIMARY KEY,
+                   name TEXT NOT NULL UNIQUE,
+                   description TEXT,
+                   color TEXT,
+                   status TEXT NOT NULL DEFAULT 'ACTIVE',
+                   created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                   updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                 );
+                 CREATE TABLE tasks (
+                   id TEXT PRIMARY KEY,
+                   title TEXT NOT NULL,
+                   description TEXT,
+                   status TEXT NOT NULL,
+                   priority TEXT NOT NULL,
+                   is_important BOOLEAN DEFAULT 0,
+                   created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                   updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                 );
+                 CREATE TABLE task_templates (
+                   id TEXT PRIMARY KEY,
+                   name TEXT NOT NULL UNIQUE,
+                   title_template TEXT,
+                   description TEXT,
+                   priority TEXT NOT NULL,
+                   is_important BOOLEAN DEFAULT 0,
+                   recurrence TEXT NOT NULL DEFAULT 'NONE',
+                   created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                   updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                 );
+                 CREATE TABLE task_subtasks (
+                   id TEXT PRIMARY KEY,
+                   task_id TEXT NOT NULL,
+                   title TEXT NOT NULL,
+                   is_done BOOLEAN DEFAULT 0,
+                   created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                   updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                 );
+                 INSERT INTO tasks (id, title, status, priority)
+                 VALUES ('legacy-task-1', 'Legacy Task', 'TODO', 'NORMAL');",
+            )
+            .unwrap();
+
+        let report = run_schema_migrations(database.path());
+        assert_eq!(report.migration_error, None);
+        assert_eq!(report.current_version, latest_schema_version());
+        for (table_name, column_name) in [
+            ("projects", "sync_version"),
+            ("projects", "updated_by_device"),
+            ("tasks", "due_at"),
+            ("tasks", "remind_at"),
+            ("tasks", "recurrence"),
+            ("tasks", "project_id"),
+            ("tasks", "notes_markdown"),
+            ("tasks", "sync_version"),
+            ("tasks", "updated_by_device"),
+            ("task_templates", "due_offset_minutes"),
+            ("task_templates", "remind_offset_minutes"),
+            ("task_templates", "sync_version"),
+            ("task_templates", "updated_by_device"),
+            ("task_subtasks", "sync_version"),
+            ("task_subtasks", "updated_by_device"),
+        ] {
+            assert!(
+                column_names(&database, table_name).contains(&column_name.to_string()),
+                "{table_name}.{column_name}"
+            );
+        }
+        database.mark_ready(database.path().to_path_buf());
+        assert_eq!(
+            count_rows(
+                &database,
+                "SELECT COUNT(*) FROM tasks
+                  WHERE id = 'legacy-task-1' AND recurrence = 'NONE' AND sync_version = 1",
+                [],
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn backup_includes_pages_still_in_the_wal() {
+        let source = TestDatabase::unmigrated();
+        let raw_copy = TestDatabase::unmigrated();
+        let backup = TestDatabase::unmigrated();
+        let count_tasks = |path: &Path| -> i64 {
+            Connection::open(path)
+                .unwrap()
+                .query_row("SELECT COUNT(*) FROM tasks", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        // Kept open with checkpoints off, like a legacy app that is still running.
+        let writer = Connection::open(source.path()).unwrap();
+        writer
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+                 PRAGMA wal_autocheckpoint = 0;
+                 CREATE TABLE tasks (id TEXT PRIMARY KEY);
+                 PRAGMA wal_checkpoint(TRUNCATE);
+                 INSERT INTO tasks VALUES ('task-1'), ('task-2');",
+            )
+            .unwrap();
+        std::fs::copy(source.path(), raw_copy.path()).unwrap();
+        assert_eq!(count_tasks(raw_copy.path()), 0);
+
+        let page_count = backup_database(source.path(), backup.path(), &mut |_, _| {}).unwrap();
+        assert!(page_count > 0);
+        assert_eq!(count_tasks(backup.path()), 2);
+        drop(writer);
+    }
+
+    #[test]
+    fn schema_snapshot_matches_the_migrations() {
+        let database = TestDatabase::migrated();
+        let snapshot = schema_snapshot(&database);
+        if std::env::var_os("SOLOSTACK_UPDATE_SCHEMA_SNAPSHOT").is_some() {
+            std::fs::write(SCHEMA_SNAPSHOT_PATH, &snapshot).unwrap();
+        }
+        assert_eq!(
+            std::fs::read_to_string(SCHEMA_SNAPSHOT_PATH).unwrap(),
+            snapshot,
+            "schema snapshot is stale; refresh it with SOLOSTACK_UPDATE_SCHEMA_SNAPSHOT=1"
+        );
+
+        let webview_module = std::fs::read_to_string(WEBVIEW_DATABASE_MODULE_PATH).unwrap();
+        assert!(
+            webview_module.contains(&format!(
+                "const REQUIRED_SCHEMA_VERSION = {};",
+                latest_schema_version()
+            )),
+            "REQUIRED_SCHEMA_VERSION in src/lib/database.ts must match the latest migration"
+        );
+    }
+}
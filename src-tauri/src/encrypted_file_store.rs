@@ -0,0 +1,423 @@
+// Fallback secure store for desktops without a usable OS keyring, e.g. headless Linux machines
+// with neither Secret Service nor a keyutils session. Every secret is sealed with
+// XChaCha20-Poly1305 under a key that Argon2id derives from the user's passphrase, or from the
+// machine id when no passphrase is configured. The account name is bound as associated data so
+// sealed values cannot be swapped between providers.
+//
+// `/etc/machine-id` is world-readable, so a machine-derived key only keeps the file useless once
+// it is copied off this machine; any local account that can read the file can also unseal it.
+// Shared machines should set `SOLOSTACK_SECURE_STORE_PASSPHRASE` before the first secret is stored.
+
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    secure_store::{sync_provider_auth_account, SecureStoreBackend},
+};
+
+const ENCRYPTED_STORE_FILENAME: &str = "sync-provider-auth.enc.json";
+const ENCRYPTED_STORE_VERSION: u8 = 1;
+const ENCRYPTED_STORE_SALT_BYTES: usize = 16;
+const SECURE_STORE_PASSPHRASE_ENV: &str = "SOLOSTACK_SECURE_STORE_PASSPHRASE";
+const MACHINE_ID_PATHS: &[&str] = &["/etc/machine-id", "/var/lib/dbus/machine-id"];
+// Keeps the machine-derived key specific to this app even though the machine id is shared.
+const MACHINE_KEY_CONTEXT: &str = "com.solutionsstudio.solostack.sync-provider-auth";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum EncryptedStoreKeySource {
+    Passphrase,
+    MachineId,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SealedSecret {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct EncryptedStoreFile {
+    version: u8,
+    key_source: EncryptedStoreKeySource,
+    salt: String,
+    entries: BTreeMap<String, SealedSecret>,
+}
+
+pub(crate) struct EncryptedFileStore {
+    path: PathBuf,
+    passphrase_env: &'static str,
+    // Serializes read-modify-write cycles; commands run concurrently on the async runtime.
+    file_lock: Mutex<()>,
+}
+
+fn unavailable(detail: String) -> SoloStackError {
+    SoloStackError::with_detail(SoloStackErrorCode::SecureStoreUnavailable, detail)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(value.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+fn read_passphrase(passphrase_env: &str) -> Option<String> {
+    std::env::var(passphrase_env)
+        .ok()
+        .filter(|passphrase| !passphrase.trim().is_empty())
+}
+
+fn read_machine_id() -> Option<String> {
+    MACHINE_ID_PATHS.iter().find_map(|path| {
+        fs::read_to_string(path)
+            .ok()
+            .map(|machine_id| machine_id.trim().to_string())
+            .filter(|machine_id| !machine_id.is_empty())
+    })
+}
+
+// A new store prefers the passphrase; an existing store keeps the source it was created with,
+// so setting the passphrase later does not orphan secrets sealed with the machine key.
+fn preferred_key_source(passphrase_env: &str) -> Result<EncryptedStoreKeySource, SoloStackError> {
+    if read_passphrase(passphrase_env).is_some() {
+        Ok(EncryptedStoreKeySource::Passphrase)
+    } else if read_machine_id().is_some() {
+        Ok(EncryptedStoreKeySource::MachineId)
+    } else {
+        Err(unavailable(format!(
+            "no machine id found; set {passphrase_env} to enable the encrypted file store"
+        )))
+    }
+}
+
+fn derive_key(
+    passphrase_env: &str,
+    key_source: EncryptedStoreKeySource,
+    salt: &[u8],
+) -> Result<XChaCha20Poly1305, SoloStackError> {
+    let secret = match key_source {
+        EncryptedStoreKeySource::Passphrase => {
+            read_passphrase(passphrase_env).ok_or_else(|| {
+                unavailable(format!(
+                    "encrypted file store is passphrase protected; set {passphrase_env}"
+                ))
+            })?
+        }
+        EncryptedStoreKeySource::MachineId => read_machine_id()
+            .map(|machine_id| format!("{MACHINE_KEY_CONTEXT}:{machine_id}"))
+            .ok_or_else(|| unavailable("machine id is no longer readable".to_string()))?,
+    };
+
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(secret.as_bytes(), salt, &mut key)
+        .map_err(|error| unavailable(format!("derive encrypted store key failed: {error}")))?;
+    Ok(XChaCha20Poly1305::new(&key.into()))
+}
+
+impl EncryptedFileStore {
+    pub(crate) fn in_app_data_dir(app_data_dir: &Path) -> Self {
+        Self {
+            path: app_data_dir.join(ENCRYPTED_STORE_FILENAME),
+            passphrase_env: SECURE_STORE_PASSPHRASE_ENV,
+            file_lock: Mutex::new(()),
+        }
+    }
+
+    // Tests read the passphrase from their own variable so they can change it without racing.
+    #[cfg(test)]
+    fn with_passphrase_env(self, passphrase_env: &'static str) -> Self {
+        Self {
+            passphrase_env,
+            ..self
+        }
+    }
+
+    fn key_source(&self) -> Result<EncryptedStoreKeySource, SoloStackError> {
+        match self.load()? {
+            Some(store_file) => Ok(store_file.key_source),
+            None => preferred_key_source(self.passphrase_env),
+        }
+    }
+
+    fn load(&self) -> Result<Option<EncryptedStoreFile>, SoloStackError> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => {
+                return Err(SoloStackError::with_detail(
+                    SoloStackErrorCode::SecureStoreReadFailed,
+                    format!("read encrypted store failed: {error}"),
+                ))
+            }
+        };
+        let store_file: EncryptedStoreFile = serde_json::from_str(&contents).map_err(|error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::SecureStoreReadFailed,
+                format!("parse encrypted store failed: {error}"),
+            )
+        })?;
+        if store_file.version != ENCRYPTED_STORE_VERSION {
+            return Err(SoloStackError::with_detail(
+                SoloStackErrorCode::SecureStoreReadFailed,
+                format!("unsupported encrypted store version {}", store_file.version),
+            ));
+        }
+        Ok(Some(store_file))
+    }
+
+    // Written to a 0600 temp file and renamed into place so a crash never leaves a torn store.
+    fn save(&self, store_file: &EncryptedStoreFile) -> Result<(), SoloStackError> {
+        let write_failed = |detail: String| {
+            SoloStackError::with_detail(SoloStackErrorCode::SecureStoreWriteFailed, detail)
+        };
+        let contents = serde_json::to_string_pretty(store_file)
+            .map_err(|error| write_failed(format!("serialize encrypted store failed: {error}")))?;
+        if let Some(parent_dir) = self.path.parent() {
+            fs::create_dir_all(parent_dir)
+                .map_err(|error| write_failed(format!("create store dir failed: {error}")))?;
+        }
+
+        let temp_path = self.path.with_extension("json.tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut temp_file = options
+            .open(&temp_path)
+            .map_err(|error| write_failed(format!("open encrypted store failed: {error}")))?;
+        temp_file
+            .write_all(contents.as_bytes())
+            .and_then(|_| temp_file.sync_all())
+            .map_err(|error| write_failed(format!("write encrypted store failed: {error}")))?;
+        fs::rename(&temp_path, &self.path)
+            .map_err(|error| write_failed(format!("replace encrypted store failed: {error}")))
+    }
+
+    fn read_account(&self, account: &str) -> Result<Option<String>, SoloStackError> {
+        let _guard = self
+            .file_lock
+            .lock()
+            .map_err(|_| SoloStackError::new(SoloStackErrorCode::Internal))?;
+        let Some(store_file) = self.load()? else {
+            return Ok(None);
+        };
+        let Some(sealed) = store_file.entries.get(account) else {
+            return Ok(None);
+        };
+
+        let read_failed = |detail: &str| {
+            SoloStackError::with_detail(SoloStackErrorCode::SecureStoreReadFailed, detail)
+        };
+        let salt = decode_hex(&store_file.salt).ok_or_else(|| read_failed("corrupt store salt"))?;
+        let nonce = decode_hex(&sealed.nonce)
+            .filter(|nonce| nonce.len() == 24)
+            .ok_or_else(|| read_failed("corrupt secret nonce"))?;
+        let ciphertext =
+            decode_hex(&sealed.ciphertext).ok_or_else(|| read_failed("corrupt secret"))?;
+        let cipher = derive_key(self.passphrase_env, store_file.key_source, &salt)?;
+        let plaintext = cipher
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: account.as_bytes(),
+                },
+            )
+            .map_err(|_| read_failed("decrypt secret failed; the store key has changed"))?;
+        String::from_utf8(plaintext)
+            .map(Some)
+            .map_err(|_| read_failed("decrypted secret is not UTF-8"))
+    }
+
+    fn write_account(&self, account: &str, secret: &str) -> Result<(), SoloStackError> {
+        let _guard = self
+            .file_lock
+            .lock()
+            .map_err(|_| SoloStackError::new(SoloStackErrorCode::Internal))?;
+        let mut store_file = match self.load()? {
+            Some(store_file) => store_file,
+            None => {
+                let mut salt = [0u8; ENCRYPTED_STORE_SALT_BYTES];
+                OsRng.fill_bytes(&mut salt);
+                EncryptedStoreFile {
+                    version: ENCRYPTED_STORE_VERSION,
+                    key_source: preferred_key_source(self.passphrase_env)?,
+                    salt: encode_hex(&salt),
+                    entries: BTreeMap::new(),
+                }
+            }
+        };
+
+        let salt = decode_hex(&store_file.salt).ok_or_else(|| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::SecureStoreWriteFailed,
+                "corrupt store salt",
+            )
+        })?;
+        let cipher = derive_key(self.passphrase_env, store_file.key_source, &salt)?;
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: secret.as_bytes(),
+                    aad: account.as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                SoloStackError::with_detail(
+                    SoloStackErrorCode::SecureStoreWriteFailed,
+                    "encrypt secret failed",
+                )
+            })?;
+        store_file.entries.insert(
+            account.to_string(),
+            SealedSecret {
+                nonce: encode_hex(&nonce),
+                ciphertext: encode_hex(&ciphertext),
+            },
+        );
+        self.save(&store_file)
+    }
+
+    fn delete_account(&self, account: &str) -> Result<(), SoloStackError> {
+        let _guard = self
+            .file_lock
+            .lock()
+            .map_err(|_| SoloStackError::new(SoloStackErrorCode::Internal))?;
+        let Some(mut store_file) = self.load()? else {
+            return Ok(());
+        };
+        if store_file.entries.remove(account).is_none() {
+            return Ok(());
+        }
+        self.save(&store_file).map_err(|error| SoloStackError {
+            code: SoloStackErrorCode::SecureStoreDeleteFailed,
+            detail: error.detail,
+        })
+    }
+}
+
+impl SecureStoreBackend for EncryptedFileStore {
+    fn name(&self) -> &'static str {
+        "encrypted_file"
+    }
+
+    fn probe(&self) -> Result<(), SoloStackError> {
+        self.key_source().map(|_| ())
+    }
+
+    fn read(&self, key: &str) -> Result<Option<String>, SoloStackError> {
+        self.read_account(&sync_provider_auth_account(key))
+    }
+
+    fn write(&self, key: &str, value: &str) -> Result<(), SoloStackError> {
+        self.write_account(&sync_provider_auth_account(key), value)
+    }
+
+    fn delete(&self, key: &str) -> Result<(), SoloStackError> {
+        self.delete_account(&sync_provider_auth_account(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(passphrase_env: &'static str, passphrase: &str) -> (PathBuf, EncryptedFileStore) {
+        let app_data_dir = std::env::temp_dir().join(format!(
+            "solostack-encrypted-store-{}",
+            uuid::Uuid::new_v4()
+        ));
+        std::env::set_var(passphrase_env, passphrase);
+        let store =
+            EncryptedFileStore::in_app_data_dir(&app_data_dir).with_passphrase_env(passphrase_env);
+        (app_data_dir, store)
+    }
+
+    #[test]
+    fn roundtrips_sealed_secrets() {
+        let (app_data_dir, store) =
+            temp_store("SOLOSTACK_TEST_PASSPHRASE_ROUNDTRIP", "correct horse");
+        assert_eq!(store.read("google_appdata").unwrap(), None);
+        store.write("google_appdata", "token-1").unwrap();
+        store.write("onedrive_approot", "token-2").unwrap();
+        assert_eq!(
+            store.read("google_appdata").unwrap().as_deref(),
+            Some("token-1")
+        );
+        assert_eq!(
+            store.key_source().unwrap(),
+            EncryptedStoreKeySource::Passphrase
+        );
+
+        let contents = fs::read_to_string(app_data_dir.join(ENCRYPTED_STORE_FILENAME)).unwrap();
+        assert!(!contents.contains("token-1"));
+
+        store.delete("google_appdata").unwrap();
+        store.delete("google_appdata").unwrap();
+        assert_eq!(store.read("google_appdata").unwrap(), None);
+        assert_eq!(
+            store.read("onedrive_approot").unwrap().as_deref(),
+            Some("token-2")
+        );
+        fs::remove_dir_all(app_data_dir).unwrap();
+    }
+
+    #[test]
+    fn wrong_passphrase_fails_instead_of_reading_garbage() {
+        let passphrase_env = "SOLOSTACK_TEST_PASSPHRASE_WRONG";
+        let (app_data_dir, store) = temp_store(passphrase_env, "correct horse");
+        store.write("google_appdata", "token").unwrap();
+
+        std::env::set_var(passphrase_env, "battery staple");
+        let error = store.read("google_appdata").unwrap_err();
+        assert_eq!(error.code, SoloStackErrorCode::SecureStoreReadFailed);
+
+        std::env::remove_var(passphrase_env);
+        let error = store.read("google_appdata").unwrap_err();
+        assert_eq!(error.code, SoloStackErrorCode::SecureStoreUnavailable);
+        fs::remove_dir_all(app_data_dir).unwrap();
+    }
+
+    #[test]
+    fn corrupted_file_is_reported_and_left_in_place() {
+        let (app_data_dir, store) =
+            temp_store("SOLOSTACK_TEST_PASSPHRASE_CORRUPT", "correct horse");
+        store.write("google_appdata", "token").unwrap();
+        let store_path = app_data_dir.join(ENCRYPTED_STORE_FILENAME);
+        fs::write(&store_path, "{\"version\": 1, \"entries\": ").unwrap();
+
+        let error = store.read("google_appdata").unwrap_err();
+        assert_eq!(error.code, SoloStackErrorCode::SecureStoreReadFailed);
+        assert!(store.write("onedrive_approot", "token").is_err());
+        assert_eq!(
+            fs::read_to_string(&store_path).unwrap(),
+            "{\"version\": 1, \"entries\": "
+        );
+        fs::remove_dir_all(app_data_dir).unwrap();
+    }
+}
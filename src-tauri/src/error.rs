@@ -0,0 +1,171 @@
+// Error type returned by every Tauri command. The frontend matches on `code`, which is stable
+// across releases; `detail` carries the underlying message for logs and diagnostics only and
+// must not be parsed.
+
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::fmt;
+
+// The OAuth loopback flow, global shortcuts, the tray and the native reminder scheduler only
+// exist on desktop, so their codes go unused on mobile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[cfg_attr(any(target_os = "android", target_os = "ios"), allow(dead_code))]
+pub(crate) enum SoloStackErrorCode {
+    ProviderRequired,
+    ProviderAccountIdInvalid,
+    ProviderAccountNotFound,
+    AuthPayloadRequired,
+    CredentialInvalid,
+    SecureStoreUnavailable,
+    // Only the Android bridge hands secure store calls to another thread with a deadline.
+    #[cfg(target_os = "android")]
+    SecureStoreTimeout,
+    SecureStoreReadFailed,
+    SecureStoreWriteFailed,
+    SecureStoreDeleteFailed,
+    SecureStoreAuditReadFailed,
+    OauthProviderUnsupported,
+    OauthClientIdRequired,
+    OauthAuthorizationDenied,
+    OauthTimeout,
+    OauthTokenExchangeFailed,
+    ShortcutInvalid,
+    ShortcutConflict,
+    ShortcutActionBound,
+    ShortcutUnavailable,
+    ShortcutUnregisterFailed,
+    ShortcutConfigWriteFailed,
+    TraySettingsWriteFailed,
+    ReminderStateWriteFailed,
+    SyncTransportUnavailable,
+    SyncRequestFailed,
+    SyncRateLimited,
+    SyncServerUnavailable,
+    SyncUnauthorized,
+    SyncSchemaMismatch,
+    SyncInvalidCursor,
+    SyncRequestRejected,
+    SyncResponseInvalid,
+    AppDataDirUnavailable,
+    MigrationCopyFailed,
+    MigrationIntegrityCheckFailed,
+    MigrationMarkerWriteFailed,
+    MigrationFailed,
+    LegacyCleanupRefused,
+    LegacyCleanupFailed,
+    DatabaseUnavailable,
+    DatabaseBusy,
+    DatabaseQueryFailed,
+    DbSelectedProjectMissing,
+    DbProjectNameRequired,
+    DbProjectNameExists,
+    DbProjectNotFound,
+    DbTaskTitleRequired,
+    DbTaskNotFound,
+    DbSubtaskTitleRequired,
+    DbSubtaskNotFound,
+    DbTemplateNameRequired,
+    DbRecurringTemplateDueOffsetRequired,
+    DbReminderOffsetMustBeEarlier,
+    DbTemplateNameExists,
+    Internal,
+}
+
+impl SoloStackErrorCode {
+    // Retryable means the same call can succeed later without the user changing anything.
+    pub(crate) fn is_retryable(self) -> bool {
+        match self {
+            #[cfg(target_os = "android")]
+            Self::SecureStoreTimeout => true,
+            Self::OauthTimeout
+            | Self::MigrationCopyFailed
+            | Self::DatabaseUnavailable
+            | Self::DatabaseBusy
+            | Self::SyncRequestFailed
+            | Self::SyncRateLimited
+            | Self::SyncServerUnavailable => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SoloStackError {
+    pub(crate) code: SoloStackErrorCode,
+    pub(crate) detail: Option<String>,
+}
+
+impl SoloStackError {
+    pub(crate) fn new(code: SoloStackErrorCode) -> Self {
+        Self { code, detail: None }
+    }
+
+    pub(crate) fn with_detail(code: SoloStackErrorCode, detail: impl Into<String>) -> Self {
+        Self {
+            code,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+impl From<SoloStackErrorCode> for SoloStackError {
+    fn from(code: SoloStackErrorCode) -> Self {
+        Self::new(code)
+    }
+}
+
+impl fmt::Display for SoloStackError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = serde_json::to_value(self.code)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default();
+        match self.detail.as_deref() {
+            Some(detail) => write!(formatter, "{code}: {detail}"),
+            None => formatter.write_str(&code),
+        }
+    }
+}
+
+impl Serialize for SoloStackError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SoloStackError", 3)?;
+        state.serialize_field("code", &self.code)?;
+        state.serialize_field("detail", &self.detail)?;
+        state.serialize_field("retryable", &self.code.is_retryable())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `isCommandError` and the code lookup in `src/lib/error-message.ts` rely on this shape.
+    #[test]
+    fn serializes_code_detail_and_retryable() {
+        assert_eq!(
+            serde_json::to_value(SoloStackError::with_detail(
+                SoloStackErrorCode::DatabaseBusy,
+                "database is locked",
+            ))
+            .unwrap(),
+            serde_json::json!({
+                "code": "DATABASE_BUSY",
+                "detail": "database is locked",
+                "retryable": true,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(SoloStackError::new(
+                SoloStackErrorCode::DbReminderOffsetMustBeEarlier
+            ))
+            .unwrap(),
+            serde_json::json!({
+                "code": "DB_REMINDER_OFFSET_MUST_BE_EARLIER",
+                "detail": null,
+                "retryable": false,
+            })
+        );
+    }
+}
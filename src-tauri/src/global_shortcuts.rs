@@ -0,0 +1,549 @@
+// Global shortcuts for named app actions. Bindings live in `global-shortcuts.json` in the app
+// config dir and are registered again at startup; an action with no entry in the file uses its
+// default, and an explicit `null` keeps it unbound. The plugin calls are behind
+// `ShortcutRegistrar` so the bind/rebind/unbind rules can be tested without a window system.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Mutex, MutexGuard},
+};
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::error::{SoloStackError, SoloStackErrorCode};
+
+pub(crate) const QUICK_CAPTURE_EVENT: &str = "quick-capture:open";
+pub(crate) const OPEN_TODAY_EVENT: &str = "today-view:open";
+pub(crate) const START_FOCUS_SESSION_EVENT: &str = "focus-session:start";
+const GLOBAL_SHORTCUTS_FILENAME: &str = "global-shortcuts.json";
+const GLOBAL_SHORTCUTS_CONFIG_VERSION: u8 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum GlobalShortcutAction {
+    QuickCapture,
+    OpenToday,
+    StartFocusSession,
+}
+
+impl GlobalShortcutAction {
+    const ALL: [Self; 3] = [Self::QuickCapture, Self::OpenToday, Self::StartFocusSession];
+
+    // Only quick capture is bound out of the box; a global binding takes the key combination
+    // away from every other app, so the rest wait until the user picks one.
+    fn default_shortcut(self) -> Option<&'static str> {
+        match self {
+            Self::QuickCapture if cfg!(target_os = "macos") => Some("cmd+shift+n"),
+            Self::QuickCapture => Some("ctrl+shift+n"),
+            Self::OpenToday | Self::StartFocusSession => None,
+        }
+    }
+
+    pub(crate) fn event(self) -> &'static str {
+        match self {
+            Self::QuickCapture => QUICK_CAPTURE_EVENT,
+            Self::OpenToday => OPEN_TODAY_EVENT,
+            Self::StartFocusSession => START_FOCUS_SESSION_EVENT,
+        }
+    }
+
+    fn name(self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct GlobalShortcutConfigFile {
+    version: u8,
+    #[serde(default)]
+    bindings: BTreeMap<GlobalShortcutAction, Option<String>>,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct GlobalShortcutBinding {
+    action: GlobalShortcutAction,
+    shortcut: Option<String>,
+    default_shortcut: Option<String>,
+    registered: bool,
+    error: Option<SoloStackError>,
+}
+
+pub(crate) trait ShortcutRegistrar {
+    fn register(&self, shortcut: Shortcut) -> Result<(), String>;
+
+    fn unregister(&self, shortcut: Shortcut) -> Result<(), String>;
+}
+
+impl<R: tauri::Runtime> ShortcutRegistrar for tauri_plugin_global_shortcut::GlobalShortcut<R> {
+    fn register(&self, shortcut: Shortcut) -> Result<(), String> {
+        tauri_plugin_global_shortcut::GlobalShortcut::register(self, shortcut)
+            .map_err(|error| error.to_string())
+    }
+
+    fn unregister(&self, shortcut: Shortcut) -> Result<(), String> {
+        tauri_plugin_global_shortcut::GlobalShortcut::unregister(self, shortcut)
+            .map_err(|error| error.to_string())
+    }
+}
+
+#[derive(Default)]
+struct ActionBinding {
+    shortcut: Option<Shortcut>,
+    registered: bool,
+    error: Option<SoloStackError>,
+}
+
+pub(crate) struct GlobalShortcutState {
+    config_path: Option<PathBuf>,
+    bindings: Mutex<BTreeMap<GlobalShortcutAction, ActionBinding>>,
+}
+
+fn parse_shortcut(value: &str) -> Result<Shortcut, SoloStackError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(SoloStackError::new(SoloStackErrorCode::ShortcutInvalid));
+    }
+    Shortcut::from_str(value).map_err(|error| {
+        SoloStackError::with_detail(SoloStackErrorCode::ShortcutInvalid, error.to_string())
+    })
+}
+
+fn shortcut_unavailable(shortcut: Shortcut, error: String) -> SoloStackError {
+    SoloStackError::with_detail(
+        SoloStackErrorCode::ShortcutUnavailable,
+        format!("register {shortcut} failed (held by another app or the system?): {error}"),
+    )
+}
+
+fn config_write_failed(detail: String) -> SoloStackError {
+    SoloStackError::with_detail(SoloStackErrorCode::ShortcutConfigWriteFailed, detail)
+}
+
+fn read_config(config_path: &Path) -> GlobalShortcutConfigFile {
+    match fs::read_to_string(config_path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|error| {
+            eprintln!("Global shortcut config warning: ignoring unreadable config: {error}");
+            GlobalShortcutConfigFile::default()
+        }),
+        Err(_) => GlobalShortcutConfigFile::default(),
+    }
+}
+
+impl GlobalShortcutState {
+    pub(crate) fn load(app_config_dir: Option<&Path>) -> Self {
+        let config_path = app_config_dir.map(|dir| dir.join(GLOBAL_SHORTCUTS_FILENAME));
+        let config = config_path.as_deref().map(read_config).unwrap_or_default();
+        let bindings = GlobalShortcutAction::ALL
+            .into_iter()
+            .map(|action| {
+                let stored = match config.bindings.get(&action) {
+                    Some(stored) => stored.as_deref(),
+                    None => action.default_shortcut(),
+                };
+                let binding = match stored.map(parse_shortcut) {
+                    Some(Ok(shortcut)) => ActionBinding {
+                        shortcut: Some(shortcut),
+                        ..ActionBinding::default()
+                    },
+                    Some(Err(error)) => ActionBinding {
+                        error: Some(error),
+                        ..ActionBinding::default()
+                    },
+                    None => ActionBinding::default(),
+                };
+                (action, binding)
+            })
+            .collect();
+        Self {
+            config_path,
+            bindings: Mutex::new(bindings),
+        }
+    }
+
+    fn lock_bindings(
+        &self,
+    ) -> Result<MutexGuard<'_, BTreeMap<GlobalShortcutAction, ActionBinding>>, SoloStackError> {
+        self.bindings.lock().map_err(|_| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::Internal,
+                "global shortcut state lock poisoned",
+            )
+        })
+    }
+
+    // Failures stay on the binding, so the UI can show which action did not come back.
+    pub(crate) fn register_all(&self, registrar: &dyn ShortcutRegistrar) {
+        let Ok(mut bindings) = self.lock_bindings() else {
+            return;
+        };
+        for binding in bindings.values_mut() {
+            let Some(shortcut) = binding.shortcut else {
+                continue;
+            };
+            match registrar.register(shortcut) {
+                Ok(()) => binding.registered = true,
+                Err(error) => binding.error = Some(shortcut_unavailable(shortcut, error)),
+            }
+        }
+    }
+
+    pub(crate) fn action_for(&self, shortcut: &Shortcut) -> Option<GlobalShortcutAction> {
+        let bindings = self.lock_bindings().ok()?;
+        bindings
+            .iter()
+            .find(|(_, binding)| binding.registered && binding.shortcut.as_ref() == Some(shortcut))
+            .map(|(action, _)| *action)
+    }
+
+    pub(crate) fn list(&self) -> Result<Vec<GlobalShortcutBinding>, SoloStackError> {
+        let bindings = self.lock_bindings()?;
+        Ok(bindings
+            .iter()
+            .map(|(action, binding)| GlobalShortcutBinding {
+                action: *action,
+                shortcut: binding.shortcut.map(Shortcut::into_string),
+                default_shortcut: action
+                    .default_shortcut()
+                    .and_then(|shortcut| Shortcut::from_str(shortcut).ok())
+                    .map(Shortcut::into_string),
+                registered: binding.registered,
+                error: binding.error.clone(),
+            })
+            .collect())
+    }
+
+    // `replace` separates register (the action must be unbound) from rebind.
+    pub(crate) fn bind(
+        &self,
+        registrar: &dyn ShortcutRegistrar,
+        action: GlobalShortcutAction,
+        shortcut: &str,
+        replace: bool,
+    ) -> Result<Vec<GlobalShortcutBinding>, SoloStackError> {
+        let shortcut = parse_shortcut(shortcut)?;
+        {
+            let mut bindings = self.lock_bindings()?;
+            let current = bindings.get(&action).and_then(|binding| binding.shortcut);
+            if current.is_some() && !replace {
+                return Err(SoloStackError::with_detail(
+                    SoloStackErrorCode::ShortcutActionBound,
+                    format!("{} is already bound; rebind it instead", action.name()),
+                ));
+            }
+            if let Some((other_action, _)) = bindings.iter().find(|(other_action, binding)| {
+                **other_action != action && binding.shortcut == Some(shortcut)
+            }) {
+                return Err(SoloStackError::with_detail(
+                    SoloStackErrorCode::ShortcutConflict,
+                    format!("{shortcut} is already bound to {}", other_action.name()),
+                ));
+            }
+
+            let binding = bindings.entry(action).or_default();
+            if binding.shortcut == Some(shortcut) && binding.registered {
+                drop(bindings);
+                return self.list();
+            }
+            if let (Some(previous), true) = (binding.shortcut, binding.registered) {
+                let _ = registrar.unregister(previous);
+                binding.registered = false;
+            }
+            if let Err(error) = registrar.register(shortcut) {
+                // Keep the previous binding working rather than leaving the action unbound.
+                if let Some(previous) = binding.shortcut {
+                    binding.registered = registrar.register(previous).is_ok();
+                }
+                return Err(shortcut_unavailable(shortcut, error));
+            }
+            *binding = ActionBinding {
+                shortcut: Some(shortcut),
+                registered: true,
+                error: None,
+            };
+            self.save(&bindings)?;
+        }
+        self.list()
+    }
+
+    pub(crate) fn unbind(
+        &self,
+        registrar: &dyn ShortcutRegistrar,
+        action: GlobalShortcutAction,
+    ) -> Result<Vec<GlobalShortcutBinding>, SoloStackError> {
+        {
+            let mut bindings = self.lock_bindings()?;
+            let binding = bindings.entry(action).or_default();
+            if let (Some(shortcut), true) = (binding.shortcut, binding.registered) {
+                registrar.unregister(shortcut).map_err(|error| {
+                    SoloStackError::with_detail(
+                        SoloStackErrorCode::ShortcutUnregisterFailed,
+                        format!("unregister {shortcut} failed: {error}"),
+                    )
+                })?;
+            }
+            *binding = ActionBinding::default();
+            self.save(&bindings)?;
+        }
+        self.list()
+    }
+
+    fn save(
+        &self,
+        bindings: &BTreeMap<GlobalShortcutAction, ActionBinding>,
+    ) -> Result<(), SoloStackError> {
+        let Some(config_path) = self.config_path.as_ref() else {
+            return Err(SoloStackError::with_detail(
+                SoloStackErrorCode::AppDataDirUnavailable,
+                "global shortcut config needs the app config dir",
+            ));
+        };
+        let config = GlobalShortcutConfigFile {
+            version: GLOBAL_SHORTCUTS_CONFIG_VERSION,
+            bindings: bindings
+                .iter()
+                .map(|(action, binding)| (*action, binding.shortcut.map(Shortcut::into_string)))
+                .collect(),
+        };
+        let contents = serde_json::to_string_pretty(&config).map_err(|error| {
+            config_write_failed(format!("serialize shortcut config failed: {error}"))
+        })?;
+        if let Some(parent_dir) = config_path.parent() {
+            fs::create_dir_all(parent_dir).map_err(|error| {
+                config_write_failed(format!("create config dir failed: {error}"))
+            })?;
+        }
+        let temp_path = config_path.with_extension("json.tmp");
+        fs::File::create(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(contents.as_bytes())?;
+                file.sync_all()
+            })
+            .map_err(|error| {
+                config_write_failed(format!("write shortcut config failed: {error}"))
+            })?;
+        fs::rename(&temp_path, config_path).map_err(|error| {
+            config_write_failed(format!("replace shortcut config failed: {error}"))
+        })
+    }
+}
+
+// The plugin may have failed to start (e.g. no display server); commands then report the
+// shortcut as unavailable instead of panicking on missing state.
+fn with_registrar<T>(
+    app: &tauri::AppHandle,
+    operation: impl FnOnce(&dyn ShortcutRegistrar) -> Result<T, SoloStackError>,
+) -> Result<T, SoloStackError> {
+    use tauri::Manager;
+
+    match app.try_state::<tauri_plugin_global_shortcut::GlobalShortcut<tauri::Wry>>() {
+        Some(registrar) => operation(registrar.inner()),
+        None => Err(SoloStackError::with_detail(
+            SoloStackErrorCode::ShortcutUnavailable,
+            "global shortcut plugin is not running",
+        )),
+    }
+}
+
+#[tauri::command]
+pub(crate) fn list_global_shortcuts(
+    state: tauri::State<GlobalShortcutState>,
+) -> Result<Vec<GlobalShortcutBinding>, SoloStackError> {
+    state.list()
+}
+
+#[tauri::command]
+pub(crate) fn register_global_shortcut(
+    app: tauri::AppHandle,
+    state: tauri::State<GlobalShortcutState>,
+    action: GlobalShortcutAction,
+    shortcut: String,
+) -> Result<Vec<GlobalShortcutBinding>, SoloStackError> {
+    with_registrar(&app, |registrar| {
+        state.bind(registrar, action, &shortcut, false)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn rebind_global_shortcut(
+    app: tauri::AppHandle,
+    state: tauri::State<GlobalShortcutState>,
+    action: GlobalShortcutAction,
+    shortcut: String,
+) -> Result<Vec<GlobalShortcutBinding>, SoloStackError> {
+    with_registrar(&app, |registrar| {
+        state.bind(registrar, action, &shortcut, true)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn unregister_global_shortcut(
+    app: tauri::AppHandle,
+    state: tauri::State<GlobalShortcutState>,
+    action: GlobalShortcutAction,
+) -> Result<Vec<GlobalShortcutBinding>, SoloStackError> {
+    with_registrar(&app, |registrar| state.unbind(registrar, action))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    // Stands in for the OS: `taken` shortcuts belong to another app.
+    #[derive(Default)]
+    struct FakeRegistrar {
+        registered: Mutex<HashSet<Shortcut>>,
+        taken: HashSet<Shortcut>,
+    }
+
+    impl ShortcutRegistrar for FakeRegistrar {
+        fn register(&self, shortcut: Shortcut) -> Result<(), String> {
+            if self.taken.contains(&shortcut) {
+                return Err("already registered".to_string());
+            }
+            self.registered.lock().unwrap().insert(shortcut);
+            Ok(())
+        }
+
+        fn unregister(&self, shortcut: Shortcut) -> Result<(), String> {
+            self.registered.lock().unwrap().remove(&shortcut);
+            Ok(())
+        }
+    }
+
+    fn temp_config_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("solostack-shortcuts-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn binding(
+        bindings: &[GlobalShortcutBinding],
+        action: GlobalShortcutAction,
+    ) -> &GlobalShortcutBinding {
+        bindings
+            .iter()
+            .find(|binding| binding.action == action)
+            .unwrap()
+    }
+
+    #[test]
+    fn persists_rebinds_and_reapplies_them() {
+        let config_dir = temp_config_dir();
+        let registrar = FakeRegistrar::default();
+        let state = GlobalShortcutState::load(Some(&config_dir));
+        state.register_all(&registrar);
+        assert_eq!(registrar.registered.lock().unwrap().len(), 1);
+
+        state
+            .bind(
+                &registrar,
+                GlobalShortcutAction::OpenToday,
+                "alt+shift+t",
+                false,
+            )
+            .unwrap();
+        state
+            .bind(
+                &registrar,
+                GlobalShortcutAction::QuickCapture,
+                "alt+shift+q",
+                true,
+            )
+            .unwrap();
+        state
+            .unbind(&registrar, GlobalShortcutAction::StartFocusSession)
+            .unwrap();
+        let quick_capture = Shortcut::from_str("alt+shift+q").unwrap();
+        assert_eq!(
+            state.action_for(&quick_capture),
+            Some(GlobalShortcutAction::QuickCapture)
+        );
+        assert_eq!(registrar.registered.lock().unwrap().len(), 2);
+
+        let reloaded = GlobalShortcutState::load(Some(&config_dir));
+        let after_restart = FakeRegistrar::default();
+        reloaded.register_all(&after_restart);
+        let bindings = reloaded.list().unwrap();
+        assert_eq!(
+            binding(&bindings, GlobalShortcutAction::QuickCapture)
+                .shortcut
+                .as_deref(),
+            Some("shift+alt+KeyQ")
+        );
+        assert!(binding(&bindings, GlobalShortcutAction::OpenToday).registered);
+        assert!(binding(&bindings, GlobalShortcutAction::StartFocusSession)
+            .shortcut
+            .is_none());
+        assert!(after_restart
+            .registered
+            .lock()
+            .unwrap()
+            .contains(&quick_capture));
+        fs::remove_dir_all(config_dir).unwrap();
+    }
+
+    #[test]
+    fn reports_conflicts_as_structured_errors() {
+        let config_dir = temp_config_dir();
+        let taken = Shortcut::from_str("ctrl+alt+f").unwrap();
+        let registrar = FakeRegistrar {
+            taken: HashSet::from([taken]),
+            ..FakeRegistrar::default()
+        };
+        let state = GlobalShortcutState::load(Some(&config_dir));
+        state.register_all(&registrar);
+        let quick_capture_default = GlobalShortcutAction::QuickCapture
+            .default_shortcut()
+            .unwrap();
+
+        let code =
+            |result: Result<Vec<GlobalShortcutBinding>, SoloStackError>| result.err().unwrap().code;
+        assert_eq!(
+            code(state.bind(
+                &registrar,
+                GlobalShortcutAction::OpenToday,
+                quick_capture_default,
+                false
+            )),
+            SoloStackErrorCode::ShortcutConflict
+        );
+        assert_eq!(
+            code(state.bind(
+                &registrar,
+                GlobalShortcutAction::QuickCapture,
+                "alt+shift+q",
+                false
+            )),
+            SoloStackErrorCode::ShortcutActionBound
+        );
+        assert_eq!(
+            code(state.bind(
+                &registrar,
+                GlobalShortcutAction::QuickCapture,
+                "ctrl+alt+f",
+                true
+            )),
+            SoloStackErrorCode::ShortcutUnavailable
+        );
+        assert_eq!(
+            code(state.bind(
+                &registrar,
+                GlobalShortcutAction::StartFocusSession,
+                "ctrl+nope",
+                false
+            )),
+            SoloStackErrorCode::ShortcutInvalid
+        );
+
+        // The failed rebind left the previous quick capture binding in place.
+        let bindings = state.list().unwrap();
+        assert!(binding(&bindings, GlobalShortcutAction::QuickCapture).registered);
+        assert!(!config_dir.exists());
+    }
+}
@@ -0,0 +1,479 @@
+// Removes the app data dirs left behind by historical bundle identifiers once the startup
+// migration has moved the database. Archiving keeps a zip of the old dir under the current
+// app data dir for users who want the stale copy around a little longer.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use tauri::Manager;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    db,
+    error::{SoloStackError, SoloStackErrorCode},
+    startup_migration,
+};
+
+const LEGACY_DATA_ARCHIVE_DIRNAME: &str = "legacy-data-archives";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum LegacyDataCleanupMode {
+    List,
+    Archive,
+    Delete,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct LegacyDataEntry {
+    relative_path: String,
+    size_bytes: u64,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct LegacyDataDirListing {
+    bundle_identifier: String,
+    dir_path: String,
+    entries: Vec<LegacyDataEntry>,
+    total_bytes: u64,
+    archive_path: Option<String>,
+    archive_size_bytes: Option<u64>,
+    removed: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct LegacyDataCleanupReport {
+    mode: LegacyDataCleanupMode,
+    legacy_dirs: Vec<LegacyDataDirListing>,
+    bytes_removed: u64,
+    bytes_archived: u64,
+    bytes_reclaimed: u64,
+}
+
+fn collect_legacy_data_entries(
+    root_dir: &Path,
+    current_dir: &Path,
+    entries: &mut Vec<LegacyDataEntry>,
+) -> Result<(), String> {
+    let dir_entries = fs::read_dir(current_dir).map_err(|error| {
+        format!(
+            "read legacy dir {} failed: {error}",
+            current_dir.to_string_lossy()
+        )
+    })?;
+    for dir_entry in dir_entries {
+        let dir_entry = dir_entry.map_err(|error| format!("read legacy entry failed: {error}"))?;
+        let path = dir_entry.path();
+        // Symlinks are listed but never followed, so cleanup cannot escape the legacy dir.
+        let metadata = fs::symlink_metadata(&path)
+            .map_err(|error| format!("stat {} failed: {error}", path.to_string_lossy()))?;
+        if metadata.is_dir() {
+            collect_legacy_data_entries(root_dir, &path, entries)?;
+            continue;
+        }
+        let relative_path = path
+            .strip_prefix(root_dir)
+            .unwrap_or(&path)
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        entries.push(LegacyDataEntry {
+            relative_path,
+            size_bytes: if metadata.is_file() {
+                metadata.len()
+            } else {
+                0
+            },
+        });
+    }
+    Ok(())
+}
+
+fn list_legacy_data_dir(
+    bundle_identifier: &str,
+    dir_path: &Path,
+) -> Result<LegacyDataDirListing, String> {
+    let mut entries = Vec::new();
+    collect_legacy_data_entries(dir_path, dir_path, &mut entries)?;
+    entries.sort_by(|left, right| left.relative_path.cmp(&right.relative_path));
+    let total_bytes = entries.iter().map(|entry| entry.size_bytes).sum();
+    Ok(LegacyDataDirListing {
+        bundle_identifier: bundle_identifier.to_string(),
+        dir_path: dir_path.to_string_lossy().to_string(),
+        entries,
+        total_bytes,
+        archive_path: None,
+        archive_size_bytes: None,
+        removed: false,
+    })
+}
+
+// Deleting the legacy dir destroys the only other copy of the user's data, so the marker
+// alone is not enough: the migrated database must still pass an integrity check right now.
+fn ensure_migration_verified(new_app_data_dir: &Path) -> Result<(), SoloStackError> {
+    let refused = |detail: String| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::LegacyCleanupRefused,
+            format!("legacy data cleanup refused: {detail}"),
+        )
+    };
+    let marker = startup_migration::read_completed_migration_marker(new_app_data_dir)
+        .map_err(refused)?
+        .ok_or_else(|| refused("migration marker is missing".to_string()))?;
+    match marker.integrity_check.as_deref() {
+        Some("ok") => {}
+        Some(integrity_check) => {
+            return Err(refused(format!(
+                "marker integrity check is {integrity_check}"
+            )));
+        }
+        // v1 markers predate the recorded check. They are accepted because the live check
+        // below is what actually gates the cleanup.
+        None => {}
+    }
+
+    let new_db_path = new_app_data_dir.join(startup_migration::DATABASE_FILENAME);
+    db::run_integrity_check(&new_db_path).map_err(refused)
+}
+
+// A failed archive is removed so `legacy-data-archives/` never holds a truncated zip that
+// looks like a usable copy.
+fn archive_legacy_data_dir(
+    listing: &LegacyDataDirListing,
+    archive_path: &Path,
+) -> Result<u64, String> {
+    write_legacy_data_archive(listing, archive_path).inspect_err(|_| {
+        let _ = fs::remove_file(archive_path);
+    })
+}
+
+fn write_legacy_data_archive(
+    listing: &LegacyDataDirListing,
+    archive_path: &Path,
+) -> Result<u64, String> {
+    let dir_path = PathBuf::from(&listing.dir_path);
+    let archive_file = fs::File::create(archive_path)
+        .map_err(|error| format!("create legacy data archive failed: {error}"))?;
+    let mut writer = ZipWriter::new(archive_file);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+
+    for entry in &listing.entries {
+        let entry_path = dir_path.join(&entry.relative_path);
+        let metadata = fs::symlink_metadata(&entry_path)
+            .map_err(|error| format!("stat {} failed: {error}", entry.relative_path))?;
+        if !metadata.is_file() {
+            continue;
+        }
+        writer
+            .start_file(entry.relative_path.as_str(), options)
+            .map_err(|error| format!("archive {} failed: {error}", entry.relative_path))?;
+        let mut source_file = fs::File::open(&entry_path)
+            .map_err(|error| format!("open {} failed: {error}", entry.relative_path))?;
+        io::copy(&mut source_file, &mut writer)
+            .map_err(|error| format!("archive {} failed: {error}", entry.relative_path))?;
+    }
+
+    let archive_file = writer
+        .finish()
+        .map_err(|error| format!("finish legacy data archive failed: {error}"))?;
+    archive_file
+        .sync_all()
+        .map_err(|error| format!("fsync legacy data archive failed: {error}"))?;
+    archive_file
+        .metadata()
+        .map(|metadata| metadata.len())
+        .map_err(|error| format!("stat legacy data archive failed: {error}"))
+}
+
+fn clean_up_legacy_data(
+    new_app_data_dir: &Path,
+    mode: LegacyDataCleanupMode,
+) -> Result<LegacyDataCleanupReport, SoloStackError> {
+    let failed = |detail: String| {
+        SoloStackError::with_detail(SoloStackErrorCode::LegacyCleanupFailed, detail)
+    };
+    let mut legacy_dirs = Vec::new();
+    for (bundle_identifier, dir_path) in startup_migration::legacy_app_data_dirs(new_app_data_dir) {
+        if !dir_path.is_dir() {
+            continue;
+        }
+        legacy_dirs.push(list_legacy_data_dir(bundle_identifier, &dir_path).map_err(failed)?);
+    }
+
+    let mut report = LegacyDataCleanupReport {
+        mode,
+        legacy_dirs,
+        bytes_removed: 0,
+        bytes_archived: 0,
+        bytes_reclaimed: 0,
+    };
+    if mode == LegacyDataCleanupMode::List || report.legacy_dirs.is_empty() {
+        return Ok(report);
+    }
+
+    ensure_migration_verified(new_app_data_dir)?;
+
+    let archive_dir = new_app_data_dir.join(LEGACY_DATA_ARCHIVE_DIRNAME);
+    let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    for listing in report.legacy_dirs.iter_mut() {
+        if mode == LegacyDataCleanupMode::Archive {
+            fs::create_dir_all(&archive_dir).map_err(|error| {
+                failed(format!("create legacy data archive dir failed: {error}"))
+            })?;
+            let archive_path =
+                archive_dir.join(format!("{}-{timestamp}.zip", listing.bundle_identifier));
+            let archive_size_bytes =
+                archive_legacy_data_dir(listing, &archive_path).map_err(failed)?;
+            listing.archive_path = Some(archive_path.to_string_lossy().to_string());
+            listing.archive_size_bytes = Some(archive_size_bytes);
+            report.bytes_archived += archive_size_bytes;
+        }
+
+        fs::remove_dir_all(&listing.dir_path).map_err(|error| {
+            failed(format!(
+                "remove legacy dir {} failed: {error}",
+                listing.dir_path
+            ))
+        })?;
+        listing.removed = true;
+        report.bytes_removed += listing.total_bytes;
+    }
+    report.bytes_reclaimed = report.bytes_removed.saturating_sub(report.bytes_archived);
+    Ok(report)
+}
+
+// Zipping and removing whole legacy dirs can take a while, so it runs off the async runtime.
+#[tauri::command]
+pub(crate) async fn clean_up_legacy_app_data(
+    app: tauri::AppHandle,
+    mode: LegacyDataCleanupMode,
+) -> Result<LegacyDataCleanupReport, SoloStackError> {
+    let new_app_data_dir = app.path().app_data_dir().map_err(|error| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::AppDataDirUnavailable,
+            format!("resolve app data dir failed: {error}"),
+        )
+    })?;
+    tauri::async_runtime::spawn_blocking(move || clean_up_legacy_data(&new_app_data_dir, mode))
+        .await
+        .map_err(|error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::Internal,
+                format!("legacy data cleanup task failed: {error}"),
+            )
+        })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    const LEGACY_DB_BYTES: usize = 64 * 1024;
+    const LEGACY_LOG: &str = "opened database\n";
+
+    struct CleanupLayout {
+        root_dir: PathBuf,
+        new_app_data_dir: PathBuf,
+        legacy_app_data_dir: PathBuf,
+    }
+
+    impl CleanupLayout {
+        fn new() -> Self {
+            let root_dir = std::env::temp_dir()
+                .join(format!("solostack-legacy-cleanup-{}", uuid::Uuid::new_v4()));
+            let new_app_data_dir = root_dir.join("com.solutionsstudio.solostack");
+            let (_, legacy_app_data_dir) =
+                startup_migration::legacy_app_data_dirs(&new_app_data_dir)
+                    .into_iter()
+                    .next()
+                    .unwrap();
+            fs::create_dir_all(&new_app_data_dir).unwrap();
+            fs::create_dir_all(legacy_app_data_dir.join("logs")).unwrap();
+            fs::write(
+                legacy_app_data_dir.join(startup_migration::DATABASE_FILENAME),
+                "a".repeat(LEGACY_DB_BYTES),
+            )
+            .unwrap();
+            fs::write(legacy_app_data_dir.join("logs/app.log"), LEGACY_LOG).unwrap();
+            rusqlite::Connection::open(new_app_data_dir.join(startup_migration::DATABASE_FILENAME))
+                .unwrap()
+                .execute_batch("CREATE TABLE tasks (id TEXT PRIMARY KEY);")
+                .unwrap();
+            Self {
+                root_dir,
+                new_app_data_dir,
+                legacy_app_data_dir,
+            }
+        }
+
+        fn write_marker(&self, file_name: &str, marker: serde_json::Value) {
+            fs::write(self.new_app_data_dir.join(file_name), marker.to_string()).unwrap();
+        }
+
+        fn write_verified_marker(&self) {
+            self.write_marker(
+                "startup-migration-v2.json",
+                serde_json::json!({ "version": 2, "integrity_check": "ok" }),
+            );
+        }
+
+        fn clean_up(
+            &self,
+            mode: LegacyDataCleanupMode,
+        ) -> Result<LegacyDataCleanupReport, SoloStackError> {
+            clean_up_legacy_data(&self.new_app_data_dir, mode)
+        }
+
+        fn assert_refused(&self, mode: LegacyDataCleanupMode) {
+            let error = self.clean_up(mode).err().unwrap();
+            assert_eq!(error.code, SoloStackErrorCode::LegacyCleanupRefused);
+            assert!(self.legacy_app_data_dir.is_dir());
+        }
+    }
+
+    impl Drop for CleanupLayout {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root_dir);
+        }
+    }
+
+    #[test]
+    fn refuses_without_a_migration_marker() {
+        let layout = CleanupLayout::new();
+        layout.assert_refused(LegacyDataCleanupMode::Delete);
+        layout.assert_refused(LegacyDataCleanupMode::Archive);
+        assert!(!layout
+            .new_app_data_dir
+            .join(LEGACY_DATA_ARCHIVE_DIRNAME)
+            .exists());
+    }
+
+    #[test]
+    fn refuses_when_the_marker_recorded_a_failed_integrity_check() {
+        let layout = CleanupLayout::new();
+        layout.write_marker(
+            "startup-migration-v2.json",
+            serde_json::json!({ "version": 2, "integrity_check": "malformed" }),
+        );
+        layout.assert_refused(LegacyDataCleanupMode::Delete);
+    }
+
+    #[test]
+    fn refuses_when_the_migrated_database_is_corrupt() {
+        let layout = CleanupLayout::new();
+        layout.write_verified_marker();
+        fs::write(
+            layout
+                .new_app_data_dir
+                .join(startup_migration::DATABASE_FILENAME),
+            "not a sqlite database",
+        )
+        .unwrap();
+        layout.assert_refused(LegacyDataCleanupMode::Delete);
+    }
+
+    #[test]
+    fn accepts_a_v1_marker_without_a_recorded_integrity_check() {
+        let layout = CleanupLayout::new();
+        layout.write_marker(
+            "startup-migration-v1.json",
+            serde_json::json!({ "source_db_path": "solostack.db" }),
+        );
+        let report = layout.clean_up(LegacyDataCleanupMode::Delete).unwrap();
+        assert!(report.legacy_dirs[0].removed);
+        assert!(!layout.legacy_app_data_dir.exists());
+    }
+
+    #[test]
+    fn list_leaves_the_legacy_dir_alone() {
+        let layout = CleanupLayout::new();
+        let report = layout.clean_up(LegacyDataCleanupMode::List).unwrap();
+        let listing = &report.legacy_dirs[0];
+        let paths = listing
+            .entries
+            .iter()
+            .map(|entry| entry.relative_path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec!["logs/app.log", "solostack.db"]);
+        assert_eq!(
+            listing.total_bytes,
+            (LEGACY_DB_BYTES + LEGACY_LOG.len()) as u64
+        );
+        assert_eq!(report.bytes_removed, 0);
+        assert!(layout.legacy_app_data_dir.is_dir());
+    }
+
+    #[test]
+    fn delete_reclaims_every_removed_byte() {
+        let layout = CleanupLayout::new();
+        layout.write_verified_marker();
+        let report = layout.clean_up(LegacyDataCleanupMode::Delete).unwrap();
+        assert_eq!(
+            report.bytes_removed,
+            (LEGACY_DB_BYTES + LEGACY_LOG.len()) as u64
+        );
+        assert_eq!(report.bytes_archived, 0);
+        assert_eq!(report.bytes_reclaimed, report.bytes_removed);
+        assert!(!layout.legacy_app_data_dir.exists());
+    }
+
+    #[test]
+    fn archive_zips_the_legacy_dir_before_removing_it() {
+        let layout = CleanupLayout::new();
+        layout.write_verified_marker();
+        let report = layout.clean_up(LegacyDataCleanupMode::Archive).unwrap();
+        let listing = &report.legacy_dirs[0];
+        assert!(listing.removed);
+        assert!(!layout.legacy_app_data_dir.exists());
+
+        let archive_path = PathBuf::from(listing.archive_path.as_deref().unwrap());
+        let archive_size_bytes = fs::metadata(&archive_path).unwrap().len();
+        assert_eq!(listing.archive_size_bytes, Some(archive_size_bytes));
+        assert_eq!(
+            report.bytes_removed,
+            (LEGACY_DB_BYTES + LEGACY_LOG.len()) as u64
+        );
+        assert_eq!(report.bytes_archived, archive_size_bytes);
+        assert_eq!(
+            report.bytes_reclaimed,
+            report.bytes_removed - report.bytes_archived
+        );
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&archive_path).unwrap()).unwrap();
+        let mut contents = Vec::new();
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index).unwrap();
+            let mut text = String::new();
+            file.read_to_string(&mut text).unwrap();
+            contents.push((file.name().to_string(), text));
+        }
+        assert_eq!(
+            contents,
+            vec![
+                ("logs/app.log".to_string(), LEGACY_LOG.to_string()),
+                ("solostack.db".to_string(), "a".repeat(LEGACY_DB_BYTES)),
+            ]
+        );
+    }
+
+    #[test]
+    fn failed_archive_leaves_no_partial_zip() {
+        let layout = CleanupLayout::new();
+        let listing = list_legacy_data_dir("legacy", &layout.legacy_app_data_dir).unwrap();
+        fs::remove_file(
+            layout
+                .legacy_app_data_dir
+                .join(startup_migration::DATABASE_FILENAME),
+        )
+        .unwrap();
+        let archive_path = layout.root_dir.join("legacy.zip");
+        assert!(archive_legacy_data_dir(&listing, &archive_path).is_err());
+        assert!(!archive_path.exists());
+    }
+}
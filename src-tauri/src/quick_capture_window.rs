@@ -0,0 +1,154 @@
+// Spotlight-style capture window for the quick capture shortcut. The window is created on first
+// use, hidden (not closed) when it is dismissed or loses focus, and writes tasks through the Rust
+// repository so the main window never has to come to the front.
+
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::{
+    db::DatabaseState,
+    error::SoloStackError,
+    repository::{self, CreateTaskInput, Task, TaskPriority},
+};
+
+pub(crate) const QUICK_CAPTURE_WINDOW_LABEL: &str = "quick-capture";
+pub(crate) const QUICK_CAPTURE_TASK_CREATED_EVENT: &str = "quick-capture:task-created";
+const QUICK_CAPTURE_WINDOW_WIDTH: f64 = 560.0;
+const QUICK_CAPTURE_WINDOW_HEIGHT: f64 = 168.0;
+
+fn build_window(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    // The frontend picks the capture view from the window label.
+    let window = WebviewWindowBuilder::new(
+        app,
+        QUICK_CAPTURE_WINDOW_LABEL,
+        WebviewUrl::App("index.html".into()),
+    )
+    .title("Quick Capture")
+    .inner_size(QUICK_CAPTURE_WINDOW_WIDTH, QUICK_CAPTURE_WINDOW_HEIGHT)
+    .resizable(false)
+    .maximizable(false)
+    .minimizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible(false)
+    .center()
+    .build()?;
+
+    let hide_target = window.clone();
+    window.on_window_event(move |event| match event {
+        tauri::WindowEvent::Focused(false) => {
+            let _ = hide_target.hide();
+        }
+        // Closing from the OS (e.g. Alt+F4) would otherwise pay the webview start-up cost again
+        // on the next shortcut press.
+        tauri::WindowEvent::CloseRequested { api, .. } => {
+            api.prevent_close();
+            let _ = hide_target.hide();
+        }
+        _ => {}
+    });
+    Ok(window)
+}
+
+pub(crate) fn show(app: &AppHandle) -> tauri::Result<()> {
+    let window = match app.get_webview_window(QUICK_CAPTURE_WINDOW_LABEL) {
+        Some(window) => window,
+        None => build_window(app)?,
+    };
+    window.center()?;
+    window.show()?;
+    window.set_focus()
+}
+
+fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(QUICK_CAPTURE_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+// Captured tasks only carry a title; everything else is left at the defaults of a new task.
+fn insert_quick_capture_task(state: &DatabaseState, title: String) -> Result<Task, SoloStackError> {
+    repository::with_write_transaction(state, |transaction| {
+        repository::create_task_record(
+            transaction,
+            CreateTaskInput {
+                title,
+                description: None,
+                notes_markdown: None,
+                project_id: None,
+                priority: TaskPriority::Normal,
+                is_important: false,
+                due_at: None,
+                remind_at: None,
+                recurrence: None,
+                subtasks: Vec::new(),
+            },
+        )
+    })
+}
+
+#[tauri::command]
+pub(crate) fn create_quick_capture_task(
+    app: AppHandle,
+    state: tauri::State<DatabaseState>,
+    title: String,
+) -> Result<Task, SoloStackError> {
+    let task = insert_quick_capture_task(&state, title)?;
+    hide(&app);
+    crate::tray::refresh(&app);
+    // The main window refreshes its task queries from this; it stays where it was.
+    if let Err(error) = app.emit(QUICK_CAPTURE_TASK_CREATED_EVENT, &task) {
+        eprintln!("Quick capture warning: {error}");
+    }
+    Ok(task)
+}
+
+#[tauri::command]
+pub(crate) fn dismiss_quick_capture_window(app: AppHandle) {
+    hide(&app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{count_rows, TestDatabase},
+        error::SoloStackErrorCode,
+    };
+
+    #[test]
+    fn captured_task_is_written_with_its_changelog_and_outbox_entry() {
+        let database = TestDatabase::migrated();
+        let task = insert_quick_capture_task(&database, "  Call the bank  ".to_string()).unwrap();
+        assert_eq!(task.title, "Call the bank");
+        assert_eq!(task.priority, TaskPriority::Normal);
+        assert_eq!(task.is_important, 0);
+        assert_eq!(task.project_id, None);
+        assert_eq!(
+            count_rows(
+                &database,
+                "SELECT COUNT(*) FROM task_changelogs WHERE task_id = ?1",
+                [&task.id],
+            ),
+            1
+        );
+        assert_eq!(
+            count_rows(
+                &database,
+                "SELECT COUNT(*) FROM sync_outbox WHERE entity_id = ?1",
+                [&task.id],
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn blank_title_writes_nothing() {
+        let database = TestDatabase::migrated();
+        let error = insert_quick_capture_task(&database, "   ".to_string())
+            .err()
+            .unwrap();
+        assert_eq!(error.code, SoloStackErrorCode::DbTaskTitleRequired);
+        assert_eq!(count_rows(&database, "SELECT COUNT(*) FROM tasks", []), 0);
+    }
+}
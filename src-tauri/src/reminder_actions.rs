@@ -0,0 +1,285 @@
+// Snooze and complete actions on native reminder notifications. Actions write through the Rust
+// repository, so the `tasks` update, its `task_changelogs` rows and the `sync_outbox` change land
+// whether or not the webview is loaded; the main window catches up from
+// `reminders:action-applied`.
+// Only the freedesktop notification server reports which action was picked, so the buttons are
+// offered on Linux. Other desktops keep the plain notification from the plugin.
+
+use chrono::{DateTime, Days, SecondsFormat, TimeZone, Utc};
+use rusqlite::Transaction;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    db::DatabaseState,
+    error::{SoloStackError, SoloStackErrorCode},
+    reminder_scheduler::ReminderSchedulerState,
+    repository::{self, Task, TaskStatus, UpdateTaskInput},
+    tray,
+};
+
+pub(crate) const REMINDER_ACTION_APPLIED_EVENT: &str = "reminders:action-applied";
+const SNOOZE_SHORT_MINUTES: i64 = 10;
+// Local wall-clock hour for "Snooze until tomorrow", same as the webview preset.
+const SNOOZE_TOMORROW_HOUR: u32 = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ReminderAction {
+    Snooze10Minutes,
+    SnoozeUntilTomorrow,
+    MarkDone,
+}
+
+impl ReminderAction {
+    pub(crate) const ALL: [Self; 3] = [
+        Self::Snooze10Minutes,
+        Self::SnoozeUntilTomorrow,
+        Self::MarkDone,
+    ];
+
+    pub(crate) fn id(self) -> &'static str {
+        match self {
+            Self::Snooze10Minutes => "snooze_10m",
+            Self::SnoozeUntilTomorrow => "snooze_tomorrow",
+            Self::MarkDone => "mark_done",
+        }
+    }
+
+    pub(crate) fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+}
+
+// `None` for actions that do not move the reminder.
+fn snoozed_until<Tz: TimeZone>(
+    action: ReminderAction,
+    now: &DateTime<Tz>,
+) -> Option<DateTime<Utc>> {
+    match action {
+        ReminderAction::Snooze10Minutes => {
+            Some(now.with_timezone(&Utc) + chrono::Duration::minutes(SNOOZE_SHORT_MINUTES))
+        }
+        ReminderAction::SnoozeUntilTomorrow => {
+            let tomorrow = now.date_naive() + Days::new(1);
+            let target = tomorrow.and_hms_opt(SNOOZE_TOMORROW_HOUR, 0, 0)?;
+            // A DST gap at 09:00 falls back to the same time tomorrow.
+            Some(
+                now.timezone()
+                    .from_local_datetime(&target)
+                    .earliest()
+                    .map(|target| target.with_timezone(&Utc))
+                    .unwrap_or_else(|| now.with_timezone(&Utc) + chrono::Duration::days(1)),
+            )
+        }
+        ReminderAction::MarkDone => None,
+    }
+}
+
+// Goes through `update_task_record`, so completing a recurring task also creates its next
+// occurrence. A notification can outlive its task being completed elsewhere; acting on it then
+// leaves the task as it is.
+pub(crate) fn apply_reminder_action_record<Tz: TimeZone>(
+    transaction: &Transaction<'_>,
+    task_id: &str,
+    action: ReminderAction,
+    now: &DateTime<Tz>,
+) -> Result<Task, SoloStackError> {
+    let task = repository::find_task(transaction, task_id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbTaskNotFound))?;
+    if !matches!(task.status, TaskStatus::Todo | TaskStatus::Doing) {
+        return Ok(task);
+    }
+    let input = match snoozed_until(action, now) {
+        Some(remind_at) => UpdateTaskInput {
+            id: task_id.to_string(),
+            remind_at: Some(Some(remind_at.to_rfc3339_opts(SecondsFormat::Millis, true))),
+            ..UpdateTaskInput::default()
+        },
+        None => UpdateTaskInput {
+            id: task_id.to_string(),
+            status: Some(TaskStatus::Done),
+            ..UpdateTaskInput::default()
+        },
+    };
+    repository::update_task_record(transaction, input)
+}
+
+pub(crate) fn apply(app: &AppHandle, task_id: &str, action: ReminderAction) {
+    let state = app.state::<DatabaseState>();
+    let result = repository::with_write_transaction(&state, |transaction| {
+        apply_reminder_action_record(transaction, task_id, action, &chrono::Local::now())
+    });
+    let task = match result {
+        Ok(task) => task,
+        Err(error) => {
+            eprintln!("Reminder action warning: {error}");
+            return;
+        }
+    };
+    tray::refresh(app);
+    if let Some(scheduler) = app.try_state::<ReminderSchedulerState>() {
+        scheduler.request_resync();
+    }
+    if let Err(error) = app.emit(REMINDER_ACTION_APPLIED_EVENT, &task) {
+        eprintln!("Reminder action warning: {error}");
+    }
+}
+
+// Blocks a short-lived thread per notification until it is acted on or dismissed. Returns the
+// error so the caller can fall back to the plugin when no notification server is reachable.
+pub(crate) fn show_actionable(
+    app: &AppHandle,
+    task_id: String,
+    title: &str,
+    body: &str,
+    action_labels: &[(ReminderAction, &str)],
+) -> Result<(), notify_rust::error::Error> {
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .appname(&app.package_info().name)
+        .summary(title)
+        .body(body)
+        .auto_icon()
+        // Clicking the notification body.
+        .action("default", "");
+    for (action, label) in action_labels {
+        notification.action(action.id(), label);
+    }
+    let handle = notification.show()?;
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        handle.wait_for_action(|action_id| match ReminderAction::from_id(action_id) {
+            Some(action) => apply(&app, &task_id, action),
+            None if action_id == "default" => crate::show_main_window(&app),
+            None => {}
+        });
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    use crate::{
+        db::{count_rows, TestDatabase},
+        repository::CreateTaskInput,
+        repository::TaskPriority,
+    };
+
+    fn at(value: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(value).unwrap()
+    }
+
+    fn create_reminded_task(state: &DatabaseState) -> Task {
+        repository::with_write_transaction(state, |transaction| {
+            repository::create_task_record(
+                transaction,
+                CreateTaskInput {
+                    title: "Call the bank".to_string(),
+                    description: None,
+                    notes_markdown: None,
+                    project_id: None,
+                    priority: TaskPriority::Normal,
+                    is_important: false,
+                    due_at: None,
+                    remind_at: Some("2026-03-10T02:00:00.000Z".to_string()),
+                    recurrence: None,
+                    subtasks: Vec::new(),
+                },
+            )
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn snooze_targets_follow_the_local_clock() {
+        let now = at("2026-03-10T23:30:00+07:00");
+        assert_eq!(
+            snoozed_until(ReminderAction::Snooze10Minutes, &now),
+            Some(at("2026-03-10T16:40:00Z").with_timezone(&Utc))
+        );
+        assert_eq!(
+            snoozed_until(ReminderAction::SnoozeUntilTomorrow, &now),
+            Some(at("2026-03-11T09:00:00+07:00").with_timezone(&Utc))
+        );
+        assert_eq!(snoozed_until(ReminderAction::MarkDone, &now), None);
+        assert_eq!(
+            ReminderAction::from_id("snooze_tomorrow"),
+            Some(ReminderAction::SnoozeUntilTomorrow)
+        );
+    }
+
+    #[test]
+    fn actions_write_the_task_changelog_and_sync_outbox() {
+        let state = TestDatabase::migrated();
+        let task = create_reminded_task(&state);
+        let outbox_before = count_rows(
+            &state,
+            "SELECT COUNT(*) FROM sync_outbox WHERE entity_id = ?1",
+            [&task.id],
+        );
+
+        let snoozed = repository::with_write_transaction(&state, |transaction| {
+            apply_reminder_action_record(
+                transaction,
+                &task.id,
+                ReminderAction::Snooze10Minutes,
+                &at("2026-03-10T09:00:00+07:00"),
+            )
+        })
+        .unwrap();
+        assert_eq!(
+            snoozed.remind_at.as_deref(),
+            Some("2026-03-10T02:10:00.000Z")
+        );
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM task_changelogs WHERE task_id = ?1 AND field_name = 'remind_at'",
+                [&task.id],
+            ),
+            1
+        );
+
+        let done = repository::with_write_transaction(&state, |transaction| {
+            apply_reminder_action_record(
+                transaction,
+                &task.id,
+                ReminderAction::MarkDone,
+                &at("2026-03-10T09:05:00+07:00"),
+            )
+        })
+        .unwrap();
+        assert_eq!(done.status, TaskStatus::Done);
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM task_changelogs WHERE task_id = ?1 AND field_name = 'status'",
+                [&task.id],
+            ),
+            1
+        );
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM sync_outbox WHERE entity_id = ?1",
+                [&task.id],
+            ),
+            outbox_before + 2
+        );
+
+        // A stale notification on a finished task changes nothing.
+        let unchanged = repository::with_write_transaction(&state, |transaction| {
+            apply_reminder_action_record(
+                transaction,
+                &task.id,
+                ReminderAction::SnoozeUntilTomorrow,
+                &at("2026-03-10T09:10:00+07:00"),
+            )
+        })
+        .unwrap();
+        assert_eq!(unchanged.remind_at, done.remind_at);
+    }
+}
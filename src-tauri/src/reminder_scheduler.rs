@@ -0,0 +1,600 @@
+// Native reminder scheduler. A background thread reads `remind_at` from open tasks, sleeps until
+// the next one is due and sends it through the notification plugin, so reminders keep firing while
+// the webview is hidden, throttled or closed to the tray. Fired reminders are keyed by
+// `<task_id>:<remind_at>`, so moving a reminder re-arms it.
+// The ledger lives in `reminder-scheduler.json` in the app data dir together with the enabled flag
+// and locale pushed from the webview settings.
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Condvar, Mutex, MutexGuard},
+    time::Duration,
+};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+#[cfg(target_os = "linux")]
+use crate::reminder_actions::{self, ReminderAction};
+use crate::{
+    db::DatabaseState,
+    error::{SoloStackError, SoloStackErrorCode},
+    repository, tray,
+};
+
+const REMINDER_STATE_FILENAME: &str = "reminder-scheduler.json";
+const REMINDER_STATE_VERSION: u8 = 1;
+// Upper bound on one sleep. Monotonic waits stop while the machine sleeps, and the webview writes
+// tasks through the SQL plugin, so the thread also wakes on its own to catch both.
+const MAX_SCHEDULER_SLEEP: Duration = Duration::from_secs(60);
+// Reminders missed by more than this (a long sleep, the app not running) are recorded without a
+// notification; a stack of day-old alerts is noise.
+const MISSED_REMINDER_CATCH_UP_HOURS: i64 = 24;
+// After a wake-up with many missed reminders, the rest are folded into one summary notification.
+const MAX_NOTIFICATIONS_PER_PASS: usize = 5;
+const FIRED_RETENTION_DAYS: i64 = 14;
+const FIRED_MAX_ENTRIES: usize = 500;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ScheduledReminder {
+    task_id: String,
+    title: String,
+    remind_at: DateTime<Utc>,
+    due_at: Option<DateTime<Utc>>,
+}
+
+impl ScheduledReminder {
+    fn signature(&self) -> String {
+        format!("{}:{}", self.task_id, format_iso(self.remind_at))
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ReminderPlan {
+    notify: Vec<ScheduledReminder>,
+    // Due in this pass but recorded without a notification (muted, stale or before `armed_at`).
+    silenced: usize,
+    next_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ReminderLedger {
+    version: u8,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default)]
+    locale: Option<String>,
+    // Reminders due before the scheduler first ran were already handled by the webview.
+    armed_at: String,
+    // Signature -> when it fired, both as ISO timestamps.
+    #[serde(default)]
+    fired: BTreeMap<String, String>,
+}
+
+fn format_iso(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ReminderLedger {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            version: REMINDER_STATE_VERSION,
+            enabled: true,
+            locale: None,
+            armed_at: format_iso(now),
+            fired: BTreeMap::new(),
+        }
+    }
+
+    // Keeps every signature still attached to a task plus anything recent, newest first up to
+    // the cap, so a reminder never fires twice while its task still carries it.
+    fn trim(&mut self, active_signatures: &HashSet<String>, now: DateTime<Utc>) {
+        let cutoff = now - chrono::Duration::days(FIRED_RETENTION_DAYS);
+        let mut kept = std::mem::take(&mut self.fired)
+            .into_iter()
+            .filter_map(|(signature, fired_at)| {
+                let fired_at = repository::parse_iso_datetime(&fired_at)?;
+                (active_signatures.contains(&signature) || fired_at >= cutoff)
+                    .then_some((signature, fired_at))
+            })
+            .collect::<Vec<_>>();
+        kept.sort_by_key(|(_, fired_at)| std::cmp::Reverse(*fired_at));
+        kept.truncate(FIRED_MAX_ENTRIES);
+        self.fired = kept
+            .into_iter()
+            .map(|(signature, fired_at)| (signature, format_iso(fired_at)))
+            .collect();
+    }
+}
+
+fn plan_reminders(
+    ledger: &mut ReminderLedger,
+    reminders: Vec<ScheduledReminder>,
+    now: DateTime<Utc>,
+    muted: bool,
+) -> ReminderPlan {
+    let catch_up_from = now - chrono::Duration::hours(MISSED_REMINDER_CATCH_UP_HOURS);
+    let armed_at =
+        repository::parse_iso_datetime(&ledger.armed_at).unwrap_or(DateTime::<Utc>::MIN_UTC);
+    let mut plan = ReminderPlan::default();
+    let mut active_signatures = HashSet::new();
+    for reminder in reminders {
+        let signature = reminder.signature();
+        active_signatures.insert(signature.clone());
+        if ledger.fired.contains_key(&signature) {
+            continue;
+        }
+        if reminder.remind_at > now {
+            plan.next_at = Some(match plan.next_at {
+                Some(next_at) => next_at.min(reminder.remind_at),
+                None => reminder.remind_at,
+            });
+            continue;
+        }
+        ledger.fired.insert(signature, format_iso(now));
+        if muted || reminder.remind_at < armed_at || reminder.remind_at < catch_up_from {
+            plan.silenced += 1;
+        } else {
+            plan.notify.push(reminder);
+        }
+    }
+    plan.notify.sort_by_key(|reminder| reminder.remind_at);
+    ledger.trim(&active_signatures, now);
+    plan
+}
+
+// Same eligibility as the webview: open tasks with a parseable `remind_at`.
+fn load_scheduled_reminders(connection: &Connection) -> rusqlite::Result<Vec<ScheduledReminder>> {
+    let mut statement = connection.prepare(
+        "SELECT id, title, remind_at, due_at FROM tasks
+         WHERE status IN ('TODO', 'DOING') AND remind_at IS NOT NULL",
+    )?;
+    let rows = statement.query_map(params![], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    })?;
+    let mut reminders = Vec::new();
+    for row in rows {
+        let (task_id, title, remind_at, due_at) = row?;
+        let Some(remind_at) = repository::parse_iso_datetime(&remind_at) else {
+            continue;
+        };
+        reminders.push(ScheduledReminder {
+            task_id,
+            title,
+            remind_at,
+            due_at: due_at.as_deref().and_then(repository::parse_iso_datetime),
+        });
+    }
+    Ok(reminders)
+}
+
+struct ReminderLabels {
+    title: &'static str,
+    due_at: &'static str,
+    due_at_format: &'static str,
+    more: &'static str,
+    snooze_10m: &'static str,
+    snooze_tomorrow: &'static str,
+    mark_done: &'static str,
+}
+
+const REMINDER_LABELS_EN: ReminderLabels = ReminderLabels {
+    title: "SoloStack Reminder",
+    due_at: "Due",
+    due_at_format: "%b %-d, %-I:%M %p",
+    more: "more reminders while you were away",
+    snooze_10m: "Snooze 10 min",
+    snooze_tomorrow: "Tomorrow 9:00",
+    mark_done: "Mark done",
+};
+
+const REMINDER_LABELS_TH: ReminderLabels = ReminderLabels {
+    title: "การแจ้งเตือน SoloStack",
+    due_at: "ครบกำหนด",
+    due_at_format: "%-d/%-m %H:%M",
+    more: "การแจ้งเตือนที่พลาดไประหว่างที่ไม่ได้ใช้งาน",
+    snooze_10m: "เลื่อน 10 นาที",
+    snooze_tomorrow: "พรุ่งนี้ 9:00",
+    mark_done: "ทำเสร็จแล้ว",
+};
+
+fn labels_for_locale(locale: Option<&str>) -> &'static ReminderLabels {
+    match locale {
+        Some("th") => &REMINDER_LABELS_TH,
+        _ => &REMINDER_LABELS_EN,
+    }
+}
+
+fn reminder_body(reminder: &ScheduledReminder, labels: &ReminderLabels) -> String {
+    match reminder.due_at {
+        Some(due_at) => format!(
+            "{}\n{} {}",
+            reminder.title,
+            labels.due_at,
+            due_at.with_timezone(&Local).format(labels.due_at_format)
+        ),
+        None => reminder.title.clone(),
+    }
+}
+
+fn state_write_failed(detail: String) -> SoloStackError {
+    SoloStackError::with_detail(SoloStackErrorCode::ReminderStateWriteFailed, detail)
+}
+
+fn read_ledger(state_path: &Path, now: DateTime<Utc>) -> ReminderLedger {
+    match fs::read_to_string(state_path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|error| {
+            eprintln!("Reminder scheduler warning: ignoring unreadable state: {error}");
+            ReminderLedger::new(now)
+        }),
+        Err(_) => ReminderLedger::new(now),
+    }
+}
+
+struct SchedulerInner {
+    ledger: ReminderLedger,
+    resync_requested: bool,
+}
+
+pub(crate) struct ReminderSchedulerState {
+    state_path: Option<PathBuf>,
+    inner: Mutex<SchedulerInner>,
+    resync: Condvar,
+}
+
+impl ReminderSchedulerState {
+    pub(crate) fn load(app_data_dir: Option<&Path>) -> Self {
+        let state_path = app_data_dir.map(|dir| dir.join(REMINDER_STATE_FILENAME));
+        let now = Utc::now();
+        let ledger = match state_path.as_deref() {
+            Some(state_path) => read_ledger(state_path, now),
+            None => ReminderLedger::new(now),
+        };
+        Self {
+            state_path,
+            inner: Mutex::new(SchedulerInner {
+                ledger,
+                resync_requested: false,
+            }),
+            resync: Condvar::new(),
+        }
+    }
+
+    fn lock_inner(&self) -> Result<MutexGuard<'_, SchedulerInner>, SoloStackError> {
+        self.inner.lock().map_err(|_| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::Internal,
+                "reminder scheduler lock is poisoned",
+            )
+        })
+    }
+
+    fn save(&self, ledger: &ReminderLedger) -> Result<(), SoloStackError> {
+        let Some(state_path) = self.state_path.as_ref() else {
+            return Err(SoloStackError::with_detail(
+                SoloStackErrorCode::AppDataDirUnavailable,
+                "reminder scheduler state needs the app data dir",
+            ));
+        };
+        let contents = serde_json::to_string_pretty(ledger).map_err(|error| {
+            state_write_failed(format!("serialize reminder state failed: {error}"))
+        })?;
+        if let Some(parent_dir) = state_path.parent() {
+            fs::create_dir_all(parent_dir).map_err(|error| {
+                state_write_failed(format!("create app data dir failed: {error}"))
+            })?;
+        }
+        let temp_path = state_path.with_extension("json.tmp");
+        fs::File::create(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(contents.as_bytes())?;
+                file.sync_all()
+            })
+            .map_err(|error| state_write_failed(format!("write reminder state failed: {error}")))?;
+        fs::rename(&temp_path, state_path)
+            .map_err(|error| state_write_failed(format!("replace reminder state failed: {error}")))
+    }
+
+    fn configure(&self, enabled: bool, locale: Option<String>) -> Result<(), SoloStackError> {
+        let mut inner = self.lock_inner()?;
+        inner.ledger.enabled = enabled;
+        inner.ledger.locale = locale;
+        let result = self.save(&inner.ledger);
+        inner.resync_requested = true;
+        self.resync.notify_all();
+        result
+    }
+
+    pub(crate) fn request_resync(&self) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.resync_requested = true;
+            self.resync.notify_all();
+        }
+    }
+
+    fn wait_for_resync(&self, timeout: Duration) {
+        let Ok(inner) = self.inner.lock() else {
+            std::thread::sleep(timeout);
+            return;
+        };
+        if let Ok((mut inner, _)) = self
+            .resync
+            .wait_timeout_while(inner, timeout, |inner| !inner.resync_requested)
+        {
+            inner.resync_requested = false;
+        }
+    }
+
+    // One scheduling pass: fire what is due, persist the ledger and return when to look again.
+    fn run_pass(&self, app: &AppHandle) -> Option<DateTime<Utc>> {
+        // Before startup migration finishes there is nothing to schedule from.
+        let connection = app.state::<DatabaseState>().open().ok()?;
+        let reminders = match load_scheduled_reminders(&connection) {
+            Ok(reminders) => reminders,
+            Err(error) => {
+                eprintln!("Reminder scheduler warning: {error}");
+                return None;
+            }
+        };
+        drop(connection);
+
+        let paused = app
+            .try_state::<tray::TrayState>()
+            .is_some_and(|state| state.settings().reminders_paused);
+        let mut inner = self.lock_inner().ok()?;
+        let fired_before = inner.ledger.fired.clone();
+        let muted = paused || !inner.ledger.enabled;
+        let plan = plan_reminders(&mut inner.ledger, reminders, Utc::now(), muted);
+        if inner.ledger.fired != fired_before {
+            if let Err(error) = self.save(&inner.ledger) {
+                eprintln!("Reminder scheduler warning: {error}");
+            }
+        }
+        let labels = labels_for_locale(inner.ledger.locale.as_deref());
+        drop(inner);
+
+        for reminder in plan.notify.iter().take(MAX_NOTIFICATIONS_PER_PASS) {
+            send_reminder_notification(app, reminder, labels);
+        }
+        let folded = plan.notify.len().saturating_sub(MAX_NOTIFICATIONS_PER_PASS);
+        if folded > 0 {
+            send_notification(app, labels.title, &format!("+{folded} {}", labels.more));
+        }
+        plan.next_at
+    }
+}
+
+fn send_notification(app: &AppHandle, title: &str, body: &str) {
+    if let Err(error) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("Reminder notification warning: {error}");
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_reminder_notification(
+    app: &AppHandle,
+    reminder: &ScheduledReminder,
+    labels: &ReminderLabels,
+) {
+    let body = reminder_body(reminder, labels);
+    let action_labels = [
+        (ReminderAction::Snooze10Minutes, labels.snooze_10m),
+        (ReminderAction::SnoozeUntilTomorrow, labels.snooze_tomorrow),
+        (ReminderAction::MarkDone, labels.mark_done),
+    ];
+    if let Err(error) = reminder_actions::show_actionable(
+        app,
+        reminder.task_id.clone(),
+        labels.title,
+        &body,
+        &action_labels,
+    ) {
+        eprintln!("Reminder notification warning: {error}");
+        send_notification(app, labels.title, &body);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn send_reminder_notification(
+    app: &AppHandle,
+    reminder: &ScheduledReminder,
+    labels: &ReminderLabels,
+) {
+    send_notification(app, labels.title, &reminder_body(reminder, labels));
+}
+
+pub(crate) fn spawn(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let state = app.state::<ReminderSchedulerState>();
+        let next_at = state.run_pass(&app);
+        let until_next = next_at
+            .and_then(|next_at| (next_at - Utc::now()).to_std().ok())
+            .unwrap_or(MAX_SCHEDULER_SLEEP);
+        state.wait_for_resync(until_next.min(MAX_SCHEDULER_SLEEP));
+    });
+}
+
+// The webview owns the reminders toggle and the language setting; it pushes both here. `locale`
+// is `en` or `th`.
+#[tauri::command]
+pub(crate) fn configure_native_reminders(
+    state: tauri::State<ReminderSchedulerState>,
+    enabled: bool,
+    locale: Option<String>,
+) -> Result<(), SoloStackError> {
+    state.configure(enabled, locale)
+}
+
+// Task writes from the webview go through the SQL plugin, which the scheduler cannot observe.
+#[tauri::command]
+pub(crate) fn resync_native_reminders(state: tauri::State<ReminderSchedulerState>) {
+    state.request_resync();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        repository::parse_iso_datetime(value).unwrap()
+    }
+
+    fn reminder(task_id: &str, remind_at: &str) -> ScheduledReminder {
+        ScheduledReminder {
+            task_id: task_id.to_string(),
+            title: format!("Task {task_id}"),
+            remind_at: at(remind_at),
+            due_at: None,
+        }
+    }
+
+    #[test]
+    fn fires_due_reminders_once_and_reports_the_next_one() {
+        let mut ledger = ReminderLedger::new(at("2026-03-10T08:00:00Z"));
+        let reminders = vec![
+            reminder("later", "2026-03-10T11:00:00Z"),
+            reminder("due", "2026-03-10T09:30:00Z"),
+            reminder("soon", "2026-03-10T10:15:00Z"),
+        ];
+
+        let plan = plan_reminders(
+            &mut ledger,
+            reminders.clone(),
+            at("2026-03-10T10:00:00Z"),
+            false,
+        );
+        assert_eq!(plan.notify, vec![reminder("due", "2026-03-10T09:30:00Z")]);
+        assert_eq!(plan.next_at, Some(at("2026-03-10T10:15:00Z")));
+
+        let plan = plan_reminders(&mut ledger, reminders, at("2026-03-10T10:00:30Z"), false);
+        assert!(plan.notify.is_empty());
+
+        // Moving the reminder re-arms it.
+        let plan = plan_reminders(
+            &mut ledger,
+            vec![reminder("due", "2026-03-10T09:45:00Z")],
+            at("2026-03-10T10:01:00Z"),
+            false,
+        );
+        assert_eq!(plan.notify.len(), 1);
+    }
+
+    #[test]
+    fn catches_up_after_sleep_but_silences_stale_and_muted_reminders() {
+        let mut ledger = ReminderLedger::new(at("2026-03-01T00:00:00Z"));
+        let now = at("2026-03-10T10:00:00Z");
+        let reminders = vec![
+            reminder("missed", "2026-03-10T02:00:00Z"),
+            reminder("stale", "2026-03-08T09:00:00Z"),
+            reminder("before-armed", "2026-02-27T09:00:00Z"),
+        ];
+
+        let plan = plan_reminders(&mut ledger, reminders, now, false);
+        assert_eq!(
+            plan.notify,
+            vec![reminder("missed", "2026-03-10T02:00:00Z")]
+        );
+        assert_eq!(plan.silenced, 2);
+
+        let plan = plan_reminders(
+            &mut ledger,
+            vec![reminder("paused", "2026-03-10T09:59:00Z")],
+            now,
+            true,
+        );
+        assert!(plan.notify.is_empty());
+        assert_eq!(plan.silenced, 1);
+        assert!(ledger
+            .fired
+            .contains_key(&reminder("paused", "2026-03-10T09:59:00Z").signature()));
+    }
+
+    #[test]
+    fn ledger_keeps_active_signatures_and_drops_old_ones() {
+        let mut ledger = ReminderLedger::new(at("2026-01-01T00:00:00Z"));
+        let active = reminder("active", "2026-01-02T09:00:00Z");
+        for (signature, fired_at) in [
+            (active.signature(), "2026-01-02T09:00:00.000Z"),
+            (
+                "gone:2026-01-02T09:00:00.000Z".to_string(),
+                "2026-01-02T09:00:00.000Z",
+            ),
+            (
+                "recent:2026-03-09T09:00:00.000Z".to_string(),
+                "2026-03-09T09:00:00.000Z",
+            ),
+        ] {
+            ledger.fired.insert(signature, fired_at.to_string());
+        }
+
+        plan_reminders(
+            &mut ledger,
+            vec![active.clone()],
+            at("2026-03-10T10:00:00Z"),
+            false,
+        );
+        assert_eq!(
+            ledger.fired.keys().cloned().collect::<Vec<_>>(),
+            vec![
+                active.signature(),
+                "recent:2026-03-09T09:00:00.000Z".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn state_roundtrips_through_the_app_data_dir() {
+        let app_data_dir =
+            std::env::temp_dir().join(format!("solostack-reminders-{}", uuid::Uuid::new_v4()));
+        let state = ReminderSchedulerState::load(Some(&app_data_dir));
+        state.configure(false, Some("th".to_string())).unwrap();
+
+        let reloaded = ReminderSchedulerState::load(Some(&app_data_dir));
+        let inner = reloaded.lock_inner().unwrap();
+        assert!(!inner.ledger.enabled);
+        assert_eq!(inner.ledger.locale.as_deref(), Some("th"));
+        assert_eq!(
+            inner.ledger.armed_at,
+            state.lock_inner().unwrap().ledger.armed_at
+        );
+        drop(inner);
+        fs::remove_dir_all(app_data_dir).unwrap();
+    }
+
+    #[test]
+    fn loads_reminders_for_open_tasks_only() {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE tasks (
+                   id TEXT PRIMARY KEY, title TEXT, status TEXT, remind_at TEXT, due_at TEXT
+                 );
+                 INSERT INTO tasks VALUES
+                   ('a', 'Open', 'TODO', '2026-03-10T09:00:00.000Z', '2026-03-10T12:00:00.000Z'),
+                   ('b', 'Done', 'DONE', '2026-03-10T09:00:00.000Z', NULL),
+                   ('c', 'Broken', 'DOING', 'soon', NULL),
+                   ('d', 'No reminder', 'TODO', NULL, NULL);",
+            )
+            .unwrap();
+
+        let reminders = load_scheduled_reminders(&connection).unwrap();
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].task_id, "a");
+        assert_eq!(reminders[0].due_at, Some(at("2026-03-10T12:00:00Z")));
+        assert!(reminder_body(&reminders[0], &REMINDER_LABELS_EN).starts_with("Open\nDue "));
+    }
+}
@@ -0,0 +1,2462 @@
+// Validated write path for tasks, projects, subtasks and templates.
+// Mirrors the behaviour of `src/lib/database.ts` (changelogs, sync outbox, deleted records,
+// sync_version bumps) so every caller produces the same rows the webview would.
+
+use chrono::{DateTime, Days, Local, Months, SecondsFormat, Utc};
+use rusqlite::{
+    types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef},
+    Connection, ErrorCode, OptionalExtension, Row, ToSql, Transaction,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    db::DatabaseState,
+    error::{SoloStackError, SoloStackErrorCode},
+};
+
+pub(crate) const SYNC_SETTINGS_DEVICE_ID_KEY: &str = "sync.device_id";
+
+// Text enums backing the SQLite CHECK constraints. Deserializing into them rejects
+// unsupported values before any statement runs.
+macro_rules! sql_text_enum {
+    ($name:ident { $($variant:ident => $text:literal),+ $(,)? }) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+        pub(crate) enum $name {
+            $(
+                #[serde(rename = $text)]
+                $variant,
+            )+
+        }
+
+        impl $name {
+            pub(crate) fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $text,)+
+                }
+            }
+
+            pub(crate) fn parse(value: &str) -> Option<Self> {
+                match value {
+                    $($text => Some(Self::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+
+        impl ToSql for $name {
+            fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(self.as_str()))
+            }
+        }
+
+        impl FromSql for $name {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                let text = value.as_str()?;
+                Self::parse(text).ok_or_else(|| {
+                    FromSqlError::Other(
+                        format!("unsupported {} value: {text}", stringify!($name)).into(),
+                    )
+                })
+            }
+        }
+    };
+}
+
+sql_text_enum!(TaskStatus {
+    Todo => "TODO",
+    Doing => "DOING",
+    Done => "DONE",
+    Archived => "ARCHIVED",
+});
+
+sql_text_enum!(TaskPriority {
+    Urgent => "URGENT",
+    Normal => "NORMAL",
+    Low => "LOW",
+});
+
+sql_text_enum!(TaskRecurrence {
+    None => "NONE",
+    Daily => "DAILY",
+    Weekly => "WEEKLY",
+    Monthly => "MONTHLY",
+});
+
+sql_text_enum!(ProjectStatus {
+    Active => "ACTIVE",
+    Completed => "COMPLETED",
+    Archived => "ARCHIVED",
+});
+
+sql_text_enum!(TaskChangelogAction {
+    Created => "CREATED",
+    Updated => "UPDATED",
+    StatusChanged => "STATUS_CHANGED",
+});
+
+sql_text_enum!(SyncEntityType {
+    Project => "PROJECT",
+    Task => "TASK",
+    TaskSubtask => "TASK_SUBTASK",
+    TaskTemplate => "TASK_TEMPLATE",
+    Setting => "SETTING",
+});
+
+sql_text_enum!(SyncOperation {
+    Upsert => "UPSERT",
+    Delete => "DELETE",
+});
+
+#[derive(Clone, Serialize)]
+pub(crate) struct Task {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    pub(crate) description: Option<String>,
+    pub(crate) notes_markdown: Option<String>,
+    pub(crate) project_id: Option<String>,
+    pub(crate) status: TaskStatus,
+    pub(crate) priority: TaskPriority,
+    pub(crate) is_important: i64,
+    pub(crate) due_at: Option<String>,
+    pub(crate) remind_at: Option<String>,
+    pub(crate) recurrence: TaskRecurrence,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+    pub(crate) sync_version: i64,
+    pub(crate) updated_by_device: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct Project {
+    id: String,
+    name: String,
+    description: Option<String>,
+    color: Option<String>,
+    status: ProjectStatus,
+    created_at: String,
+    updated_at: String,
+    sync_version: i64,
+    updated_by_device: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct TaskSubtask {
+    id: String,
+    task_id: String,
+    title: String,
+    is_done: i64,
+    created_at: String,
+    updated_at: String,
+    sync_version: i64,
+    updated_by_device: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct TaskTemplate {
+    id: String,
+    name: String,
+    title_template: Option<String>,
+    description: Option<String>,
+    priority: TaskPriority,
+    is_important: i64,
+    due_offset_minutes: Option<i64>,
+    remind_offset_minutes: Option<i64>,
+    recurrence: TaskRecurrence,
+    created_at: String,
+    updated_at: String,
+    sync_version: i64,
+    updated_by_device: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CreateTaskSubtaskDraft {
+    title: String,
+    #[serde(default)]
+    is_done: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CreateTaskInput {
+    pub(crate) title: String,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) notes_markdown: Option<String>,
+    #[serde(default)]
+    pub(crate) project_id: Option<String>,
+    pub(crate) priority: TaskPriority,
+    #[serde(default)]
+    pub(crate) is_important: bool,
+    #[serde(default)]
+    pub(crate) due_at: Option<String>,
+    #[serde(default)]
+    pub(crate) remind_at: Option<String>,
+    #[serde(default)]
+    pub(crate) recurrence: Option<TaskRecurrence>,
+    #[serde(default)]
+    pub(crate) subtasks: Vec<CreateTaskSubtaskDraft>,
+}
+
+// Nullable update fields use `Option<Option<T>>`: an absent key leaves the column untouched,
+// an explicit `null` clears it.
+#[derive(Default, Deserialize)]
+pub(crate) struct UpdateTaskInput {
+    pub(crate) id: String,
+    #[serde(default)]
+    pub(crate) title: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_present_field")]
+    pub(crate) description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_present_field")]
+    pub(crate) notes_markdown: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_present_field")]
+    pub(crate) project_id: Option<Option<String>>,
+    #[serde(default)]
+    pub(crate) status: Option<TaskStatus>,
+    #[serde(default)]
+    pub(crate) priority: Option<TaskPriority>,
+    #[serde(default)]
+    pub(crate) is_important: Option<bool>,
+    #[serde(default, deserialize_with = "deserialize_present_field")]
+    pub(crate) due_at: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_present_field")]
+    pub(crate) remind_at: Option<Option<String>>,
+    #[serde(default)]
+    pub(crate) recurrence: Option<TaskRecurrence>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CreateProjectInput {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    status: Option<ProjectStatus>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UpdateProjectInput {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_present_field")]
+    description: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_present_field")]
+    color: Option<Option<String>>,
+    #[serde(default)]
+    status: Option<ProjectStatus>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CreateTaskSubtaskInput {
+    task_id: String,
+    title: String,
+    #[serde(default)]
+    is_done: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UpdateTaskSubtaskInput {
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    is_done: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct UpsertTaskTemplateInput {
+    #[serde(default)]
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    title_template: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    priority: TaskPriority,
+    #[serde(default)]
+    is_important: bool,
+    #[serde(default)]
+    due_offset_minutes: Option<f64>,
+    #[serde(default)]
+    remind_offset_minutes: Option<f64>,
+    #[serde(default)]
+    recurrence: Option<TaskRecurrence>,
+}
+
+fn deserialize_present_field<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+// Busy/locked errors come from another writer holding the database and are worth retrying;
+// anything else is reported as a failed query.
+pub(crate) fn map_sql_error(context: &str) -> impl Fn(rusqlite::Error) -> SoloStackError + '_ {
+    move |error| {
+        let code = match error.sqlite_error_code() {
+            Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => {
+                SoloStackErrorCode::DatabaseBusy
+            }
+            _ => SoloStackErrorCode::DatabaseQueryFailed,
+        };
+        SoloStackError::with_detail(code, format!("{context} failed: {error}"))
+    }
+}
+
+pub(crate) fn now_iso() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn normalize_optional_text(value: Option<String>) -> Option<String> {
+    value
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+pub(crate) fn normalize_task_notes_markdown(value: Option<String>) -> Option<String> {
+    let normalized_notes = value?.replace("\r\n", "\n");
+    if normalized_notes.trim().is_empty() {
+        None
+    } else {
+        Some(normalized_notes)
+    }
+}
+
+fn normalize_template_offset(value: Option<f64>) -> Option<i64> {
+    let offset_minutes = value?;
+    if offset_minutes.is_nan() {
+        return None;
+    }
+    Some(offset_minutes.round().max(0.0) as i64)
+}
+
+fn read_text_column(row: &Row<'_>, column: &str) -> rusqlite::Result<String> {
+    Ok(row.get::<_, Option<String>>(column)?.unwrap_or_default())
+}
+
+fn read_flag_column(row: &Row<'_>, column: &str) -> rusqlite::Result<i64> {
+    Ok(row.get::<_, Option<i64>>(column)?.unwrap_or(0))
+}
+
+fn read_sync_version_column(row: &Row<'_>) -> rusqlite::Result<i64> {
+    Ok(row.get::<_, Option<i64>>("sync_version")?.unwrap_or(1))
+}
+
+pub(crate) fn read_task_row(row: &Row<'_>) -> rusqlite::Result<Task> {
+    Ok(Task {
+        id: row.get("id")?,
+        title: row.get("title")?,
+        description: row.get("description")?,
+        notes_markdown: row.get("notes_markdown")?,
+        project_id: row.get("project_id")?,
+        status: row.get("status")?,
+        priority: row.get("priority")?,
+        is_important: read_flag_column(row, "is_important")?,
+        due_at: row.get("due_at")?,
+        remind_at: row.get("remind_at")?,
+        recurrence: row
+            .get::<_, Option<TaskRecurrence>>("recurrence")?
+            .unwrap_or(TaskRecurrence::None),
+        created_at: read_text_column(row, "created_at")?,
+        updated_at: read_text_column(row, "updated_at")?,
+        sync_version: read_sync_version_column(row)?,
+        updated_by_device: row.get("updated_by_device")?,
+    })
+}
+
+fn read_project_row(row: &Row<'_>) -> rusqlite::Result<Project> {
+    Ok(Project {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        description: row.get("description")?,
+        color: row.get("color")?,
+        status: row.get("status")?,
+        created_at: read_text_column(row, "created_at")?,
+        updated_at: read_text_column(row, "updated_at")?,
+        sync_version: read_sync_version_column(row)?,
+        updated_by_device: row.get("updated_by_device")?,
+    })
+}
+
+fn read_task_subtask_row(row: &Row<'_>) -> rusqlite::Result<TaskSubtask> {
+    Ok(TaskSubtask {
+        id: row.get("id")?,
+        task_id: row.get("task_id")?,
+        title: row.get("title")?,
+        is_done: read_flag_column(row, "is_done")?,
+        created_at: read_text_column(row, "created_at")?,
+        updated_at: read_text_column(row, "updated_at")?,
+        sync_version: read_sync_version_column(row)?,
+        updated_by_device: row.get("updated_by_device")?,
+    })
+}
+
+fn read_task_template_row(row: &Row<'_>) -> rusqlite::Result<TaskTemplate> {
+    Ok(TaskTemplate {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        title_template: row.get("title_template")?,
+        description: row.get("description")?,
+        priority: row.get("priority")?,
+        is_important: read_flag_column(row, "is_important")?,
+        due_offset_minutes: row.get("due_offset_minutes")?,
+        remind_offset_minutes: row.get("remind_offset_minutes")?,
+        recurrence: row
+            .get::<_, Option<TaskRecurrence>>("recurrence")?
+            .unwrap_or(TaskRecurrence::None),
+        created_at: read_text_column(row, "created_at")?,
+        updated_at: read_text_column(row, "updated_at")?,
+        sync_version: read_sync_version_column(row)?,
+        updated_by_device: row.get("updated_by_device")?,
+    })
+}
+
+pub(crate) fn find_task(
+    connection: &Connection,
+    task_id: &str,
+) -> Result<Option<Task>, SoloStackError> {
+    connection
+        .query_row(
+            "SELECT * FROM tasks WHERE id = ?1 LIMIT 1",
+            [task_id],
+            read_task_row,
+        )
+        .optional()
+        .map_err(map_sql_error("read task"))
+}
+
+fn find_project(
+    connection: &Connection,
+    project_id: &str,
+) -> Result<Option<Project>, SoloStackError> {
+    connection
+        .query_row(
+            "SELECT * FROM projects WHERE id = ?1 LIMIT 1",
+            [project_id],
+            read_project_row,
+        )
+        .optional()
+        .map_err(map_sql_error("read project"))
+}
+
+fn find_task_subtask(
+    connection: &Connection,
+    subtask_id: &str,
+) -> Result<Option<TaskSubtask>, SoloStackError> {
+    connection
+        .query_row(
+            "SELECT * FROM task_subtasks WHERE id = ?1 LIMIT 1",
+            [subtask_id],
+            read_task_subtask_row,
+        )
+        .optional()
+        .map_err(map_sql_error("read subtask"))
+}
+
+fn find_task_template(
+    connection: &Connection,
+    template_id: &str,
+) -> Result<Option<TaskTemplate>, SoloStackError> {
+    connection
+        .query_row(
+            "SELECT * FROM task_templates WHERE id = ?1 LIMIT 1",
+            [template_id],
+            read_task_template_row,
+        )
+        .optional()
+        .map_err(map_sql_error("read task template"))
+}
+
+pub(crate) fn get_or_create_device_id(connection: &Connection) -> Result<String, SoloStackError> {
+    let existing_device_id = connection
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+            [SYNC_SETTINGS_DEVICE_ID_KEY],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(map_sql_error("read device id"))?
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default();
+    if !existing_device_id.is_empty() {
+        return Ok(existing_device_id);
+    }
+
+    let generated_device_id = Uuid::new_v4().to_string();
+    connection
+        .execute(
+            "INSERT INTO settings (key, value)
+                  VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            (SYNC_SETTINGS_DEVICE_ID_KEY, &generated_device_id),
+        )
+        .map_err(map_sql_error("store device id"))?;
+    Ok(generated_device_id)
+}
+
+pub(crate) fn insert_task_changelog(
+    connection: &Connection,
+    task_id: &str,
+    action: TaskChangelogAction,
+    field_name: Option<&str>,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+    created_at: &str,
+) -> Result<(), SoloStackError> {
+    connection
+        .execute(
+            "INSERT INTO task_changelogs (id, task_id, action, field_name, old_value, new_value, created_at)
+                  VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            (
+                Uuid::new_v4().to_string(),
+                task_id,
+                action,
+                field_name,
+                old_value,
+                new_value,
+                created_at,
+            ),
+        )
+        .map(|_| ())
+        .map_err(map_sql_error("insert task changelog"))
+}
+
+fn insert_sync_outbox_change(
+    connection: &Connection,
+    entity_type: SyncEntityType,
+    entity_id: &str,
+    operation: SyncOperation,
+    payload_json: Option<String>,
+    created_at: &str,
+) -> Result<(), SoloStackError> {
+    connection
+        .execute(
+            "INSERT INTO sync_outbox (
+                id,
+                entity_type,
+                entity_id,
+                operation,
+                payload_json,
+                idempotency_key,
+                attempts,
+                last_error,
+                created_at,
+                updated_at
+              )
+              VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, NULL, ?7, ?7)",
+            (
+                Uuid::new_v4().to_string(),
+                entity_type,
+                entity_id,
+                operation,
+                payload_json,
+                Uuid::new_v4().to_string(),
+                created_at,
+            ),
+        )
+        .map(|_| ())
+        .map_err(map_sql_error("enqueue sync outbox change"))
+}
+
+pub(crate) fn enqueue_entity_upsert<T: Serialize>(
+    connection: &Connection,
+    entity_type: SyncEntityType,
+    entity_id: &str,
+    payload: &T,
+    created_at: &str,
+) -> Result<(), SoloStackError> {
+    let payload_json = serde_json::to_string(payload).map_err(|error| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::Internal,
+            format!("serialize sync payload failed: {error}"),
+        )
+    })?;
+    insert_sync_outbox_change(
+        connection,
+        entity_type,
+        entity_id,
+        SyncOperation::Upsert,
+        Some(payload_json),
+        created_at,
+    )
+}
+
+pub(crate) fn upsert_deleted_record(
+    connection: &Connection,
+    entity_type: SyncEntityType,
+    entity_id: &str,
+    device_id: &str,
+    deleted_at: &str,
+) -> Result<(), SoloStackError> {
+    connection
+        .execute(
+            "INSERT INTO deleted_records (
+                id,
+                entity_type,
+                entity_id,
+                deleted_at,
+                deleted_by_device,
+                created_at,
+                updated_at
+              )
+              VALUES (?1, ?2, ?3, ?4, ?5, ?4, ?4)
+              ON CONFLICT(entity_type, entity_id) DO UPDATE SET
+                deleted_at = excluded.deleted_at,
+                deleted_by_device = excluded.deleted_by_device,
+                updated_at = excluded.updated_at",
+            (
+                Uuid::new_v4().to_string(),
+                entity_type,
+                entity_id,
+                deleted_at,
+                device_id,
+            ),
+        )
+        .map(|_| ())
+        .map_err(map_sql_error("record deleted entity"))
+}
+
+pub(crate) fn enqueue_entity_delete(
+    connection: &Connection,
+    entity_type: SyncEntityType,
+    entity_id: &str,
+    device_id: &str,
+    deleted_at: &str,
+) -> Result<(), SoloStackError> {
+    upsert_deleted_record(connection, entity_type, entity_id, device_id, deleted_at)?;
+    insert_sync_outbox_change(
+        connection,
+        entity_type,
+        entity_id,
+        SyncOperation::Delete,
+        None,
+        deleted_at,
+    )
+}
+
+fn assert_project_exists(
+    connection: &Connection,
+    project_id: Option<&str>,
+) -> Result<(), SoloStackError> {
+    let Some(project_id) = project_id else {
+        return Ok(());
+    };
+    if find_project(connection, project_id)?.is_none() {
+        return Err(SoloStackError::new(
+            SoloStackErrorCode::DbSelectedProjectMissing,
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn parse_iso_datetime(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|datetime| datetime.with_timezone(&Utc))
+}
+
+fn format_iso_datetime(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+// Recurrence follows the user's local calendar, matching `getNextRecurringDueAt` in the webview.
+fn get_next_recurring_due_at(current_due_at: &str, recurrence: TaskRecurrence) -> Option<String> {
+    let current_due_date = parse_iso_datetime(current_due_at)?.with_timezone(&Local);
+    let next_due_date = match recurrence {
+        TaskRecurrence::None => return None,
+        TaskRecurrence::Daily => current_due_date.checked_add_days(Days::new(1))?,
+        TaskRecurrence::Weekly => current_due_date.checked_add_days(Days::new(7))?,
+        TaskRecurrence::Monthly => current_due_date.checked_add_months(Months::new(1))?,
+    };
+    Some(format_iso_datetime(next_due_date.with_timezone(&Utc)))
+}
+
+fn shift_reminder_with_due_date(
+    current_remind_at: Option<&str>,
+    current_due_at: &str,
+    next_due_at: &str,
+) -> Option<String> {
+    let remind_date = parse_iso_datetime(current_remind_at?)?;
+    let due_date = parse_iso_datetime(current_due_at)?;
+    let next_due_date = parse_iso_datetime(next_due_at)?;
+    Some(format_iso_datetime(
+        next_due_date + (remind_date - due_date),
+    ))
+}
+
+fn insert_task_subtask_row(
+    connection: &Connection,
+    task_id: &str,
+    title: &str,
+    is_done: bool,
+    device_id: &str,
+    now: &str,
+) -> Result<TaskSubtask, SoloStackError> {
+    let subtask_id = Uuid::new_v4().to_string();
+    connection
+        .execute(
+            "INSERT INTO task_subtasks (
+                id,
+                task_id,
+                title,
+                is_done,
+                created_at,
+                updated_at,
+                sync_version,
+                updated_by_device
+              )
+              VALUES (?1, ?2, ?3, ?4, ?5, ?5, 1, ?6)",
+            (&subtask_id, task_id, title, is_done, now, device_id),
+        )
+        .map_err(map_sql_error("insert subtask"))?;
+    find_task_subtask(connection, &subtask_id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbSubtaskNotFound))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_task_row(
+    connection: &Connection,
+    task_id: &str,
+    title: &str,
+    description: Option<&str>,
+    notes_markdown: Option<&str>,
+    project_id: Option<&str>,
+    priority: TaskPriority,
+    is_important: bool,
+    due_at: Option<&str>,
+    remind_at: Option<&str>,
+    recurrence: TaskRecurrence,
+    device_id: &str,
+    now: &str,
+) -> Result<(), SoloStackError> {
+    connection
+        .execute(
+            "INSERT INTO tasks (
+                id,
+                title,
+                description,
+                notes_markdown,
+                project_id,
+                status,
+                priority,
+                is_important,
+                due_at,
+                remind_at,
+                recurrence,
+                created_at,
+                updated_at,
+                sync_version,
+                updated_by_device
+              )
+              VALUES (?1, ?2, ?3, ?4, ?5, 'TODO', ?6, ?7, ?8, ?9, ?10, ?11, ?11, 1, ?12)",
+            rusqlite::params![
+                task_id,
+                title,
+                description,
+                notes_markdown,
+                project_id,
+                priority,
+                is_important,
+                due_at,
+                remind_at,
+                recurrence,
+                now,
+                device_id,
+            ],
+        )
+        .map(|_| ())
+        .map_err(map_sql_error("insert task"))
+}
+
+pub(crate) fn create_task_record(
+    transaction: &Transaction<'_>,
+    input: CreateTaskInput,
+) -> Result<Task, SoloStackError> {
+    let title = input.title.trim().to_string();
+    if title.is_empty() {
+        return Err(SoloStackError::new(SoloStackErrorCode::DbTaskTitleRequired));
+    }
+    let project_id = normalize_optional_text(input.project_id);
+    assert_project_exists(transaction, project_id.as_deref())?;
+
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    let task_id = Uuid::new_v4().to_string();
+    insert_task_row(
+        transaction,
+        &task_id,
+        &title,
+        input.description.as_deref(),
+        normalize_task_notes_markdown(input.notes_markdown).as_deref(),
+        project_id.as_deref(),
+        input.priority,
+        input.is_important,
+        input.due_at.as_deref(),
+        input.remind_at.as_deref(),
+        input.recurrence.unwrap_or(TaskRecurrence::None),
+        &device_id,
+        &now,
+    )?;
+    insert_task_changelog(
+        transaction,
+        &task_id,
+        TaskChangelogAction::Created,
+        None,
+        None,
+        Some(&title),
+        &now,
+    )?;
+
+    let mut created_subtasks = Vec::new();
+    for subtask in input.subtasks {
+        let subtask_title = subtask.title.trim();
+        if subtask_title.is_empty() {
+            continue;
+        }
+        created_subtasks.push(insert_task_subtask_row(
+            transaction,
+            &task_id,
+            subtask_title,
+            subtask.is_done,
+            &device_id,
+            &now,
+        )?);
+    }
+
+    let created_task = find_task(transaction, &task_id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbTaskNotFound))?;
+    enqueue_entity_upsert(
+        transaction,
+        SyncEntityType::Task,
+        &created_task.id,
+        &created_task,
+        &now,
+    )?;
+    for subtask in &created_subtasks {
+        enqueue_entity_upsert(
+            transaction,
+            SyncEntityType::TaskSubtask,
+            &subtask.id,
+            subtask,
+            &now,
+        )?;
+    }
+    Ok(created_task)
+}
+
+struct TaskFieldChange {
+    action: TaskChangelogAction,
+    field_name: &'static str,
+    old_value: Option<String>,
+    new_value: Option<String>,
+}
+
+fn push_task_field_change(
+    changes: &mut Vec<TaskFieldChange>,
+    field_name: &'static str,
+    old_value: Option<String>,
+    new_value: Option<String>,
+) {
+    if old_value != new_value {
+        changes.push(TaskFieldChange {
+            action: TaskChangelogAction::Updated,
+            field_name,
+            old_value,
+            new_value,
+        });
+    }
+}
+
+pub(crate) fn update_task_record(
+    transaction: &Transaction<'_>,
+    input: UpdateTaskInput,
+) -> Result<Task, SoloStackError> {
+    let existing_task = find_task(transaction, &input.id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbTaskNotFound))?;
+    let mut next_task = existing_task.clone();
+
+    if let Some(title) = input.title {
+        let normalized_title = title.trim().to_string();
+        if normalized_title.is_empty() {
+            return Err(SoloStackError::new(SoloStackErrorCode::DbTaskTitleRequired));
+        }
+        next_task.title = normalized_title;
+    }
+    if let Some(description) = input.description {
+        next_task.description = description;
+    }
+    if let Some(notes_markdown) = input.notes_markdown {
+        next_task.notes_markdown = normalize_task_notes_markdown(notes_markdown);
+    }
+    if let Some(project_id) = input.project_id {
+        let normalized_project_id = normalize_optional_text(project_id);
+        assert_project_exists(transaction, normalized_project_id.as_deref())?;
+        next_task.project_id = normalized_project_id;
+    }
+    if let Some(status) = input.status {
+        next_task.status = status;
+    }
+    if let Some(priority) = input.priority {
+        next_task.priority = priority;
+    }
+    if let Some(is_important) = input.is_important {
+        next_task.is_important = i64::from(is_important);
+    }
+    if let Some(due_at) = input.due_at {
+        next_task.due_at = due_at;
+    }
+    if let Some(remind_at) = input.remind_at {
+        next_task.remind_at = remind_at;
+    }
+    if let Some(recurrence) = input.recurrence {
+        next_task.recurrence = recurrence;
+    }
+
+    let mut changes = Vec::new();
+    push_task_field_change(
+        &mut changes,
+        "title",
+        Some(existing_task.title.clone()),
+        Some(next_task.title.clone()),
+    );
+    push_task_field_change(
+        &mut changes,
+        "description",
+        existing_task.description.clone(),
+        next_task.description.clone(),
+    );
+    push_task_field_change(
+        &mut changes,
+        "notes_markdown",
+        existing_task.notes_markdown.clone(),
+        next_task.notes_markdown.clone(),
+    );
+    push_task_field_change(
+        &mut changes,
+        "project_id",
+        existing_task.project_id.clone(),
+        next_task.project_id.clone(),
+    );
+    if existing_task.status != next_task.status {
+        changes.push(TaskFieldChange {
+            action: TaskChangelogAction::StatusChanged,
+            field_name: "status",
+            old_value: Some(existing_task.status.as_str().to_string()),
+            new_value: Some(next_task.status.as_str().to_string()),
+        });
+    }
+    push_task_field_change(
+        &mut changes,
+        "priority",
+        Some(existing_task.priority.as_str().to_string()),
+        Some(next_task.priority.as_str().to_string()),
+    );
+    push_task_field_change(
+        &mut changes,
+        "is_important",
+        Some((existing_task.is_important != 0).to_string()),
+        Some((next_task.is_important != 0).to_string()),
+    );
+    push_task_field_change(
+        &mut changes,
+        "due_at",
+        existing_task.due_at.clone(),
+        next_task.due_at.clone(),
+    );
+    push_task_field_change(
+        &mut changes,
+        "remind_at",
+        existing_task.remind_at.clone(),
+        next_task.remind_at.clone(),
+    );
+    push_task_field_change(
+        &mut changes,
+        "recurrence",
+        Some(existing_task.recurrence.as_str().to_string()),
+        Some(next_task.recurrence.as_str().to_string()),
+    );
+
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    transaction
+        .execute(
+            "UPDATE tasks
+                SET title = ?1,
+                    description = ?2,
+                    notes_markdown = ?3,
+                    project_id = ?4,
+                    status = ?5,
+                    priority = ?6,
+                    is_important = ?7,
+                    due_at = ?8,
+                    remind_at = ?9,
+                    recurrence = ?10,
+                    sync_version = COALESCE(sync_version, 0) + 1,
+                    updated_by_device = ?11,
+                    updated_at = ?12
+              WHERE id = ?13",
+            rusqlite::params![
+                next_task.title,
+                next_task.description,
+                next_task.notes_markdown,
+                next_task.project_id,
+                next_task.status,
+                next_task.priority,
+                next_task.is_important,
+                next_task.due_at,
+                next_task.remind_at,
+                next_task.recurrence,
+                device_id,
+                now,
+                input.id,
+            ],
+        )
+        .map_err(map_sql_error("update task"))?;
+
+    for change in &changes {
+        insert_task_changelog(
+            transaction,
+            &input.id,
+            change.action,
+            Some(change.field_name),
+            change.old_value.as_deref(),
+            change.new_value.as_deref(),
+            &now,
+        )?;
+    }
+
+    let updated_task = find_task(transaction, &input.id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbTaskNotFound))?;
+
+    if existing_task.status != TaskStatus::Done && updated_task.status == TaskStatus::Done {
+        create_next_recurring_task(transaction, &updated_task, &device_id, &now)?;
+    }
+
+    enqueue_entity_upsert(
+        transaction,
+        SyncEntityType::Task,
+        &updated_task.id,
+        &updated_task,
+        &now,
+    )?;
+    Ok(updated_task)
+}
+
+fn create_next_recurring_task(
+    transaction: &Transaction<'_>,
+    completed_task: &Task,
+    device_id: &str,
+    now: &str,
+) -> Result<(), SoloStackError> {
+    let Some(current_due_at) = completed_task.due_at.as_deref() else {
+        return Ok(());
+    };
+    let Some(next_due_at) = get_next_recurring_due_at(current_due_at, completed_task.recurrence)
+    else {
+        return Ok(());
+    };
+    let next_remind_at = shift_reminder_with_due_date(
+        completed_task.remind_at.as_deref(),
+        current_due_at,
+        &next_due_at,
+    );
+
+    let next_task_id = Uuid::new_v4().to_string();
+    insert_task_row(
+        transaction,
+        &next_task_id,
+        &completed_task.title,
+        completed_task.description.as_deref(),
+        completed_task.notes_markdown.as_deref(),
+        completed_task.project_id.as_deref(),
+        completed_task.priority,
+        completed_task.is_important != 0,
+        Some(&next_due_at),
+        next_remind_at.as_deref(),
+        completed_task.recurrence,
+        device_id,
+        now,
+    )?;
+    insert_task_changelog(
+        transaction,
+        &next_task_id,
+        TaskChangelogAction::Created,
+        None,
+        None,
+        Some(&completed_task.title),
+        now,
+    )?;
+
+    if let Some(next_task) = find_task(transaction, &next_task_id)? {
+        enqueue_entity_upsert(
+            transaction,
+            SyncEntityType::Task,
+            &next_task.id,
+            &next_task,
+            now,
+        )?;
+    }
+    Ok(())
+}
+
+fn delete_task_record(transaction: &Transaction<'_>, task_id: &str) -> Result<(), SoloStackError> {
+    let Some(task) = find_task(transaction, task_id)? else {
+        return Ok(());
+    };
+    let subtask_ids = list_task_subtask_records(transaction, task_id)?
+        .into_iter()
+        .map(|subtask| subtask.id)
+        .collect::<Vec<_>>();
+
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    transaction
+        .execute(
+            "UPDATE sessions SET task_id = NULL WHERE task_id = ?1",
+            [task_id],
+        )
+        .map_err(map_sql_error("detach task sessions"))?;
+    transaction
+        .execute("DELETE FROM tasks WHERE id = ?1", [task_id])
+        .map_err(map_sql_error("delete task"))?;
+    for subtask_id in &subtask_ids {
+        enqueue_entity_delete(
+            transaction,
+            SyncEntityType::TaskSubtask,
+            subtask_id,
+            &device_id,
+            &now,
+        )?;
+    }
+    enqueue_entity_delete(
+        transaction,
+        SyncEntityType::Task,
+        &task.id,
+        &device_id,
+        &now,
+    )
+}
+
+fn list_task_records(connection: &Connection) -> Result<Vec<Task>, SoloStackError> {
+    let mut statement = connection
+        .prepare("SELECT * FROM tasks WHERE status != 'ARCHIVED' ORDER BY created_at DESC")
+        .map_err(map_sql_error("prepare task list"))?;
+    let rows = statement
+        .query_map([], read_task_row)
+        .map_err(map_sql_error("list tasks"))?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(map_sql_error("read tasks"))
+}
+
+fn list_project_records(connection: &Connection) -> Result<Vec<Project>, SoloStackError> {
+    let mut statement = connection
+        .prepare(
+            "SELECT *
+               FROM projects
+              WHERE status != 'ARCHIVED'
+              ORDER BY
+                CASE status
+                  WHEN 'ACTIVE' THEN 0
+                  WHEN 'COMPLETED' THEN 1
+                  ELSE 2
+                END ASC,
+                updated_at DESC,
+                name COLLATE NOCASE ASC",
+        )
+        .map_err(map_sql_error("prepare project list"))?;
+    let rows = statement
+        .query_map([], read_project_row)
+        .map_err(map_sql_error("list projects"))?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(map_sql_error("read projects"))
+}
+
+fn assert_project_name_available(
+    connection: &Connection,
+    name: &str,
+    excluded_project_id: Option<&str>,
+) -> Result<(), SoloStackError> {
+    let duplicate_id = connection
+        .query_row(
+            "SELECT id FROM projects WHERE LOWER(name) = LOWER(?1) AND id != COALESCE(?2, '') LIMIT 1",
+            (name, excluded_project_id),
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(map_sql_error("check project name"))?;
+    if duplicate_id.is_some() {
+        return Err(SoloStackError::new(SoloStackErrorCode::DbProjectNameExists));
+    }
+    Ok(())
+}
+
+fn create_project_record(
+    transaction: &Transaction<'_>,
+    input: CreateProjectInput,
+) -> Result<Project, SoloStackError> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(SoloStackError::new(
+            SoloStackErrorCode::DbProjectNameRequired,
+        ));
+    }
+    assert_project_name_available(transaction, &name, None)?;
+
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    let project_id = Uuid::new_v4().to_string();
+    transaction
+        .execute(
+            "INSERT INTO projects (
+                id,
+                name,
+                description,
+                color,
+                status,
+                created_at,
+                updated_at,
+                sync_version,
+                updated_by_device
+              )
+              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, 1, ?7)",
+            (
+                &project_id,
+                &name,
+                normalize_optional_text(input.description),
+                normalize_optional_text(input.color),
+                input.status.unwrap_or(ProjectStatus::Active),
+                &now,
+                &device_id,
+            ),
+        )
+        .map_err(map_sql_error("insert project"))?;
+
+    let created_project = find_project(transaction, &project_id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbProjectNotFound))?;
+    enqueue_entity_upsert(
+        transaction,
+        SyncEntityType::Project,
+        &created_project.id,
+        &created_project,
+        &now,
+    )?;
+    Ok(created_project)
+}
+
+fn update_project_record(
+    transaction: &Transaction<'_>,
+    input: UpdateProjectInput,
+) -> Result<Project, SoloStackError> {
+    let existing_project = find_project(transaction, &input.id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbProjectNotFound))?;
+    if input.name.is_none()
+        && input.description.is_none()
+        && input.color.is_none()
+        && input.status.is_none()
+    {
+        return Ok(existing_project);
+    }
+
+    let mut next_project = existing_project;
+    if let Some(name) = input.name {
+        let normalized_name = name.trim().to_string();
+        if normalized_name.is_empty() {
+            return Err(SoloStackError::new(
+                SoloStackErrorCode::DbProjectNameRequired,
+            ));
+        }
+        assert_project_name_available(transaction, &normalized_name, Some(&input.id))?;
+        next_project.name = normalized_name;
+    }
+    if let Some(description) = input.description {
+        next_project.description = normalize_optional_text(description);
+    }
+    if let Some(color) = input.color {
+        next_project.color = normalize_optional_text(color);
+    }
+    if let Some(status) = input.status {
+        next_project.status = status;
+    }
+
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    transaction
+        .execute(
+            "UPDATE projects
+                SET name = ?1,
+                    description = ?2,
+                    color = ?3,
+                    status = ?4,
+                    sync_version = COALESCE(sync_version, 0) + 1,
+                    updated_by_device = ?5,
+                    updated_at = ?6
+              WHERE id = ?7",
+            (
+                &next_project.name,
+                &next_project.description,
+                &next_project.color,
+                next_project.status,
+                &device_id,
+                &now,
+                &input.id,
+            ),
+        )
+        .map_err(map_sql_error("update project"))?;
+
+    let updated_project = find_project(transaction, &input.id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbProjectNotFound))?;
+    enqueue_entity_upsert(
+        transaction,
+        SyncEntityType::Project,
+        &updated_project.id,
+        &updated_project,
+        &now,
+    )?;
+    Ok(updated_project)
+}
+
+fn delete_project_record(
+    transaction: &Transaction<'_>,
+    project_id: &str,
+) -> Result<(), SoloStackError> {
+    let Some(project) = find_project(transaction, project_id)? else {
+        return Ok(());
+    };
+
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    let affected_task_ids = {
+        let mut statement = transaction
+            .prepare("SELECT id FROM tasks WHERE project_id = ?1")
+            .map_err(map_sql_error("prepare project task lookup"))?;
+        let rows = statement
+            .query_map([project_id], |row| row.get::<_, String>(0))
+            .map_err(map_sql_error("list project tasks"))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(map_sql_error("read project tasks"))?
+    };
+    transaction
+        .execute(
+            "UPDATE tasks
+                SET project_id = NULL,
+                    sync_version = COALESCE(sync_version, 0) + 1,
+                    updated_by_device = ?2,
+                    updated_at = ?3
+              WHERE project_id = ?1",
+            (project_id, &device_id, &now),
+        )
+        .map_err(map_sql_error("unassign project tasks"))?;
+    for task_id in &affected_task_ids {
+        if let Some(updated_task) = find_task(transaction, task_id)? {
+            enqueue_entity_upsert(
+                transaction,
+                SyncEntityType::Task,
+                &updated_task.id,
+                &updated_task,
+                &now,
+            )?;
+        }
+    }
+
+    transaction
+        .execute("DELETE FROM projects WHERE id = ?1", [project_id])
+        .map_err(map_sql_error("delete project"))?;
+    enqueue_entity_delete(
+        transaction,
+        SyncEntityType::Project,
+        &project.id,
+        &device_id,
+        &now,
+    )
+}
+
+fn list_task_subtask_records(
+    connection: &Connection,
+    task_id: &str,
+) -> Result<Vec<TaskSubtask>, SoloStackError> {
+    let mut statement = connection
+        .prepare("SELECT * FROM task_subtasks WHERE task_id = ?1 ORDER BY created_at ASC")
+        .map_err(map_sql_error("prepare subtask list"))?;
+    let rows = statement
+        .query_map([task_id], read_task_subtask_row)
+        .map_err(map_sql_error("list subtasks"))?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(map_sql_error("read subtasks"))
+}
+
+fn create_task_subtask_record(
+    transaction: &Transaction<'_>,
+    input: CreateTaskSubtaskInput,
+) -> Result<TaskSubtask, SoloStackError> {
+    let title = input.title.trim();
+    if title.is_empty() {
+        return Err(SoloStackError::new(
+            SoloStackErrorCode::DbSubtaskTitleRequired,
+        ));
+    }
+    if find_task(transaction, &input.task_id)?.is_none() {
+        return Err(SoloStackError::new(SoloStackErrorCode::DbTaskNotFound));
+    }
+
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    let created_subtask = insert_task_subtask_row(
+        transaction,
+        &input.task_id,
+        title,
+        input.is_done,
+        &device_id,
+        &now,
+    )?;
+    enqueue_entity_upsert(
+        transaction,
+        SyncEntityType::TaskSubtask,
+        &created_subtask.id,
+        &created_subtask,
+        &now,
+    )?;
+    Ok(created_subtask)
+}
+
+fn update_task_subtask_record(
+    transaction: &Transaction<'_>,
+    input: UpdateTaskSubtaskInput,
+) -> Result<TaskSubtask, SoloStackError> {
+    let existing_subtask = find_task_subtask(transaction, &input.id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbSubtaskNotFound))?;
+    if input.title.is_none() && input.is_done.is_none() {
+        return Ok(existing_subtask);
+    }
+
+    let mut next_subtask = existing_subtask;
+    if let Some(title) = input.title {
+        let normalized_title = title.trim().to_string();
+        if normalized_title.is_empty() {
+            return Err(SoloStackError::new(
+                SoloStackErrorCode::DbSubtaskTitleRequired,
+            ));
+        }
+        next_subtask.title = normalized_title;
+    }
+    if let Some(is_done) = input.is_done {
+        next_subtask.is_done = i64::from(is_done);
+    }
+
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    transaction
+        .execute(
+            "UPDATE task_subtasks
+                SET title = ?1,
+                    is_done = ?2,
+                    sync_version = COALESCE(sync_version, 0) + 1,
+                    updated_by_device = ?3,
+                    updated_at = ?4
+              WHERE id = ?5",
+            (
+                &next_subtask.title,
+                next_subtask.is_done,
+                &device_id,
+                &now,
+                &input.id,
+            ),
+        )
+        .map_err(map_sql_error("update subtask"))?;
+
+    let updated_subtask = find_task_subtask(transaction, &input.id)?
+        .ok_or_else(|| SoloStackError::new(SoloStackErrorCode::DbSubtaskNotFound))?;
+    enqueue_entity_upsert(
+        transaction,
+        SyncEntityType::TaskSubtask,
+        &updated_subtask.id,
+        &updated_subtask,
+        &now,
+    )?;
+    Ok(updated_subtask)
+}
+
+fn delete_task_subtask_record(
+    transaction: &Transaction<'_>,
+    subtask_id: &str,
+) -> Result<(), SoloStackError> {
+    let Some(subtask) = find_task_subtask(transaction, subtask_id)? else {
+        return Ok(());
+    };
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    transaction
+        .execute("DELETE FROM task_subtasks WHERE id = ?1", [subtask_id])
+        .map_err(map_sql_error("delete subtask"))?;
+    enqueue_entity_delete(
+        transaction,
+        SyncEntityType::TaskSubtask,
+        &subtask.id,
+        &device_id,
+        &now,
+    )
+}
+
+fn list_task_template_records(
+    connection: &Connection,
+) -> Result<Vec<TaskTemplate>, SoloStackError> {
+    let mut statement = connection
+        .prepare("SELECT * FROM task_templates ORDER BY updated_at DESC, name COLLATE NOCASE ASC")
+        .map_err(map_sql_error("prepare task template list"))?;
+    let rows = statement
+        .query_map([], read_task_template_row)
+        .map_err(map_sql_error("list task templates"))?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(map_sql_error("read task templates"))
+}
+
+fn upsert_task_template_record(
+    transaction: &Transaction<'_>,
+    input: UpsertTaskTemplateInput,
+) -> Result<TaskTemplate, SoloStackError> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(SoloStackError::new(
+            SoloStackErrorCode::DbTemplateNameRequired,
+        ));
+    }
+
+    let due_offset_minutes = normalize_template_offset(input.due_offset_minutes);
+    let remind_offset_minutes = normalize_template_offset(input.remind_offset_minutes);
+    let recurrence = input.recurrence.unwrap_or(TaskRecurrence::None);
+    if recurrence != TaskRecurrence::None && due_offset_minutes.is_none() {
+        return Err(SoloStackError::new(
+            SoloStackErrorCode::DbRecurringTemplateDueOffsetRequired,
+        ));
+    }
+    if let (Some(due_offset), Some(remind_offset)) = (due_offset_minutes, remind_offset_minutes) {
+        if remind_offset > due_offset {
+            return Err(SoloStackError::new(
+                SoloStackErrorCode::DbReminderOffsetMustBeEarlier,
+            ));
+        }
+    }
+
+    let existing_by_name_id = transaction
+        .query_row(
+            "SELECT id FROM task_templates WHERE LOWER(name) = LOWER(?1) LIMIT 1",
+            [&name],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(map_sql_error("check task template name"))?;
+    let requested_id = normalize_optional_text(input.id);
+    if let (Some(requested_id), Some(existing_id)) = (&requested_id, &existing_by_name_id) {
+        if requested_id != existing_id {
+            return Err(SoloStackError::new(
+                SoloStackErrorCode::DbTemplateNameExists,
+            ));
+        }
+    }
+
+    let target_template_id = requested_id
+        .or(existing_by_name_id)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let title_template = normalize_optional_text(input.title_template);
+    let description = normalize_optional_text(input.description);
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+
+    if find_task_template(transaction, &target_template_id)?.is_some() {
+        transaction
+            .execute(
+                "UPDATE task_templates
+                    SET name = ?1,
+                        title_template = ?2,
+                        description = ?3,
+                        priority = ?4,
+                        is_important = ?5,
+                        due_offset_minutes = ?6,
+                        remind_offset_minutes = ?7,
+                        recurrence = ?8,
+                        sync_version = COALESCE(sync_version, 0) + 1,
+                        updated_by_device = ?9,
+                        updated_at = ?10
+                  WHERE id = ?11",
+                rusqlite::params![
+                    name,
+                    title_template,
+                    description,
+                    input.priority,
+                    input.is_important,
+                    due_offset_minutes,
+                    remind_offset_minutes,
+                    recurrence,
+                    device_id,
+                    now,
+                    target_template_id,
+                ],
+            )
+            .map_err(map_sql_error("update task template"))?;
+    } else {
+        transaction
+            .execute(
+                "INSERT INTO task_templates (
+                    id,
+                    name,
+                    title_template,
+                    description,
+                    priority,
+                    is_important,
+                    due_offset_minutes,
+                    remind_offset_minutes,
+                    recurrence,
+                    created_at,
+                    updated_at,
+                    sync_version,
+                    updated_by_device
+                  )
+                  VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10, 1, ?11)",
+                rusqlite::params![
+                    target_template_id,
+                    name,
+                    title_template,
+                    description,
+                    input.priority,
+                    input.is_important,
+                    due_offset_minutes,
+                    remind_offset_minutes,
+                    recurrence,
+                    now,
+                    device_id,
+                ],
+            )
+            .map_err(map_sql_error("insert task template"))?;
+    }
+
+    let upserted_template =
+        find_task_template(transaction, &target_template_id)?.ok_or_else(|| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::Internal,
+                "task template upsert did not persist",
+            )
+        })?;
+    enqueue_entity_upsert(
+        transaction,
+        SyncEntityType::TaskTemplate,
+        &upserted_template.id,
+        &upserted_template,
+        &now,
+    )?;
+    Ok(upserted_template)
+}
+
+fn delete_task_template_record(
+    transaction: &Transaction<'_>,
+    template_id: &str,
+) -> Result<(), SoloStackError> {
+    let Some(task_template) = find_task_template(transaction, template_id)? else {
+        return Ok(());
+    };
+    let now = now_iso();
+    let device_id = get_or_create_device_id(transaction)?;
+    transaction
+        .execute("DELETE FROM task_templates WHERE id = ?1", [template_id])
+        .map_err(map_sql_error("delete task template"))?;
+    enqueue_entity_delete(
+        transaction,
+        SyncEntityType::TaskTemplate,
+        &task_template.id,
+        &device_id,
+        &now,
+    )
+}
+
+pub(crate) fn run_in_transaction<T>(
+    connection: &mut Connection,
+    operation: impl FnOnce(&Transaction<'_>) -> Result<T, SoloStackError>,
+) -> Result<T, SoloStackError> {
+    let transaction = connection
+        .transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)
+        .map_err(map_sql_error("begin transaction"))?;
+    let result = operation(&transaction)?;
+    transaction
+        .commit()
+        .map_err(map_sql_error("commit transaction"))?;
+    Ok(result)
+}
+
+pub(crate) fn with_write_transaction<T>(
+    state: &DatabaseState,
+    operation: impl FnOnce(&Transaction<'_>) -> Result<T, SoloStackError>,
+) -> Result<T, SoloStackError> {
+    let mut connection = state.open()?;
+    run_in_transaction(&mut connection, operation)
+}
+
+#[tauri::command]
+pub(crate) fn list_tasks(state: tauri::State<DatabaseState>) -> Result<Vec<Task>, SoloStackError> {
+    list_task_records(&state.open()?)
+}
+
+#[tauri::command]
+pub(crate) fn create_task(
+    state: tauri::State<DatabaseState>,
+    input: CreateTaskInput,
+) -> Result<Task, SoloStackError> {
+    with_write_transaction(&state, |transaction| create_task_record(transaction, input))
+}
+
+#[tauri::command]
+pub(crate) fn update_task(
+    state: tauri::State<DatabaseState>,
+    input: UpdateTaskInput,
+) -> Result<Task, SoloStackError> {
+    with_write_transaction(&state, |transaction| update_task_record(transaction, input))
+}
+
+#[tauri::command]
+pub(crate) fn delete_task(
+    state: tauri::State<DatabaseState>,
+    id: String,
+) -> Result<(), SoloStackError> {
+    with_write_transaction(&state, |transaction| delete_task_record(transaction, &id))
+}
+
+#[tauri::command]
+pub(crate) fn list_projects(
+    state: tauri::State<DatabaseState>,
+) -> Result<Vec<Project>, SoloStackError> {
+    list_project_records(&state.open()?)
+}
+
+#[tauri::command]
+pub(crate) fn create_project(
+    state: tauri::State<DatabaseState>,
+    input: CreateProjectInput,
+) -> Result<Project, SoloStackError> {
+    with_write_transaction(&state, |transaction| {
+        create_project_record(transaction, input)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn update_project(
+    state: tauri::State<DatabaseState>,
+    input: UpdateProjectInput,
+) -> Result<Project, SoloStackError> {
+    with_write_transaction(&state, |transaction| {
+        update_project_record(transaction, input)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn delete_project(
+    state: tauri::State<DatabaseState>,
+    id: String,
+) -> Result<(), SoloStackError> {
+    with_write_transaction(&state, |transaction| {
+        delete_project_record(transaction, &id)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn list_task_subtasks(
+    state: tauri::State<DatabaseState>,
+    task_id: String,
+) -> Result<Vec<TaskSubtask>, SoloStackError> {
+    list_task_subtask_records(&state.open()?, &task_id)
+}
+
+#[tauri::command]
+pub(crate) fn create_task_subtask(
+    state: tauri::State<DatabaseState>,
+    input: CreateTaskSubtaskInput,
+) -> Result<TaskSubtask, SoloStackError> {
+    with_write_transaction(&state, |transaction| {
+        create_task_subtask_record(transaction, input)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn update_task_subtask(
+    state: tauri::State<DatabaseState>,
+    input: UpdateTaskSubtaskInput,
+) -> Result<TaskSubtask, SoloStackError> {
+    with_write_transaction(&state, |transaction| {
+        update_task_subtask_record(transaction, input)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn delete_task_subtask(
+    state: tauri::State<DatabaseState>,
+    id: String,
+) -> Result<(), SoloStackError> {
+    with_write_transaction(&state, |transaction| {
+        delete_task_subtask_record(transaction, &id)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn list_task_templates(
+    state: tauri::State<DatabaseState>,
+) -> Result<Vec<TaskTemplate>, SoloStackError> {
+    list_task_template_records(&state.open()?)
+}
+
+#[tauri::command]
+pub(crate) fn upsert_task_template(
+    state: tauri::State<DatabaseState>,
+    input: UpsertTaskTemplateInput,
+) -> Result<TaskTemplate, SoloStackError> {
+    with_write_transaction(&state, |transaction| {
+        upsert_task_template_record(transaction, input)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn delete_task_template(
+    state: tauri::State<DatabaseState>,
+    id: String,
+) -> Result<(), SoloStackError> {
+    with_write_transaction(&state, |transaction| {
+        delete_task_template_record(transaction, &id)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{count_rows, TestDatabase};
+
+    fn outbox_rows(state: &DatabaseState, entity_id: &str, operation: SyncOperation) -> i64 {
+        count_rows(
+            state,
+            "SELECT COUNT(*) FROM sync_outbox WHERE entity_id = ?1 AND operation = ?2",
+            (entity_id, operation),
+        )
+    }
+
+    fn deleted_record_rows(state: &DatabaseState, entity_id: &str) -> i64 {
+        count_rows(
+            state,
+            "SELECT COUNT(*) FROM deleted_records WHERE entity_id = ?1",
+            [entity_id],
+        )
+    }
+
+    fn error_code<T>(result: Result<T, SoloStackError>) -> SoloStackErrorCode {
+        match result {
+            Ok(_) => panic!("expected the write to be rejected"),
+            Err(error) => error.code,
+        }
+    }
+
+    fn task_input(title: &str) -> CreateTaskInput {
+        CreateTaskInput {
+            title: title.to_string(),
+            description: None,
+            notes_markdown: None,
+            project_id: None,
+            priority: TaskPriority::Normal,
+            is_important: false,
+            due_at: None,
+            remind_at: None,
+            recurrence: None,
+            subtasks: Vec::new(),
+        }
+    }
+
+    fn create_task_with(state: &DatabaseState, input: CreateTaskInput) -> Task {
+        with_write_transaction(state, |transaction| create_task_record(transaction, input)).unwrap()
+    }
+
+    fn update_task_with(
+        state: &DatabaseState,
+        input: UpdateTaskInput,
+    ) -> Result<Task, SoloStackError> {
+        with_write_transaction(state, |transaction| update_task_record(transaction, input))
+    }
+
+    fn project_input(name: &str) -> CreateProjectInput {
+        CreateProjectInput {
+            name: name.to_string(),
+            description: None,
+            color: None,
+            status: None,
+        }
+    }
+
+    fn template_input(name: &str) -> UpsertTaskTemplateInput {
+        UpsertTaskTemplateInput {
+            id: None,
+            name: name.to_string(),
+            title_template: None,
+            description: None,
+            priority: TaskPriority::Normal,
+            is_important: false,
+            due_offset_minutes: None,
+            remind_offset_minutes: None,
+            recurrence: None,
+        }
+    }
+
+    #[test]
+    fn task_writes_record_changelog_outbox_and_tombstones() {
+        let state = TestDatabase::migrated();
+        let task = create_task_with(
+            &state,
+            CreateTaskInput {
+                notes_markdown: Some("line one\r\nline two".to_string()),
+                subtasks: vec![
+                    CreateTaskSubtaskDraft {
+                        title: " Find the statement ".to_string(),
+                        is_done: false,
+                    },
+                    CreateTaskSubtaskDraft {
+                        title: "   ".to_string(),
+                        is_done: true,
+                    },
+                ],
+                ..task_input("  Call the bank  ")
+            },
+        );
+        assert_eq!(task.title, "Call the bank");
+        assert_eq!(task.notes_markdown.as_deref(), Some("line one\nline two"));
+        assert_eq!(task.status, TaskStatus::Todo);
+        assert_eq!(task.sync_version, 1);
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM task_changelogs WHERE task_id = ?1 AND action = 'CREATED'",
+                [&task.id],
+            ),
+            1
+        );
+        assert_eq!(outbox_rows(&state, &task.id, SyncOperation::Upsert), 1);
+        let subtasks = list_task_subtask_records(&state.open().unwrap(), &task.id).unwrap();
+        assert_eq!(subtasks.len(), 1);
+        assert_eq!(subtasks[0].title, "Find the statement");
+        assert_eq!(
+            outbox_rows(&state, &subtasks[0].id, SyncOperation::Upsert),
+            1
+        );
+
+        let updated = update_task_with(
+            &state,
+            UpdateTaskInput {
+                id: task.id.clone(),
+                title: Some("Call the bank today".to_string()),
+                priority: Some(TaskPriority::Urgent),
+                description: Some(None),
+                ..UpdateTaskInput::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(updated.title, "Call the bank today");
+        assert_eq!(updated.priority, TaskPriority::Urgent);
+        assert_eq!(updated.sync_version, 2);
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM task_changelogs WHERE task_id = ?1 AND action = 'UPDATED'",
+                [&task.id],
+            ),
+            2
+        );
+
+        update_task_with(
+            &state,
+            UpdateTaskInput {
+                id: task.id.clone(),
+                status: Some(TaskStatus::Doing),
+                ..UpdateTaskInput::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM task_changelogs
+                  WHERE task_id = ?1 AND action = 'STATUS_CHANGED' AND new_value = 'DOING'",
+                [&task.id],
+            ),
+            1
+        );
+        assert_eq!(outbox_rows(&state, &task.id, SyncOperation::Upsert), 3);
+
+        with_write_transaction(&state, |transaction| {
+            delete_task_record(transaction, &task.id)
+        })
+        .unwrap();
+        assert!(find_task(&state.open().unwrap(), &task.id)
+            .unwrap()
+            .is_none());
+        assert_eq!(outbox_rows(&state, &task.id, SyncOperation::Delete), 1);
+        assert_eq!(
+            outbox_rows(&state, &subtasks[0].id, SyncOperation::Delete),
+            1
+        );
+        assert_eq!(deleted_record_rows(&state, &task.id), 1);
+        assert_eq!(deleted_record_rows(&state, &subtasks[0].id), 1);
+
+        // Deleting a task that is already gone is a no-op, not a second tombstone.
+        with_write_transaction(&state, |transaction| {
+            delete_task_record(transaction, &task.id)
+        })
+        .unwrap();
+        assert_eq!(outbox_rows(&state, &task.id, SyncOperation::Delete), 1);
+    }
+
+    #[test]
+    fn completing_a_recurring_task_schedules_the_next_one() {
+        let state = TestDatabase::migrated();
+        let task = create_task_with(
+            &state,
+            CreateTaskInput {
+                due_at: Some("2026-06-10T02:00:00.000Z".to_string()),
+                remind_at: Some("2026-06-10T01:30:00.000Z".to_string()),
+                recurrence: Some(TaskRecurrence::Daily),
+                ..task_input("Water the plants")
+            },
+        );
+        let complete = || {
+            update_task_with(
+                &state,
+                UpdateTaskInput {
+                    id: task.id.clone(),
+                    status: Some(TaskStatus::Done),
+                    ..UpdateTaskInput::default()
+                },
+            )
+            .unwrap()
+        };
+        assert_eq!(complete().status, TaskStatus::Done);
+
+        let tasks = list_task_records(&state.open().unwrap()).unwrap();
+        assert_eq!(tasks.len(), 2);
+        let next_task = tasks
+            .iter()
+            .find(|candidate| candidate.id != task.id)
+            .unwrap();
+        assert_eq!(next_task.title, "Water the plants");
+        assert_eq!(next_task.status, TaskStatus::Todo);
+        assert_eq!(next_task.recurrence, TaskRecurrence::Daily);
+        assert_eq!(
+            next_task.due_at.as_deref(),
+            Some("2026-06-11T02:00:00.000Z")
+        );
+        assert_eq!(
+            next_task.remind_at.as_deref(),
+            Some("2026-06-11T01:30:00.000Z")
+        );
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM task_changelogs WHERE task_id = ?1 AND action = 'CREATED'",
+                [&next_task.id],
+            ),
+            1
+        );
+        assert_eq!(outbox_rows(&state, &next_task.id, SyncOperation::Upsert), 1);
+
+        // Saving an already finished task again does not schedule another occurrence.
+        complete();
+        assert_eq!(list_task_records(&state.open().unwrap()).unwrap().len(), 2);
+
+        let one_off = create_task_with(
+            &state,
+            CreateTaskInput {
+                due_at: Some("2026-06-10T02:00:00.000Z".to_string()),
+                ..task_input("Renew the passport")
+            },
+        );
+        update_task_with(
+            &state,
+            UpdateTaskInput {
+                id: one_off.id,
+                status: Some(TaskStatus::Done),
+                ..UpdateTaskInput::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(list_task_records(&state.open().unwrap()).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn task_writes_reject_invalid_input() {
+        let state = TestDatabase::migrated();
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                create_task_record(transaction, task_input("   "))
+            })),
+            SoloStackErrorCode::DbTaskTitleRequired
+        );
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                create_task_record(
+                    transaction,
+                    CreateTaskInput {
+                        project_id: Some("missing-project".to_string()),
+                        ..task_input("Call the bank")
+                    },
+                )
+            })),
+            SoloStackErrorCode::DbSelectedProjectMissing
+        );
+        assert_eq!(
+            error_code(update_task_with(
+                &state,
+                UpdateTaskInput {
+                    id: "missing-task".to_string(),
+                    ..UpdateTaskInput::default()
+                },
+            )),
+            SoloStackErrorCode::DbTaskNotFound
+        );
+
+        let task = create_task_with(&state, task_input("Call the bank"));
+        assert_eq!(
+            error_code(update_task_with(
+                &state,
+                UpdateTaskInput {
+                    id: task.id.clone(),
+                    title: Some(" ".to_string()),
+                    ..UpdateTaskInput::default()
+                },
+            )),
+            SoloStackErrorCode::DbTaskTitleRequired
+        );
+        assert_eq!(
+            error_code(update_task_with(
+                &state,
+                UpdateTaskInput {
+                    id: task.id.clone(),
+                    project_id: Some(Some("missing-project".to_string())),
+                    ..UpdateTaskInput::default()
+                },
+            )),
+            SoloStackErrorCode::DbSelectedProjectMissing
+        );
+
+        // Rejected writes roll back without leaving changelog or outbox rows behind.
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM tasks WHERE id != ?1",
+                [&task.id],
+            ),
+            0
+        );
+        assert_eq!(outbox_rows(&state, &task.id, SyncOperation::Upsert), 1);
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM task_changelogs WHERE task_id = ?1",
+                [&task.id],
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn project_writes_record_outbox_and_release_their_tasks() {
+        let state = TestDatabase::migrated();
+        let project = with_write_transaction(&state, |transaction| {
+            create_project_record(
+                transaction,
+                CreateProjectInput {
+                    description: Some("  ".to_string()),
+                    ..project_input(" Household ")
+                },
+            )
+        })
+        .unwrap();
+        assert_eq!(project.name, "Household");
+        assert_eq!(project.description, None);
+        assert_eq!(project.status, ProjectStatus::Active);
+        assert_eq!(outbox_rows(&state, &project.id, SyncOperation::Upsert), 1);
+
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                create_project_record(transaction, project_input("HOUSEHOLD"))
+            })),
+            SoloStackErrorCode::DbProjectNameExists
+        );
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                create_project_record(transaction, project_input(""))
+            })),
+            SoloStackErrorCode::DbProjectNameRequired
+        );
+
+        // An update with no fields returns the project without bumping it.
+        let unchanged = with_write_transaction(&state, |transaction| {
+            update_project_record(
+                transaction,
+                UpdateProjectInput {
+                    id: project.id.clone(),
+                    name: None,
+                    description: None,
+                    color: None,
+                    status: None,
+                },
+            )
+        })
+        .unwrap();
+        assert_eq!(unchanged.sync_version, 1);
+        assert_eq!(outbox_rows(&state, &project.id, SyncOperation::Upsert), 1);
+
+        let updated = with_write_transaction(&state, |transaction| {
+            update_project_record(
+                transaction,
+                UpdateProjectInput {
+                    id: project.id.clone(),
+                    name: Some("household".to_string()),
+                    description: None,
+                    color: Some(Some("#22c55e".to_string())),
+                    status: Some(ProjectStatus::Completed),
+                },
+            )
+        })
+        .unwrap();
+        assert_eq!(updated.name, "household");
+        assert_eq!(updated.color.as_deref(), Some("#22c55e"));
+        assert_eq!(updated.status, ProjectStatus::Completed);
+        assert_eq!(updated.sync_version, 2);
+        assert_eq!(outbox_rows(&state, &project.id, SyncOperation::Upsert), 2);
+
+        let other = with_write_transaction(&state, |transaction| {
+            create_project_record(transaction, project_input("Work"))
+        })
+        .unwrap();
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                update_project_record(
+                    transaction,
+                    UpdateProjectInput {
+                        id: other.id.clone(),
+                        name: Some("HOUSEHOLD".to_string()),
+                        description: None,
+                        color: None,
+                        status: None,
+                    },
+                )
+            })),
+            SoloStackErrorCode::DbProjectNameExists
+        );
+
+        let task = create_task_with(
+            &state,
+            CreateTaskInput {
+                project_id: Some(project.id.clone()),
+                ..task_input("Fix the sink")
+            },
+        );
+        with_write_transaction(&state, |transaction| {
+            delete_project_record(transaction, &project.id)
+        })
+        .unwrap();
+        let released = find_task(&state.open().unwrap(), &task.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(released.project_id, None);
+        assert_eq!(released.sync_version, 2);
+        assert_eq!(outbox_rows(&state, &task.id, SyncOperation::Upsert), 2);
+        assert_eq!(outbox_rows(&state, &project.id, SyncOperation::Delete), 1);
+        assert_eq!(deleted_record_rows(&state, &project.id), 1);
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                update_project_record(
+                    transaction,
+                    UpdateProjectInput {
+                        id: project.id.clone(),
+                        name: Some("Household".to_string()),
+                        description: None,
+                        color: None,
+                        status: None,
+                    },
+                )
+            })),
+            SoloStackErrorCode::DbProjectNotFound
+        );
+    }
+
+    #[test]
+    fn subtask_writes_record_outbox_and_tombstones() {
+        let state = TestDatabase::migrated();
+        let task = create_task_with(&state, task_input("Call the bank"));
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                create_task_subtask_record(
+                    transaction,
+                    CreateTaskSubtaskInput {
+                        task_id: "missing-task".to_string(),
+                        title: "Find the statement".to_string(),
+                        is_done: false,
+                    },
+                )
+            })),
+            SoloStackErrorCode::DbTaskNotFound
+        );
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                create_task_subtask_record(
+                    transaction,
+                    CreateTaskSubtaskInput {
+                        task_id: task.id.clone(),
+                        title: " ".to_string(),
+                        is_done: false,
+                    },
+                )
+            })),
+            SoloStackErrorCode::DbSubtaskTitleRequired
+        );
+
+        let subtask = with_write_transaction(&state, |transaction| {
+            create_task_subtask_record(
+                transaction,
+                CreateTaskSubtaskInput {
+                    task_id: task.id.clone(),
+                    title: "Find the statement".to_string(),
+                    is_done: false,
+                },
+            )
+        })
+        .unwrap();
+        assert_eq!(subtask.is_done, 0);
+        assert_eq!(outbox_rows(&state, &subtask.id, SyncOperation::Upsert), 1);
+
+        let done = with_write_transaction(&state, |transaction| {
+            update_task_subtask_record(
+                transaction,
+                UpdateTaskSubtaskInput {
+                    id: subtask.id.clone(),
+                    title: None,
+                    is_done: Some(true),
+                },
+            )
+        })
+        .unwrap();
+        assert_eq!(done.is_done, 1);
+        assert_eq!(done.sync_version, 2);
+        assert_eq!(outbox_rows(&state, &subtask.id, SyncOperation::Upsert), 2);
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                update_task_subtask_record(
+                    transaction,
+                    UpdateTaskSubtaskInput {
+                        id: subtask.id.clone(),
+                        title: Some("".to_string()),
+                        is_done: None,
+                    },
+                )
+            })),
+            SoloStackErrorCode::DbSubtaskTitleRequired
+        );
+
+        with_write_transaction(&state, |transaction| {
+            delete_task_subtask_record(transaction, &subtask.id)
+        })
+        .unwrap();
+        assert!(list_task_subtask_records(&state.open().unwrap(), &task.id)
+            .unwrap()
+            .is_empty());
+        assert_eq!(outbox_rows(&state, &subtask.id, SyncOperation::Delete), 1);
+        assert_eq!(deleted_record_rows(&state, &subtask.id), 1);
+        assert_eq!(
+            error_code(with_write_transaction(&state, |transaction| {
+                update_task_subtask_record(
+                    transaction,
+                    UpdateTaskSubtaskInput {
+                        id: subtask.id.clone(),
+                        title: None,
+                        is_done: Some(false),
+                    },
+                )
+            })),
+            SoloStackErrorCode::DbSubtaskNotFound
+        );
+    }
+
+    #[test]
+    fn template_upserts_match_by_name_and_validate_offsets() {
+        let state = TestDatabase::migrated();
+        let upsert = |input: UpsertTaskTemplateInput| {
+            with_write_transaction(&state, |transaction| {
+                upsert_task_template_record(transaction, input)
+            })
+        };
+
+        assert_eq!(
+            error_code(upsert(template_input(" "))),
+            SoloStackErrorCode::DbTemplateNameRequired
+        );
+        assert_eq!(
+            error_code(upsert(UpsertTaskTemplateInput {
+                recurrence: Some(TaskRecurrence::Weekly),
+                ..template_input("Weekly review")
+            })),
+            SoloStackErrorCode::DbRecurringTemplateDueOffsetRequired
+        );
+        assert_eq!(
+            error_code(upsert(UpsertTaskTemplateInput {
+                due_offset_minutes: Some(60.0),
+                remind_offset_minutes: Some(90.0),
+                ..template_input("Weekly review")
+            })),
+            SoloStackErrorCode::DbReminderOffsetMustBeEarlier
+        );
+
+        let template = upsert(UpsertTaskTemplateInput {
+            due_offset_minutes: Some(120.4),
+            remind_offset_minutes: Some(-5.0),
+            recurrence: Some(TaskRecurrence::Weekly),
+            ..template_input("Weekly review")
+        })
+        .unwrap();
+        assert_eq!(template.due_offset_minutes, Some(120));
+        assert_eq!(template.remind_offset_minutes, Some(0));
+        assert_eq!(template.sync_version, 1);
+        assert_eq!(outbox_rows(&state, &template.id, SyncOperation::Upsert), 1);
+
+        // Saving under an existing name updates that template instead of adding a second one.
+        let renamed = upsert(UpsertTaskTemplateInput {
+            title_template: Some("Review the week".to_string()),
+            ..template_input("WEEKLY REVIEW")
+        })
+        .unwrap();
+        assert_eq!(renamed.id, template.id);
+        assert_eq!(renamed.name, "WEEKLY REVIEW");
+        assert_eq!(renamed.sync_version, 2);
+        assert_eq!(outbox_rows(&state, &template.id, SyncOperation::Upsert), 2);
+        assert_eq!(
+            list_task_template_records(&state.open().unwrap())
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let other = upsert(template_input("Inbox zero")).unwrap();
+        assert_eq!(
+            error_code(upsert(UpsertTaskTemplateInput {
+                id: Some(other.id.clone()),
+                ..template_input("weekly review")
+            })),
+            SoloStackErrorCode::DbTemplateNameExists
+        );
+
+        with_write_transaction(&state, |transaction| {
+            delete_task_template_record(transaction, &template.id)
+        })
+        .unwrap();
+        assert_eq!(
+            list_task_template_records(&state.open().unwrap())
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(outbox_rows(&state, &template.id, SyncOperation::Delete), 1);
+        assert_eq!(deleted_record_rows(&state, &template.id), 1);
+    }
+}
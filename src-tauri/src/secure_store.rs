@@ -0,0 +1,903 @@
+// Storage behind the sync provider auth commands. Each platform provides a `SecureStoreBackend`
+// (OS keyring, Android EncryptedSharedPreferences, the encrypted file fallback, or memory for
+// tests and unsupported targets); one is picked at startup and kept in managed state, so the
+// command bodies never branch on the target OS.
+
+use serde::Serialize;
+use std::sync::{Mutex, MutexGuard};
+
+#[cfg(any(
+    test,
+    not(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios",
+        target_os = "android"
+    ))
+))]
+use std::collections::HashMap;
+#[cfg(any(
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "ios"
+))]
+use std::path::Path;
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    secure_store_audit::{
+        SecureStoreAuditLog, SecureStoreAuditOperation, SecureStoreAuditOutcome,
+        SecureStoreAuditPage,
+    },
+};
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "ios"
+))]
+const SYNC_PROVIDER_AUTH_SERVICE: &str = "com.solutionsstudio.solostack.sync-provider-auth";
+// Never a valid provider key, so the self-test cannot overwrite managed provider tokens.
+const SELF_TEST_KEY: &str = "__sync_provider_secure_store_self_test__";
+const SELF_TEST_PAYLOAD: &str = "solostack-secure-store-self-test";
+
+pub(crate) trait SecureStoreBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // Cheap availability check; the self-test reports `available: false` when it fails.
+    fn probe(&self) -> Result<(), SoloStackError> {
+        Ok(())
+    }
+
+    fn read(&self, key: &str) -> Result<Option<String>, SoloStackError>;
+
+    fn write(&self, key: &str, value: &str) -> Result<(), SoloStackError>;
+
+    // Deleting a missing key succeeds.
+    fn delete(&self, key: &str) -> Result<(), SoloStackError>;
+
+    // Like `read` and `write`, but also name the backend that served the call, for the audit log.
+    // Only stores that pick a backend per call need to override these.
+    fn read_from(&self, key: &str) -> (&'static str, Result<Option<String>, SoloStackError>) {
+        (self.name(), self.read(key))
+    }
+
+    fn write_to(&self, key: &str, value: &str) -> (&'static str, Result<(), SoloStackError>) {
+        (self.name(), self.write(key, value))
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct SyncProviderSecureStoreSelfTestResult {
+    pub(crate) runtime: String,
+    pub(crate) backend: String,
+    pub(crate) available: bool,
+    pub(crate) write_ok: bool,
+    pub(crate) read_ok: bool,
+    pub(crate) delete_ok: bool,
+    pub(crate) roundtrip_ok: bool,
+    pub(crate) detail: Option<String>,
+    pub(crate) primary_backend: String,
+    pub(crate) fallback_reason: Option<String>,
+}
+
+// Keyring and encrypted-file entries share this account naming, so switching between them keeps
+// the same keys.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "ios"
+))]
+pub(crate) fn sync_provider_auth_account(key: &str) -> String {
+    format!("sync-provider::{key}")
+}
+
+fn run_backend_self_test(
+    backend: &dyn SecureStoreBackend,
+) -> SyncProviderSecureStoreSelfTestResult {
+    let result =
+        |available, write_ok, read_ok, delete_ok, detail| SyncProviderSecureStoreSelfTestResult {
+            runtime: "tauri".to_string(),
+            backend: backend.name().to_string(),
+            available,
+            write_ok,
+            read_ok,
+            delete_ok,
+            roundtrip_ok: available && write_ok && read_ok && delete_ok,
+            detail,
+            primary_backend: backend.name().to_string(),
+            fallback_reason: None,
+        };
+    if let Err(error) = backend.probe() {
+        return result(false, false, false, false, Some(error.to_string()));
+    }
+
+    let mut detail: Option<String> = None;
+    let write_ok = match backend.write(SELF_TEST_KEY, SELF_TEST_PAYLOAD) {
+        Ok(()) => true,
+        Err(error) => {
+            detail = Some(format!("write failed: {error}"));
+            false
+        }
+    };
+
+    let read_ok = if write_ok {
+        match backend.read(SELF_TEST_KEY) {
+            Ok(Some(value)) if value == SELF_TEST_PAYLOAD => true,
+            Ok(Some(_)) => {
+                detail = Some("read payload mismatch".to_string());
+                false
+            }
+            Ok(None) => {
+                detail = Some("read payload missing".to_string());
+                false
+            }
+            Err(error) => {
+                detail = Some(format!("read failed: {error}"));
+                false
+            }
+        }
+    } else {
+        false
+    };
+
+    let delete_ok = match backend.delete(SELF_TEST_KEY) {
+        Ok(()) => true,
+        Err(error) => {
+            if detail.is_none() {
+                detail = Some(format!("delete failed: {error}"));
+            }
+            false
+        }
+    };
+
+    result(true, write_ok, read_ok, delete_ok, detail)
+}
+
+struct SecureStoreFallback {
+    primary_backend: &'static str,
+    reason: String,
+}
+
+pub(crate) struct SecureStoreState {
+    backend: Box<dyn SecureStoreBackend>,
+    fallback: Option<SecureStoreFallback>,
+    audit_log: Option<SecureStoreAuditLog>,
+    // Serializes read-modify-write cycles on provider credentials, so a background refresh cannot
+    // overwrite a sign-in that landed while its token request was in flight.
+    credential_lock: Mutex<()>,
+}
+
+impl SecureStoreState {
+    pub(crate) fn new(backend: Box<dyn SecureStoreBackend>) -> Self {
+        Self {
+            backend,
+            fallback: None,
+            audit_log: None,
+            credential_lock: Mutex::new(()),
+        }
+    }
+
+    pub(crate) fn with_audit_log(self, audit_log: SecureStoreAuditLog) -> Self {
+        Self {
+            audit_log: Some(audit_log),
+            ..self
+        }
+    }
+
+    fn audit(
+        &self,
+        operation: SecureStoreAuditOperation,
+        key: Option<&str>,
+        outcome: SecureStoreAuditOutcome,
+        error: Option<&SoloStackError>,
+    ) {
+        self.audit_from(self.backend.name(), operation, key, outcome, error);
+    }
+
+    fn audit_from(
+        &self,
+        backend: &str,
+        operation: SecureStoreAuditOperation,
+        key: Option<&str>,
+        outcome: SecureStoreAuditOutcome,
+        error: Option<&SoloStackError>,
+    ) {
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record(
+                operation,
+                key,
+                backend,
+                outcome,
+                error.map(|error| error.code),
+            );
+        }
+    }
+
+    fn audit_result(
+        &self,
+        backend: &str,
+        operation: SecureStoreAuditOperation,
+        key: &str,
+        result: &Result<(), SoloStackError>,
+    ) {
+        match result {
+            Ok(()) => self.audit_from(
+                backend,
+                operation,
+                Some(key),
+                SecureStoreAuditOutcome::Ok,
+                None,
+            ),
+            Err(error) => self.audit_from(
+                backend,
+                operation,
+                Some(key),
+                SecureStoreAuditOutcome::Failed,
+                Some(error),
+            ),
+        }
+    }
+
+    // Machines without a working keyring (no Secret Service or keyutils session) keep provider
+    // auth in the encrypted file instead of silently losing it. The fallback is taken per call,
+    // so a keyring that is only briefly unavailable at startup does not hide its entries for the
+    // whole session; the startup probe only decides what the self-test reports.
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios"
+    ))]
+    pub(crate) fn select_native(app_data_dir: Option<&Path>) -> Self {
+        let Some(app_data_dir) = app_data_dir else {
+            return Self::new(Box::new(KeyringSecureStore));
+        };
+        let probe = run_backend_self_test(&KeyringSecureStore);
+        let backend = LayeredSecureStore {
+            primary: Box::new(KeyringSecureStore),
+            fallback: Box::new(
+                crate::encrypted_file_store::EncryptedFileStore::in_app_data_dir(app_data_dir),
+            ),
+            primary_failed_probe: !probe.roundtrip_ok,
+        };
+        let fallback = (!probe.roundtrip_ok).then(|| SecureStoreFallback {
+            primary_backend: backend.primary.name(),
+            reason: probe
+                .detail
+                .unwrap_or_else(|| "keyring roundtrip failed".to_string()),
+        });
+        Self {
+            fallback,
+            ..Self::new(Box::new(backend))
+        }
+    }
+
+    fn read_backend(&self, key: &str) -> (&'static str, Result<Option<String>, SoloStackError>) {
+        let (backend, result) = self.backend.read_from(key);
+        let result = result.map(|value| {
+            value
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        });
+        (backend, result)
+    }
+
+    pub(crate) fn read(&self, key: &str) -> Result<Option<String>, SoloStackError> {
+        let (backend, result) = self.read_backend(key);
+        let (outcome, error) = match &result {
+            Ok(Some(_)) => (SecureStoreAuditOutcome::Ok, None),
+            Ok(None) => (SecureStoreAuditOutcome::NotFound, None),
+            Err(error) => (SecureStoreAuditOutcome::Failed, Some(error)),
+        };
+        self.audit_from(
+            backend,
+            SecureStoreAuditOperation::Read,
+            Some(key),
+            outcome,
+            error,
+        );
+        result
+    }
+
+    // For the token refresh poll, which reads every provider's entries once a minute. Only
+    // failures are audited: recording each idle look would bury the accesses the log exists to
+    // show, and whatever the poll goes on to read under the lock or write is audited as usual.
+    pub(crate) fn poll_read(&self, key: &str) -> Result<Option<String>, SoloStackError> {
+        let (backend, result) = self.read_backend(key);
+        if let Err(error) = &result {
+            self.audit_from(
+                backend,
+                SecureStoreAuditOperation::Read,
+                Some(key),
+                SecureStoreAuditOutcome::Failed,
+                Some(error),
+            );
+        }
+        result
+    }
+
+    pub(crate) fn write(&self, key: &str, value: &str) -> Result<(), SoloStackError> {
+        let (backend, result) = self.backend.write_to(key, value);
+        self.audit_result(backend, SecureStoreAuditOperation::Write, key, &result);
+        result
+    }
+
+    pub(crate) fn delete(&self, key: &str) -> Result<(), SoloStackError> {
+        let result = self.backend.delete(key);
+        self.audit_result(
+            self.backend.name(),
+            SecureStoreAuditOperation::Delete,
+            key,
+            &result,
+        );
+        result
+    }
+
+    pub(crate) fn audit_page(
+        &self,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<SecureStoreAuditPage, SoloStackError> {
+        match &self.audit_log {
+            Some(audit_log) => audit_log.page(offset, limit),
+            None => Err(SoloStackError::with_detail(
+                SoloStackErrorCode::AppDataDirUnavailable,
+                "secure store audit log needs the app data dir",
+            )),
+        }
+    }
+
+    pub(crate) fn lock_credentials(&self) -> Result<MutexGuard<'_, ()>, SoloStackError> {
+        self.credential_lock.lock().map_err(|_| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::Internal,
+                "sync provider credential lock poisoned",
+            )
+        })
+    }
+
+    pub(crate) fn self_test(&self) -> SyncProviderSecureStoreSelfTestResult {
+        let mut result = run_backend_self_test(self.backend.as_ref());
+        let outcome = if result.roundtrip_ok {
+            SecureStoreAuditOutcome::Ok
+        } else {
+            SecureStoreAuditOutcome::Failed
+        };
+        self.audit(SecureStoreAuditOperation::SelfTest, None, outcome, None);
+        if let Some(fallback) = &self.fallback {
+            result.primary_backend = fallback.primary_backend.to_string();
+            result.fallback_reason = Some(fallback.reason.clone());
+        }
+        result
+    }
+}
+
+// The keyring backed by the encrypted file, one call at a time. Reads try the keyring first and
+// then the file, which holds whatever was written while the keyring was unavailable; a write that
+// lands in the keyring drops the file copy, so entries move back as they are rewritten.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "ios"
+))]
+struct LayeredSecureStore {
+    primary: Box<dyn SecureStoreBackend>,
+    fallback: Box<dyn SecureStoreBackend>,
+    // Set when the startup probe failed: audit records and the self-test then name the file, and
+    // a keyring error on read means "not there" rather than "cannot tell".
+    primary_failed_probe: bool,
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "ios"
+))]
+impl SecureStoreBackend for LayeredSecureStore {
+    fn name(&self) -> &'static str {
+        if self.primary_failed_probe {
+            self.fallback.name()
+        } else {
+            self.primary.name()
+        }
+    }
+
+    fn probe(&self) -> Result<(), SoloStackError> {
+        self.primary.probe().or_else(|_| self.fallback.probe())
+    }
+
+    fn read(&self, key: &str) -> Result<Option<String>, SoloStackError> {
+        self.read_from(key).1
+    }
+
+    fn write(&self, key: &str, value: &str) -> Result<(), SoloStackError> {
+        self.write_to(key, value).1
+    }
+
+    fn read_from(&self, key: &str) -> (&'static str, Result<Option<String>, SoloStackError>) {
+        match self.primary.read(key) {
+            Ok(Some(value)) => (self.primary.name(), Ok(Some(value))),
+            Ok(None) => (self.fallback.name(), self.fallback.read(key)),
+            Err(_) if self.primary_failed_probe => (self.fallback.name(), self.fallback.read(key)),
+            // A keyring that worked at startup may still hold the entry once it is back.
+            Err(error) => match self.fallback.read(key) {
+                Ok(Some(value)) => (self.fallback.name(), Ok(Some(value))),
+                Ok(None) => (self.primary.name(), Err(error)),
+                Err(fallback_error) => (self.fallback.name(), Err(fallback_error)),
+            },
+        }
+    }
+
+    // The keyring copy is read first, so a failed keyring write must not leave the old value
+    // there: a rotated refresh token would keep being served after the provider revoked it. The
+    // old copy is removed before the file takes the write, and the write fails if it survives.
+    fn write_to(&self, key: &str, value: &str) -> (&'static str, Result<(), SoloStackError>) {
+        let error = match self.primary.write(key, value) {
+            Ok(()) => {
+                // A stale file copy only lingers until the next write if this delete fails.
+                let _ = self.fallback.delete(key);
+                return (self.primary.name(), Ok(()));
+            }
+            Err(error) => error,
+        };
+        let _ = self.primary.delete(key);
+        if matches!(self.primary.read(key), Ok(Some(_))) {
+            return (self.primary.name(), Err(error));
+        }
+        (self.fallback.name(), self.fallback.write(key, value))
+    }
+
+    // A keyring delete that fails while the entry is still readable there is a real failure;
+    // one that fails because the keyring cannot be reached leaves nothing this session can serve.
+    fn delete(&self, key: &str) -> Result<(), SoloStackError> {
+        self.fallback.delete(key)?;
+        match self.primary.delete(key) {
+            Ok(()) => Ok(()),
+            Err(error) => match self.primary.read(key) {
+                Ok(Some(_)) => Err(error),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "ios"
+))]
+pub(crate) struct KeyringSecureStore;
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "ios"
+))]
+impl KeyringSecureStore {
+    fn entry(&self, key: &str) -> Result<keyring::Entry, SoloStackError> {
+        keyring::Entry::new(SYNC_PROVIDER_AUTH_SERVICE, &sync_provider_auth_account(key)).map_err(
+            |error| {
+                SoloStackError::with_detail(
+                    SoloStackErrorCode::SecureStoreUnavailable,
+                    format!("create keyring entry failed: {error}"),
+                )
+            },
+        )
+    }
+}
+
+#[cfg(any(
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "ios"
+))]
+impl SecureStoreBackend for KeyringSecureStore {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn probe(&self) -> Result<(), SoloStackError> {
+        self.entry(SELF_TEST_KEY).map(|_| ())
+    }
+
+    fn read(&self, key: &str) -> Result<Option<String>, SoloStackError> {
+        match self.entry(key)?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(error) => Err(SoloStackError::with_detail(
+                SoloStackErrorCode::SecureStoreReadFailed,
+                format!("read secure auth failed: {error}"),
+            )),
+        }
+    }
+
+    fn write(&self, key: &str, value: &str) -> Result<(), SoloStackError> {
+        self.entry(key)?.set_password(value).map_err(|error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::SecureStoreWriteFailed,
+                format!("store secure auth failed: {error}"),
+            )
+        })
+    }
+
+    fn delete(&self, key: &str) -> Result<(), SoloStackError> {
+        match self.entry(key)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(error) => Err(SoloStackError::with_detail(
+                SoloStackErrorCode::SecureStoreDeleteFailed,
+                format!("delete secure auth failed: {error}"),
+            )),
+        }
+    }
+}
+
+// Used by tests, and on targets without a native secure store where auth only lives for the
+// session.
+#[cfg(any(
+    test,
+    not(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios",
+        target_os = "android"
+    ))
+))]
+#[derive(Default)]
+pub(crate) struct InMemorySecureStore {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+#[cfg(any(
+    test,
+    not(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios",
+        target_os = "android"
+    ))
+))]
+impl SecureStoreBackend for InMemorySecureStore {
+    fn name(&self) -> &'static str {
+        "in_memory"
+    }
+
+    fn read(&self, key: &str) -> Result<Option<String>, SoloStackError> {
+        let entries = self
+            .entries
+            .lock()
+            .map_err(|_| SoloStackError::new(SoloStackErrorCode::SecureStoreReadFailed))?;
+        Ok(entries.get(key).cloned())
+    }
+
+    fn write(&self, key: &str, value: &str) -> Result<(), SoloStackError> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| SoloStackError::new(SoloStackErrorCode::SecureStoreWriteFailed))?;
+        entries.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> Result<(), SoloStackError> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| SoloStackError::new(SoloStackErrorCode::SecureStoreDeleteFailed))?;
+        entries.remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FailingWriteStore;
+
+    impl SecureStoreBackend for FailingWriteStore {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn read(&self, _key: &str) -> Result<Option<String>, SoloStackError> {
+            Ok(None)
+        }
+
+        fn write(&self, _key: &str, _value: &str) -> Result<(), SoloStackError> {
+            Err(SoloStackError::with_detail(
+                SoloStackErrorCode::SecureStoreWriteFailed,
+                "disk full",
+            ))
+        }
+
+        fn delete(&self, _key: &str) -> Result<(), SoloStackError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn self_test_roundtrips_through_the_backend() {
+        let state = SecureStoreState::new(Box::new(InMemorySecureStore::default()));
+        let result = state.self_test();
+        assert!(result.roundtrip_ok);
+        assert_eq!(result.backend, "in_memory");
+        assert_eq!(result.primary_backend, "in_memory");
+        assert!(state.read(SELF_TEST_KEY).unwrap().is_none());
+    }
+
+    #[test]
+    fn self_test_reports_write_failures() {
+        let state = SecureStoreState::new(Box::new(FailingWriteStore));
+        let result = state.self_test();
+        assert!(result.available);
+        assert!(!result.write_ok);
+        assert!(!result.roundtrip_ok);
+        assert_eq!(
+            result.detail.as_deref(),
+            Some("write failed: SECURE_STORE_WRITE_FAILED: disk full")
+        );
+    }
+
+    #[test]
+    fn self_test_names_the_primary_backend_after_fallback() {
+        let state = SecureStoreState {
+            fallback: Some(SecureStoreFallback {
+                primary_backend: "keyring",
+                reason: "no secret service".to_string(),
+            }),
+            ..SecureStoreState::new(Box::new(InMemorySecureStore::default()))
+        };
+        let result = state.self_test();
+        assert_eq!(result.backend, "in_memory");
+        assert_eq!(result.primary_backend, "keyring");
+        assert_eq!(result.fallback_reason.as_deref(), Some("no secret service"));
+    }
+
+    struct UnavailableStore;
+
+    impl SecureStoreBackend for UnavailableStore {
+        fn name(&self) -> &'static str {
+            "unavailable"
+        }
+
+        fn read(&self, _key: &str) -> Result<Option<String>, SoloStackError> {
+            Err(SoloStackError::new(
+                SoloStackErrorCode::SecureStoreUnavailable,
+            ))
+        }
+
+        fn write(&self, _key: &str, _value: &str) -> Result<(), SoloStackError> {
+            Err(SoloStackError::new(
+                SoloStackErrorCode::SecureStoreUnavailable,
+            ))
+        }
+
+        fn delete(&self, _key: &str) -> Result<(), SoloStackError> {
+            Err(SoloStackError::new(
+                SoloStackErrorCode::SecureStoreUnavailable,
+            ))
+        }
+    }
+
+    // Lets a test keep a handle on a backend after boxing it into a layered store.
+    impl SecureStoreBackend for std::sync::Arc<InMemorySecureStore> {
+        fn name(&self) -> &'static str {
+            self.as_ref().name()
+        }
+
+        fn read(&self, key: &str) -> Result<Option<String>, SoloStackError> {
+            self.as_ref().read(key)
+        }
+
+        fn write(&self, key: &str, value: &str) -> Result<(), SoloStackError> {
+            self.as_ref().write(key, value)
+        }
+
+        fn delete(&self, key: &str) -> Result<(), SoloStackError> {
+            self.as_ref().delete(key)
+        }
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios"
+    ))]
+    #[test]
+    fn layered_store_uses_the_file_while_the_keyring_is_unavailable() {
+        let file = std::sync::Arc::new(InMemorySecureStore::default());
+        let store = LayeredSecureStore {
+            primary: Box::new(UnavailableStore),
+            fallback: Box::new(file.clone()),
+            primary_failed_probe: true,
+        };
+        assert_eq!(store.name(), "in_memory");
+        assert_eq!(store.read("google_appdata").unwrap(), None);
+
+        store.write("google_appdata", "token").unwrap();
+        assert_eq!(
+            file.read("google_appdata").unwrap().as_deref(),
+            Some("token")
+        );
+        assert_eq!(
+            store.read("google_appdata").unwrap().as_deref(),
+            Some("token")
+        );
+
+        store.delete("google_appdata").unwrap();
+        assert_eq!(file.read("google_appdata").unwrap(), None);
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios"
+    ))]
+    #[test]
+    fn layered_store_reads_both_backends_and_moves_entries_back_on_write() {
+        let keyring = std::sync::Arc::new(InMemorySecureStore::default());
+        let file = std::sync::Arc::new(InMemorySecureStore::default());
+        keyring.write("google_appdata", "keyring-token").unwrap();
+        file.write("onedrive_approot", "file-token").unwrap();
+        let store = LayeredSecureStore {
+            primary: Box::new(keyring.clone()),
+            fallback: Box::new(file.clone()),
+            primary_failed_probe: false,
+        };
+
+        assert_eq!(
+            store.read("google_appdata").unwrap().as_deref(),
+            Some("keyring-token")
+        );
+        assert_eq!(
+            store.read("onedrive_approot").unwrap().as_deref(),
+            Some("file-token")
+        );
+
+        store.write("onedrive_approot", "rotated").unwrap();
+        assert_eq!(
+            keyring.read("onedrive_approot").unwrap().as_deref(),
+            Some("rotated")
+        );
+        assert_eq!(file.read("onedrive_approot").unwrap(), None);
+
+        file.write("google_appdata", "stale").unwrap();
+        store.delete("google_appdata").unwrap();
+        assert_eq!(store.read("google_appdata").unwrap(), None);
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios"
+    ))]
+    #[test]
+    fn layered_store_reports_a_keyring_that_failed_after_startup() {
+        let store = LayeredSecureStore {
+            primary: Box::new(UnavailableStore),
+            fallback: Box::new(InMemorySecureStore::default()),
+            primary_failed_probe: false,
+        };
+        assert_eq!(store.name(), "unavailable");
+        assert_eq!(
+            store.read("google_appdata").unwrap_err().code,
+            SoloStackErrorCode::SecureStoreUnavailable
+        );
+    }
+
+    // A keyring that still serves what it holds but rejects writes, optionally deletes too.
+    #[derive(Default)]
+    struct ReadOnlyKeyring {
+        entries: InMemorySecureStore,
+        delete_fails: bool,
+    }
+
+    impl SecureStoreBackend for ReadOnlyKeyring {
+        fn name(&self) -> &'static str {
+            "keyring"
+        }
+
+        fn read(&self, key: &str) -> Result<Option<String>, SoloStackError> {
+            self.entries.read(key)
+        }
+
+        fn write(&self, _key: &str, _value: &str) -> Result<(), SoloStackError> {
+            Err(SoloStackError::new(
+                SoloStackErrorCode::SecureStoreWriteFailed,
+            ))
+        }
+
+        fn delete(&self, key: &str) -> Result<(), SoloStackError> {
+            if self.delete_fails {
+                return Err(SoloStackError::new(
+                    SoloStackErrorCode::SecureStoreDeleteFailed,
+                ));
+            }
+            self.entries.delete(key)
+        }
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios"
+    ))]
+    #[test]
+    fn failed_keyring_write_does_not_leave_the_old_value_readable() {
+        let keyring = ReadOnlyKeyring::default();
+        keyring
+            .entries
+            .write("google_appdata", "refresh-1")
+            .unwrap();
+        let app_data_dir =
+            std::env::temp_dir().join(format!("solostack-layered-audit-{}", uuid::Uuid::new_v4()));
+        let state = SecureStoreState::new(Box::new(LayeredSecureStore {
+            primary: Box::new(keyring),
+            fallback: Box::new(InMemorySecureStore::default()),
+            primary_failed_probe: false,
+        }))
+        .with_audit_log(SecureStoreAuditLog::in_app_data_dir(&app_data_dir));
+
+        state.write("google_appdata", "refresh-2").unwrap();
+        assert_eq!(
+            state.read("google_appdata").unwrap().as_deref(),
+            Some("refresh-2")
+        );
+        // Both the write and the read were served by the file, and the audit log says so.
+        let page = state.audit_page(None, Some(10)).unwrap();
+        assert_eq!(page.records.len(), 2);
+        assert!(page
+            .records
+            .iter()
+            .all(|record| record.backend == "in_memory"));
+        std::fs::remove_dir_all(app_data_dir).unwrap();
+    }
+
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios"
+    ))]
+    #[test]
+    fn failed_keyring_write_is_reported_when_the_old_value_cannot_be_removed() {
+        let keyring = ReadOnlyKeyring {
+            delete_fails: true,
+            ..ReadOnlyKeyring::default()
+        };
+        keyring
+            .entries
+            .write("google_appdata", "refresh-1")
+            .unwrap();
+        let file = std::sync::Arc::new(InMemorySecureStore::default());
+        let store = LayeredSecureStore {
+            primary: Box::new(keyring),
+            fallback: Box::new(file.clone()),
+            primary_failed_probe: false,
+        };
+
+        let (backend, result) = store.write_to("google_appdata", "refresh-2");
+        assert_eq!(backend, "keyring");
+        assert_eq!(
+            result.unwrap_err().code,
+            SoloStackErrorCode::SecureStoreWriteFailed
+        );
+        assert_eq!(file.read("google_appdata").unwrap(), None);
+    }
+}
@@ -0,0 +1,312 @@
+// Append-only record of secure store access, one JSON line per operation in a daily file under
+// the app data dir, pruned after a retention window (the same layout as the MCP server's file
+// audit sink). Records only name the entry and the outcome; secrets, account ids and error
+// details never reach the log.
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    sync_provider_accounts::{self, SyncProviderStorageEntry},
+};
+
+const AUDIT_DIRECTORY_NAME: &str = "secure-store-audit";
+const AUDIT_FILE_PREFIX: &str = "secure-store-access-";
+const AUDIT_FILE_SUFFIX: &str = ".log";
+const AUDIT_RETENTION_DAYS: i64 = 30;
+const AUDIT_PAGE_DEFAULT_LIMIT: usize = 50;
+const AUDIT_PAGE_MAX_LIMIT: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SecureStoreAuditOperation {
+    Read,
+    Write,
+    Delete,
+    SelfTest,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SecureStoreAuditEntry {
+    Credential,
+    AccountIndex,
+    SelfTest,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SecureStoreAuditOutcome {
+    Ok,
+    NotFound,
+    Failed,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct SecureStoreAuditRecord {
+    pub(crate) timestamp_iso: String,
+    pub(crate) operation: SecureStoreAuditOperation,
+    pub(crate) entry: SecureStoreAuditEntry,
+    pub(crate) provider: Option<String>,
+    pub(crate) backend: String,
+    pub(crate) outcome: SecureStoreAuditOutcome,
+    pub(crate) error_code: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct SecureStoreAuditPage {
+    pub(crate) records: Vec<SecureStoreAuditRecord>,
+    pub(crate) next_offset: Option<usize>,
+}
+
+pub(crate) struct SecureStoreAuditLog {
+    directory: PathBuf,
+    append_lock: Mutex<()>,
+}
+
+fn audit_read_failed(detail: String) -> SoloStackError {
+    SoloStackError::with_detail(SoloStackErrorCode::SecureStoreAuditReadFailed, detail)
+}
+
+fn error_code_name(code: SoloStackErrorCode) -> Option<String> {
+    serde_json::to_value(code)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+}
+
+fn audit_file_day(filename: &str) -> Option<NaiveDate> {
+    let day = filename
+        .strip_prefix(AUDIT_FILE_PREFIX)?
+        .strip_suffix(AUDIT_FILE_SUFFIX)?;
+    NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()
+}
+
+impl SecureStoreAuditLog {
+    pub(crate) fn in_app_data_dir(app_data_dir: &Path) -> Self {
+        let audit_log = Self {
+            directory: app_data_dir.join(AUDIT_DIRECTORY_NAME),
+            append_lock: Mutex::new(()),
+        };
+        audit_log.prune(Utc::now());
+        audit_log
+    }
+
+    fn file_path(&self, day: NaiveDate) -> PathBuf {
+        self.directory.join(format!(
+            "{AUDIT_FILE_PREFIX}{}{AUDIT_FILE_SUFFIX}",
+            day.format("%Y-%m-%d")
+        ))
+    }
+
+    // Newest day first.
+    fn audit_files(&self) -> Result<Vec<(NaiveDate, PathBuf)>, SoloStackError> {
+        let entries = match fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(audit_read_failed(format!("list audit log failed: {error}"))),
+        };
+        let mut files = entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let day = audit_file_day(entry.file_name().to_str()?)?;
+                Some((day, entry.path()))
+            })
+            .collect::<Vec<_>>();
+        files.sort_by_key(|(day, _)| std::cmp::Reverse(*day));
+        Ok(files)
+    }
+
+    fn prune(&self, now: DateTime<Utc>) {
+        let cutoff = now.date_naive() - chrono::Duration::days(AUDIT_RETENTION_DAYS);
+        let Ok(files) = self.audit_files() else {
+            return;
+        };
+        for (day, path) in files {
+            if day < cutoff {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+
+    pub(crate) fn record(
+        &self,
+        operation: SecureStoreAuditOperation,
+        key: Option<&str>,
+        backend: &str,
+        outcome: SecureStoreAuditOutcome,
+        error_code: Option<SoloStackErrorCode>,
+    ) {
+        let (entry, provider) = match key.map(sync_provider_accounts::parse_storage_key) {
+            Some(SyncProviderStorageEntry::Credential { provider }) => (
+                SecureStoreAuditEntry::Credential,
+                Some(provider.to_string()),
+            ),
+            Some(SyncProviderStorageEntry::AccountIndex { provider }) => (
+                SecureStoreAuditEntry::AccountIndex,
+                Some(provider.to_string()),
+            ),
+            None => (SecureStoreAuditEntry::SelfTest, None),
+        };
+        let now = Utc::now();
+        let record = SecureStoreAuditRecord {
+            timestamp_iso: now.to_rfc3339_opts(SecondsFormat::Millis, true),
+            operation,
+            entry,
+            provider,
+            backend: backend.to_string(),
+            outcome,
+            error_code: error_code.and_then(error_code_name),
+        };
+        // Auditing never fails the secure store call it describes.
+        if let Err(error) = self.append(&record, now) {
+            eprintln!("Secure store audit warning: {error}");
+        }
+    }
+
+    fn append(&self, record: &SecureStoreAuditRecord, now: DateTime<Utc>) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let _guard = self
+            .append_lock
+            .lock()
+            .map_err(|_| std::io::Error::other("audit log lock poisoned"))?;
+        fs::create_dir_all(&self.directory)?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.file_path(now.date_naive()))?
+            .write_all(line.as_bytes())
+    }
+
+    // Pages newest record first. Lines that do not parse (e.g. cut short by a crash) are skipped.
+    pub(crate) fn page(
+        &self,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<SecureStoreAuditPage, SoloStackError> {
+        let offset = offset.unwrap_or(0);
+        let limit = limit
+            .unwrap_or(AUDIT_PAGE_DEFAULT_LIMIT)
+            .clamp(1, AUDIT_PAGE_MAX_LIMIT);
+        let mut skipped = 0;
+        let mut records = Vec::with_capacity(limit + 1);
+        'files: for (_, path) in self.audit_files()? {
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                // Pruned between listing and reading.
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(error) => {
+                    return Err(audit_read_failed(format!("read audit log failed: {error}")))
+                }
+            };
+            for line in contents.lines().rev() {
+                let Ok(record) = serde_json::from_str::<SecureStoreAuditRecord>(line) else {
+                    continue;
+                };
+                if skipped < offset {
+                    skipped += 1;
+                    continue;
+                }
+                records.push(record);
+                // One extra record tells whether another page exists.
+                if records.len() > limit {
+                    break 'files;
+                }
+            }
+        }
+        let next_offset = (records.len() > limit).then_some(offset + limit);
+        records.truncate(limit);
+        Ok(SecureStoreAuditPage {
+            records,
+            next_offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_audit_log() -> (PathBuf, SecureStoreAuditLog) {
+        let app_data_dir =
+            std::env::temp_dir().join(format!("solostack-audit-{}", uuid::Uuid::new_v4()));
+        let audit_log = SecureStoreAuditLog::in_app_data_dir(&app_data_dir);
+        (app_data_dir, audit_log)
+    }
+
+    #[test]
+    fn records_page_newest_first_without_secrets() {
+        let (app_data_dir, audit_log) = temp_audit_log();
+        audit_log.record(
+            SecureStoreAuditOperation::Write,
+            Some("onedrive_approot::work@example.com"),
+            "keyring",
+            SecureStoreAuditOutcome::Ok,
+            None,
+        );
+        audit_log.record(
+            SecureStoreAuditOperation::Write,
+            Some("onedrive_approot#accounts"),
+            "keyring",
+            SecureStoreAuditOutcome::Ok,
+            None,
+        );
+        audit_log.record(
+            SecureStoreAuditOperation::Delete,
+            Some("google_appdata"),
+            "keyring",
+            SecureStoreAuditOutcome::Failed,
+            Some(SoloStackErrorCode::SecureStoreDeleteFailed),
+        );
+
+        let first = audit_log.page(None, Some(2)).unwrap();
+        assert_eq!(first.records.len(), 2);
+        assert_eq!(first.next_offset, Some(2));
+        assert_eq!(
+            first.records[0].operation,
+            SecureStoreAuditOperation::Delete
+        );
+        assert_eq!(
+            first.records[0].error_code.as_deref(),
+            Some("SECURE_STORE_DELETE_FAILED")
+        );
+        assert_eq!(first.records[1].entry, SecureStoreAuditEntry::AccountIndex);
+
+        let second = audit_log.page(first.next_offset, Some(2)).unwrap();
+        assert_eq!(second.records.len(), 1);
+        assert_eq!(second.next_offset, None);
+        assert_eq!(
+            second.records[0].provider.as_deref(),
+            Some("onedrive_approot")
+        );
+
+        let written = fs::read_to_string(audit_log.file_path(Utc::now().date_naive())).unwrap();
+        assert!(!written.contains("work@example.com"));
+        fs::remove_dir_all(app_data_dir).unwrap();
+    }
+
+    #[test]
+    fn prunes_files_past_retention() {
+        let (app_data_dir, audit_log) = temp_audit_log();
+        fs::create_dir_all(&audit_log.directory).unwrap();
+        let now = Utc::now();
+        let expired = audit_log
+            .file_path(now.date_naive() - chrono::Duration::days(AUDIT_RETENTION_DAYS + 1));
+        let kept = audit_log.file_path(now.date_naive() - chrono::Duration::days(1));
+        fs::write(&expired, "").unwrap();
+        fs::write(&kept, "not json\n").unwrap();
+
+        audit_log.prune(now);
+        assert!(!expired.exists());
+        assert!(kept.exists());
+        assert!(audit_log.page(None, None).unwrap().records.is_empty());
+        fs::remove_dir_all(app_data_dir).unwrap();
+    }
+}
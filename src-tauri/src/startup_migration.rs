@@ -0,0 +1,1636 @@
+// Moves `solostack.db` from a historical app data dir into the current one. Historical
+// locations are listed in `LEGACY_DATA_SOURCES`, newest first, and the first one that still
+// holds a database is copied. The copy is written to a temporary file, fsynced and renamed
+// into place; a journal records every completed step so an interrupted launch resumes or
+// rolls back instead of trusting a half-copied database.
+//
+// The migration runs on a background thread started from `setup` and reports its phases to
+// the webview through `migration:*` events.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, Mutex},
+};
+use tauri::{Emitter, Manager};
+
+use crate::{
+    db,
+    error::{SoloStackError, SoloStackErrorCode},
+    repository::now_iso,
+};
+
+const CURRENT_BUNDLE_IDENTIFIER: &str = "com.solutionsstudio.solostack";
+pub(crate) const DATABASE_FILENAME: &str = "solostack.db";
+const STARTUP_MIGRATION_MARKER_FILENAME: &str = "startup-migration-v2.json";
+const STARTUP_MIGRATION_MARKER_VERSION: u8 = 2;
+const LEGACY_STARTUP_MIGRATION_MARKER_FILENAME: &str = "startup-migration-v1.json";
+const STARTUP_MIGRATION_JOURNAL_FILENAME: &str = "startup-migration-journal.json";
+const STARTUP_MIGRATION_TEMP_SUFFIX: &str = ".migrating";
+const STARTUP_MIGRATION_JOURNAL_VERSION: u8 = 1;
+const DATABASE_SIDECAR_SUFFIXES: [&str; 2] = ["-wal", "-shm"];
+const STARTUP_MIGRATION_COPY_METHOD: &str = "sqlite_online_backup";
+const STARTUP_MIGRATION_KEEP_METHOD: &str = "kept_existing_database";
+const MIGRATION_PROGRESS_EVENT: &str = "migration:progress";
+const MIGRATION_COMPLETED_EVENT: &str = "migration:completed";
+const MIGRATION_FAILED_EVENT: &str = "migration:failed";
+
+// Newest first: when several generations still hold a database, the most recent one wins and
+// the older ones are recorded as superseded.
+const LEGACY_DATA_SOURCES: &[LegacyDataSource] = &[LegacyDataSource {
+    bundle_identifier: "com.antigravity.solostack",
+    data_subdir: None,
+    database_filename: DATABASE_FILENAME,
+}];
+
+struct LegacyDataSource {
+    bundle_identifier: &'static str,
+    // Relative to the identifier's app data dir, for layouts that nested the database.
+    data_subdir: Option<&'static str>,
+    database_filename: &'static str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum LegacySourceStatus {
+    Unresolved,
+    NotFound,
+    Detected,
+    Migrated,
+    Superseded,
+    Consumed,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct LegacySourceResult {
+    pub(crate) bundle_identifier: String,
+    pub(crate) db_path: Option<String>,
+    pub(crate) detected: bool,
+    pub(crate) status: LegacySourceStatus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum StartupMigrationStep {
+    Started,
+    BackupWritten,
+    IntegrityChecked,
+    Synced,
+    Renamed,
+    MarkerWritten,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum StartupMigrationPhase {
+    Detecting,
+    ResumingJournal,
+    Copying,
+    CheckingIntegrity,
+    Syncing,
+    Renaming,
+    WritingMarker,
+    Completed,
+    Failed,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct StartupMigrationPhaseRecord {
+    phase: StartupMigrationPhase,
+    started_at: String,
+    bytes_copied: u64,
+    total_bytes: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct StartupMigrationProgressPayload {
+    phase: StartupMigrationPhase,
+    bytes_copied: u64,
+    total_bytes: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct StartupMigrationFailedPayload {
+    phase: Option<StartupMigrationPhase>,
+    error: SoloStackError,
+    report: StartupMigrationReport,
+}
+
+#[derive(Clone, Serialize, Default)]
+pub(crate) struct StartupMigrationReport {
+    pub(crate) legacy_path_detected: bool,
+    pub(crate) marker_present: bool,
+    pub(crate) migration_attempted: bool,
+    pub(crate) migration_completed: bool,
+    pub(crate) migration_error: Option<String>,
+    pub(crate) migration_error_code: Option<SoloStackErrorCode>,
+    pub(crate) legacy_db_path: Option<String>,
+    pub(crate) new_db_path: Option<String>,
+    pub(crate) integrity_check_passed: bool,
+    pub(crate) rollback_performed: bool,
+    pub(crate) copy_method: Option<String>,
+    pub(crate) page_count: Option<i64>,
+    pub(crate) resumed_from_journal: bool,
+    pub(crate) last_completed_step: Option<StartupMigrationStep>,
+    pub(crate) legacy_sources: Vec<LegacySourceResult>,
+    pub(crate) phase_timeline: Vec<StartupMigrationPhaseRecord>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct DatabaseFileChecksum {
+    pub(crate) file_name: String,
+    pub(crate) size_bytes: u64,
+    pub(crate) sha256: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct ConsumedLegacySource {
+    bundle_identifier: String,
+    source_db_path: String,
+    status: LegacySourceStatus,
+}
+
+#[derive(Serialize)]
+struct StartupMigrationMarkerPayload {
+    version: u8,
+    consumed_sources: Vec<ConsumedLegacySource>,
+    source_db_path: String,
+    destination_db_path: String,
+    copy_method: String,
+    page_count: i64,
+    checksums: Vec<DatabaseFileChecksum>,
+    integrity_check: String,
+}
+
+// Reads both marker versions: v1 only names the copied database, v2 lists every source.
+#[derive(Deserialize)]
+pub(crate) struct StartupMigrationMarkerRecord {
+    #[serde(default)]
+    source_db_path: Option<String>,
+    #[serde(default)]
+    consumed_sources: Vec<ConsumedLegacySource>,
+    #[serde(default)]
+    pub(crate) integrity_check: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct StartupMigrationJournal {
+    version: u8,
+    #[serde(default)]
+    source_bundle_identifier: String,
+    source_db_path: String,
+    temp_db_path: String,
+    destination_db_path: String,
+    last_completed_step: StartupMigrationStep,
+    page_count: Option<i64>,
+    checksums: Vec<DatabaseFileChecksum>,
+    updated_at: String,
+}
+
+type ProgressSink<'a> = &'a mut dyn FnMut(&StartupMigrationProgressPayload);
+
+// Holds no report until the background startup migrations have finished, so readers of the
+// report also wait for the database file and schema to be ready.
+#[derive(Clone, Default)]
+pub(crate) struct StartupMigrationState {
+    report: Arc<(Mutex<Option<StartupMigrationReport>>, Condvar)>,
+}
+
+impl StartupMigrationState {
+    pub(crate) fn finish(&self, report: StartupMigrationReport) {
+        let (report_slot, finished) = &*self.report;
+        if let Ok(mut guard) = report_slot.lock() {
+            *guard = Some(report);
+        }
+        finished.notify_all();
+    }
+
+    fn wait_for_report(&self) -> StartupMigrationReport {
+        let (report_slot, finished) = &*self.report;
+        let Ok(guard) = report_slot.lock() else {
+            return StartupMigrationReport::default();
+        };
+        match finished.wait_while(guard, |report| report.is_none()) {
+            Ok(guard) => guard.clone().unwrap_or_default(),
+            Err(_) => StartupMigrationReport::default(),
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn get_startup_migration_report(
+    state: tauri::State<'_, StartupMigrationState>,
+) -> Result<StartupMigrationReport, SoloStackError> {
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || state.wait_for_report())
+        .await
+        .map_err(|error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::Internal,
+                format!("wait for startup migration failed: {error}"),
+            )
+        })
+}
+
+fn enter_migration_phase(
+    report: &mut StartupMigrationReport,
+    progress: ProgressSink,
+    phase: StartupMigrationPhase,
+) {
+    report.phase_timeline.push(StartupMigrationPhaseRecord {
+        phase,
+        started_at: now_iso(),
+        bytes_copied: 0,
+        total_bytes: 0,
+    });
+    progress(&StartupMigrationProgressPayload {
+        phase,
+        bytes_copied: 0,
+        total_bytes: 0,
+    });
+}
+
+fn record_copy_progress(
+    report: &mut StartupMigrationReport,
+    progress: ProgressSink,
+    bytes_copied: u64,
+    total_bytes: u64,
+) {
+    let Some(current_phase) = report.phase_timeline.last_mut() else {
+        return;
+    };
+    current_phase.bytes_copied = bytes_copied;
+    current_phase.total_bytes = total_bytes;
+    progress(&StartupMigrationProgressPayload {
+        phase: current_phase.phase,
+        bytes_copied,
+        total_bytes,
+    });
+}
+
+fn derive_app_data_dir_for_identifier(
+    new_app_data_dir: &Path,
+    bundle_identifier: &str,
+) -> Option<PathBuf> {
+    let new_path = new_app_data_dir.to_string_lossy();
+    if new_path.contains(CURRENT_BUNDLE_IDENTIFIER) {
+        return Some(PathBuf::from(new_path.replacen(
+            CURRENT_BUNDLE_IDENTIFIER,
+            bundle_identifier,
+            1,
+        )));
+    }
+
+    let file_name = new_app_data_dir.file_name()?.to_string_lossy();
+    if file_name == CURRENT_BUNDLE_IDENTIFIER {
+        return Some(new_app_data_dir.parent()?.join(bundle_identifier));
+    }
+
+    None
+}
+
+fn resolve_legacy_source_db_path(
+    new_app_data_dir: &Path,
+    source: &LegacyDataSource,
+) -> Option<PathBuf> {
+    let app_data_dir =
+        derive_app_data_dir_for_identifier(new_app_data_dir, source.bundle_identifier)?;
+    let data_dir = match source.data_subdir {
+        Some(subdir) => app_data_dir.join(subdir),
+        None => app_data_dir,
+    };
+    Some(data_dir.join(source.database_filename))
+}
+
+fn detect_legacy_sources(
+    new_app_data_dir: &Path,
+    sources: &[LegacyDataSource],
+) -> Vec<LegacySourceResult> {
+    sources
+        .iter()
+        .map(|source| {
+            let db_path = resolve_legacy_source_db_path(new_app_data_dir, source);
+            let detected = db_path.as_ref().is_some_and(|path| path.exists());
+            let status = match (&db_path, detected) {
+                (None, _) => LegacySourceStatus::Unresolved,
+                (Some(_), false) => LegacySourceStatus::NotFound,
+                (Some(_), true) => LegacySourceStatus::Detected,
+            };
+            LegacySourceResult {
+                bundle_identifier: source.bundle_identifier.to_string(),
+                db_path: db_path.map(|path| path.to_string_lossy().to_string()),
+                detected,
+                status,
+            }
+        })
+        .collect()
+}
+
+// Every detected source other than the copied one is settled too, so a later launch never
+// copies an older generation over the database that was just migrated.
+fn settle_detected_sources(report: &mut StartupMigrationReport, migrated_db_path: Option<&str>) {
+    for source in report
+        .legacy_sources
+        .iter_mut()
+        .filter(|source| source.detected)
+    {
+        source.status = if source.db_path.as_deref() == migrated_db_path {
+            LegacySourceStatus::Migrated
+        } else {
+            LegacySourceStatus::Superseded
+        };
+    }
+}
+
+// Identifier dirs of every historical source, deduplicated because several layouts may share
+// one identifier.
+pub(crate) fn legacy_app_data_dirs(new_app_data_dir: &Path) -> Vec<(&'static str, PathBuf)> {
+    app_data_dirs_of_sources(new_app_data_dir, LEGACY_DATA_SOURCES)
+}
+
+fn app_data_dirs_of_sources(
+    new_app_data_dir: &Path,
+    sources: &[LegacyDataSource],
+) -> Vec<(&'static str, PathBuf)> {
+    let mut app_data_dirs: Vec<(&'static str, PathBuf)> = Vec::new();
+    for source in sources {
+        let Some(app_data_dir) =
+            derive_app_data_dir_for_identifier(new_app_data_dir, source.bundle_identifier)
+        else {
+            continue;
+        };
+        if app_data_dir == new_app_data_dir
+            || app_data_dirs.iter().any(|(_, dir)| *dir == app_data_dir)
+        {
+            continue;
+        }
+        app_data_dirs.push((source.bundle_identifier, app_data_dir));
+    }
+    app_data_dirs
+}
+
+fn find_migration_marker_path(new_app_data_dir: &Path) -> Option<PathBuf> {
+    [
+        STARTUP_MIGRATION_MARKER_FILENAME,
+        LEGACY_STARTUP_MIGRATION_MARKER_FILENAME,
+    ]
+    .into_iter()
+    .map(|file_name| new_app_data_dir.join(file_name))
+    .find(|path| path.exists())
+}
+
+pub(crate) fn read_completed_migration_marker(
+    new_app_data_dir: &Path,
+) -> Result<Option<StartupMigrationMarkerRecord>, String> {
+    match find_migration_marker_path(new_app_data_dir) {
+        Some(marker_path) => read_migration_marker(&marker_path).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn read_migration_marker(marker_path: &Path) -> Result<StartupMigrationMarkerRecord, String> {
+    let marker_text = fs::read_to_string(marker_path)
+        .map_err(|error| format!("read migration marker failed: {error}"))?;
+    serde_json::from_str(&marker_text)
+        .map_err(|error| format!("parse migration marker failed: {error}"))
+}
+
+fn apply_marker_to_sources(
+    report: &mut StartupMigrationReport,
+    marker: &StartupMigrationMarkerRecord,
+) {
+    for source in report.legacy_sources.iter_mut() {
+        let Some(db_path) = source.db_path.as_deref() else {
+            continue;
+        };
+        let recorded = marker.source_db_path.as_deref() == Some(db_path)
+            || marker
+                .consumed_sources
+                .iter()
+                .any(|consumed| consumed.source_db_path == db_path);
+        if recorded {
+            source.status = LegacySourceStatus::Consumed;
+        } else if source.detected {
+            source.status = LegacySourceStatus::Superseded;
+        }
+    }
+}
+
+fn database_sidecar_path(db_path: &Path, suffix: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}", db_path.to_string_lossy(), suffix))
+}
+
+pub(crate) fn compute_file_checksum(path: &Path) -> Result<DatabaseFileChecksum, String> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut file = fs::File::open(path)
+        .map_err(|error| format!("open {file_name} for hashing failed: {error}"))?;
+    let mut hasher = Sha256::new();
+    let size_bytes = io::copy(&mut file, &mut hasher)
+        .map_err(|error| format!("hash {file_name} failed: {error}"))?;
+    Ok(DatabaseFileChecksum {
+        file_name,
+        size_bytes,
+        sha256: format!("{:x}", hasher.finalize()),
+    })
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<(), String> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(format!("remove {} failed: {error}", path.to_string_lossy())),
+    }
+}
+
+fn remove_database_sidecars(db_path: &Path) -> Result<(), String> {
+    for suffix in DATABASE_SIDECAR_SUFFIXES {
+        remove_file_if_exists(&database_sidecar_path(db_path, suffix))?;
+    }
+    Ok(())
+}
+
+fn remove_database_files(db_path: &Path) -> Result<(), String> {
+    remove_file_if_exists(db_path)?;
+    remove_database_sidecars(db_path)
+}
+
+fn sync_file(path: &Path) -> Result<(), String> {
+    fs::File::open(path)
+        .and_then(|file| file.sync_all())
+        .map_err(|error| format!("fsync {} failed: {error}", path.to_string_lossy()))
+}
+
+// Persists the rename itself; Windows cannot open directory handles and flushes renames
+// through the file system journal instead.
+fn sync_parent_dir(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        if let Some(parent_dir) = path.parent() {
+            sync_file(parent_dir)?;
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+    }
+    Ok(())
+}
+
+fn write_file_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    let temp_path = database_sidecar_path(path, ".tmp");
+    fs::write(&temp_path, contents)
+        .map_err(|error| format!("write {} failed: {error}", temp_path.to_string_lossy()))?;
+    sync_file(&temp_path)?;
+    fs::rename(&temp_path, path)
+        .map_err(|error| format!("replace {} failed: {error}", path.to_string_lossy()))?;
+    sync_parent_dir(path)
+}
+
+fn read_migration_journal(journal_path: &Path) -> Result<Option<StartupMigrationJournal>, String> {
+    let journal_text = match fs::read_to_string(journal_path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(format!("read migration journal failed: {error}")),
+    };
+    serde_json::from_str(&journal_text)
+        .map(Some)
+        .map_err(|error| format!("parse migration journal failed: {error}"))
+}
+
+fn record_migration_step(
+    journal_path: &Path,
+    journal: &mut StartupMigrationJournal,
+    report: &mut StartupMigrationReport,
+    step: StartupMigrationStep,
+) -> Result<(), String> {
+    journal.last_completed_step = step;
+    journal.updated_at = now_iso();
+    let journal_text = serde_json::to_string_pretty(journal)
+        .map_err(|error| format!("serialize migration journal failed: {error}"))?;
+    write_file_atomically(journal_path, &journal_text)
+        .map_err(|error| format!("write migration journal failed: {error}"))?;
+    report.last_completed_step = Some(step);
+    Ok(())
+}
+
+fn write_migration_marker(
+    marker_path: &Path,
+    journal: &StartupMigrationJournal,
+    copy_method: &str,
+    legacy_sources: &[LegacySourceResult],
+) -> Result<(), String> {
+    let consumed_sources = legacy_sources
+        .iter()
+        .filter(|source| source.detected)
+        .filter_map(|source| {
+            Some(ConsumedLegacySource {
+                bundle_identifier: source.bundle_identifier.clone(),
+                source_db_path: source.db_path.clone()?,
+                status: source.status,
+            })
+        })
+        .collect();
+    let marker_payload = StartupMigrationMarkerPayload {
+        version: STARTUP_MIGRATION_MARKER_VERSION,
+        consumed_sources,
+        source_db_path: journal.source_db_path.clone(),
+        destination_db_path: journal.destination_db_path.clone(),
+        copy_method: copy_method.to_string(),
+        page_count: journal.page_count.unwrap_or_default(),
+        checksums: journal.checksums.clone(),
+        integrity_check: "ok".to_string(),
+    };
+    let marker_text = serde_json::to_string_pretty(&marker_payload)
+        .unwrap_or_else(|_| "{\"version\":2}".to_string());
+    write_file_atomically(marker_path, &marker_text)
+        .map_err(|error| format!("write migration marker failed: {error}"))
+}
+
+// Nothing before `Renamed` is visible at the destination path, so rolling back only has to
+// discard the temporary copy and the journal.
+fn roll_back_unrenamed_copy(temp_db_path: &Path, journal_path: &Path) -> Result<(), String> {
+    remove_database_files(temp_db_path)?;
+    remove_file_if_exists(journal_path)?;
+    sync_parent_dir(journal_path)
+}
+
+fn fail_startup_migration_with_rollback(
+    report: &mut StartupMigrationReport,
+    temp_db_path: &Path,
+    journal_path: &Path,
+    error: String,
+) {
+    match roll_back_unrenamed_copy(temp_db_path, journal_path) {
+        Ok(()) => {
+            report.rollback_performed = true;
+            report.migration_error = Some(error);
+        }
+        Err(rollback_error) => {
+            report.migration_error = Some(format!("{error}; rollback failed: {rollback_error}"));
+        }
+    }
+}
+
+fn finish_renamed_migration(
+    report: &mut StartupMigrationReport,
+    progress: ProgressSink,
+    journal: &mut StartupMigrationJournal,
+    journal_path: &Path,
+    marker_path: &Path,
+    new_db_path: &Path,
+) -> Result<(), String> {
+    // A resumed run cannot trust checksums from before the crash without re-checking the file;
+    // a fresh run already checked these exact bytes before the rename.
+    if report.resumed_from_journal {
+        enter_migration_phase(report, progress, StartupMigrationPhase::CheckingIntegrity);
+        db::run_integrity_check(new_db_path)?;
+        report.integrity_check_passed = true;
+    }
+    let destination_checksum = compute_file_checksum(new_db_path)?;
+    if let Some(recorded_checksum) = journal.checksums.first() {
+        if recorded_checksum.sha256 != destination_checksum.sha256 {
+            return Err("migrated database checksum changed before marker write".to_string());
+        }
+    }
+
+    enter_migration_phase(report, progress, StartupMigrationPhase::WritingMarker);
+    settle_detected_sources(report, Some(&journal.source_db_path));
+    write_migration_marker(
+        marker_path,
+        journal,
+        STARTUP_MIGRATION_COPY_METHOD,
+        &report.legacy_sources,
+    )?;
+    report.marker_present = true;
+    report.last_completed_step = Some(StartupMigrationStep::MarkerWritten);
+    remove_file_if_exists(journal_path)?;
+    report.migration_completed = true;
+    Ok(())
+}
+
+// The destination already holds a database this launch did not copy: the app created it before
+// the legacy dir showed up, or it changed under a copy that was just rolled back. It is kept
+// instead of overwritten, and only marked as migrated once it passes an integrity check.
+fn keep_existing_destination(
+    report: &mut StartupMigrationReport,
+    progress: ProgressSink,
+    source_bundle_identifier: &str,
+    legacy_db_path: &Path,
+    new_db_path: &Path,
+    marker_path: &Path,
+) -> Result<(), String> {
+    enter_migration_phase(report, progress, StartupMigrationPhase::CheckingIntegrity);
+    db::run_integrity_check(new_db_path)?;
+    report.integrity_check_passed = true;
+    let destination_checksum = compute_file_checksum(new_db_path)?;
+
+    enter_migration_phase(report, progress, StartupMigrationPhase::WritingMarker);
+    settle_detected_sources(report, None);
+    let kept = StartupMigrationJournal {
+        version: STARTUP_MIGRATION_JOURNAL_VERSION,
+        source_bundle_identifier: source_bundle_identifier.to_string(),
+        source_db_path: legacy_db_path.to_string_lossy().to_string(),
+        temp_db_path: String::new(),
+        destination_db_path: new_db_path.to_string_lossy().to_string(),
+        last_completed_step: StartupMigrationStep::MarkerWritten,
+        page_count: None,
+        checksums: vec![destination_checksum],
+        updated_at: now_iso(),
+    };
+    write_migration_marker(
+        marker_path,
+        &kept,
+        STARTUP_MIGRATION_KEEP_METHOD,
+        &report.legacy_sources,
+    )?;
+    report.copy_method = Some(STARTUP_MIGRATION_KEEP_METHOD.to_string());
+    report.marker_present = true;
+    report.migration_completed = true;
+    Ok(())
+}
+
+// A crash between the rename and journaling `Renamed` leaves the journal at `Synced` with the
+// copy already at the destination; the checksum recorded at `Synced` tells it apart from a
+// database that was there before.
+fn rename_landed_before_crash(journal: &StartupMigrationJournal, new_db_path: &Path) -> bool {
+    journal.last_completed_step == StartupMigrationStep::Synced
+        && !Path::new(&journal.temp_db_path).exists()
+        && journal.checksums.first().is_some_and(|recorded| {
+            compute_file_checksum(new_db_path)
+                .is_ok_and(|checksum| checksum.sha256 == recorded.sha256)
+        })
+}
+
+fn resume_startup_migration(
+    report: &mut StartupMigrationReport,
+    progress: ProgressSink,
+    mut journal: StartupMigrationJournal,
+    journal_path: &Path,
+    marker_path: &Path,
+    new_db_path: &Path,
+) {
+    enter_migration_phase(report, progress, StartupMigrationPhase::ResumingJournal);
+    report.resumed_from_journal = true;
+    report.migration_attempted = true;
+    report.copy_method = Some(STARTUP_MIGRATION_COPY_METHOD.to_string());
+    report.page_count = journal.page_count;
+    report.last_completed_step = Some(journal.last_completed_step);
+    report.legacy_db_path = Some(journal.source_db_path.clone());
+
+    if rename_landed_before_crash(&journal, new_db_path) {
+        journal.last_completed_step = StartupMigrationStep::Renamed;
+        report.last_completed_step = Some(StartupMigrationStep::Renamed);
+    }
+    if journal.last_completed_step < StartupMigrationStep::Renamed {
+        let temp_db_path = PathBuf::from(&journal.temp_db_path);
+        match roll_back_unrenamed_copy(&temp_db_path, journal_path) {
+            Ok(()) => {
+                report.rollback_performed = true;
+                report.last_completed_step = None;
+            }
+            Err(error) => {
+                report.migration_error =
+                    Some(format!("roll back interrupted migration failed: {error}"));
+            }
+        }
+        return;
+    }
+
+    if let Err(error) = finish_renamed_migration(
+        report,
+        progress,
+        &mut journal,
+        journal_path,
+        marker_path,
+        new_db_path,
+    ) {
+        report.migration_error = Some(format!("resume interrupted migration failed: {error}"));
+    }
+}
+
+fn copy_legacy_database(
+    report: &mut StartupMigrationReport,
+    progress: ProgressSink,
+    source_bundle_identifier: &str,
+    legacy_db_path: &Path,
+    new_db_path: &Path,
+    journal_path: &Path,
+    marker_path: &Path,
+) {
+    let temp_db_path = database_sidecar_path(new_db_path, STARTUP_MIGRATION_TEMP_SUFFIX);
+    let mut journal = StartupMigrationJournal {
+        version: STARTUP_MIGRATION_JOURNAL_VERSION,
+        source_bundle_identifier: source_bundle_identifier.to_string(),
+        source_db_path: legacy_db_path.to_string_lossy().to_string(),
+        temp_db_path: temp_db_path.to_string_lossy().to_string(),
+        destination_db_path: new_db_path.to_string_lossy().to_string(),
+        last_completed_step: StartupMigrationStep::Started,
+        page_count: None,
+        checksums: Vec::new(),
+        updated_at: now_iso(),
+    };
+
+    report.migration_attempted = true;
+    report.copy_method = Some(STARTUP_MIGRATION_COPY_METHOD.to_string());
+
+    if let Err(error) = remove_database_files(&temp_db_path).and_then(|_| {
+        record_migration_step(
+            journal_path,
+            &mut journal,
+            report,
+            StartupMigrationStep::Started,
+        )
+    }) {
+        fail_startup_migration_with_rollback(report, &temp_db_path, journal_path, error);
+        return;
+    }
+
+    enter_migration_phase(report, progress, StartupMigrationPhase::Copying);
+    let backup_result = db::backup_database(
+        legacy_db_path,
+        &temp_db_path,
+        &mut |bytes_copied, total_bytes| {
+            record_copy_progress(report, progress, bytes_copied, total_bytes)
+        },
+    );
+    let page_count = match backup_result {
+        Ok(page_count) => page_count,
+        Err(error) => {
+            fail_startup_migration_with_rollback(report, &temp_db_path, journal_path, error);
+            return;
+        }
+    };
+    report.page_count = Some(page_count);
+    journal.page_count = Some(page_count);
+    if let Err(error) = record_migration_step(
+        journal_path,
+        &mut journal,
+        report,
+        StartupMigrationStep::BackupWritten,
+    ) {
+        fail_startup_migration_with_rollback(report, &temp_db_path, journal_path, error);
+        return;
+    }
+
+    enter_migration_phase(report, progress, StartupMigrationPhase::CheckingIntegrity);
+    let integrity_result = db::run_integrity_check(&temp_db_path)
+        .and_then(|_| remove_database_sidecars(&temp_db_path));
+    if let Err(error) = integrity_result {
+        fail_startup_migration_with_rollback(report, &temp_db_path, journal_path, error);
+        return;
+    }
+    report.integrity_check_passed = true;
+    if let Err(error) = record_migration_step(
+        journal_path,
+        &mut journal,
+        report,
+        StartupMigrationStep::IntegrityChecked,
+    ) {
+        fail_startup_migration_with_rollback(report, &temp_db_path, journal_path, error);
+        return;
+    }
+
+    enter_migration_phase(report, progress, StartupMigrationPhase::Syncing);
+    let sync_result = sync_file(&temp_db_path).and_then(|_| compute_file_checksum(&temp_db_path));
+    let temp_checksum = match sync_result {
+        Ok(checksum) => checksum,
+        Err(error) => {
+            fail_startup_migration_with_rollback(report, &temp_db_path, journal_path, error);
+            return;
+        }
+    };
+    journal.checksums = vec![DatabaseFileChecksum {
+        file_name: DATABASE_FILENAME.to_string(),
+        ..temp_checksum
+    }];
+    if let Err(error) = record_migration_step(
+        journal_path,
+        &mut journal,
+        report,
+        StartupMigrationStep::Synced,
+    ) {
+        fail_startup_migration_with_rollback(report, &temp_db_path, journal_path, error);
+        return;
+    }
+
+    enter_migration_phase(report, progress, StartupMigrationPhase::Renaming);
+    let rename_result = fs::rename(&temp_db_path, new_db_path)
+        .map_err(|error| format!("move migrated database into place failed: {error}"))
+        .and_then(|_| sync_parent_dir(new_db_path));
+    if let Err(error) = rename_result {
+        fail_startup_migration_with_rollback(report, &temp_db_path, journal_path, error);
+        return;
+    }
+    // From here on the destination is complete; failures leave the journal so the next
+    // launch resumes at the marker step instead of deleting a verified database.
+    if let Err(error) = record_migration_step(
+        journal_path,
+        &mut journal,
+        report,
+        StartupMigrationStep::Renamed,
+    ) {
+        report.migration_error = Some(error);
+        return;
+    }
+
+    if let Err(error) = finish_renamed_migration(
+        report,
+        progress,
+        &mut journal,
+        journal_path,
+        marker_path,
+        new_db_path,
+    ) {
+        report.migration_error = Some(error);
+    }
+}
+
+fn migrate_legacy_database(
+    new_app_data_dir: &Path,
+    sources: &[LegacyDataSource],
+    progress: ProgressSink,
+) -> StartupMigrationReport {
+    let mut report = StartupMigrationReport::default();
+    enter_migration_phase(&mut report, progress, StartupMigrationPhase::Detecting);
+
+    if let Err(error) = fs::create_dir_all(new_app_data_dir) {
+        report.migration_error = Some(format!("create app data dir failed: {error}"));
+        return report;
+    }
+
+    let new_db_path = new_app_data_dir.join(DATABASE_FILENAME);
+    report.new_db_path = Some(new_db_path.to_string_lossy().to_string());
+
+    let marker_path = new_app_data_dir.join(STARTUP_MIGRATION_MARKER_FILENAME);
+    let journal_path = new_app_data_dir.join(STARTUP_MIGRATION_JOURNAL_FILENAME);
+
+    report.legacy_sources = detect_legacy_sources(new_app_data_dir, sources);
+    let selected_source = report
+        .legacy_sources
+        .iter()
+        .find(|source| source.detected)
+        .or_else(|| {
+            report
+                .legacy_sources
+                .iter()
+                .find(|source| source.db_path.is_some())
+        })
+        .cloned();
+    report.legacy_path_detected = selected_source
+        .as_ref()
+        .is_some_and(|source| source.detected);
+    report.legacy_db_path = selected_source
+        .as_ref()
+        .and_then(|source| source.db_path.clone());
+
+    if let Some(existing_marker_path) = find_migration_marker_path(new_app_data_dir) {
+        report.marker_present = true;
+        report.migration_completed = true;
+        // An unreadable marker still proves a finished migration; only the per-source
+        // detail is lost.
+        match read_migration_marker(&existing_marker_path) {
+            Ok(marker) => apply_marker_to_sources(&mut report, &marker),
+            Err(error) => eprintln!("Startup migration marker warning: {error}"),
+        }
+        return report;
+    }
+
+    match read_migration_journal(&journal_path) {
+        Ok(Some(journal)) => {
+            resume_startup_migration(
+                &mut report,
+                progress,
+                journal,
+                &journal_path,
+                &marker_path,
+                &new_db_path,
+            );
+            if report.migration_completed || report.migration_error.is_some() {
+                return report;
+            }
+        }
+        Ok(None) => {}
+        Err(error) => {
+            report.migration_error = Some(error);
+            return report;
+        }
+    }
+
+    let Some(selected_source) = selected_source.filter(|source| source.detected) else {
+        return report;
+    };
+    let Some(legacy_db_path) = selected_source.db_path.as_ref().map(PathBuf::from) else {
+        return report;
+    };
+
+    if new_db_path.exists() {
+        if let Err(error) = keep_existing_destination(
+            &mut report,
+            progress,
+            &selected_source.bundle_identifier,
+            &legacy_db_path,
+            &new_db_path,
+            &marker_path,
+        ) {
+            report.migration_error = Some(format!("keep existing database failed: {error}"));
+        }
+        return report;
+    }
+
+    copy_legacy_database(
+        &mut report,
+        progress,
+        &selected_source.bundle_identifier,
+        &legacy_db_path,
+        &new_db_path,
+        &journal_path,
+        &marker_path,
+    );
+    report
+}
+
+fn migration_error_code_for_phase(phase: Option<StartupMigrationPhase>) -> SoloStackErrorCode {
+    match phase {
+        Some(StartupMigrationPhase::Copying) => SoloStackErrorCode::MigrationCopyFailed,
+        Some(StartupMigrationPhase::CheckingIntegrity) => {
+            SoloStackErrorCode::MigrationIntegrityCheckFailed
+        }
+        Some(StartupMigrationPhase::WritingMarker) => {
+            SoloStackErrorCode::MigrationMarkerWriteFailed
+        }
+        _ => SoloStackErrorCode::MigrationFailed,
+    }
+}
+
+fn finish_migration_timeline(report: &mut StartupMigrationReport, progress: ProgressSink) {
+    let terminal_phase = match report.migration_error {
+        Some(_) => {
+            // The last phase entered before failing is where things broke.
+            let failed_phase = report.phase_timeline.last().map(|record| record.phase);
+            if report.migration_error_code.is_none() {
+                report.migration_error_code = Some(migration_error_code_for_phase(failed_phase));
+            }
+            StartupMigrationPhase::Failed
+        }
+        None => StartupMigrationPhase::Completed,
+    };
+    enter_migration_phase(report, progress, terminal_phase);
+}
+
+// Everything one migration run tells the webview, in the order it is emitted.
+enum StartupMigrationEvent<'a> {
+    Progress(&'a StartupMigrationProgressPayload),
+    Completed(&'a StartupMigrationReport),
+    Failed(&'a StartupMigrationFailedPayload),
+}
+
+fn run_migration_with_events(
+    new_app_data_dir: Result<PathBuf, String>,
+    sources: &[LegacyDataSource],
+    events: &mut dyn FnMut(StartupMigrationEvent),
+) -> StartupMigrationReport {
+    let mut emit_progress = |payload: &StartupMigrationProgressPayload| {
+        events(StartupMigrationEvent::Progress(payload));
+    };
+    let mut report = match new_app_data_dir {
+        Ok(new_app_data_dir) => {
+            migrate_legacy_database(&new_app_data_dir, sources, &mut emit_progress)
+        }
+        Err(error) => StartupMigrationReport {
+            migration_error: Some(format!("resolve app data dir failed: {error}")),
+            migration_error_code: Some(SoloStackErrorCode::AppDataDirUnavailable),
+            ..StartupMigrationReport::default()
+        },
+    };
+    finish_migration_timeline(&mut report, &mut emit_progress);
+
+    match report.migration_error.clone() {
+        Some(error) => {
+            // The terminal `failed` entry is last; the phase before it is where things broke.
+            let failed_phase = report
+                .phase_timeline
+                .iter()
+                .rev()
+                .nth(1)
+                .map(|record| record.phase);
+            events(StartupMigrationEvent::Failed(
+                &StartupMigrationFailedPayload {
+                    phase: failed_phase,
+                    error: SoloStackError {
+                        code: report
+                            .migration_error_code
+                            .unwrap_or(SoloStackErrorCode::MigrationFailed),
+                        detail: Some(error),
+                    },
+                    report: report.clone(),
+                },
+            ));
+        }
+        None => events(StartupMigrationEvent::Completed(&report)),
+    }
+    report
+}
+
+pub(crate) fn run_startup_legacy_db_migration<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> StartupMigrationReport {
+    let new_app_data_dir = app.path().app_data_dir().map_err(|error| error.to_string());
+    run_migration_with_events(new_app_data_dir, LEGACY_DATA_SOURCES, &mut |event| {
+        let _ = match event {
+            StartupMigrationEvent::Progress(payload) => app.emit(MIGRATION_PROGRESS_EVENT, payload),
+            StartupMigrationEvent::Completed(report) => app.emit(MIGRATION_COMPLETED_EVENT, report),
+            StartupMigrationEvent::Failed(payload) => app.emit(MIGRATION_FAILED_EVENT, payload),
+        };
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MigrationLayout {
+        root_dir: PathBuf,
+        new_app_data_dir: PathBuf,
+        legacy_db_path: PathBuf,
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum RecordedEvent {
+        Progress(StartupMigrationPhase),
+        Completed,
+        Failed(Option<StartupMigrationPhase>, SoloStackErrorCode),
+    }
+
+    // Records the events of one run, folding the repeated progress events of a phase into one.
+    fn record_events(
+        new_app_data_dir: Result<PathBuf, String>,
+    ) -> (StartupMigrationReport, Vec<RecordedEvent>) {
+        let mut events = Vec::new();
+        let report =
+            run_migration_with_events(new_app_data_dir, LEGACY_DATA_SOURCES, &mut |event| {
+                let recorded = match event {
+                    StartupMigrationEvent::Progress(payload) => {
+                        RecordedEvent::Progress(payload.phase)
+                    }
+                    StartupMigrationEvent::Completed(_) => RecordedEvent::Completed,
+                    StartupMigrationEvent::Failed(payload) => {
+                        RecordedEvent::Failed(payload.phase, payload.error.code)
+                    }
+                };
+                if events.last() != Some(&recorded) {
+                    events.push(recorded);
+                }
+            });
+        (report, events)
+    }
+
+    fn timeline_phases(report: &StartupMigrationReport) -> Vec<StartupMigrationPhase> {
+        report
+            .phase_timeline
+            .iter()
+            .map(|record| record.phase)
+            .collect()
+    }
+
+    impl MigrationLayout {
+        fn new() -> Self {
+            let root_dir = std::env::temp_dir().join(format!(
+                "solostack-startup-migration-{}",
+                uuid::Uuid::new_v4()
+            ));
+            let new_app_data_dir = root_dir.join(CURRENT_BUNDLE_IDENTIFIER);
+            fs::create_dir_all(&new_app_data_dir).unwrap();
+            let layout = Self {
+                legacy_db_path: resolve_legacy_source_db_path(
+                    &new_app_data_dir,
+                    &LEGACY_DATA_SOURCES[0],
+                )
+                .unwrap(),
+                root_dir,
+                new_app_data_dir,
+            };
+            layout.seed_legacy_database(&LEGACY_DATA_SOURCES[0], "Call the bank");
+            layout
+        }
+
+        fn seed_legacy_database(&self, source: &LegacyDataSource, task_title: &str) {
+            let db_path = resolve_legacy_source_db_path(&self.new_app_data_dir, source).unwrap();
+            fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+            let connection = rusqlite::Connection::open(&db_path).unwrap();
+            connection
+                .execute_batch("CREATE TABLE tasks (id TEXT PRIMARY KEY, title TEXT NOT NULL);")
+                .unwrap();
+            connection
+                .execute("INSERT INTO tasks VALUES ('task-1', ?1)", [task_title])
+                .unwrap();
+        }
+
+        // Points the title index at another column so every page still copies verbatim but
+        // `PRAGMA integrity_check` reports rows missing from the index.
+        fn corrupt_legacy_database(&self) {
+            let connection = rusqlite::Connection::open(&self.legacy_db_path).unwrap();
+            connection
+                .execute_batch(
+                    "CREATE INDEX tasks_title ON tasks(title);
+                     PRAGMA writable_schema = ON;
+                     UPDATE sqlite_master SET sql = 'CREATE INDEX tasks_title ON tasks(id)'
+                      WHERE name = 'tasks_title';
+                     PRAGMA writable_schema = OFF;",
+                )
+                .unwrap();
+        }
+
+        fn source_statuses(report: &StartupMigrationReport) -> Vec<LegacySourceStatus> {
+            report
+                .legacy_sources
+                .iter()
+                .map(|source| source.status)
+                .collect()
+        }
+
+        fn new_db_path(&self) -> PathBuf {
+            self.new_app_data_dir.join(DATABASE_FILENAME)
+        }
+
+        fn journal_path(&self) -> PathBuf {
+            self.new_app_data_dir
+                .join(STARTUP_MIGRATION_JOURNAL_FILENAME)
+        }
+
+        fn temp_db_path(&self) -> PathBuf {
+            database_sidecar_path(&self.new_db_path(), STARTUP_MIGRATION_TEMP_SUFFIX)
+        }
+
+        // Leaves the files a launch that died right after journaling `step` would leave; with
+        // `renamed` the copy has also been moved into place.
+        fn interrupt_after(&self, step: StartupMigrationStep, renamed: bool) {
+            let page_count =
+                db::backup_database(&self.legacy_db_path, &self.temp_db_path(), &mut |_, _| {})
+                    .unwrap();
+            let checksums = if step >= StartupMigrationStep::Synced {
+                vec![DatabaseFileChecksum {
+                    file_name: DATABASE_FILENAME.to_string(),
+                    ..compute_file_checksum(&self.temp_db_path()).unwrap()
+                }]
+            } else {
+                Vec::new()
+            };
+            let journal = StartupMigrationJournal {
+                version: STARTUP_MIGRATION_JOURNAL_VERSION,
+                source_bundle_identifier: LEGACY_DATA_SOURCES[0].bundle_identifier.to_string(),
+                source_db_path: self.legacy_db_path.to_string_lossy().to_string(),
+                temp_db_path: self.temp_db_path().to_string_lossy().to_string(),
+                destination_db_path: self.new_db_path().to_string_lossy().to_string(),
+                last_completed_step: step,
+                page_count: Some(page_count),
+                checksums,
+                updated_at: now_iso(),
+            };
+            fs::write(
+                self.journal_path(),
+                serde_json::to_string_pretty(&journal).unwrap(),
+            )
+            .unwrap();
+            if renamed {
+                fs::rename(self.temp_db_path(), self.new_db_path()).unwrap();
+            }
+        }
+
+        fn migrate(&self) -> StartupMigrationReport {
+            self.migrate_from(LEGACY_DATA_SOURCES)
+        }
+
+        fn migrate_from(&self, sources: &[LegacyDataSource]) -> StartupMigrationReport {
+            migrate_legacy_database(&self.new_app_data_dir, sources, &mut |_| {})
+        }
+
+        fn task_titles(&self) -> Vec<String> {
+            let connection = rusqlite::Connection::open(self.new_db_path()).unwrap();
+            let mut statement = connection.prepare("SELECT title FROM tasks").unwrap();
+            let titles = statement
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<Vec<String>, _>>()
+                .unwrap();
+            titles
+        }
+
+        fn assert_completed_with_marker(&self, report: &StartupMigrationReport) {
+            assert_eq!(report.migration_error, None);
+            assert!(report.migration_completed);
+            assert!(report.marker_present);
+            assert!(!self.journal_path().exists());
+            assert!(!self.temp_db_path().exists());
+            let marker = read_completed_migration_marker(&self.new_app_data_dir)
+                .unwrap()
+                .unwrap();
+            assert_eq!(marker.integrity_check.as_deref(), Some("ok"));
+            assert_eq!(self.task_titles(), vec!["Call the bank".to_string()]);
+        }
+    }
+
+    impl Drop for MigrationLayout {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root_dir);
+        }
+    }
+
+    #[test]
+    fn fresh_migration_copies_and_writes_the_marker() {
+        let layout = MigrationLayout::new();
+        let report = layout.migrate();
+        layout.assert_completed_with_marker(&report);
+        assert!(!report.resumed_from_journal);
+        assert_eq!(
+            report.last_completed_step,
+            Some(StartupMigrationStep::MarkerWritten)
+        );
+        assert_eq!(
+            report.legacy_sources[0].status,
+            LegacySourceStatus::Migrated
+        );
+
+        let relaunch = layout.migrate();
+        assert!(relaunch.migration_completed);
+        assert!(!relaunch.migration_attempted);
+        assert_eq!(
+            relaunch.legacy_sources[0].status,
+            LegacySourceStatus::Consumed
+        );
+    }
+
+    // Newest first, like `LEGACY_DATA_SOURCES`: a generation that was never installed, the one
+    // `MigrationLayout` seeds, an older layout nested in the same identifier dir and an older
+    // identifier.
+    const SOURCE_CHAIN: &[LegacyDataSource] = &[
+        LegacyDataSource {
+            bundle_identifier: "com.solutionsstudio.solostack-next",
+            data_subdir: None,
+            database_filename: DATABASE_FILENAME,
+        },
+        LegacyDataSource {
+            bundle_identifier: "com.antigravity.solostack",
+            data_subdir: None,
+            database_filename: DATABASE_FILENAME,
+        },
+        LegacyDataSource {
+            bundle_identifier: "com.antigravity.solostack",
+            data_subdir: Some("data"),
+            database_filename: "tasks.db",
+        },
+        LegacyDataSource {
+            bundle_identifier: "app.solostack.beta",
+            data_subdir: None,
+            database_filename: DATABASE_FILENAME,
+        },
+    ];
+
+    fn chain_layout() -> MigrationLayout {
+        let layout = MigrationLayout::new();
+        layout.seed_legacy_database(&SOURCE_CHAIN[2], "From the nested layout");
+        layout.seed_legacy_database(&SOURCE_CHAIN[3], "From the beta");
+        layout
+    }
+
+    #[test]
+    fn newest_detected_source_is_copied_and_older_ones_are_superseded() {
+        let layout = chain_layout();
+        let report = layout.migrate_from(SOURCE_CHAIN);
+        layout.assert_completed_with_marker(&report);
+        assert_eq!(
+            report.legacy_db_path.as_deref(),
+            Some(layout.legacy_db_path.to_string_lossy().as_ref())
+        );
+        assert_eq!(
+            MigrationLayout::source_statuses(&report),
+            vec![
+                LegacySourceStatus::NotFound,
+                LegacySourceStatus::Migrated,
+                LegacySourceStatus::Superseded,
+                LegacySourceStatus::Superseded,
+            ]
+        );
+
+        // The marker lists every detected source, so older generations are never copied over
+        // the migrated database on a later launch.
+        let relaunch = layout.migrate_from(SOURCE_CHAIN);
+        assert!(!relaunch.migration_attempted);
+        assert_eq!(
+            MigrationLayout::source_statuses(&relaunch),
+            vec![
+                LegacySourceStatus::NotFound,
+                LegacySourceStatus::Consumed,
+                LegacySourceStatus::Consumed,
+                LegacySourceStatus::Consumed,
+            ]
+        );
+        assert_eq!(layout.task_titles(), vec!["Call the bank".to_string()]);
+    }
+
+    #[test]
+    fn older_source_is_copied_when_the_newer_ones_are_gone() {
+        let layout = chain_layout();
+        fs::remove_file(&layout.legacy_db_path).unwrap();
+        let report = layout.migrate_from(SOURCE_CHAIN);
+        assert!(report.migration_completed);
+        assert_eq!(
+            MigrationLayout::source_statuses(&report),
+            vec![
+                LegacySourceStatus::NotFound,
+                LegacySourceStatus::NotFound,
+                LegacySourceStatus::Migrated,
+                LegacySourceStatus::Superseded,
+            ]
+        );
+        assert_eq!(
+            layout.task_titles(),
+            vec!["From the nested layout".to_string()]
+        );
+    }
+
+    #[test]
+    fn v1_marker_finishes_the_migration_and_supersedes_unrecorded_sources() {
+        let layout = chain_layout();
+        fs::write(
+            layout
+                .new_app_data_dir
+                .join(LEGACY_STARTUP_MIGRATION_MARKER_FILENAME),
+            serde_json::json!({ "source_db_path": layout.legacy_db_path }).to_string(),
+        )
+        .unwrap();
+
+        let report = layout.migrate_from(SOURCE_CHAIN);
+        assert!(report.marker_present);
+        assert!(report.migration_completed);
+        assert!(!report.migration_attempted);
+        assert!(!layout.new_db_path().exists());
+        assert_eq!(
+            MigrationLayout::source_statuses(&report),
+            vec![
+                LegacySourceStatus::NotFound,
+                LegacySourceStatus::Consumed,
+                LegacySourceStatus::Superseded,
+                LegacySourceStatus::Superseded,
+            ]
+        );
+        let marker = read_completed_migration_marker(&layout.new_app_data_dir)
+            .unwrap()
+            .unwrap();
+        assert_eq!(marker.integrity_check, None);
+    }
+
+    #[test]
+    fn identifier_dirs_are_listed_once_per_identifier() {
+        let layout = MigrationLayout::new();
+        let app_data_dirs = app_data_dirs_of_sources(&layout.new_app_data_dir, SOURCE_CHAIN)
+            .into_iter()
+            .map(|(bundle_identifier, dir)| {
+                assert_eq!(dir, layout.root_dir.join(bundle_identifier));
+                bundle_identifier
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            app_data_dirs,
+            vec![
+                "com.solutionsstudio.solostack-next",
+                "com.antigravity.solostack",
+                "app.solostack.beta",
+            ]
+        );
+    }
+
+    #[test]
+    fn corrupt_source_is_rolled_back_after_the_integrity_check() {
+        let layout = MigrationLayout::new();
+        layout.corrupt_legacy_database();
+
+        let report = layout.migrate();
+        assert!(report
+            .migration_error
+            .as_deref()
+            .is_some_and(|error| error.starts_with("integrity check failed")));
+        assert!(report.rollback_performed);
+        assert!(!report.integrity_check_passed);
+        assert!(!report.migration_completed);
+        assert!(!report.marker_present);
+        assert_eq!(
+            report.phase_timeline.last().map(|record| record.phase),
+            Some(StartupMigrationPhase::CheckingIntegrity)
+        );
+        assert!(!layout.new_db_path().exists());
+        assert!(!layout.temp_db_path().exists());
+        assert!(!layout.journal_path().exists());
+        assert!(layout.legacy_db_path.exists());
+    }
+
+    #[test]
+    fn destination_changed_after_the_rename_fails_the_checksum_and_stays_unmarked() {
+        let layout = MigrationLayout::new();
+        layout.interrupt_after(StartupMigrationStep::Renamed, true);
+        rusqlite::Connection::open(layout.new_db_path())
+            .unwrap()
+            .execute(
+                "INSERT INTO tasks VALUES ('task-2', 'Edited elsewhere')",
+                [],
+            )
+            .unwrap();
+
+        let report = layout.migrate();
+        assert!(report
+            .migration_error
+            .as_deref()
+            .is_some_and(|error| error.contains("checksum changed")));
+        assert!(!report.migration_completed);
+        assert!(!report.marker_present);
+        assert_eq!(
+            read_completed_migration_marker(&layout.new_app_data_dir)
+                .unwrap()
+                .map(|_| ()),
+            None
+        );
+        assert!(layout.journal_path().exists());
+    }
+
+    #[test]
+    fn crash_before_the_rename_rolls_back_and_copies_again() {
+        for step in [
+            StartupMigrationStep::Started,
+            StartupMigrationStep::BackupWritten,
+            StartupMigrationStep::IntegrityChecked,
+            StartupMigrationStep::Synced,
+        ] {
+            let layout = MigrationLayout::new();
+            layout.interrupt_after(step, false);
+            let report = layout.migrate();
+            assert!(report.resumed_from_journal, "{step:?}");
+            assert!(report.rollback_performed, "{step:?}");
+            layout.assert_completed_with_marker(&report);
+        }
+    }
+
+    #[test]
+    fn crash_between_the_rename_and_its_journal_entry_finishes_with_a_marker() {
+        let layout = MigrationLayout::new();
+        layout.interrupt_after(StartupMigrationStep::Synced, true);
+        let report = layout.migrate();
+        assert!(report.resumed_from_journal);
+        assert!(!report.rollback_performed);
+        assert!(report.integrity_check_passed);
+        layout.assert_completed_with_marker(&report);
+    }
+
+    #[test]
+    fn crash_after_the_rename_resumes_at_the_marker() {
+        let layout = MigrationLayout::new();
+        layout.interrupt_after(StartupMigrationStep::Renamed, true);
+        let report = layout.migrate();
+        assert!(report.resumed_from_journal);
+        assert!(!report.rollback_performed);
+        layout.assert_completed_with_marker(&report);
+    }
+
+    #[test]
+    fn changed_destination_is_kept_and_marked_once_it_checks_out() {
+        let layout = MigrationLayout::new();
+        layout.interrupt_after(StartupMigrationStep::Synced, true);
+        rusqlite::Connection::open(layout.new_db_path())
+            .unwrap()
+            .execute(
+                "INSERT INTO tasks VALUES ('task-2', 'Edited elsewhere')",
+                [],
+            )
+            .unwrap();
+
+        let report = layout.migrate();
+        assert!(report.rollback_performed);
+        assert_eq!(report.migration_error, None);
+        assert!(report.migration_completed);
+        assert!(report.marker_present);
+        assert!(report.integrity_check_passed);
+        let marker = read_completed_migration_marker(&layout.new_app_data_dir)
+            .unwrap()
+            .unwrap();
+        assert_eq!(marker.integrity_check.as_deref(), Some("ok"));
+        let marker_json: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(
+                layout
+                    .new_app_data_dir
+                    .join(STARTUP_MIGRATION_MARKER_FILENAME),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(marker_json["copy_method"], STARTUP_MIGRATION_KEEP_METHOD);
+        assert_eq!(
+            report.legacy_sources[0].status,
+            LegacySourceStatus::Superseded
+        );
+        assert!(!layout.journal_path().exists());
+        assert_eq!(layout.task_titles().len(), 2);
+    }
+
+    #[test]
+    fn corrupt_existing_destination_is_not_reported_as_migrated() {
+        let layout = MigrationLayout::new();
+        fs::write(layout.new_db_path(), b"not a sqlite database").unwrap();
+
+        let report = layout.migrate();
+        assert!(report
+            .migration_error
+            .as_deref()
+            .is_some_and(|error| error.starts_with("keep existing database failed")));
+        assert!(!report.migration_completed);
+        assert!(!report.marker_present);
+        assert_eq!(
+            report.phase_timeline.last().map(|record| record.phase),
+            Some(StartupMigrationPhase::CheckingIntegrity)
+        );
+        assert_eq!(
+            read_completed_migration_marker(&layout.new_app_data_dir)
+                .unwrap()
+                .map(|_| ()),
+            None
+        );
+    }
+
+    #[test]
+    fn migration_reports_its_phases_in_order() {
+        use StartupMigrationPhase::*;
+
+        let layout = MigrationLayout::new();
+        let (report, events) = record_events(Ok(layout.new_app_data_dir.clone()));
+        let phases = vec![
+            Detecting,
+            Copying,
+            CheckingIntegrity,
+            Syncing,
+            Renaming,
+            WritingMarker,
+            Completed,
+        ];
+        assert_eq!(timeline_phases(&report), phases);
+        let mut expected_events: Vec<RecordedEvent> =
+            phases.into_iter().map(RecordedEvent::Progress).collect();
+        expected_events.push(RecordedEvent::Completed);
+        assert_eq!(events, expected_events);
+    }
+
+    #[test]
+    fn failed_event_names_the_phase_that_broke() {
+        use StartupMigrationPhase::*;
+
+        let layout = MigrationLayout::new();
+        fs::write(layout.new_db_path(), b"not a sqlite database").unwrap();
+        let (report, events) = record_events(Ok(layout.new_app_data_dir.clone()));
+        assert_eq!(
+            report.migration_error_code,
+            Some(SoloStackErrorCode::MigrationIntegrityCheckFailed)
+        );
+        assert_eq!(
+            timeline_phases(&report),
+            vec![Detecting, CheckingIntegrity, Failed]
+        );
+        assert_eq!(
+            events,
+            vec![
+                RecordedEvent::Progress(Detecting),
+                RecordedEvent::Progress(CheckingIntegrity),
+                RecordedEvent::Progress(Failed),
+                RecordedEvent::Failed(
+                    Some(CheckingIntegrity),
+                    SoloStackErrorCode::MigrationIntegrityCheckFailed
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unresolved_app_data_dir_fails_before_any_phase() {
+        let (report, events) = record_events(Err("no home directory".to_string()));
+        assert!(!report.migration_completed);
+        assert_eq!(
+            events,
+            vec![
+                RecordedEvent::Progress(StartupMigrationPhase::Failed),
+                RecordedEvent::Failed(None, SoloStackErrorCode::AppDataDirUnavailable),
+            ]
+        );
+    }
+
+    #[test]
+    fn unreadable_journal_stops_the_migration() {
+        let layout = MigrationLayout::new();
+        fs::write(layout.journal_path(), "{").unwrap();
+        let report = layout.migrate();
+        assert!(report
+            .migration_error
+            .as_deref()
+            .is_some_and(|error| error.starts_with("parse migration journal failed")));
+        assert!(!layout.new_db_path().exists());
+    }
+}
@@ -0,0 +1,822 @@
+// Applies pulled changes to the local tables. Port of `applyIncomingSyncChange` and its conflict
+// bookkeeping in `src/lib/database.ts`: last-writer-wins on `updated_at` (device id breaks ties),
+// payloads that cannot be applied become rows in `sync_conflicts` with a `detected` event, and an
+// applied change closes any open conflict raised by the same idempotency key.
+// Runs inside the caller's transaction; nothing here opens its own.
+
+use rusqlite::{params, types::ValueRef, Connection, OptionalExtension, Row};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    error::SoloStackError,
+    repository::{
+        self, map_sql_error, ProjectStatus, SyncEntityType, SyncOperation, TaskPriority,
+        TaskRecurrence, TaskStatus,
+    },
+    sync_contract::SyncPushChange,
+};
+
+const LOCAL_ONLY_SETTING_PREFIX: &str = "local.";
+const SYNC_CONFLICT_EVENT_MAX_PER_CONFLICT: i64 = 200;
+const SYNC_CONFLICT_EVENT_RETENTION_DAYS: i64 = 90;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PullApplyStatus {
+    Applied,
+    Skipped,
+    Conflict { reason_code: &'static str },
+}
+
+struct IncomingConflict {
+    conflict_type: &'static str,
+    reason_code: &'static str,
+    message: &'static str,
+}
+
+struct EntityVersionState {
+    updated_at: Option<String>,
+    updated_by_device: Option<String>,
+}
+
+fn entity_table(entity_type: SyncEntityType) -> &'static str {
+    match entity_type {
+        SyncEntityType::Project => "projects",
+        SyncEntityType::Task => "tasks",
+        SyncEntityType::TaskSubtask => "task_subtasks",
+        SyncEntityType::TaskTemplate => "task_templates",
+        SyncEntityType::Setting => "settings",
+    }
+}
+
+fn entity_key_column(entity_type: SyncEntityType) -> &'static str {
+    match entity_type {
+        SyncEntityType::Setting => "key",
+        _ => "id",
+    }
+}
+
+fn read_entity_version_state(
+    connection: &Connection,
+    entity_type: SyncEntityType,
+    entity_id: &str,
+) -> Result<Option<EntityVersionState>, SoloStackError> {
+    connection
+        .query_row(
+            &format!(
+                "SELECT updated_at, updated_by_device FROM {} WHERE id = ?1 LIMIT 1",
+                entity_table(entity_type)
+            ),
+            [entity_id],
+            |row| {
+                Ok(EntityVersionState {
+                    updated_at: row.get(0)?,
+                    updated_by_device: row.get(1)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(map_sql_error("read sync entity version"))
+}
+
+fn comparable_timestamp(value: Option<&str>) -> Option<i64> {
+    value
+        .and_then(repository::parse_iso_datetime)
+        .map(|datetime| datetime.timestamp_millis())
+}
+
+fn normalize_device_id(value: Option<&str>) -> String {
+    value.unwrap_or_default().trim().to_lowercase()
+}
+
+fn should_apply_incoming_lww(
+    existing: Option<&EntityVersionState>,
+    incoming: &SyncPushChange,
+) -> bool {
+    let Some(existing) = existing else {
+        return true;
+    };
+    let Some(incoming_time) = comparable_timestamp(Some(&incoming.updated_at)) else {
+        return false;
+    };
+    let Some(existing_time) = comparable_timestamp(existing.updated_at.as_deref()) else {
+        return true;
+    };
+    if incoming_time != existing_time {
+        return incoming_time > existing_time;
+    }
+    normalize_device_id(Some(&incoming.updated_by_device))
+        >= normalize_device_id(existing.updated_by_device.as_deref())
+}
+
+// Two devices wrote different notes in the same millisecond; neither side can win silently.
+fn is_task_notes_collision(
+    existing: &EntityVersionState,
+    existing_notes_markdown: Option<&str>,
+    incoming: &SyncPushChange,
+    incoming_notes_markdown: Option<&str>,
+) -> bool {
+    let local_notes = existing_notes_markdown.unwrap_or_default().trim();
+    let incoming_notes = incoming_notes_markdown.unwrap_or_default().trim();
+    if local_notes.is_empty() || incoming_notes.is_empty() || local_notes == incoming_notes {
+        return false;
+    }
+    let (Some(existing_time), Some(incoming_time)) = (
+        comparable_timestamp(existing.updated_at.as_deref()),
+        comparable_timestamp(Some(&incoming.updated_at)),
+    ) else {
+        return false;
+    };
+    if existing_time != incoming_time {
+        return false;
+    }
+    let existing_device = normalize_device_id(existing.updated_by_device.as_deref());
+    let incoming_device = normalize_device_id(Some(&incoming.updated_by_device));
+    !existing_device.is_empty() && !incoming_device.is_empty() && existing_device != incoming_device
+}
+
+fn payload_text(payload: &Map<String, Value>, key: &str) -> Option<String> {
+    payload.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn payload_optional_text(payload: &Map<String, Value>, key: &str) -> Option<String> {
+    payload_text(payload, key)
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+fn payload_enum<T: DeserializeOwned>(payload: &Map<String, Value>, key: &str, fallback: T) -> T {
+    payload
+        .get(key)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or(fallback)
+}
+
+fn payload_bool_flag(payload: &Map<String, Value>, key: &str) -> i64 {
+    let enabled = match payload.get(key) {
+        Some(Value::Bool(value)) => *value,
+        Some(Value::Number(value)) => value.as_f64() == Some(1.0),
+        Some(Value::String(value)) => value == "1",
+        _ => false,
+    };
+    i64::from(enabled)
+}
+
+fn payload_non_negative_integer(payload: &Map<String, Value>, key: &str) -> Option<i64> {
+    let value = match payload.get(key)? {
+        Value::Number(value) => value.as_f64()?,
+        Value::String(value) if !value.trim().is_empty() => value.trim().parse::<f64>().ok()?,
+        _ => return None,
+    };
+    value.is_finite().then(|| value.trunc().max(0.0) as i64)
+}
+
+fn payload_timestamp_or_now(payload: &Map<String, Value>, key: &str) -> String {
+    payload_text(payload, key)
+        .filter(|value| repository::parse_iso_datetime(value).is_some())
+        .unwrap_or_else(repository::now_iso)
+}
+
+fn setting_value(payload: &Map<String, Value>) -> String {
+    match payload.get("value") {
+        Some(Value::String(value)) => value.clone(),
+        None | Some(Value::Null) => String::new(),
+        Some(value) => value.to_string(),
+    }
+}
+
+fn sql_value_to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(value) => json!(value),
+        ValueRef::Real(value) => json!(value),
+        ValueRef::Text(value) | ValueRef::Blob(value) => {
+            Value::String(String::from_utf8_lossy(value).into_owned())
+        }
+    }
+}
+
+fn row_to_json(row: &Row<'_>) -> rusqlite::Result<Value> {
+    let statement = row.as_ref();
+    let mut object = Map::new();
+    for index in 0..statement.column_count() {
+        object.insert(
+            statement.column_name(index)?.to_string(),
+            sql_value_to_json(row.get_ref(index)?),
+        );
+    }
+    Ok(Value::Object(object))
+}
+
+fn read_entity_snapshot(
+    connection: &Connection,
+    entity_type: SyncEntityType,
+    entity_id: &str,
+) -> Result<Option<Value>, SoloStackError> {
+    let columns = match entity_type {
+        SyncEntityType::Setting => "key, value",
+        _ => "*",
+    };
+    connection
+        .query_row(
+            &format!(
+                "SELECT {columns} FROM {} WHERE {} = ?1 LIMIT 1",
+                entity_table(entity_type),
+                entity_key_column(entity_type)
+            ),
+            [entity_id],
+            row_to_json,
+        )
+        .optional()
+        .map_err(map_sql_error("read sync entity snapshot"))
+}
+
+fn add_conflict_event(
+    connection: &Connection,
+    conflict_id: &str,
+    event_type: &str,
+    event_payload: Value,
+    now: &str,
+) -> Result<(), SoloStackError> {
+    connection
+        .execute(
+            "INSERT INTO sync_conflict_events (id, conflict_id, event_type, event_payload_json, created_at)
+                  VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                Uuid::new_v4().to_string(),
+                conflict_id,
+                event_type,
+                event_payload.to_string(),
+                now
+            ],
+        )
+        .map_err(map_sql_error("insert sync conflict event"))?;
+
+    connection
+        .execute(
+            "DELETE FROM sync_conflict_events
+              WHERE conflict_id = ?1
+                AND id NOT IN (
+                  SELECT id
+                    FROM sync_conflict_events
+                   WHERE conflict_id = ?1
+                   ORDER BY created_at DESC, id DESC
+                   LIMIT ?2
+                )",
+            params![conflict_id, SYNC_CONFLICT_EVENT_MAX_PER_CONFLICT],
+        )
+        .map_err(map_sql_error("prune sync conflict events"))?;
+    let anchor = repository::parse_iso_datetime(now).unwrap_or_else(chrono::Utc::now);
+    let cutoff = anchor - chrono::Duration::days(SYNC_CONFLICT_EVENT_RETENTION_DAYS);
+    connection
+        .execute(
+            "DELETE FROM sync_conflict_events WHERE created_at < ?1",
+            [cutoff.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)],
+        )
+        .map(|_| ())
+        .map_err(map_sql_error("prune sync conflict events"))
+}
+
+fn persist_conflict(
+    connection: &Connection,
+    change: &SyncPushChange,
+    entity_id: &str,
+    conflict: IncomingConflict,
+) -> Result<PullApplyStatus, SoloStackError> {
+    let now = repository::now_iso();
+    let incoming_key = match change.idempotency_key.trim() {
+        "" => format!(
+            "{}:{}:{}",
+            change.entity_type.as_str(),
+            change.entity_id,
+            change.updated_at
+        ),
+        key => key.to_string(),
+    };
+    let existing = connection
+        .query_row(
+            "SELECT id, status FROM sync_conflicts WHERE incoming_idempotency_key = ?1 LIMIT 1",
+            [&incoming_key],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )
+        .optional()
+        .map_err(map_sql_error("read sync conflict"))?;
+
+    // The user already settled this exact change; replaying it only leaves a trace.
+    if let Some((conflict_id, status)) = &existing {
+        if status == "resolved" || status == "ignored" {
+            add_conflict_event(
+                connection,
+                conflict_id,
+                "retried",
+                json!({
+                    "reason": "incoming_change_repeated",
+                    "reason_code": conflict.reason_code,
+                }),
+                &now,
+            )?;
+            return Ok(PullApplyStatus::Skipped);
+        }
+    }
+
+    let local_payload = read_entity_snapshot(connection, change.entity_type, entity_id)?
+        .map(|payload| payload.to_string());
+    let remote_payload = match change.operation {
+        SyncOperation::Upsert => change
+            .payload
+            .as_ref()
+            .map(|payload| Value::Object(payload.clone()).to_string()),
+        SyncOperation::Delete => None,
+    };
+    let conflict_id = match existing {
+        Some((conflict_id, _)) => {
+            connection
+                .execute(
+                    "UPDATE sync_conflicts
+                        SET entity_type = ?1,
+                            entity_id = ?2,
+                            operation = ?3,
+                            conflict_type = ?4,
+                            reason_code = ?5,
+                            message = ?6,
+                            local_payload_json = ?7,
+                            remote_payload_json = ?8,
+                            base_payload_json = NULL,
+                            status = 'open',
+                            resolution_strategy = NULL,
+                            resolution_payload_json = NULL,
+                            resolved_by_device = NULL,
+                            resolved_at = NULL,
+                            detected_at = ?9,
+                            updated_at = ?9
+                      WHERE id = ?10",
+                    params![
+                        change.entity_type,
+                        entity_id,
+                        change.operation,
+                        conflict.conflict_type,
+                        conflict.reason_code,
+                        conflict.message,
+                        local_payload,
+                        remote_payload,
+                        now,
+                        conflict_id,
+                    ],
+                )
+                .map_err(map_sql_error("update sync conflict"))?;
+            conflict_id
+        }
+        None => {
+            let conflict_id = Uuid::new_v4().to_string();
+            connection
+                .execute(
+                    "INSERT INTO sync_conflicts (
+                        id,
+                        incoming_idempotency_key,
+                        entity_type,
+                        entity_id,
+                        operation,
+                        conflict_type,
+                        reason_code,
+                        message,
+                        local_payload_json,
+                        remote_payload_json,
+                        base_payload_json,
+                        status,
+                        detected_at,
+                        created_at,
+                        updated_at
+                      )
+                      VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, NULL, 'open', ?11, ?11, ?11)",
+                    params![
+                        conflict_id,
+                        incoming_key,
+                        change.entity_type,
+                        entity_id,
+                        change.operation,
+                        conflict.conflict_type,
+                        conflict.reason_code,
+                        conflict.message,
+                        local_payload,
+                        remote_payload,
+                        now,
+                    ],
+                )
+                .map_err(map_sql_error("insert sync conflict"))?;
+            conflict_id
+        }
+    };
+
+    add_conflict_event(
+        connection,
+        &conflict_id,
+        "detected",
+        json!({
+            "reason_code": conflict.reason_code,
+            "message": conflict.message,
+            "entity_type": change.entity_type,
+            "entity_id": entity_id,
+            "operation": change.operation,
+        }),
+        &now,
+    )?;
+    Ok(PullApplyStatus::Conflict {
+        reason_code: conflict.reason_code,
+    })
+}
+
+fn resolve_conflicts_applied_by(
+    connection: &Connection,
+    change: &SyncPushChange,
+) -> Result<(), SoloStackError> {
+    let incoming_key = change.idempotency_key.trim();
+    if incoming_key.is_empty() {
+        return Ok(());
+    }
+    let conflict_ids = connection
+        .prepare(
+            "SELECT id FROM sync_conflicts WHERE incoming_idempotency_key = ?1 AND status = 'open'",
+        )
+        .and_then(|mut statement| {
+            statement
+                .query_map([incoming_key], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .map_err(map_sql_error("read open sync conflicts"))?;
+    if conflict_ids.is_empty() {
+        return Ok(());
+    }
+
+    let now = repository::now_iso();
+    for conflict_id in conflict_ids {
+        connection
+            .execute(
+                "UPDATE sync_conflicts
+                    SET status = 'resolved',
+                        resolution_strategy = 'retry',
+                        resolved_by_device = ?1,
+                        resolved_at = ?2,
+                        updated_at = ?2
+                  WHERE id = ?3",
+                params![change.updated_by_device, now, conflict_id],
+            )
+            .map_err(map_sql_error("resolve sync conflict"))?;
+        add_conflict_event(
+            connection,
+            &conflict_id,
+            "resolved",
+            json!({ "strategy": "retry", "reason": "incoming_change_applied" }),
+            &now,
+        )?;
+    }
+    Ok(())
+}
+
+fn upsert_project(
+    connection: &Connection,
+    change: &SyncPushChange,
+    entity_id: &str,
+    payload: &Map<String, Value>,
+    sync_version: i64,
+) -> Result<Option<IncomingConflict>, SoloStackError> {
+    let Some(name) = payload_optional_text(payload, "name") else {
+        return Ok(Some(IncomingConflict {
+            conflict_type: "field_conflict",
+            reason_code: "MISSING_PROJECT_NAME",
+            message: "Project name is required in incoming payload.",
+        }));
+    };
+    connection
+        .execute(
+            "INSERT INTO projects (
+                id, name, description, color, status, created_at, updated_at, sync_version,
+                updated_by_device
+              )
+              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+              ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                description = excluded.description,
+                color = excluded.color,
+                status = excluded.status,
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at,
+                sync_version = excluded.sync_version,
+                updated_by_device = excluded.updated_by_device",
+            params![
+                entity_id,
+                name,
+                payload_optional_text(payload, "description"),
+                payload_optional_text(payload, "color"),
+                payload_enum(payload, "status", ProjectStatus::Active),
+                payload_timestamp_or_now(payload, "created_at"),
+                change.updated_at,
+                sync_version,
+                change.updated_by_device,
+            ],
+        )
+        .map_err(map_sql_error("apply incoming project"))?;
+    Ok(None)
+}
+
+fn upsert_task(
+    connection: &Connection,
+    change: &SyncPushChange,
+    entity_id: &str,
+    payload: &Map<String, Value>,
+    sync_version: i64,
+    existing: Option<&EntityVersionState>,
+) -> Result<Option<IncomingConflict>, SoloStackError> {
+    let Some(title) = payload_optional_text(payload, "title") else {
+        return Ok(Some(IncomingConflict {
+            conflict_type: "field_conflict",
+            reason_code: "MISSING_TASK_TITLE",
+            message: "Task title is required in incoming payload.",
+        }));
+    };
+
+    let incoming_notes_markdown =
+        repository::normalize_task_notes_markdown(payload_text(payload, "notes_markdown"));
+    if let Some(existing) = existing.filter(|_| payload.contains_key("notes_markdown")) {
+        let existing_notes_markdown = connection
+            .query_row(
+                "SELECT notes_markdown FROM tasks WHERE id = ?1",
+                [entity_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .map_err(map_sql_error("read task notes"))?;
+        let existing_notes_markdown =
+            repository::normalize_task_notes_markdown(existing_notes_markdown);
+        if is_task_notes_collision(
+            existing,
+            existing_notes_markdown.as_deref(),
+            change,
+            incoming_notes_markdown.as_deref(),
+        ) {
+            return Ok(Some(IncomingConflict {
+                conflict_type: "notes_collision",
+                reason_code: "TASK_NOTES_COLLISION",
+                message: "Incoming task notes collide with locally edited notes.",
+            }));
+        }
+    }
+
+    let project_id = payload_optional_text(payload, "project_id");
+    if let Some(project_id) = project_id.as_deref() {
+        if read_entity_version_state(connection, SyncEntityType::Project, project_id)?.is_none() {
+            return Ok(Some(IncomingConflict {
+                conflict_type: "delete_vs_update",
+                reason_code: "TASK_PROJECT_NOT_FOUND",
+                message: "Incoming task references a project that does not exist locally.",
+            }));
+        }
+    }
+
+    connection
+        .execute(
+            "INSERT INTO tasks (
+                id, title, description, notes_markdown, project_id, status, priority,
+                is_important, due_at, remind_at, recurrence, created_at, updated_at, sync_version,
+                updated_by_device
+              )
+              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+              ON CONFLICT(id) DO UPDATE SET
+                title = excluded.title,
+                description = excluded.description,
+                notes_markdown = excluded.notes_markdown,
+                project_id = excluded.project_id,
+                status = excluded.status,
+                priority = excluded.priority,
+                is_important = excluded.is_important,
+                due_at = excluded.due_at,
+                remind_at = excluded.remind_at,
+                recurrence = excluded.recurrence,
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at,
+                sync_version = excluded.sync_version,
+                updated_by_device = excluded.updated_by_device",
+            params![
+                entity_id,
+                title,
+                payload_optional_text(payload, "description"),
+                incoming_notes_markdown,
+                project_id,
+                payload_enum(payload, "status", TaskStatus::Todo),
+                payload_enum(payload, "priority", TaskPriority::Normal),
+                payload_bool_flag(payload, "is_important"),
+                payload_optional_text(payload, "due_at"),
+                payload_optional_text(payload, "remind_at"),
+                payload_enum(payload, "recurrence", TaskRecurrence::None),
+                payload_timestamp_or_now(payload, "created_at"),
+                change.updated_at,
+                sync_version,
+                change.updated_by_device,
+            ],
+        )
+        .map_err(map_sql_error("apply incoming task"))?;
+    Ok(None)
+}
+
+fn upsert_task_subtask(
+    connection: &Connection,
+    change: &SyncPushChange,
+    entity_id: &str,
+    payload: &Map<String, Value>,
+    sync_version: i64,
+) -> Result<Option<IncomingConflict>, SoloStackError> {
+    let (Some(task_id), Some(title)) = (
+        payload_optional_text(payload, "task_id"),
+        payload_optional_text(payload, "title"),
+    ) else {
+        return Ok(Some(IncomingConflict {
+            conflict_type: "field_conflict",
+            reason_code: "INVALID_SUBTASK_PAYLOAD",
+            message: "Subtask payload requires task_id and title.",
+        }));
+    };
+    if read_entity_version_state(connection, SyncEntityType::Task, &task_id)?.is_none() {
+        return Ok(Some(IncomingConflict {
+            conflict_type: "delete_vs_update",
+            reason_code: "SUBTASK_TASK_NOT_FOUND",
+            message: "Incoming subtask references a task that does not exist locally.",
+        }));
+    }
+    connection
+        .execute(
+            "INSERT INTO task_subtasks (
+                id, task_id, title, is_done, created_at, updated_at, sync_version,
+                updated_by_device
+              )
+              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+              ON CONFLICT(id) DO UPDATE SET
+                task_id = excluded.task_id,
+                title = excluded.title,
+                is_done = excluded.is_done,
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at,
+                sync_version = excluded.sync_version,
+                updated_by_device = excluded.updated_by_device",
+            params![
+                entity_id,
+                task_id,
+                title,
+                payload_bool_flag(payload, "is_done"),
+                payload_timestamp_or_now(payload, "created_at"),
+                change.updated_at,
+                sync_version,
+                change.updated_by_device,
+            ],
+        )
+        .map_err(map_sql_error("apply incoming subtask"))?;
+    Ok(None)
+}
+
+fn upsert_task_template(
+    connection: &Connection,
+    change: &SyncPushChange,
+    entity_id: &str,
+    payload: &Map<String, Value>,
+    sync_version: i64,
+) -> Result<Option<IncomingConflict>, SoloStackError> {
+    let Some(name) = payload_optional_text(payload, "name") else {
+        return Ok(Some(IncomingConflict {
+            conflict_type: "field_conflict",
+            reason_code: "MISSING_TEMPLATE_NAME",
+            message: "Task template name is required in incoming payload.",
+        }));
+    };
+    connection
+        .execute(
+            "INSERT INTO task_templates (
+                id, name, title_template, description, priority, is_important,
+                due_offset_minutes, remind_offset_minutes, recurrence, created_at, updated_at,
+                sync_version, updated_by_device
+              )
+              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+              ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                title_template = excluded.title_template,
+                description = excluded.description,
+                priority = excluded.priority,
+                is_important = excluded.is_important,
+                due_offset_minutes = excluded.due_offset_minutes,
+                remind_offset_minutes = excluded.remind_offset_minutes,
+                recurrence = excluded.recurrence,
+                created_at = excluded.created_at,
+                updated_at = excluded.updated_at,
+                sync_version = excluded.sync_version,
+                updated_by_device = excluded.updated_by_device",
+            params![
+                entity_id,
+                name,
+                payload_optional_text(payload, "title_template"),
+                payload_optional_text(payload, "description"),
+                payload_enum(payload, "priority", TaskPriority::Normal),
+                payload_bool_flag(payload, "is_important"),
+                payload_non_negative_integer(payload, "due_offset_minutes"),
+                payload_non_negative_integer(payload, "remind_offset_minutes"),
+                payload_enum(payload, "recurrence", TaskRecurrence::None),
+                payload_timestamp_or_now(payload, "created_at"),
+                change.updated_at,
+                sync_version,
+                change.updated_by_device,
+            ],
+        )
+        .map_err(map_sql_error("apply incoming task template"))?;
+    Ok(None)
+}
+
+pub(crate) fn apply_incoming_change(
+    connection: &Connection,
+    change: &SyncPushChange,
+) -> Result<PullApplyStatus, SoloStackError> {
+    let entity_id = change.entity_id.trim();
+    if entity_id.is_empty() {
+        return Ok(PullApplyStatus::Skipped);
+    }
+    let is_setting = change.entity_type == SyncEntityType::Setting;
+    // Local-only settings and the device id stay stable per installation.
+    if is_setting
+        && (entity_id == repository::SYNC_SETTINGS_DEVICE_ID_KEY
+            || entity_id.starts_with(LOCAL_ONLY_SETTING_PREFIX))
+    {
+        return Ok(PullApplyStatus::Skipped);
+    }
+
+    let existing = if is_setting {
+        None
+    } else {
+        let existing = read_entity_version_state(connection, change.entity_type, entity_id)?;
+        if !should_apply_incoming_lww(existing.as_ref(), change) {
+            return Ok(PullApplyStatus::Skipped);
+        }
+        existing
+    };
+    let sync_version = change.sync_version.max(1);
+
+    match change.operation {
+        SyncOperation::Upsert => {
+            let empty_payload = Map::new();
+            let payload = change.payload.as_ref().unwrap_or(&empty_payload);
+            let conflict = match change.entity_type {
+                SyncEntityType::Project => {
+                    upsert_project(connection, change, entity_id, payload, sync_version)?
+                }
+                SyncEntityType::Task => upsert_task(
+                    connection,
+                    change,
+                    entity_id,
+                    payload,
+                    sync_version,
+                    existing.as_ref(),
+                )?,
+                SyncEntityType::TaskSubtask => {
+                    upsert_task_subtask(connection, change, entity_id, payload, sync_version)?
+                }
+                SyncEntityType::TaskTemplate => {
+                    upsert_task_template(connection, change, entity_id, payload, sync_version)?
+                }
+                SyncEntityType::Setting => {
+                    connection
+                        .execute(
+                            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+                             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                            params![entity_id, setting_value(payload)],
+                        )
+                        .map_err(map_sql_error("apply incoming setting"))?;
+                    None
+                }
+            };
+            if let Some(conflict) = conflict {
+                return persist_conflict(connection, change, entity_id, conflict);
+            }
+            connection
+                .execute(
+                    "DELETE FROM deleted_records WHERE entity_type = ?1 AND entity_id = ?2",
+                    params![change.entity_type, entity_id],
+                )
+                .map_err(map_sql_error("clear deleted record"))?;
+        }
+        SyncOperation::Delete => {
+            connection
+                .execute(
+                    &format!(
+                        "DELETE FROM {} WHERE {} = ?1",
+                        entity_table(change.entity_type),
+                        entity_key_column(change.entity_type)
+                    ),
+                    [entity_id],
+                )
+                .map_err(map_sql_error("apply incoming delete"))?;
+            repository::upsert_deleted_record(
+                connection,
+                change.entity_type,
+                entity_id,
+                &change.updated_by_device,
+                &change.updated_at,
+            )?;
+        }
+    }
+    resolve_conflicts_applied_by(connection, change)?;
+    Ok(PullApplyStatus::Applied)
+}
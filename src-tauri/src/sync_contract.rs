@@ -0,0 +1,389 @@
+// Rust side of the schema_version 1 sync contract (`src/lib/sync-contract.ts`). Requests are built
+// and responses parsed with the same normalization as the webview, so either side can run a
+// cycle against the same server and produce identical envelopes. Parsing is lenient in the same
+// places: malformed changes in a pull page are dropped, missing cursors are rejected.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    repository::{self, SyncEntityType, SyncOperation},
+};
+
+pub(crate) const SYNC_SCHEMA_VERSION: u8 = 1;
+pub(crate) const DEFAULT_SYNC_PULL_LIMIT: u32 = 200;
+const MAX_SYNC_PULL_LIMIT: u32 = 500;
+const SYNC_REJECTION_REASONS: [&str; 5] = [
+    "INVALID_ENTITY",
+    "INVALID_OPERATION",
+    "SCHEMA_MISMATCH",
+    "CONFLICT",
+    "VALIDATION_ERROR",
+];
+const EPOCH_ISO: &str = "1970-01-01T00:00:00.000Z";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SyncPushChange {
+    pub(crate) entity_type: SyncEntityType,
+    pub(crate) entity_id: String,
+    pub(crate) operation: SyncOperation,
+    pub(crate) updated_at: String,
+    pub(crate) updated_by_device: String,
+    pub(crate) sync_version: i64,
+    pub(crate) payload: Option<Map<String, Value>>,
+    pub(crate) idempotency_key: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct SyncPushRequest {
+    pub(crate) schema_version: u8,
+    pub(crate) device_id: String,
+    pub(crate) base_cursor: Option<String>,
+    pub(crate) changes: Vec<SyncPushChange>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct SyncPullRequest {
+    pub(crate) schema_version: u8,
+    pub(crate) device_id: String,
+    pub(crate) cursor: Option<String>,
+    pub(crate) limit: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct SyncRejectedChange {
+    pub(crate) idempotency_key: String,
+    pub(crate) reason: String,
+    pub(crate) message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct SyncPushResponse {
+    pub(crate) accepted: Vec<String>,
+    pub(crate) rejected: Vec<SyncRejectedChange>,
+    pub(crate) server_cursor: String,
+    pub(crate) server_time: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct SyncPullResponse {
+    pub(crate) server_cursor: String,
+    pub(crate) server_time: String,
+    pub(crate) changes: Vec<SyncPushChange>,
+    pub(crate) has_more: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SyncApiError {
+    pub(crate) code: String,
+    pub(crate) message: String,
+    pub(crate) retry_after_ms: Option<u64>,
+}
+
+fn response_invalid(detail: &str) -> SoloStackError {
+    SoloStackError::with_detail(SoloStackErrorCode::SyncResponseInvalid, detail)
+}
+
+fn non_empty_str(value: Option<&Value>) -> Option<&str> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+// `new Date(value).toISOString()` for the RFC 3339 timestamps both sides write.
+pub(crate) fn normalize_iso(value: Option<&str>, fallback: &str) -> String {
+    value
+        .and_then(repository::parse_iso_datetime)
+        .map(|datetime| datetime.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+pub(crate) fn normalize_sync_version(value: Option<&Value>) -> i64 {
+    match value.and_then(Value::as_f64) {
+        Some(version) if version.is_finite() && version >= 1.0 => version.floor() as i64,
+        _ => 1,
+    }
+}
+
+fn entity_priority(entity_type: SyncEntityType) -> u8 {
+    match entity_type {
+        SyncEntityType::Project => 0,
+        SyncEntityType::Task => 1,
+        SyncEntityType::TaskSubtask => 2,
+        SyncEntityType::TaskTemplate => 3,
+        SyncEntityType::Setting => 4,
+    }
+}
+
+// Parents before children within the same timestamp, so a pull never sees a task before its
+// project.
+pub(crate) fn compare_changes(left: &SyncPushChange, right: &SyncPushChange) -> std::cmp::Ordering {
+    left.updated_at
+        .cmp(&right.updated_at)
+        .then_with(|| entity_priority(left.entity_type).cmp(&entity_priority(right.entity_type)))
+        .then_with(|| left.idempotency_key.cmp(&right.idempotency_key))
+}
+
+fn normalize_change(mut change: SyncPushChange) -> SyncPushChange {
+    change.updated_at = normalize_iso(Some(&change.updated_at), EPOCH_ISO);
+    change.payload = match change.operation {
+        SyncOperation::Delete => None,
+        SyncOperation::Upsert => Some(change.payload.unwrap_or_default()),
+    };
+    change.sync_version = change.sync_version.max(1);
+    change
+}
+
+pub(crate) fn create_idempotency_key(device_id: &str, change_id: &str) -> String {
+    format!(
+        "{}:{}",
+        device_id.trim().to_lowercase(),
+        change_id.trim().to_lowercase()
+    )
+}
+
+pub(crate) fn build_push_request(
+    device_id: &str,
+    base_cursor: Option<String>,
+    changes: Vec<SyncPushChange>,
+) -> SyncPushRequest {
+    let mut changes = changes
+        .into_iter()
+        .map(normalize_change)
+        .collect::<Vec<_>>();
+    changes.sort_by(compare_changes);
+    SyncPushRequest {
+        schema_version: SYNC_SCHEMA_VERSION,
+        device_id: device_id.trim().to_string(),
+        base_cursor,
+        changes,
+    }
+}
+
+pub(crate) fn clamp_pull_limit(value: Option<i64>) -> u32 {
+    match value {
+        Some(limit) if limit > 0 => limit.min(i64::from(MAX_SYNC_PULL_LIMIT)) as u32,
+        _ => DEFAULT_SYNC_PULL_LIMIT,
+    }
+}
+
+pub(crate) fn build_pull_request(
+    device_id: &str,
+    cursor: Option<String>,
+    limit: Option<i64>,
+) -> SyncPullRequest {
+    SyncPullRequest {
+        schema_version: SYNC_SCHEMA_VERSION,
+        device_id: device_id.trim().to_string(),
+        cursor,
+        limit: clamp_pull_limit(limit),
+    }
+}
+
+pub(crate) fn parse_change(raw: &Value) -> Option<SyncPushChange> {
+    let raw = raw.as_object()?;
+    let entity_type = serde_json::from_value(raw.get("entity_type")?.clone()).ok()?;
+    let operation = serde_json::from_value(raw.get("operation")?.clone()).ok()?;
+    let entity_id = non_empty_str(raw.get("entity_id"))?;
+    let updated_by_device = non_empty_str(raw.get("updated_by_device"))?;
+    let idempotency_key = non_empty_str(raw.get("idempotency_key"))?;
+    Some(normalize_change(SyncPushChange {
+        entity_type,
+        entity_id: entity_id.to_string(),
+        operation,
+        updated_at: normalize_iso(raw.get("updated_at").and_then(Value::as_str), EPOCH_ISO),
+        updated_by_device: updated_by_device.to_string(),
+        sync_version: normalize_sync_version(raw.get("sync_version")),
+        payload: raw.get("payload").and_then(Value::as_object).cloned(),
+        idempotency_key: idempotency_key.to_string(),
+    }))
+}
+
+fn parse_response_metadata(payload: &Map<String, Value>) -> Option<(String, String)> {
+    let server_cursor = non_empty_str(payload.get("server_cursor"))?;
+    let server_time = non_empty_str(payload.get("server_time"))?;
+    Some((
+        server_cursor.to_string(),
+        normalize_iso(Some(server_time), EPOCH_ISO),
+    ))
+}
+
+pub(crate) fn parse_pull_response(payload: &Value) -> Result<SyncPullResponse, SoloStackError> {
+    let payload = payload
+        .as_object()
+        .ok_or_else(|| response_invalid("pull response is not an object"))?;
+    let (server_cursor, server_time) = parse_response_metadata(payload)
+        .ok_or_else(|| response_invalid("pull response is missing server_cursor/server_time"))?;
+    let changes = payload
+        .get("changes")
+        .and_then(Value::as_array)
+        .map(|changes| changes.iter().filter_map(parse_change).collect())
+        .unwrap_or_default();
+    Ok(SyncPullResponse {
+        server_cursor,
+        server_time,
+        changes,
+        has_more: payload
+            .get("has_more")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+    })
+}
+
+pub(crate) fn parse_push_response(payload: &Value) -> Result<SyncPushResponse, SoloStackError> {
+    let payload = payload
+        .as_object()
+        .ok_or_else(|| response_invalid("push response is not an object"))?;
+    let (server_cursor, server_time) = parse_response_metadata(payload)
+        .ok_or_else(|| response_invalid("push response is missing server_cursor/server_time"))?;
+    let accepted = payload
+        .get("accepted")
+        .and_then(Value::as_array)
+        .map(|accepted| {
+            accepted
+                .iter()
+                .filter_map(|key| non_empty_str(Some(key)).map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    let rejected = payload
+        .get("rejected")
+        .and_then(Value::as_array)
+        .map(|rejected| {
+            rejected
+                .iter()
+                .filter_map(|entry| {
+                    let entry = entry.as_object()?;
+                    let idempotency_key = non_empty_str(entry.get("idempotency_key"))?;
+                    let reason = entry.get("reason").and_then(Value::as_str)?;
+                    SYNC_REJECTION_REASONS
+                        .contains(&reason)
+                        .then(|| SyncRejectedChange {
+                            idempotency_key: idempotency_key.to_string(),
+                            reason: reason.to_string(),
+                            message: non_empty_str(entry.get("message"))
+                                .unwrap_or("Rejected by sync server.")
+                                .to_string(),
+                        })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(SyncPushResponse {
+        accepted,
+        rejected,
+        server_cursor,
+        server_time,
+    })
+}
+
+pub(crate) fn parse_api_error(payload: &Value) -> SyncApiError {
+    const KNOWN_CODES: [&str; 8] = [
+        "SCHEMA_MISMATCH",
+        "UNAUTHORIZED",
+        "FORBIDDEN",
+        "RATE_LIMITED",
+        "INVALID_CURSOR",
+        "VALIDATION_ERROR",
+        "INTERNAL_ERROR",
+        "UNAVAILABLE",
+    ];
+    let code = payload
+        .get("code")
+        .and_then(Value::as_str)
+        .filter(|code| KNOWN_CODES.contains(code))
+        .unwrap_or("INTERNAL_ERROR");
+    let retry_after_ms = payload
+        .get("retry_after_ms")
+        .and_then(Value::as_f64)
+        .filter(|value| value.is_finite() && *value >= 0.0)
+        .map(|value| value.floor() as u64);
+    SyncApiError {
+        code: code.to_string(),
+        message: non_empty_str(payload.get("message"))
+            .unwrap_or("Unknown sync error.")
+            .to_string(),
+        retry_after_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn pull_response_drops_malformed_changes_and_normalizes_the_rest() {
+        let response = parse_pull_response(&json!({
+            "server_cursor": " cursor-2 ",
+            "server_time": "2026-03-10T10:00:00+07:00",
+            "has_more": true,
+            "changes": [
+                {
+                    "entity_type": "TASK",
+                    "entity_id": "task-1",
+                    "operation": "DELETE",
+                    "updated_at": "2026-03-10T03:00:00Z",
+                    "updated_by_device": "device-b",
+                    "sync_version": 0,
+                    "payload": { "title": "ignored for deletes" },
+                    "idempotency_key": "device-b:1"
+                },
+                { "entity_type": "UNKNOWN", "entity_id": "x", "operation": "UPSERT",
+                  "updated_by_device": "device-b", "idempotency_key": "device-b:2" },
+                { "entity_type": "TASK", "entity_id": " ", "operation": "UPSERT",
+                  "updated_by_device": "device-b", "idempotency_key": "device-b:3" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(response.server_cursor, "cursor-2");
+        assert_eq!(response.server_time, "2026-03-10T03:00:00.000Z");
+        assert!(response.has_more);
+        assert_eq!(response.changes.len(), 1);
+        assert_eq!(response.changes[0].payload, None);
+        assert_eq!(response.changes[0].sync_version, 1);
+        assert_eq!(response.changes[0].updated_at, "2026-03-10T03:00:00.000Z");
+
+        let error = parse_pull_response(&json!({ "changes": [] })).unwrap_err();
+        assert_eq!(error.code, SoloStackErrorCode::SyncResponseInvalid);
+    }
+
+    #[test]
+    fn push_request_orders_parents_first_and_api_errors_keep_retry_after() {
+        let change = |entity_type, key: &str| SyncPushChange {
+            entity_type,
+            entity_id: key.to_string(),
+            operation: SyncOperation::Upsert,
+            updated_at: "2026-03-10T03:00:00Z".to_string(),
+            updated_by_device: "device-a".to_string(),
+            sync_version: 2,
+            payload: None,
+            idempotency_key: key.to_string(),
+        };
+        let request = build_push_request(
+            " device-a ",
+            None,
+            vec![
+                change(SyncEntityType::Task, "a"),
+                change(SyncEntityType::Project, "b"),
+            ],
+        );
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["schema_version"], 1);
+        assert_eq!(value["device_id"], "device-a");
+        assert_eq!(value["changes"][0]["entity_type"], "PROJECT");
+        assert_eq!(value["changes"][1]["payload"], json!({}));
+
+        let error = parse_api_error(&json!({
+            "code": "RATE_LIMITED",
+            "message": "slow down",
+            "retry_after_ms": 1500.7
+        }));
+        assert_eq!(error.code, "RATE_LIMITED");
+        assert_eq!(error.retry_after_ms, Some(1500));
+        assert_eq!(parse_api_error(&json!("oops")).code, "INTERNAL_ERROR");
+    }
+}
@@ -0,0 +1,684 @@
+// Native counterpart of `runSyncCycle` in `src/lib/sync-runner.ts`: pushes the `sync_outbox`,
+// then pulls pages from the checkpoint cursor and applies them. Requests go out without holding
+// a write lock; acknowledging a push and applying a pulled page each commit together with the
+// checkpoint they advance, so an interrupted cycle resumes from the last committed cursor.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use tauri::Manager;
+
+use crate::{
+    db::DatabaseState,
+    error::{SoloStackError, SoloStackErrorCode},
+    repository::{self, map_sql_error, SyncEntityType, SyncOperation},
+    secure_store::SecureStoreState,
+    sync_apply::{self, PullApplyStatus},
+    sync_contract::{self, SyncPullResponse, SyncPushChange, SyncPushRequest, SyncPushResponse},
+    sync_scheduler::SyncSchedulerState,
+    sync_transport::{self, SyncFailure, SyncTransport},
+};
+
+const DEFAULT_OUTBOX_LIST_LIMIT: i64 = 200;
+const MAX_OUTBOX_LIST_LIMIT: i64 = 1000;
+const DEFAULT_MAX_PULL_PAGES: u32 = 5;
+const PULL_CHANGE_SAVEPOINT: &str = "sync_pull_change";
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RunSyncCycleOptions {
+    pub(crate) push_limit: Option<i64>,
+    pub(crate) pull_limit: Option<i64>,
+    #[serde(default)]
+    pub(crate) skip_pull: bool,
+    pub(crate) max_pull_pages: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct PullConflictEnvelope {
+    pub(crate) idempotency_key: String,
+    pub(crate) entity_type: SyncEntityType,
+    pub(crate) entity_id: String,
+    pub(crate) reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub(crate) struct PullSummary {
+    pub(crate) applied: u32,
+    pub(crate) skipped: u32,
+    pub(crate) conflicts: u32,
+    pub(crate) conflict_envelopes: Vec<PullConflictEnvelope>,
+    pub(crate) skipped_self: u32,
+    pub(crate) failed: u32,
+    pub(crate) has_more: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct RunSyncCycleSummary {
+    pub(crate) device_id: String,
+    pub(crate) checkpoint_before: Option<String>,
+    pub(crate) checkpoint_after: Option<String>,
+    pub(crate) prepared_push_changes: u32,
+    pub(crate) skipped_push_changes: u32,
+    pub(crate) removed_outbox_changes: u32,
+    pub(crate) failed_outbox_changes: u32,
+    pub(crate) pending_outbox_changes: u32,
+    pub(crate) pull: Option<PullSummary>,
+}
+
+struct OutboxRecord {
+    id: String,
+    entity_type: SyncEntityType,
+    entity_id: String,
+    operation: SyncOperation,
+    payload_json: Option<String>,
+    idempotency_key: String,
+    updated_at: String,
+}
+
+struct PreparedPushBatch {
+    request: SyncPushRequest,
+    // Idempotency key to outbox row.
+    entries: HashMap<String, String>,
+    skipped: u32,
+}
+
+#[derive(Default)]
+struct PushAcknowledgeSummary {
+    removed: u32,
+    failed: u32,
+    pending: u32,
+}
+
+fn read_checkpoint_cursor(connection: &Connection) -> Result<Option<String>, SoloStackError> {
+    connection
+        .query_row(
+            "SELECT last_sync_cursor FROM sync_checkpoints WHERE id = 1 LIMIT 1",
+            [],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .optional()
+        .map(Option::flatten)
+        .map_err(map_sql_error("read sync checkpoint"))
+}
+
+fn set_checkpoint(
+    connection: &Connection,
+    cursor: &str,
+    synced_at: &str,
+) -> Result<(), SoloStackError> {
+    connection
+        .execute(
+            "INSERT INTO sync_checkpoints (id, last_sync_cursor, last_synced_at, updated_at)
+             VALUES (1, ?1, ?2, ?2)
+             ON CONFLICT(id) DO UPDATE SET
+               last_sync_cursor = excluded.last_sync_cursor,
+               last_synced_at = excluded.last_synced_at,
+               updated_at = excluded.updated_at",
+            params![cursor, synced_at],
+        )
+        .map(|_| ())
+        .map_err(map_sql_error("update sync checkpoint"))
+}
+
+fn list_outbox_records(
+    connection: &Connection,
+    limit: Option<i64>,
+) -> Result<Vec<OutboxRecord>, SoloStackError> {
+    let limit = limit
+        .unwrap_or(DEFAULT_OUTBOX_LIST_LIMIT)
+        .clamp(1, MAX_OUTBOX_LIST_LIMIT);
+    let mut statement = connection
+        .prepare(
+            "SELECT id, entity_type, entity_id, operation, payload_json, idempotency_key, updated_at
+               FROM sync_outbox
+              ORDER BY created_at ASC, id ASC
+              LIMIT ?1",
+        )
+        .map_err(map_sql_error("list sync outbox"))?;
+    let records = statement
+        .query_map([limit], |row| {
+            Ok(OutboxRecord {
+                id: row.get(0)?,
+                entity_type: row.get(1)?,
+                entity_id: row.get(2)?,
+                operation: row.get(3)?,
+                payload_json: row.get(4)?,
+                idempotency_key: row.get(5)?,
+                updated_at: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+            })
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(map_sql_error("list sync outbox"))?;
+    Ok(records)
+}
+
+// `None` marks an outbox row that cannot be sent (see `preparePushBatch`).
+fn outbox_payload(record: &OutboxRecord) -> Option<Option<Map<String, Value>>> {
+    if record.operation == SyncOperation::Delete {
+        return Some(None);
+    }
+    match record.payload_json.as_deref() {
+        None | Some("") => Some(Some(Map::new())),
+        Some(payload_json) => match serde_json::from_str::<Value>(payload_json) {
+            Ok(Value::Object(payload)) => Some(Some(payload)),
+            _ => None,
+        },
+    }
+}
+
+fn prepare_push_batch(
+    device_id: &str,
+    base_cursor: Option<String>,
+    records: Vec<OutboxRecord>,
+) -> PreparedPushBatch {
+    let mut changes = Vec::new();
+    let mut entries = HashMap::new();
+    let mut skipped = 0;
+
+    for record in records {
+        let entity_id = record.entity_id.trim();
+        let payload = match outbox_payload(&record) {
+            Some(payload) if !entity_id.is_empty() => payload,
+            _ => {
+                skipped += 1;
+                continue;
+            }
+        };
+        let payload_field = |key: &str| payload.as_ref().and_then(|payload| payload.get(key));
+        let idempotency_key = match record.idempotency_key.trim() {
+            "" => sync_contract::create_idempotency_key(device_id, &record.id),
+            key => key.to_string(),
+        };
+        changes.push(SyncPushChange {
+            entity_type: record.entity_type,
+            entity_id: entity_id.to_string(),
+            operation: record.operation,
+            updated_at: sync_contract::normalize_iso(
+                payload_field("updated_at").and_then(Value::as_str),
+                &record.updated_at,
+            ),
+            updated_by_device: payload_field("updated_by_device")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|device| !device.is_empty())
+                .unwrap_or(device_id)
+                .to_string(),
+            sync_version: sync_contract::normalize_sync_version(payload_field("sync_version")),
+            payload,
+            idempotency_key: idempotency_key.clone(),
+        });
+        entries.insert(idempotency_key, record.id);
+    }
+
+    PreparedPushBatch {
+        request: sync_contract::build_push_request(device_id, base_cursor, changes),
+        entries,
+        skipped,
+    }
+}
+
+fn acknowledge_push_result(
+    connection: &Connection,
+    entries: &HashMap<String, String>,
+    response: &SyncPushResponse,
+) -> Result<PushAcknowledgeSummary, SoloStackError> {
+    let removed = response
+        .accepted
+        .iter()
+        .filter_map(|key| entries.get(key))
+        .collect::<HashSet<_>>();
+    let mut failed = HashSet::new();
+    let now = repository::now_iso();
+
+    for rejected in &response.rejected {
+        let Some(outbox_id) = entries.get(&rejected.idempotency_key) else {
+            continue;
+        };
+        if removed.contains(outbox_id) || !failed.insert(outbox_id) {
+            continue;
+        }
+        connection
+            .execute(
+                "UPDATE sync_outbox
+                    SET attempts = attempts + 1,
+                        last_error = ?1,
+                        updated_at = ?2
+                  WHERE id = ?3",
+                params![
+                    format!("[{}] {}", rejected.reason, rejected.message),
+                    now,
+                    outbox_id
+                ],
+            )
+            .map_err(map_sql_error("mark sync outbox change failed"))?;
+    }
+    for outbox_id in &removed {
+        connection
+            .execute("DELETE FROM sync_outbox WHERE id = ?1", [outbox_id])
+            .map_err(map_sql_error("remove sync outbox change"))?;
+    }
+
+    let settled = removed.len() + failed.len();
+    Ok(PushAcknowledgeSummary {
+        removed: removed.len() as u32,
+        failed: failed.len() as u32,
+        pending: entries.len().saturating_sub(settled) as u32,
+    })
+}
+
+// A change that fails to apply rolls back on its own and is counted, like a thrown
+// `applyIncomingChange` in `applyPullBatch`; the rest of the page still commits.
+fn apply_change_in_savepoint(
+    connection: &Connection,
+    change: &SyncPushChange,
+) -> Result<Option<PullApplyStatus>, SoloStackError> {
+    connection
+        .execute_batch(&format!("SAVEPOINT {PULL_CHANGE_SAVEPOINT}"))
+        .map_err(map_sql_error("begin sync change savepoint"))?;
+    let outcome = sync_apply::apply_incoming_change(connection, change);
+    let statement = match outcome {
+        Ok(_) => format!("RELEASE {PULL_CHANGE_SAVEPOINT}"),
+        Err(_) => format!("ROLLBACK TO {PULL_CHANGE_SAVEPOINT}; RELEASE {PULL_CHANGE_SAVEPOINT}"),
+    };
+    connection
+        .execute_batch(&statement)
+        .map_err(map_sql_error("end sync change savepoint"))?;
+    Ok(outcome.ok())
+}
+
+fn apply_pull_page(
+    connection: &Connection,
+    device_id: &str,
+    response: &SyncPullResponse,
+    summary: &mut PullSummary,
+) -> Result<(), SoloStackError> {
+    let local_device_id = device_id.trim().to_lowercase();
+    let mut changes = response.changes.iter().collect::<Vec<_>>();
+    changes.sort_by(|left, right| sync_contract::compare_changes(left, right));
+    let mut seen_keys = HashSet::new();
+
+    for change in changes {
+        let idempotency_key = change.idempotency_key.trim();
+        if !seen_keys.insert(idempotency_key) {
+            summary.skipped += 1;
+            continue;
+        }
+        if change.updated_by_device.trim().to_lowercase() == local_device_id {
+            summary.skipped_self += 1;
+            continue;
+        }
+        match apply_change_in_savepoint(connection, change)? {
+            Some(PullApplyStatus::Applied) => summary.applied += 1,
+            Some(PullApplyStatus::Skipped) => summary.skipped += 1,
+            Some(PullApplyStatus::Conflict { reason_code }) => {
+                summary.conflicts += 1;
+                summary.conflict_envelopes.push(PullConflictEnvelope {
+                    idempotency_key: idempotency_key.to_string(),
+                    entity_type: change.entity_type,
+                    entity_id: change.entity_id.clone(),
+                    reason: Some(reason_code.to_string()),
+                });
+            }
+            None => summary.failed += 1,
+        }
+    }
+    summary.has_more = response.has_more;
+    set_checkpoint(connection, &response.server_cursor, &response.server_time)
+}
+
+pub(crate) fn run_sync_cycle_with(
+    state: &DatabaseState,
+    transport: &dyn SyncTransport,
+    options: RunSyncCycleOptions,
+) -> Result<RunSyncCycleSummary, SyncFailure> {
+    let max_pull_pages = options
+        .max_pull_pages
+        .unwrap_or(DEFAULT_MAX_PULL_PAGES)
+        .max(1);
+    let (device_id, checkpoint_before, outbox_records) = {
+        let connection = state.open()?;
+        (
+            repository::get_or_create_device_id(&connection)?,
+            read_checkpoint_cursor(&connection)?,
+            list_outbox_records(&connection, options.push_limit)?,
+        )
+    };
+    let mut active_cursor = checkpoint_before.clone();
+
+    let batch = prepare_push_batch(&device_id, checkpoint_before.clone(), outbox_records);
+    let mut push_summary = PushAcknowledgeSummary::default();
+    if !batch.request.changes.is_empty() {
+        let response = sync_contract::parse_push_response(&transport.push(&batch.request)?)?;
+        push_summary = repository::with_write_transaction(state, |transaction| {
+            let summary = acknowledge_push_result(transaction, &batch.entries, &response)?;
+            set_checkpoint(transaction, &response.server_cursor, &response.server_time)?;
+            Ok(summary)
+        })?;
+        active_cursor = Some(response.server_cursor);
+    }
+
+    let pull = if options.skip_pull {
+        None
+    } else {
+        let mut summary = PullSummary::default();
+        for _ in 0..max_pull_pages {
+            let request = sync_contract::build_pull_request(
+                &device_id,
+                active_cursor.clone(),
+                options.pull_limit,
+            );
+            let response = sync_contract::parse_pull_response(&transport.pull(&request)?)?;
+            repository::with_write_transaction(state, |transaction| {
+                apply_pull_page(transaction, &device_id, &response, &mut summary)
+            })?;
+            active_cursor = Some(response.server_cursor);
+            if !response.has_more {
+                break;
+            }
+        }
+        Some(summary)
+    };
+
+    Ok(RunSyncCycleSummary {
+        device_id,
+        checkpoint_before,
+        checkpoint_after: active_cursor,
+        prepared_push_changes: batch.request.changes.len() as u32,
+        skipped_push_changes: batch.skipped,
+        removed_outbox_changes: push_summary.removed,
+        failed_outbox_changes: push_summary.failed,
+        pending_outbox_changes: push_summary.pending,
+        pull,
+    })
+}
+
+pub(crate) fn run_sync_cycle_blocking(
+    state: &DatabaseState,
+    secure_store: &SecureStoreState,
+    options: RunSyncCycleOptions,
+) -> Result<RunSyncCycleSummary, SyncFailure> {
+    let transport = sync_transport::resolve_transport(&state.open()?, secure_store)?;
+    run_sync_cycle_with(state, transport.as_ref(), options)
+}
+
+// Runs off the main thread: a cycle makes blocking HTTP calls. Goes through the scheduler so it
+// never overlaps a scheduled cycle and shows up in `sync:status`.
+#[tauri::command]
+pub(crate) async fn run_sync_cycle(
+    app: tauri::AppHandle,
+    options: Option<RunSyncCycleOptions>,
+) -> Result<RunSyncCycleSummary, SoloStackError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<SyncSchedulerState>()
+            .run_cycle(&app, options.unwrap_or_default())
+            .map_err(SoloStackError::from)
+    })
+    .await
+    .map_err(|error| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::Internal,
+            format!("sync cycle task failed: {error}"),
+        )
+    })?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::RefCell;
+
+    use crate::{
+        db::{count_rows, TestDatabase},
+        repository::CreateTaskInput,
+        repository::TaskPriority,
+    };
+
+    #[derive(Default)]
+    struct FakeTransport {
+        pushed: RefCell<Vec<SyncPushRequest>>,
+        pull_cursors: RefCell<Vec<Option<String>>>,
+        pull_pages: RefCell<Vec<Value>>,
+    }
+
+    impl SyncTransport for FakeTransport {
+        fn push(&self, request: &SyncPushRequest) -> Result<Value, SyncFailure> {
+            self.pushed.borrow_mut().push(request.clone());
+            let accepted = request
+                .changes
+                .iter()
+                .map(|change| change.idempotency_key.clone())
+                .collect::<Vec<_>>();
+            Ok(json!({
+                "accepted": accepted,
+                "rejected": [],
+                "server_cursor": "cursor-push",
+                "server_time": "2026-03-10T02:00:00.000Z",
+            }))
+        }
+
+        fn pull(&self, request: &sync_contract::SyncPullRequest) -> Result<Value, SyncFailure> {
+            self.pull_cursors.borrow_mut().push(request.cursor.clone());
+            Ok(self.pull_pages.borrow_mut().remove(0))
+        }
+    }
+
+    fn remote_task(id: &str, key: &str, updated_at: &str, payload: Value) -> Value {
+        json!({
+            "entity_type": "TASK",
+            "entity_id": id,
+            "operation": "UPSERT",
+            "updated_at": updated_at,
+            "updated_by_device": "device-remote",
+            "sync_version": 2,
+            "payload": payload,
+            "idempotency_key": key,
+        })
+    }
+
+    #[test]
+    fn cycle_pushes_the_outbox_and_applies_pulled_pages() {
+        let state = TestDatabase::migrated();
+        let local_task = repository::with_write_transaction(&state, |transaction| {
+            repository::create_task_record(
+                transaction,
+                CreateTaskInput {
+                    title: "Write the report".to_string(),
+                    description: None,
+                    notes_markdown: None,
+                    project_id: None,
+                    priority: TaskPriority::Normal,
+                    is_important: false,
+                    due_at: None,
+                    remind_at: None,
+                    recurrence: None,
+                    subtasks: Vec::new(),
+                },
+            )
+        })
+        .unwrap();
+
+        let transport = FakeTransport::default();
+        transport.pull_pages.borrow_mut().extend([
+            json!({
+                "server_cursor": "cursor-1",
+                "server_time": "2026-03-10T02:01:00.000Z",
+                "has_more": true,
+                "changes": [
+                    remote_task(
+                        "task-remote",
+                        "remote:1",
+                        "2026-03-10T01:00:00.000Z",
+                        json!({ "title": "From the laptop", "status": "DOING" }),
+                    ),
+                    remote_task(
+                        "task-remote",
+                        "remote:1",
+                        "2026-03-10T01:00:00.000Z",
+                        json!({ "title": "From the laptop", "status": "DOING" }),
+                    ),
+                ],
+            }),
+            json!({
+                "server_cursor": "cursor-2",
+                "server_time": "2026-03-10T02:02:00.000Z",
+                "has_more": false,
+                "changes": [
+                    remote_task("task-untitled", "remote:2", "2026-03-10T01:01:00.000Z", json!({})),
+                    {
+                        "entity_type": "TASK_SUBTASK",
+                        "entity_id": "subtask-orphan",
+                        "operation": "UPSERT",
+                        "updated_at": "2026-03-10T01:02:00.000Z",
+                        "updated_by_device": "device-remote",
+                        "sync_version": 1,
+                        "payload": { "task_id": "task-missing", "title": "Orphan" },
+                        "idempotency_key": "remote:3",
+                    },
+                ],
+            }),
+        ]);
+
+        let summary =
+            run_sync_cycle_with(&state, &transport, RunSyncCycleOptions::default()).unwrap();
+
+        let pushed = transport.pushed.borrow();
+        assert_eq!(pushed.len(), 1);
+        assert_eq!(pushed[0].schema_version, 1);
+        assert_eq!(pushed[0].changes[0].entity_id, local_task.id);
+        assert_eq!(
+            *transport.pull_cursors.borrow(),
+            vec![
+                Some("cursor-push".to_string()),
+                Some("cursor-1".to_string())
+            ]
+        );
+        assert_eq!(summary.checkpoint_before, None);
+        assert_eq!(summary.checkpoint_after.as_deref(), Some("cursor-2"));
+        assert_eq!(summary.removed_outbox_changes, 1);
+        assert_eq!(
+            count_rows(&state, "SELECT COUNT(*) FROM sync_outbox", []),
+            0
+        );
+
+        let pull = summary.pull.unwrap();
+        assert_eq!((pull.applied, pull.skipped, pull.conflicts), (1, 1, 2));
+        assert_eq!(
+            pull.conflict_envelopes
+                .iter()
+                .map(|envelope| envelope.reason.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["MISSING_TASK_TITLE", "SUBTASK_TASK_NOT_FOUND"]
+        );
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM tasks WHERE id = 'task-remote' AND status = 'DOING'",
+                [],
+            ),
+            1
+        );
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM sync_conflict_events WHERE event_type = 'detected'",
+                [],
+            ),
+            2
+        );
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM sync_checkpoints WHERE last_sync_cursor = 'cursor-2'
+                    AND last_synced_at = '2026-03-10T02:02:00.000Z'",
+                [],
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn pulled_changes_follow_last_writer_wins_and_resolve_retried_conflicts() {
+        let state = TestDatabase::migrated();
+        let apply = |change: Value| {
+            let change = sync_contract::parse_change(&change).unwrap();
+            repository::with_write_transaction(&state, |transaction| {
+                sync_apply::apply_incoming_change(transaction, &change)
+            })
+            .unwrap()
+        };
+
+        assert_eq!(
+            apply(remote_task(
+                "task-1",
+                "remote:1",
+                "2026-03-10T01:00:00.000Z",
+                json!({})
+            )),
+            PullApplyStatus::Conflict {
+                reason_code: "MISSING_TASK_TITLE"
+            }
+        );
+        assert_eq!(
+            apply(remote_task(
+                "task-1",
+                "remote:1",
+                "2026-03-10T01:00:00.000Z",
+                json!({ "title": "Fixed upstream" }),
+            )),
+            PullApplyStatus::Applied
+        );
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM sync_conflicts WHERE status = 'resolved' AND resolution_strategy = 'retry'",
+                [],
+            ),
+            1
+        );
+        // Older than the stored row.
+        assert_eq!(
+            apply(remote_task(
+                "task-1",
+                "remote:2",
+                "2026-03-10T00:59:00.000Z",
+                json!({ "title": "Stale" }),
+            )),
+            PullApplyStatus::Skipped
+        );
+        assert_eq!(
+            apply(json!({
+                "entity_type": "SETTING",
+                "entity_id": "local.sync.push_url",
+                "operation": "UPSERT",
+                "updated_at": "2026-03-10T01:05:00.000Z",
+                "updated_by_device": "device-remote",
+                "payload": { "value": "https://example.invalid" },
+                "idempotency_key": "remote:3",
+            })),
+            PullApplyStatus::Skipped
+        );
+        assert_eq!(
+            apply(json!({
+                "entity_type": "TASK",
+                "entity_id": "task-1",
+                "operation": "DELETE",
+                "updated_at": "2026-03-10T01:10:00.000Z",
+                "updated_by_device": "device-remote",
+                "idempotency_key": "remote:4",
+            })),
+            PullApplyStatus::Applied
+        );
+        assert_eq!(count_rows(&state, "SELECT COUNT(*) FROM tasks", []), 0);
+        assert_eq!(
+            count_rows(
+                &state,
+                "SELECT COUNT(*) FROM deleted_records WHERE entity_id = 'task-1'",
+                [],
+            ),
+            1
+        );
+    }
+}
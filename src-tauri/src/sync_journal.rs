@@ -0,0 +1,336 @@
+// Journal format shared by the providers that keep sync envelopes as plain files (`local_folder`,
+// `webdav`). Each device owns one journal of `{seq, change}` JSON lines. Seqs only grow, so a pull
+// cursor is the last seq read from every other device's journal and stays valid across
+// compaction. Push and pull answers have the same shape a sync server returns.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    repository,
+    sync_contract::{
+        self, SyncPullResponse, SyncPushChange, SyncPushRequest, SyncPushResponse,
+        SYNC_SCHEMA_VERSION,
+    },
+    sync_transport::SyncFailure,
+};
+
+pub(crate) const JOURNAL_EXTENSION: &str = "jsonl";
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct JournalRecord {
+    pub(crate) seq: u64,
+    pub(crate) change: SyncPushChange,
+}
+
+pub(crate) struct Journal {
+    pub(crate) records: Vec<JournalRecord>,
+    pub(crate) ends_with_newline: bool,
+}
+
+impl Journal {
+    // A line torn by an interrupted write is skipped; its change was never acknowledged.
+    pub(crate) fn parse(content: &str) -> Self {
+        Self {
+            records: content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<JournalRecord>(line).ok())
+                .collect(),
+            ends_with_newline: content.is_empty() || content.ends_with('\n'),
+        }
+    }
+
+    pub(crate) fn last_seq(&self) -> u64 {
+        self.records.last().map_or(0, |record| record.seq)
+    }
+
+    // Records the changes that are not in the journal yet and returns the index of the first new
+    // record. A retried push re-sends changes that already made it; they get no second record.
+    pub(crate) fn append(&mut self, changes: &[SyncPushChange]) -> usize {
+        let start = self.records.len();
+        let mut known_keys = self
+            .records
+            .iter()
+            .map(|record| record.change.idempotency_key.clone())
+            .collect::<HashSet<_>>();
+        let mut next_seq = self.last_seq() + 1;
+        for change in changes {
+            if !known_keys.insert(change.idempotency_key.clone()) {
+                continue;
+            }
+            self.records.push(JournalRecord {
+                seq: next_seq,
+                change: change.clone(),
+            });
+            next_seq += 1;
+        }
+        start
+    }
+
+    // Keeps the newest change per entity, in journal order and with its original seq, so readers
+    // holding an older cursor still receive the latest state. Only worth a rewrite once the
+    // journal reaches `threshold` records and at least half of them are superseded. The last
+    // record stays even when superseded (clock skew, a re-pushed outbox row): it carries the
+    // journal's high-water seq, and without it the next append would reuse seqs that readers'
+    // cursors have already passed.
+    pub(crate) fn compact(&mut self, threshold: usize) -> bool {
+        if self.records.len() < threshold {
+            return false;
+        }
+        let mut newest = HashMap::<(&str, &str), &JournalRecord>::new();
+        for record in &self.records {
+            let key = (
+                record.change.entity_type.as_str(),
+                record.change.entity_id.as_str(),
+            );
+            let supersedes = newest
+                .get(&key)
+                .is_none_or(|current| record.change.updated_at >= current.change.updated_at);
+            if supersedes {
+                newest.insert(key, record);
+            }
+        }
+        if newest.len() * 2 > self.records.len() {
+            return false;
+        }
+        let mut kept = newest
+            .values()
+            .map(|record| record.seq)
+            .collect::<HashSet<_>>();
+        kept.insert(self.last_seq());
+        self.records.retain(|record| kept.contains(&record.seq));
+        true
+    }
+
+    pub(crate) fn encode_from(&self, start: usize) -> Result<String, SyncFailure> {
+        let mut content = String::new();
+        for record in self.records.iter().skip(start) {
+            let line = serde_json::to_string(record).map_err(|error| {
+                SoloStackError::with_detail(
+                    SoloStackErrorCode::Internal,
+                    format!("encode sync journal record failed: {error}"),
+                )
+            })?;
+            content.push_str(&line);
+            content.push('\n');
+        }
+        Ok(content)
+    }
+}
+
+// Device ids become file names, so anything that could escape the journal location is refused.
+pub(crate) fn journal_name(device_id: &str) -> Result<&str, SyncFailure> {
+    let device_id = device_id.trim();
+    let valid = !device_id.is_empty()
+        && device_id
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || matches!(character, '-' | '_'));
+    if valid {
+        Ok(device_id)
+    } else {
+        Err(SoloStackError::with_detail(
+            SoloStackErrorCode::SyncRequestRejected,
+            format!("device id {device_id:?} cannot name a sync journal"),
+        )
+        .into())
+    }
+}
+
+pub(crate) fn check_schema_version(schema_version: u8) -> Result<(), SyncFailure> {
+    if schema_version == SYNC_SCHEMA_VERSION {
+        return Ok(());
+    }
+    Err(SoloStackError::with_detail(
+        SoloStackErrorCode::SyncSchemaMismatch,
+        format!("sync journals expect schema_version {SYNC_SCHEMA_VERSION}, got {schema_version}"),
+    )
+    .into())
+}
+
+// Cursors carry the provider as a prefix. One issued by another provider restarts from the
+// beginning; replayed changes are skipped by last-write-wins when applied.
+pub(crate) fn decode_cursor(prefix: &str, cursor: Option<&str>) -> BTreeMap<String, u64> {
+    cursor
+        .and_then(|cursor| cursor.trim().strip_prefix(prefix))
+        .and_then(|offsets| offsets.strip_prefix(':'))
+        .and_then(|offsets| serde_json::from_str(offsets).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn encode_cursor(prefix: &str, offsets: &BTreeMap<String, u64>) -> String {
+    format!(
+        "{prefix}:{}",
+        serde_json::to_string(offsets).unwrap_or_else(|_| "{}".to_string())
+    )
+}
+
+// Every change is accepted once it is in the journal. The push never moves the pull cursor: the
+// pushing device's own journal is not part of it.
+pub(crate) fn push_response(cursor_prefix: &str, request: &SyncPushRequest) -> Value {
+    serde_json::json!(SyncPushResponse {
+        accepted: request
+            .changes
+            .iter()
+            .map(|change| change.idempotency_key.clone())
+            .collect(),
+        rejected: Vec::new(),
+        server_cursor: encode_cursor(
+            cursor_prefix,
+            &decode_cursor(cursor_prefix, request.base_cursor.as_deref()),
+        ),
+        server_time: repository::now_iso(),
+    })
+}
+
+// Merges the other devices' journals oldest change first, starting after `offsets`. Each journal
+// is consumed in seq order, so its offset never skips a record that did not fit in the page.
+pub(crate) fn pull_page(
+    cursor_prefix: &str,
+    journals: Vec<(String, Journal)>,
+    mut offsets: BTreeMap<String, u64>,
+    limit: u32,
+) -> Value {
+    let mut pending = journals
+        .into_iter()
+        .map(|(device_id, journal)| {
+            let after = offsets.get(&device_id).copied().unwrap_or(0);
+            let records = journal
+                .records
+                .into_iter()
+                .filter(|record| record.seq > after)
+                .collect::<VecDeque<_>>();
+            (device_id, records)
+        })
+        .collect::<Vec<_>>();
+    pending.sort_by(|left, right| left.0.cmp(&right.0));
+
+    let mut changes = Vec::new();
+    while changes.len() < limit.max(1) as usize {
+        let next = pending
+            .iter_mut()
+            .filter(|(_, records)| !records.is_empty())
+            .min_by(|(_, left), (_, right)| {
+                sync_contract::compare_changes(&left[0].change, &right[0].change)
+            });
+        let Some((device_id, records)) = next else {
+            break;
+        };
+        let Some(record) = records.pop_front() else {
+            break;
+        };
+        offsets.insert(device_id.clone(), record.seq);
+        changes.push(record.change);
+    }
+
+    serde_json::json!(SyncPullResponse {
+        server_cursor: encode_cursor(cursor_prefix, &offsets),
+        server_time: repository::now_iso(),
+        has_more: pending.iter().any(|(_, records)| !records.is_empty()),
+        changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{SyncEntityType, SyncOperation};
+    use serde_json::Map;
+
+    fn task_change(task_id: &str, updated_at: &str, key: &str) -> SyncPushChange {
+        SyncPushChange {
+            entity_type: SyncEntityType::Task,
+            entity_id: task_id.to_string(),
+            operation: SyncOperation::Upsert,
+            updated_at: updated_at.to_string(),
+            updated_by_device: "device-a".to_string(),
+            sync_version: 1,
+            payload: Some(Map::new()),
+            idempotency_key: key.to_string(),
+        }
+    }
+
+    fn seqs(journal: &Journal) -> Vec<u64> {
+        journal.records.iter().map(|record| record.seq).collect()
+    }
+
+    #[test]
+    fn compaction_never_lowers_the_high_water_seq() {
+        let mut journal = Journal::parse("");
+        journal.append(&[task_change("task-1", "2026-01-01T00:03:00.000Z", "a-1")]);
+        journal.append(&[task_change("task-1", "2026-01-01T00:01:00.000Z", "a-2")]);
+        let start = journal.append(&[
+            task_change("task-1", "2026-01-01T00:02:00.000Z", "a-3"),
+            task_change("task-1", "2026-01-01T00:00:00.000Z", "a-4"),
+            task_change("task-1", "2026-01-01T00:00:00.000Z", "a-4"),
+        ]);
+        assert_eq!(start, 2);
+        assert_eq!(seqs(&journal), vec![1, 2, 3, 4]);
+
+        assert!(!journal.compact(5));
+        assert!(journal.compact(4));
+        assert_eq!(seqs(&journal), vec![1, 4]);
+        assert_eq!(journal.last_seq(), 4);
+
+        journal.append(&[task_change("task-2", "2026-01-01T00:04:00.000Z", "a-5")]);
+        assert_eq!(journal.last_seq(), 5);
+        let reparsed = Journal::parse(&journal.encode_from(0).expect("encode"));
+        assert_eq!(seqs(&reparsed), vec![1, 4, 5]);
+        assert!(reparsed.ends_with_newline);
+
+        let torn = Journal::parse(&format!(
+            "{}{{\"seq\":6,\"chan",
+            journal.encode_from(0).expect("encode")
+        ));
+        assert_eq!(torn.last_seq(), 5);
+        assert!(!torn.ends_with_newline);
+    }
+
+    #[test]
+    fn pull_page_resumes_each_journal_from_its_cursor_offset() {
+        let mut device_b = Journal::parse("");
+        device_b.append(&[
+            task_change("task-1", "2026-01-01T00:00:00.000Z", "b-1"),
+            task_change("task-2", "2026-01-01T00:02:00.000Z", "b-2"),
+        ]);
+        let mut device_c = Journal::parse("");
+        device_c.append(&[task_change("task-3", "2026-01-01T00:01:00.000Z", "c-1")]);
+        let encode = |journal: &Journal| Journal::parse(&journal.encode_from(0).expect("encode"));
+
+        let page = pull_page(
+            "webdav",
+            vec![
+                ("device-b".to_string(), encode(&device_b)),
+                ("device-c".to_string(), encode(&device_c)),
+            ],
+            decode_cursor("webdav", Some("local_folder:{\"device-b\":2}")),
+            2,
+        );
+        let page = sync_contract::parse_pull_response(&page).expect("pull response");
+        let keys = page
+            .changes
+            .iter()
+            .map(|change| change.idempotency_key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["b-1", "c-1"]);
+        assert!(page.has_more);
+
+        let offsets = decode_cursor("webdav", Some(&page.server_cursor));
+        assert_eq!(offsets.get("device-b"), Some(&1));
+        assert_eq!(offsets.get("device-c"), Some(&1));
+        let page = pull_page(
+            "webdav",
+            vec![
+                ("device-b".to_string(), encode(&device_b)),
+                ("device-c".to_string(), encode(&device_c)),
+            ],
+            offsets,
+            10,
+        );
+        let page = sync_contract::parse_pull_response(&page).expect("pull response");
+        assert_eq!(page.changes.len(), 1);
+        assert_eq!(page.changes[0].idempotency_key, "b-2");
+        assert!(!page.has_more);
+    }
+}
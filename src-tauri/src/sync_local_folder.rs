@@ -0,0 +1,410 @@
+// `local_folder` provider: devices exchange sync envelopes through a shared directory (a synced
+// drive, a NAS share, a USB stick) instead of a server. Each device appends the changes it pushes
+// to its own `journals/<device_id>.jsonl` (see `sync_journal`) and pulls from everyone else's.
+//
+// Access is serialized with a lock file created with `create_new` rather than OS file locks,
+// which are unreliable on network shares.
+
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    repository,
+    sync_contract::{SyncPullRequest, SyncPushRequest},
+    sync_journal::{self, Journal, JOURNAL_EXTENSION},
+    sync_transport::{SyncFailure, SyncTransport},
+};
+
+pub(crate) const LOCAL_FOLDER_PROVIDER: &str = "local_folder";
+const PROVIDER_CONFIG_DIRECTORY_KEY: &str = "directory";
+const JOURNALS_DIR_NAME: &str = "journals";
+const LOCK_FILE_NAME: &str = ".solostack-sync.lock";
+const DEFAULT_LOCK_WAIT: Duration = Duration::from_secs(5);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+// A device that crashed while holding the lock never removes it.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+const DEFAULT_COMPACT_THRESHOLD: usize = 2_000;
+
+fn folder_failure(action: &str, path: &Path, error: std::io::Error) -> SyncFailure {
+    SoloStackError::with_detail(
+        SoloStackErrorCode::SyncRequestFailed,
+        format!("{action} {} failed: {error}", path.display()),
+    )
+    .into()
+}
+
+struct FolderLock {
+    path: PathBuf,
+}
+
+impl FolderLock {
+    fn acquire(root: &Path, wait: Duration) -> Result<Self, SyncFailure> {
+        let path = root.join(LOCK_FILE_NAME);
+        let deadline = Instant::now() + wait;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = writeln!(file, "{}", repository::now_iso());
+                    return Ok(Self { path });
+                }
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => {
+                    if lock_is_stale(&path) {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(SyncFailure {
+                            error: SoloStackError::with_detail(
+                                SoloStackErrorCode::SyncServerUnavailable,
+                                format!("sync folder {} is locked", root.display()),
+                            ),
+                            retry_after_ms: Some(wait.as_millis() as u64),
+                        });
+                    }
+                    std::thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(error) => return Err(folder_failure("lock sync folder", root, error)),
+            }
+        }
+    }
+}
+
+impl Drop for FolderLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn lock_is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
+fn read_journal(path: &Path) -> Result<Journal, SyncFailure> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Journal::parse(&content)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(Journal::parse("")),
+        Err(error) => Err(folder_failure("read sync journal", path, error)),
+    }
+}
+
+pub(crate) struct LocalFolderSyncTransport {
+    root: PathBuf,
+    lock_wait: Duration,
+    compact_threshold: usize,
+}
+
+impl LocalFolderSyncTransport {
+    pub(crate) fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            lock_wait: DEFAULT_LOCK_WAIT,
+            compact_threshold: DEFAULT_COMPACT_THRESHOLD,
+        }
+    }
+
+    pub(crate) fn from_provider_config(
+        provider_config: &Map<String, Value>,
+    ) -> Result<Self, SoloStackError> {
+        let Some(directory) = provider_config
+            .get(PROVIDER_CONFIG_DIRECTORY_KEY)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|directory| !directory.is_empty())
+        else {
+            return Err(SoloStackError::with_detail(
+                SoloStackErrorCode::SyncTransportUnavailable,
+                "sync folder is not configured",
+            ));
+        };
+        let root = PathBuf::from(directory);
+        if !root.is_dir() {
+            return Err(SoloStackError::with_detail(
+                SoloStackErrorCode::SyncTransportUnavailable,
+                format!("sync folder {directory} is not available"),
+            ));
+        }
+        Ok(Self::new(root))
+    }
+
+    fn journals_dir(&self) -> PathBuf {
+        self.root.join(JOURNALS_DIR_NAME)
+    }
+
+    fn journal_path(&self, device_id: &str) -> PathBuf {
+        self.journals_dir()
+            .join(format!("{device_id}.{JOURNAL_EXTENSION}"))
+    }
+
+    // Every journal except the caller's own.
+    fn other_journals(&self, own_device_id: &str) -> Result<Vec<(String, Journal)>, SyncFailure> {
+        let journals_dir = self.journals_dir();
+        let entries = match fs::read_dir(&journals_dir) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(folder_failure("list sync journals", &journals_dir, error)),
+        };
+
+        let mut journals = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|error| folder_failure("list sync journals", &journals_dir, error))?
+                .path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(JOURNAL_EXTENSION)
+            {
+                continue;
+            }
+            let Some(device_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            if device_id == own_device_id || sync_journal::journal_name(device_id).is_err() {
+                continue;
+            }
+            journals.push((device_id.to_string(), read_journal(&path)?));
+        }
+        Ok(journals)
+    }
+
+    // Written next to the journal and renamed over it, so a reader never sees half of it.
+    fn rewrite_journal(&self, path: &Path, journal: &Journal) -> Result<(), SyncFailure> {
+        let temporary_path = path.with_extension(format!("{JOURNAL_EXTENSION}.compacting"));
+        fs::write(&temporary_path, journal.encode_from(0)?).map_err(|error| {
+            folder_failure("write compacted sync journal", &temporary_path, error)
+        })?;
+        fs::rename(&temporary_path, path)
+            .map_err(|error| folder_failure("replace sync journal", path, error))
+    }
+}
+
+impl SyncTransport for LocalFolderSyncTransport {
+    fn push(&self, request: &SyncPushRequest) -> Result<Value, SyncFailure> {
+        sync_journal::check_schema_version(request.schema_version)?;
+        let device_id = sync_journal::journal_name(&request.device_id)?;
+        let journals_dir = self.journals_dir();
+        fs::create_dir_all(&journals_dir)
+            .map_err(|error| folder_failure("create sync journals", &journals_dir, error))?;
+
+        let _lock = FolderLock::acquire(&self.root, self.lock_wait)?;
+        let path = self.journal_path(device_id);
+        let mut journal = read_journal(&path)?;
+        let ended_with_newline = journal.ends_with_newline;
+        let start = journal.append(&request.changes);
+        if start < journal.records.len() {
+            let mut appended = journal.encode_from(start)?;
+            if !ended_with_newline {
+                appended.insert(0, '\n');
+            }
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|error| folder_failure("open sync journal", &path, error))?;
+            file.write_all(appended.as_bytes())
+                .and_then(|_| file.sync_all())
+                .map_err(|error| folder_failure("append sync journal", &path, error))?;
+            if journal.compact(self.compact_threshold) {
+                self.rewrite_journal(&path, &journal)?;
+            }
+        }
+
+        Ok(sync_journal::push_response(LOCAL_FOLDER_PROVIDER, request))
+    }
+
+    fn pull(&self, request: &SyncPullRequest) -> Result<Value, SyncFailure> {
+        sync_journal::check_schema_version(request.schema_version)?;
+        let device_id = sync_journal::journal_name(&request.device_id)?;
+        let offsets = sync_journal::decode_cursor(LOCAL_FOLDER_PROVIDER, request.cursor.as_deref());
+
+        let _lock = FolderLock::acquire(&self.root, self.lock_wait)?;
+        Ok(sync_journal::pull_page(
+            LOCAL_FOLDER_PROVIDER,
+            self.other_journals(device_id)?,
+            offsets,
+            request.limit,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        repository::{SyncEntityType, SyncOperation},
+        sync_contract::{self, SyncPullResponse, SyncPushChange, SyncPushResponse},
+    };
+
+    fn temp_sync_folder() -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("solostack-local-folder-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).expect("create sync folder");
+        root
+    }
+
+    fn task_change(device_id: &str, task_id: &str, updated_at: &str, key: &str) -> SyncPushChange {
+        SyncPushChange {
+            entity_type: SyncEntityType::Task,
+            entity_id: task_id.to_string(),
+            operation: SyncOperation::Upsert,
+            updated_at: updated_at.to_string(),
+            updated_by_device: device_id.to_string(),
+            sync_version: 1,
+            payload: Some(Map::new()),
+            idempotency_key: key.to_string(),
+        }
+    }
+
+    fn push(
+        transport: &LocalFolderSyncTransport,
+        device_id: &str,
+        changes: Vec<SyncPushChange>,
+    ) -> SyncPushResponse {
+        let request = sync_contract::build_push_request(device_id, None, changes);
+        sync_contract::parse_push_response(&transport.push(&request).expect("push"))
+            .expect("push response")
+    }
+
+    fn pull(
+        transport: &LocalFolderSyncTransport,
+        device_id: &str,
+        cursor: Option<String>,
+        limit: i64,
+    ) -> SyncPullResponse {
+        let request = sync_contract::build_pull_request(device_id, cursor, Some(limit));
+        sync_contract::parse_pull_response(&transport.pull(&request).expect("pull"))
+            .expect("pull response")
+    }
+
+    #[test]
+    fn journals_exchange_changes_between_devices_once() {
+        let root = temp_sync_folder();
+        let transport = LocalFolderSyncTransport::new(root.clone());
+
+        let first = task_change("device-a", "task-1", "2026-01-01T00:00:00.000Z", "a-1");
+        let second = task_change("device-a", "task-2", "2026-01-01T00:01:00.000Z", "a-2");
+        push(&transport, "device-a", vec![first.clone()]);
+        let retried = push(&transport, "device-a", vec![first.clone(), second.clone()]);
+        assert_eq!(retried.accepted, vec!["a-1", "a-2"]);
+        let journal = read_journal(&transport.journal_path("device-a")).expect("journal");
+        assert_eq!(journal.records.len(), 2);
+
+        let page = pull(&transport, "device-b", None, 1);
+        assert_eq!(page.changes, vec![first]);
+        assert!(page.has_more);
+        let page = pull(&transport, "device-b", Some(page.server_cursor), 1);
+        assert_eq!(page.changes, vec![second]);
+        assert!(!page.has_more);
+        let page = pull(&transport, "device-b", Some(page.server_cursor), 10);
+        assert!(page.changes.is_empty());
+
+        assert!(pull(&transport, "device-a", None, 10).changes.is_empty());
+        assert!(!root.join(LOCK_FILE_NAME).exists());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn compaction_keeps_latest_change_per_entity_and_existing_cursors() {
+        let root = temp_sync_folder();
+        let transport = LocalFolderSyncTransport {
+            compact_threshold: 4,
+            ..LocalFolderSyncTransport::new(root.clone())
+        };
+
+        push(
+            &transport,
+            "device-a",
+            vec![task_change(
+                "device-a",
+                "task-1",
+                "2026-01-01T00:00:00.000Z",
+                "a-1",
+            )],
+        );
+        let cursor = pull(&transport, "device-b", None, 10).server_cursor;
+        let latest = task_change("device-a", "task-1", "2026-01-01T00:03:00.000Z", "a-4");
+        push(
+            &transport,
+            "device-a",
+            vec![
+                task_change("device-a", "task-1", "2026-01-01T00:01:00.000Z", "a-2"),
+                task_change("device-a", "task-1", "2026-01-01T00:02:00.000Z", "a-3"),
+                latest.clone(),
+            ],
+        );
+
+        let journal = read_journal(&transport.journal_path("device-a")).expect("journal");
+        assert_eq!(
+            journal
+                .records
+                .iter()
+                .map(|record| record.seq)
+                .collect::<Vec<_>>(),
+            vec![4]
+        );
+        let page = pull(&transport, "device-b", Some(cursor), 10);
+        assert_eq!(page.changes, vec![latest]);
+
+        fs::write(root.join(LOCK_FILE_NAME), "held").expect("hold lock");
+        let blocked = LocalFolderSyncTransport {
+            lock_wait: Duration::from_millis(100),
+            ..LocalFolderSyncTransport::new(root.clone())
+        };
+        let request = sync_contract::build_pull_request("device-b", None, None);
+        let failure = blocked.pull(&request).expect_err("lock is held");
+        assert_eq!(
+            failure.error.code,
+            SoloStackErrorCode::SyncServerUnavailable
+        );
+        assert!(failure.retry_after_ms.is_some());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn compaction_keeps_the_tail_seq_when_an_older_seq_is_newer() {
+        let root = temp_sync_folder();
+        let transport = LocalFolderSyncTransport {
+            compact_threshold: 4,
+            ..LocalFolderSyncTransport::new(root.clone())
+        };
+
+        for (updated_at, key) in [
+            ("2026-01-01T00:03:00.000Z", "a-1"),
+            ("2026-01-01T00:01:00.000Z", "a-2"),
+            ("2026-01-01T00:02:00.000Z", "a-3"),
+        ] {
+            push(
+                &transport,
+                "device-a",
+                vec![task_change("device-a", "task-1", updated_at, key)],
+            );
+        }
+        let cursor = pull(&transport, "device-b", None, 10).server_cursor;
+        // A re-pushed outbox row with a skewed clock: superseded as soon as it lands at the tail.
+        let skewed = task_change("device-a", "task-1", "2026-01-01T00:00:00.000Z", "a-4");
+        push(&transport, "device-a", vec![skewed.clone()]);
+
+        let journal = read_journal(&transport.journal_path("device-a")).expect("journal");
+        assert_eq!(
+            journal
+                .records
+                .iter()
+                .map(|record| record.seq)
+                .collect::<Vec<_>>(),
+            vec![1, 4]
+        );
+
+        let next = task_change("device-a", "task-2", "2026-01-01T00:04:00.000Z", "a-5");
+        push(&transport, "device-a", vec![next.clone()]);
+        let journal = read_journal(&transport.journal_path("device-a")).expect("journal");
+        assert_eq!(journal.last_seq(), 5);
+        let page = pull(&transport, "device-b", Some(cursor), 10);
+        assert_eq!(page.changes, vec![skewed, next]);
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,196 @@
+// Several accounts per sync provider (e.g. work and personal OneDrive). Each account gets its own
+// secure store key; neither the OS keyring nor the Android bridge can enumerate entries, so the
+// account ids and the active one are kept in an index entry stored next to them. The default
+// account keeps the pre-account key, so existing entries stay readable without a migration.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    sync_provider_credential::SyncProviderSecureAuthStatus,
+};
+
+pub(crate) const DEFAULT_SYNC_PROVIDER_ACCOUNT_ID: &str = "default";
+const ACCOUNT_KEY_SEPARATOR: &str = "::";
+// `#` is not allowed in account ids, so the index key never collides with an account key.
+const ACCOUNT_INDEX_KEY_SUFFIX: &str = "#accounts";
+const ACCOUNT_ID_MAX_LENGTH: usize = 64;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct SyncProviderAccountIndex {
+    pub(crate) active_account_id: Option<String>,
+    pub(crate) account_ids: Vec<String>,
+}
+
+pub(crate) enum SyncProviderStorageEntry<'a> {
+    Credential { provider: &'a str },
+    AccountIndex { provider: &'a str },
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct SyncProviderAccountList {
+    pub(crate) provider: String,
+    pub(crate) active_account_id: Option<String>,
+    pub(crate) accounts: Vec<SyncProviderSecureAuthStatus>,
+}
+
+pub(crate) fn normalize_account_id(account_id: Option<&str>) -> Result<String, SoloStackError> {
+    let Some(account_id) = account_id
+        .map(str::trim)
+        .filter(|account_id| !account_id.is_empty())
+    else {
+        return Ok(DEFAULT_SYNC_PROVIDER_ACCOUNT_ID.to_string());
+    };
+    let valid = account_id.len() <= ACCOUNT_ID_MAX_LENGTH
+        && account_id
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || "._@-".contains(character));
+    if !valid {
+        return Err(SoloStackError::with_detail(
+            SoloStackErrorCode::ProviderAccountIdInvalid,
+            format!(
+                "account id must be at most {ACCOUNT_ID_MAX_LENGTH} letters, digits or . _ @ -"
+            ),
+        ));
+    }
+    Ok(account_id.to_ascii_lowercase())
+}
+
+pub(crate) fn account_storage_key(provider: &str, account_id: &str) -> String {
+    if account_id == DEFAULT_SYNC_PROVIDER_ACCOUNT_ID {
+        provider.to_string()
+    } else {
+        format!("{provider}{ACCOUNT_KEY_SEPARATOR}{account_id}")
+    }
+}
+
+pub(crate) fn account_index_storage_key(provider: &str) -> String {
+    format!("{provider}{ACCOUNT_INDEX_KEY_SUFFIX}")
+}
+
+pub(crate) fn parse_storage_key(key: &str) -> SyncProviderStorageEntry<'_> {
+    if let Some(provider) = key.strip_suffix(ACCOUNT_INDEX_KEY_SUFFIX) {
+        return SyncProviderStorageEntry::AccountIndex { provider };
+    }
+    let provider = key
+        .split_once(ACCOUNT_KEY_SEPARATOR)
+        .map_or(key, |(provider, _)| provider);
+    SyncProviderStorageEntry::Credential { provider }
+}
+
+pub(crate) fn decode_account_index(
+    stored: &str,
+) -> Result<SyncProviderAccountIndex, SoloStackError> {
+    serde_json::from_str(stored).map_err(|error| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::SecureStoreReadFailed,
+            format!("parse account index failed: {error}"),
+        )
+    })
+}
+
+pub(crate) fn encode_account_index(
+    index: &SyncProviderAccountIndex,
+) -> Result<String, SoloStackError> {
+    serde_json::to_string(index).map_err(|error| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::Internal,
+            format!("serialize account index failed: {error}"),
+        )
+    })
+}
+
+impl SyncProviderAccountIndex {
+    // Index for a provider saved before accounts existed: only the default entry, if any.
+    pub(crate) fn legacy(has_default_entry: bool) -> Self {
+        if has_default_entry {
+            Self {
+                active_account_id: Some(DEFAULT_SYNC_PROVIDER_ACCOUNT_ID.to_string()),
+                account_ids: vec![DEFAULT_SYNC_PROVIDER_ACCOUNT_ID.to_string()],
+            }
+        } else {
+            Self::default()
+        }
+    }
+
+    pub(crate) fn active_or_default(&self) -> String {
+        self.active_account_id
+            .clone()
+            .unwrap_or_else(|| DEFAULT_SYNC_PROVIDER_ACCOUNT_ID.to_string())
+    }
+
+    pub(crate) fn contains(&self, account_id: &str) -> bool {
+        self.account_ids
+            .iter()
+            .any(|existing| existing == account_id)
+    }
+
+    // The first stored account becomes active; adding more does not switch away from it.
+    pub(crate) fn add(&mut self, account_id: &str) {
+        if !self.contains(account_id) {
+            self.account_ids.push(account_id.to_string());
+            self.account_ids.sort();
+        }
+        if self.active_account_id.is_none() {
+            self.active_account_id = Some(account_id.to_string());
+        }
+    }
+
+    pub(crate) fn remove(&mut self, account_id: &str) {
+        self.account_ids.retain(|existing| existing != account_id);
+        if self.active_account_id.as_deref() == Some(account_id) {
+            self.active_account_id = self.account_ids.first().cloned();
+        }
+    }
+
+    pub(crate) fn set_active(&mut self, account_id: &str) -> Result<(), SoloStackError> {
+        if !self.contains(account_id) {
+            return Err(SoloStackError::with_detail(
+                SoloStackErrorCode::ProviderAccountNotFound,
+                format!("no stored credential for account {account_id}"),
+            ));
+        }
+        self.active_account_id = Some(account_id.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_account_keeps_the_legacy_key() {
+        let default_id = normalize_account_id(None).unwrap();
+        assert_eq!(
+            account_storage_key("onedrive_approot", &default_id),
+            "onedrive_approot"
+        );
+        let work_id = normalize_account_id(Some(" Work@Example.com ")).unwrap();
+        assert_eq!(
+            account_storage_key("onedrive_approot", &work_id),
+            "onedrive_approot::work@example.com"
+        );
+        assert_eq!(
+            normalize_account_id(Some("a#accounts")).unwrap_err().code,
+            SoloStackErrorCode::ProviderAccountIdInvalid
+        );
+    }
+
+    #[test]
+    fn removing_the_active_account_promotes_the_next_one() {
+        let mut index = SyncProviderAccountIndex::legacy(true);
+        index.add("work");
+        assert_eq!(index.active_or_default(), "default");
+        index.set_active("work").unwrap();
+        assert_eq!(
+            index.set_active("personal").unwrap_err().code,
+            SoloStackErrorCode::ProviderAccountNotFound
+        );
+        index.remove("work");
+        assert_eq!(index.active_account_id.as_deref(), Some("default"));
+        index.remove("default");
+        assert!(index.active_account_id.is_none());
+        assert!(index.account_ids.is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+// Structured credential kept per sync provider in the secure store. The whole record is
+// serialized into the keyring, Android or encrypted-file entry so expiry, scopes and rotation
+// travel with the secret. Status reports are built from the same record but never carry the
+// secret itself.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SoloStackError, SoloStackErrorCode};
+
+pub(crate) const MANAGED_SYNC_PROVIDERS: &[&str] = &[
+    "google_appdata",
+    "onedrive_approot",
+    "icloud_cloudkit",
+    "solostack_cloud_aws",
+    "webdav",
+];
+const CREDENTIAL_RECORD_VERSION: u8 = 1;
+// Token-set fields only Rust needs (native refresh); they are stripped before a record reaches
+// the webview and carried over from the stored entry when the webview writes the set back.
+const WEBVIEW_WITHHELD_TOKEN_FIELDS: &[&str] = &["refresh_token", "client_secret"];
+// Long enough for the app to prompt before a sync cycle that starts now would hit UNAUTHORIZED.
+pub(crate) const EXPIRING_SOON_WINDOW_SECONDS: i64 = 10 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SyncProviderCredentialKind {
+    OauthTokens,
+    AccessToken,
+    RefreshToken,
+    ApiKey,
+    Opaque,
+}
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct SyncProviderCredentialInput {
+    kind: SyncProviderCredentialKind,
+    secret: String,
+    #[serde(default)]
+    expires_at: Option<String>,
+    #[serde(default)]
+    scopes: Vec<String>,
+    #[serde(default)]
+    issued_at: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct SyncProviderCredentialRecord {
+    version: u8,
+    pub(crate) kind: SyncProviderCredentialKind,
+    pub(crate) secret: String,
+    pub(crate) expires_at: Option<String>,
+    pub(crate) scopes: Vec<String>,
+    pub(crate) issued_at: Option<String>,
+    pub(crate) rotation: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SyncProviderCredentialExpiry {
+    Missing,
+    NoExpiry,
+    Valid,
+    ExpiringSoon,
+    Expired,
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct SyncProviderSecureAuthStatus {
+    provider: String,
+    account_id: String,
+    expiry: SyncProviderCredentialExpiry,
+    kind: Option<SyncProviderCredentialKind>,
+    expires_at: Option<String>,
+    expires_in_seconds: Option<i64>,
+    scopes: Vec<String>,
+    issued_at: Option<String>,
+    rotation: Option<u32>,
+    error: Option<SoloStackError>,
+}
+
+impl SyncProviderCredentialInput {
+    // Used when the credential is obtained natively (e.g. the OAuth flow) instead of sent by the
+    // frontend.
+    #[cfg_attr(any(target_os = "android", target_os = "ios"), allow(dead_code))]
+    pub(crate) fn new(
+        kind: SyncProviderCredentialKind,
+        secret: String,
+        expires_at: Option<String>,
+        scopes: Vec<String>,
+    ) -> Self {
+        Self {
+            kind,
+            secret,
+            expires_at,
+            scopes,
+            issued_at: None,
+        }
+    }
+}
+
+fn credential_invalid(detail: String) -> SoloStackError {
+    SoloStackError::with_detail(SoloStackErrorCode::CredentialInvalid, detail)
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub(crate) fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+fn normalize_timestamp(field: &str, value: Option<&str>) -> Result<Option<String>, SoloStackError> {
+    match value.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) => parse_timestamp(value)
+            .map(|timestamp| Some(format_timestamp(timestamp)))
+            .ok_or_else(|| credential_invalid(format!("{field} must be an RFC 3339 timestamp"))),
+        None => Ok(None),
+    }
+}
+
+fn normalize_scopes<'a>(scopes: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut normalized = scopes
+        .into_iter()
+        .map(str::trim)
+        .filter(|scope| !scope.is_empty())
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+// The rotation counter only moves when the secret itself changes, so re-saving the same token
+// with refreshed metadata does not look like a rotation.
+pub(crate) fn build_credential_record(
+    input: SyncProviderCredentialInput,
+    previous: Option<&SyncProviderCredentialRecord>,
+    now: DateTime<Utc>,
+) -> Result<SyncProviderCredentialRecord, SoloStackError> {
+    let secret = input.secret.trim().to_string();
+    if secret.is_empty() {
+        return Err(SoloStackError::new(SoloStackErrorCode::AuthPayloadRequired));
+    }
+    let expires_at = normalize_timestamp("expires_at", input.expires_at.as_deref())?;
+    let explicit_issued_at = normalize_timestamp("issued_at", input.issued_at.as_deref())?;
+
+    let unchanged_previous = previous.filter(|previous| previous.secret == secret);
+    let rotation = match (previous, unchanged_previous) {
+        (_, Some(previous)) => previous.rotation,
+        (Some(previous), None) => previous.rotation.saturating_add(1),
+        (None, None) => 0,
+    };
+    let issued_at = explicit_issued_at
+        .or_else(|| unchanged_previous.and_then(|previous| previous.issued_at.clone()))
+        .unwrap_or_else(|| format_timestamp(now));
+
+    Ok(SyncProviderCredentialRecord {
+        version: CREDENTIAL_RECORD_VERSION,
+        kind: input.kind,
+        secret,
+        expires_at,
+        scopes: normalize_scopes(input.scopes.iter().map(String::as_str)),
+        issued_at: Some(issued_at),
+        rotation,
+    })
+}
+
+pub(crate) fn encode_credential_record(
+    record: &SyncProviderCredentialRecord,
+) -> Result<String, SoloStackError> {
+    serde_json::to_string(record).map_err(|error| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::Internal,
+            format!("serialize credential record failed: {error}"),
+        )
+    })
+}
+
+// Entries written before credential records existed hold the frontend's token-set JSON or a bare
+// token string; both are surfaced as records so callers only deal with one shape.
+pub(crate) fn decode_credential_record(stored: &str) -> SyncProviderCredentialRecord {
+    let stored = stored.trim();
+    let parsed = serde_json::from_str::<serde_json::Value>(stored).ok();
+    if let Some(record) = parsed
+        .as_ref()
+        .filter(|value| value.get("version").is_some() && value.get("secret").is_some())
+        .and_then(|value| {
+            serde_json::from_value::<SyncProviderCredentialRecord>(value.clone()).ok()
+        })
+    {
+        return record;
+    }
+
+    let token_set = parsed.as_ref().filter(|value| {
+        value.get("access_token").is_some() || value.get("refresh_token").is_some()
+    });
+    SyncProviderCredentialRecord {
+        version: CREDENTIAL_RECORD_VERSION,
+        kind: match token_set {
+            Some(_) => SyncProviderCredentialKind::OauthTokens,
+            None => SyncProviderCredentialKind::Opaque,
+        },
+        secret: stored.to_string(),
+        expires_at: token_set
+            .and_then(|value| value.get("expires_at"))
+            .and_then(serde_json::Value::as_str)
+            .and_then(parse_timestamp)
+            .map(format_timestamp),
+        scopes: token_set
+            .and_then(|value| value.get("scope"))
+            .and_then(serde_json::Value::as_str)
+            .map(|scope| normalize_scopes(scope.split_whitespace()))
+            .unwrap_or_default(),
+        issued_at: None,
+        rotation: 0,
+    }
+}
+
+fn token_set_object(secret: &str) -> Option<serde_json::Map<String, serde_json::Value>> {
+    match serde_json::from_str::<serde_json::Value>(secret).ok()? {
+        serde_json::Value::Object(object) => Some(object),
+        _ => None,
+    }
+}
+
+fn token_set_field<'a>(
+    token_set: &'a serde_json::Map<String, serde_json::Value>,
+    field: &str,
+) -> Option<&'a serde_json::Value> {
+    token_set.get(field).filter(|value| !value.is_null())
+}
+
+// The webview keeps the access token for the sync transport, but never the refresh token or
+// client secret. A bare refresh-token credential has nothing else to show.
+pub(crate) fn redact_for_webview(
+    mut record: SyncProviderCredentialRecord,
+) -> SyncProviderCredentialRecord {
+    match record.kind {
+        SyncProviderCredentialKind::OauthTokens => {
+            let Some(mut token_set) = token_set_object(&record.secret) else {
+                record.secret = String::new();
+                return record;
+            };
+            for field in WEBVIEW_WITHHELD_TOKEN_FIELDS {
+                token_set.remove(*field);
+            }
+            record.secret = serde_json::Value::Object(token_set).to_string();
+        }
+        SyncProviderCredentialKind::RefreshToken => record.secret = String::new(),
+        SyncProviderCredentialKind::AccessToken
+        | SyncProviderCredentialKind::ApiKey
+        | SyncProviderCredentialKind::Opaque => {}
+    }
+    record
+}
+
+// A token set written back by the webview lacks the withheld fields. They are restored from the
+// stored set when both belong to the same OAuth client and token endpoint, so saving settings
+// does not throw away the refresh token.
+pub(crate) fn restore_withheld_fields(
+    mut input: SyncProviderCredentialInput,
+    previous: Option<&SyncProviderCredentialRecord>,
+) -> SyncProviderCredentialInput {
+    let Some(previous) = previous.filter(|previous| {
+        input.kind == SyncProviderCredentialKind::OauthTokens
+            && previous.kind == SyncProviderCredentialKind::OauthTokens
+    }) else {
+        return input;
+    };
+    let (Some(mut token_set), Some(stored)) = (
+        token_set_object(&input.secret),
+        token_set_object(&previous.secret),
+    ) else {
+        return input;
+    };
+    let same_client = ["client_id", "token_refresh_url"]
+        .iter()
+        .all(|field| token_set_field(&token_set, field) == token_set_field(&stored, field));
+    if !same_client {
+        return input;
+    }
+
+    let mut restored = false;
+    for field in WEBVIEW_WITHHELD_TOKEN_FIELDS {
+        if token_set_field(&token_set, field).is_none() {
+            if let Some(value) = token_set_field(&stored, field) {
+                token_set.insert(field.to_string(), value.clone());
+                restored = true;
+            }
+        }
+    }
+    if restored {
+        input.secret = serde_json::Value::Object(token_set).to_string();
+    }
+    input
+}
+
+pub(crate) fn credential_status(
+    provider: &str,
+    account_id: &str,
+    record: Option<&SyncProviderCredentialRecord>,
+    now: DateTime<Utc>,
+) -> SyncProviderSecureAuthStatus {
+    let Some(record) = record else {
+        return SyncProviderSecureAuthStatus {
+            provider: provider.to_string(),
+            account_id: account_id.to_string(),
+            expiry: SyncProviderCredentialExpiry::Missing,
+            kind: None,
+            expires_at: None,
+            expires_in_seconds: None,
+            scopes: Vec::new(),
+            issued_at: None,
+            rotation: None,
+            error: None,
+        };
+    };
+
+    let expires_in_seconds = record
+        .expires_at
+        .as_deref()
+        .and_then(parse_timestamp)
+        .map(|expires_at| (expires_at - now).num_seconds());
+    let expiry = match expires_in_seconds {
+        None => SyncProviderCredentialExpiry::NoExpiry,
+        Some(seconds) if seconds <= 0 => SyncProviderCredentialExpiry::Expired,
+        Some(seconds) if seconds <= EXPIRING_SOON_WINDOW_SECONDS => {
+            SyncProviderCredentialExpiry::ExpiringSoon
+        }
+        Some(_) => SyncProviderCredentialExpiry::Valid,
+    };
+    SyncProviderSecureAuthStatus {
+        provider: provider.to_string(),
+        account_id: account_id.to_string(),
+        expiry,
+        kind: Some(record.kind),
+        expires_at: record.expires_at.clone(),
+        expires_in_seconds,
+        scopes: record.scopes.clone(),
+        issued_at: record.issued_at.clone(),
+        rotation: Some(record.rotation),
+        error: None,
+    }
+}
+
+pub(crate) fn credential_status_error(
+    provider: &str,
+    account_id: &str,
+    error: SoloStackError,
+) -> SyncProviderSecureAuthStatus {
+    SyncProviderSecureAuthStatus {
+        error: Some(error),
+        ..credential_status(provider, account_id, None, Utc::now())
+    }
+}
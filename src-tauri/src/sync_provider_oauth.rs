@@ -0,0 +1,626 @@
+// Authorization-code + PKCE flow for the managed sync providers. A one-shot loopback listener on
+// 127.0.0.1 receives the redirect, and the code is exchanged here so tokens go straight into the
+// secure store without passing through the webview. Debug builds and tests can override the
+// endpoints per request to point the flow at a local stub authorization server; release builds
+// only talk to the provider's own https endpoints.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+use url::Url;
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    sync_provider_credential::{SyncProviderCredentialInput, SyncProviderCredentialKind},
+    sync_transport,
+};
+
+pub(crate) const OAUTH_CALLBACK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const OAUTH_CALLBACK_PATH: &str = "/callback";
+const OAUTH_CALLBACK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const OAUTH_CALLBACK_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const OAUTH_CALLBACK_MAX_REQUEST_BYTES: usize = 16 * 1024;
+const OAUTH_TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const OAUTH_CALLBACK_SUCCESS_HTML: &str =
+    "<!doctype html><title>SoloStack</title><p>Sign-in complete. You can close this window.</p>";
+const OAUTH_CALLBACK_FAILURE_HTML: &str =
+    "<!doctype html><title>SoloStack</title><p>Sign-in failed. Return to SoloStack and try again.</p>";
+
+struct OAuthProviderEndpoints {
+    provider: &'static str,
+    authorization_endpoint: &'static str,
+    token_endpoint: &'static str,
+    default_scopes: &'static [&'static str],
+    // Google only issues a refresh token when offline access and consent are requested.
+    extra_authorization_params: &'static [(&'static str, &'static str)],
+}
+
+const OAUTH_PROVIDER_ENDPOINTS: &[OAuthProviderEndpoints] = &[
+    OAuthProviderEndpoints {
+        provider: "google_appdata",
+        authorization_endpoint: "https://accounts.google.com/o/oauth2/v2/auth",
+        token_endpoint: "https://oauth2.googleapis.com/token",
+        default_scopes: &["https://www.googleapis.com/auth/drive.appdata"],
+        extra_authorization_params: &[("access_type", "offline"), ("prompt", "consent")],
+    },
+    OAuthProviderEndpoints {
+        provider: "onedrive_approot",
+        authorization_endpoint: "https://login.microsoftonline.com/common/oauth2/v2.0/authorize",
+        token_endpoint: "https://login.microsoftonline.com/common/oauth2/v2.0/token",
+        default_scopes: &["Files.ReadWrite.AppFolder", "offline_access"],
+        extra_authorization_params: &[],
+    },
+];
+
+#[derive(Clone, Deserialize)]
+pub(crate) struct SyncProviderOAuthRequest {
+    pub(crate) provider: String,
+    #[serde(default)]
+    pub(crate) account_id: Option<String>,
+    client_id: String,
+    #[serde(default)]
+    client_secret: Option<String>,
+    #[serde(default)]
+    scopes: Option<Vec<String>>,
+    #[cfg(any(test, debug_assertions))]
+    #[serde(default)]
+    authorization_endpoint: Option<String>,
+    #[cfg(any(test, debug_assertions))]
+    #[serde(default)]
+    token_endpoint: Option<String>,
+}
+
+impl SyncProviderOAuthRequest {
+    #[cfg(any(test, debug_assertions))]
+    fn endpoint_overrides(&self) -> (Option<&str>, Option<&str>) {
+        (
+            self.authorization_endpoint.as_deref(),
+            self.token_endpoint.as_deref(),
+        )
+    }
+
+    // The auth code, client secret and refresh token all go to these endpoints, so a release
+    // build never takes them from the webview.
+    #[cfg(not(any(test, debug_assertions)))]
+    fn endpoint_overrides(&self) -> (Option<&str>, Option<&str>) {
+        (None, None)
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct OAuthTokenResponse {
+    pub(crate) access_token: String,
+    #[serde(default)]
+    pub(crate) token_type: Option<String>,
+    #[serde(default)]
+    pub(crate) refresh_token: Option<String>,
+    #[serde(default)]
+    pub(crate) expires_in: Option<i64>,
+    #[serde(default)]
+    pub(crate) scope: Option<String>,
+}
+
+impl OAuthTokenResponse {
+    pub(crate) fn expires_at(&self, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+        self.expires_in
+            .filter(|seconds| *seconds > 0)
+            .map(|seconds| {
+                (now + chrono::Duration::seconds(seconds))
+                    .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+            })
+    }
+}
+
+// Same shape as the frontend's `SyncProviderAuthState`, so entries written here are readable by
+// the existing sync transport without changes.
+#[derive(Serialize)]
+struct OAuthTokenSet {
+    access_token: String,
+    token_type: String,
+    refresh_token: Option<String>,
+    token_refresh_url: String,
+    expires_at: Option<String>,
+    scope: Option<String>,
+    client_id: String,
+    client_secret: Option<String>,
+}
+
+fn oauth_error(code: SoloStackErrorCode, detail: impl Into<String>) -> SoloStackError {
+    SoloStackError::with_detail(code, detail)
+}
+
+fn random_url_safe_token(byte_count: usize) -> Result<String, SoloStackError> {
+    let mut bytes = vec![0u8; byte_count];
+    getrandom::getrandom(&mut bytes).map_err(|error| {
+        oauth_error(
+            SoloStackErrorCode::Internal,
+            format!("generate random token failed: {error}"),
+        )
+    })?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+// Every endpoint that receives an auth code, client secret or refresh token goes through here.
+pub(crate) fn parse_endpoint(value: &str, field: &str) -> Result<Url, SoloStackError> {
+    let url = Url::parse(value.trim()).map_err(|error| {
+        oauth_error(
+            SoloStackErrorCode::CredentialInvalid,
+            format!("{field} is not a valid URL: {error}"),
+        )
+    })?;
+    let stub_allowed = url.scheme() == "http" && sync_transport::allows_plain_http(&url);
+    if url.scheme() == "https" || stub_allowed {
+        Ok(url)
+    } else {
+        Err(oauth_error(
+            SoloStackErrorCode::CredentialInvalid,
+            format!("{field} must use https"),
+        ))
+    }
+}
+
+fn resolve_endpoint(
+    override_value: Option<&str>,
+    default_value: &str,
+    field: &str,
+) -> Result<Url, SoloStackError> {
+    let value = override_value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(default_value);
+    parse_endpoint(value, field)
+}
+
+fn write_callback_response(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+}
+
+fn read_request_target(stream: &mut TcpStream) -> io::Result<Option<String>> {
+    stream.set_read_timeout(Some(OAUTH_CALLBACK_READ_TIMEOUT))?;
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n")
+        && request.len() < OAUTH_CALLBACK_MAX_REQUEST_BYTES
+    {
+        let read = stream.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(target)) => Ok(Some(target.to_string())),
+        _ => Ok(None),
+    }
+}
+
+// Browsers also ask for /favicon.ico and may pre-connect, so anything that is not the callback
+// gets a 404 and the listener keeps waiting until the deadline.
+fn wait_for_authorization_code(
+    listener: &TcpListener,
+    expected_state: &str,
+    timeout: Duration,
+) -> Result<String, SoloStackError> {
+    listener.set_nonblocking(true).map_err(|error| {
+        oauth_error(
+            SoloStackErrorCode::Internal,
+            format!("configure loopback listener failed: {error}"),
+        )
+    })?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                if Instant::now() >= deadline {
+                    return Err(oauth_error(
+                        SoloStackErrorCode::OauthTimeout,
+                        "authorization was not completed in the browser in time",
+                    ));
+                }
+                thread::sleep(OAUTH_CALLBACK_POLL_INTERVAL);
+                continue;
+            }
+            Err(error) => {
+                return Err(oauth_error(
+                    SoloStackErrorCode::Internal,
+                    format!("accept loopback connection failed: {error}"),
+                ))
+            }
+        };
+        let _ = stream.set_nonblocking(false);
+
+        let Ok(Some(target)) = read_request_target(&mut stream) else {
+            write_callback_response(&mut stream, "400 Bad Request", "");
+            continue;
+        };
+        let Ok(callback_url) = Url::parse(&format!("http://127.0.0.1{target}")) else {
+            write_callback_response(&mut stream, "400 Bad Request", "");
+            continue;
+        };
+        if callback_url.path() != OAUTH_CALLBACK_PATH {
+            write_callback_response(&mut stream, "404 Not Found", "");
+            continue;
+        }
+
+        let query_value = |name: &str| {
+            callback_url
+                .query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        let result = if query_value("state").as_deref() != Some(expected_state) {
+            Err(oauth_error(
+                SoloStackErrorCode::OauthAuthorizationDenied,
+                "authorization callback state does not match",
+            ))
+        } else if let Some(error) = query_value("error") {
+            let description = query_value("error_description").unwrap_or_default();
+            Err(oauth_error(
+                SoloStackErrorCode::OauthAuthorizationDenied,
+                format!("authorization server returned {error}: {description}"),
+            ))
+        } else {
+            query_value("code")
+                .filter(|code| !code.is_empty())
+                .ok_or_else(|| {
+                    oauth_error(
+                        SoloStackErrorCode::OauthAuthorizationDenied,
+                        "authorization callback has no code",
+                    )
+                })
+        };
+        match &result {
+            Ok(_) => write_callback_response(&mut stream, "200 OK", OAUTH_CALLBACK_SUCCESS_HTML),
+            Err(_) => write_callback_response(&mut stream, "200 OK", OAUTH_CALLBACK_FAILURE_HTML),
+        }
+        return result;
+    }
+}
+
+// Shared by the code exchange here and the background refresh in `sync_provider_token_refresh`.
+pub(crate) fn request_tokens(
+    token_endpoint: &Url,
+    form: &[(&str, &str)],
+) -> Result<OAuthTokenResponse, SoloStackError> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(OAUTH_TOKEN_REQUEST_TIMEOUT)
+        .build();
+    let response = match agent.post(token_endpoint.as_str()).send_form(form) {
+        Ok(response) => response,
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            return Err(oauth_error(
+                SoloStackErrorCode::OauthTokenExchangeFailed,
+                format!("token endpoint returned {status}: {body}"),
+            ));
+        }
+        Err(error) => {
+            return Err(oauth_error(
+                SoloStackErrorCode::OauthTokenExchangeFailed,
+                format!("token request failed: {error}"),
+            ))
+        }
+    };
+    response.into_json::<OAuthTokenResponse>().map_err(|error| {
+        oauth_error(
+            SoloStackErrorCode::OauthTokenExchangeFailed,
+            format!("parse token response failed: {error}"),
+        )
+    })
+}
+
+// Blocks until the browser hits the loopback redirect or `timeout` passes. `open_browser` is
+// handed the authorization URL; the app opens it with tauri-plugin-opener.
+pub(crate) fn run_authorization_code_flow(
+    request: &SyncProviderOAuthRequest,
+    open_browser: &mut dyn FnMut(&str) -> Result<(), String>,
+    timeout: Duration,
+) -> Result<SyncProviderCredentialInput, SoloStackError> {
+    let provider = request.provider.trim();
+    let endpoints = OAUTH_PROVIDER_ENDPOINTS
+        .iter()
+        .find(|endpoints| endpoints.provider == provider)
+        .ok_or_else(|| {
+            oauth_error(
+                SoloStackErrorCode::OauthProviderUnsupported,
+                format!("{provider} does not support OAuth sign-in"),
+            )
+        })?;
+    let client_id = request.client_id.trim();
+    if client_id.is_empty() {
+        return Err(SoloStackError::new(
+            SoloStackErrorCode::OauthClientIdRequired,
+        ));
+    }
+    let client_secret = request
+        .client_secret
+        .as_deref()
+        .map(str::trim)
+        .filter(|secret| !secret.is_empty());
+    let (authorization_override, token_override) = request.endpoint_overrides();
+    let authorization_endpoint = resolve_endpoint(
+        authorization_override,
+        endpoints.authorization_endpoint,
+        "authorization_endpoint",
+    )?;
+    let token_endpoint =
+        resolve_endpoint(token_override, endpoints.token_endpoint, "token_endpoint")?;
+    let scopes = match request.scopes.as_ref().filter(|scopes| !scopes.is_empty()) {
+        Some(scopes) => scopes.join(" "),
+        None => endpoints.default_scopes.join(" "),
+    };
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).map_err(|error| {
+        oauth_error(
+            SoloStackErrorCode::Internal,
+            format!("bind loopback listener failed: {error}"),
+        )
+    })?;
+    let port = listener
+        .local_addr()
+        .map_err(|error| {
+            oauth_error(
+                SoloStackErrorCode::Internal,
+                format!("read loopback address failed: {error}"),
+            )
+        })?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{port}{OAUTH_CALLBACK_PATH}");
+    let code_verifier = random_url_safe_token(32)?;
+    let state = random_url_safe_token(16)?;
+
+    let mut authorization_url = authorization_endpoint;
+    authorization_url
+        .query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", client_id)
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("scope", &scopes)
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &pkce_challenge(&code_verifier))
+        .append_pair("code_challenge_method", "S256")
+        .extend_pairs(endpoints.extra_authorization_params.iter().copied());
+    open_browser(authorization_url.as_str()).map_err(|error| {
+        oauth_error(
+            SoloStackErrorCode::Internal,
+            format!("open browser failed: {error}"),
+        )
+    })?;
+
+    let code = wait_for_authorization_code(&listener, &state, timeout)?;
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("client_id", client_id),
+        ("code_verifier", code_verifier.as_str()),
+    ];
+    if let Some(client_secret) = client_secret {
+        form.push(("client_secret", client_secret));
+    }
+    let token_response = request_tokens(&token_endpoint, &form)?;
+
+    let expires_at = token_response.expires_at(chrono::Utc::now());
+    let granted_scope = token_response.scope.unwrap_or(scopes);
+    let token_set = OAuthTokenSet {
+        access_token: token_response.access_token,
+        token_type: token_response
+            .token_type
+            .unwrap_or_else(|| "Bearer".to_string()),
+        refresh_token: token_response.refresh_token,
+        token_refresh_url: token_endpoint.to_string(),
+        expires_at: expires_at.clone(),
+        scope: Some(granted_scope.clone()),
+        client_id: client_id.to_string(),
+        client_secret: client_secret.map(str::to_string),
+    };
+    let secret = serde_json::to_string(&token_set).map_err(|error| {
+        oauth_error(
+            SoloStackErrorCode::Internal,
+            format!("serialize token set failed: {error}"),
+        )
+    })?;
+    Ok(SyncProviderCredentialInput::new(
+        SyncProviderCredentialKind::OauthTokens,
+        secret,
+        expires_at,
+        granted_scope
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync_provider_credential::build_credential_record;
+    use std::sync::mpsc;
+
+    // Minimal authorization server: the "browser" follows the authorization URL straight to the
+    // loopback redirect, and the token endpoint checks the PKCE verifier against the challenge.
+    fn spawn_stub_token_endpoint(
+        expected_code: &'static str,
+    ) -> (String, mpsc::Receiver<Vec<(String, String)>>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            loop {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let content_length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|value| value.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= content_length {
+                        break;
+                    }
+                }
+            }
+            let text = String::from_utf8_lossy(&request).to_string();
+            let body = text.split_once("\r\n\r\n").unwrap().1.to_string();
+            let form = url::form_urlencoded::parse(body.as_bytes())
+                .into_owned()
+                .collect::<Vec<_>>();
+            let code_matches = form
+                .iter()
+                .any(|(key, value)| key == "code" && value == expected_code);
+            sender.send(form).unwrap();
+            let response_body = if code_matches {
+                r#"{"access_token":"stub-access","refresh_token":"stub-refresh","expires_in":3600,"token_type":"Bearer"}"#
+            } else {
+                r#"{"error":"invalid_grant"}"#
+            };
+            let status = if code_matches {
+                "200 OK"
+            } else {
+                "400 Bad Request"
+            };
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response_body}",
+                response_body.len()
+            )
+            .unwrap();
+        });
+        (format!("http://127.0.0.1:{port}/token"), receiver)
+    }
+
+    fn stub_request(token_endpoint: String) -> SyncProviderOAuthRequest {
+        SyncProviderOAuthRequest {
+            provider: "google_appdata".to_string(),
+            account_id: None,
+            client_id: "stub-client".to_string(),
+            client_secret: None,
+            scopes: None,
+            authorization_endpoint: Some("http://127.0.0.1:1/authorize".to_string()),
+            token_endpoint: Some(token_endpoint),
+        }
+    }
+
+    #[test]
+    fn exchanges_code_with_pkce_verifier_against_stub_server() {
+        let (token_endpoint, token_requests) = spawn_stub_token_endpoint("stub-code");
+        let mut challenge = String::new();
+        let mut open_browser = |authorization_url: &str| {
+            let url = Url::parse(authorization_url).unwrap();
+            let param = |name: &str| {
+                url.query_pairs()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.into_owned())
+                    .unwrap()
+            };
+            challenge = param("code_challenge");
+            let callback = format!(
+                "{}?code=stub-code&state={}",
+                param("redirect_uri"),
+                param("state")
+            );
+            thread::spawn(move || {
+                let _ = ureq::get(&callback).call();
+            });
+            Ok(())
+        };
+
+        let credential = run_authorization_code_flow(
+            &stub_request(token_endpoint),
+            &mut open_browser,
+            Duration::from_secs(10),
+        )
+        .unwrap();
+
+        let form = token_requests.recv().unwrap();
+        let verifier = form
+            .iter()
+            .find(|(key, _)| key == "code_verifier")
+            .map(|(_, value)| value.clone())
+            .unwrap();
+        assert_eq!(pkce_challenge(&verifier), challenge);
+        let record = build_credential_record(credential, None, chrono::Utc::now()).unwrap();
+        assert_eq!(record.kind, SyncProviderCredentialKind::OauthTokens);
+        let secret: serde_json::Value = serde_json::from_str(&record.secret).unwrap();
+        assert_eq!(secret["access_token"], "stub-access");
+        assert_eq!(secret["refresh_token"], "stub-refresh");
+        assert!(secret["expires_at"].is_string());
+    }
+
+    #[test]
+    fn rejects_callback_with_mismatched_state() {
+        let (token_endpoint, _token_requests) = spawn_stub_token_endpoint("stub-code");
+        let mut open_browser = |authorization_url: &str| {
+            let url = Url::parse(authorization_url).unwrap();
+            let redirect_uri = url
+                .query_pairs()
+                .find(|(key, _)| key == "redirect_uri")
+                .map(|(_, value)| value.into_owned())
+                .unwrap();
+            let callback = format!("{redirect_uri}?code=stub-code&state=forged");
+            thread::spawn(move || {
+                let _ = ureq::get(&callback).call();
+            });
+            Ok(())
+        };
+
+        let error = run_authorization_code_flow(
+            &stub_request(token_endpoint),
+            &mut open_browser,
+            Duration::from_secs(10),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(error.code, SoloStackErrorCode::OauthAuthorizationDenied);
+    }
+
+    #[test]
+    fn refuses_plain_http_endpoints_off_this_machine() {
+        let request = SyncProviderOAuthRequest {
+            token_endpoint: Some("http://auth.example.com/token".to_string()),
+            ..stub_request("https://auth.example.com/token".to_string())
+        };
+        let mut open_browser = |_: &str| -> Result<(), String> {
+            panic!("the browser must not open for a rejected endpoint")
+        };
+
+        let error =
+            run_authorization_code_flow(&request, &mut open_browser, Duration::from_secs(1))
+                .err()
+                .unwrap();
+        assert_eq!(error.code, SoloStackErrorCode::CredentialInvalid);
+        assert_eq!(
+            error.detail.as_deref(),
+            Some("token_endpoint must use https")
+        );
+    }
+}
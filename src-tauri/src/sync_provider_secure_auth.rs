@@ -0,0 +1,702 @@
+// Sync provider auth commands. Every body works against the `SecureStoreState` backend chosen at
+// startup, so the same code runs on the keyring, Android, the encrypted file and, in tests, the
+// in-memory store.
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    secure_store::{SecureStoreState, SyncProviderSecureStoreSelfTestResult},
+    secure_store_audit::SecureStoreAuditPage,
+    sync_provider_accounts::{self, SyncProviderAccountIndex, SyncProviderAccountList},
+    sync_provider_credential::{
+        self, SyncProviderCredentialInput, SyncProviderCredentialRecord,
+        SyncProviderSecureAuthStatus,
+    },
+};
+
+pub(crate) fn normalize_sync_provider_identifier(provider: &str) -> Result<String, SoloStackError> {
+    let normalized_provider = provider.trim();
+    if normalized_provider.is_empty() {
+        return Err(SoloStackError::new(SoloStackErrorCode::ProviderRequired));
+    }
+
+    Ok(normalized_provider.to_string())
+}
+
+// `SecureStoreState::read`, or `poll_read` for the background token refresh.
+type StoreRead = fn(&SecureStoreState, &str) -> Result<Option<String>, SoloStackError>;
+
+fn read_credential_with(
+    store: &SecureStoreState,
+    read: StoreRead,
+    provider: &str,
+    account_id: &str,
+) -> Result<Option<SyncProviderCredentialRecord>, SoloStackError> {
+    let storage_key = sync_provider_accounts::account_storage_key(provider, account_id);
+    Ok(read(store, &storage_key)?
+        .map(|stored| sync_provider_credential::decode_credential_record(&stored)))
+}
+
+fn read_credential(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: &str,
+) -> Result<Option<SyncProviderCredentialRecord>, SoloStackError> {
+    read_credential_with(store, SecureStoreState::read, provider, account_id)
+}
+
+fn read_account_index_with(
+    store: &SecureStoreState,
+    read: StoreRead,
+    provider: &str,
+) -> Result<SyncProviderAccountIndex, SoloStackError> {
+    let index_key = sync_provider_accounts::account_index_storage_key(provider);
+    match read(store, &index_key)? {
+        Some(stored) => sync_provider_accounts::decode_account_index(&stored),
+        None => Ok(SyncProviderAccountIndex::legacy(
+            read(store, provider)?.is_some(),
+        )),
+    }
+}
+
+pub(crate) fn read_account_index(
+    store: &SecureStoreState,
+    provider: &str,
+) -> Result<SyncProviderAccountIndex, SoloStackError> {
+    read_account_index_with(store, SecureStoreState::read, provider)
+}
+
+// The refresh poll's view of the account index; see `SecureStoreState::poll_read`.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub(crate) fn poll_account_index(
+    store: &SecureStoreState,
+    provider: &str,
+) -> Result<SyncProviderAccountIndex, SoloStackError> {
+    read_account_index_with(store, SecureStoreState::poll_read, provider)
+}
+
+// Callers hold the credential lock.
+fn write_account_index(
+    store: &SecureStoreState,
+    provider: &str,
+    index: &SyncProviderAccountIndex,
+) -> Result<(), SoloStackError> {
+    let index_key = sync_provider_accounts::account_index_storage_key(provider);
+    if index.account_ids.is_empty() {
+        return store.delete(&index_key);
+    }
+    store.write(
+        &index_key,
+        &sync_provider_accounts::encode_account_index(index)?,
+    )
+}
+
+// Commands take an optional account id; without one they act on the provider's active account.
+fn resolve_account(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: Option<&str>,
+) -> Result<(String, String), SoloStackError> {
+    let provider = normalize_sync_provider_identifier(provider)?;
+    let account_id = match account_id.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(account_id) => sync_provider_accounts::normalize_account_id(Some(account_id))?,
+        None => read_account_index(store, &provider)?.active_or_default(),
+    };
+    Ok((provider, account_id))
+}
+
+// Callers hold the credential lock.
+fn write_credential(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: &str,
+    credential: SyncProviderCredentialInput,
+    previous: Option<&SyncProviderCredentialRecord>,
+) -> Result<SyncProviderSecureAuthStatus, SoloStackError> {
+    let now = chrono::Utc::now();
+    let record = sync_provider_credential::build_credential_record(credential, previous, now)?;
+    store.write(
+        &sync_provider_accounts::account_storage_key(provider, account_id),
+        &sync_provider_credential::encode_credential_record(&record)?,
+    )?;
+    Ok(sync_provider_credential::credential_status(
+        provider,
+        account_id,
+        Some(&record),
+        now,
+    ))
+}
+
+fn credential_status_for(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> SyncProviderSecureAuthStatus {
+    match read_credential(store, provider, account_id) {
+        Ok(record) => {
+            sync_provider_credential::credential_status(provider, account_id, record.as_ref(), now)
+        }
+        Err(error) => {
+            sync_provider_credential::credential_status_error(provider, account_id, error)
+        }
+    }
+}
+
+pub(crate) fn get_credential(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: Option<&str>,
+) -> Result<Option<SyncProviderCredentialRecord>, SoloStackError> {
+    let (provider, account_id) = resolve_account(store, provider, account_id)?;
+    read_credential(store, &provider, &account_id)
+}
+
+// What the webview gets: the record with the refresh token and client secret stripped. Token
+// refresh and transports built in Rust use `get_credential`.
+pub(crate) fn get_webview_credential(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: Option<&str>,
+) -> Result<Option<SyncProviderCredentialRecord>, SoloStackError> {
+    get_credential(store, provider, account_id)
+        .map(|record| record.map(sync_provider_credential::redact_for_webview))
+}
+
+pub(crate) fn store_credential(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: Option<&str>,
+    credential: SyncProviderCredentialInput,
+) -> Result<SyncProviderSecureAuthStatus, SoloStackError> {
+    let _guard = store.lock_credentials()?;
+    let (provider, account_id) = resolve_account(store, provider, account_id)?;
+    // An unreadable previous entry is about to be overwritten, so it only loses its rotation.
+    let previous = read_credential(store, &provider, &account_id)
+        .ok()
+        .flatten();
+    let credential =
+        sync_provider_credential::restore_withheld_fields(credential, previous.as_ref());
+    let status = write_credential(store, &provider, &account_id, credential, previous.as_ref())?;
+
+    let mut index = read_account_index(store, &provider)?;
+    if !index.contains(&account_id) || index.active_account_id.is_none() {
+        index.add(&account_id);
+        write_account_index(store, &provider, &index)?;
+    }
+    Ok(status)
+}
+
+pub(crate) fn delete_credential(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: Option<&str>,
+) -> Result<(), SoloStackError> {
+    let _guard = store.lock_credentials()?;
+    let (provider, account_id) = resolve_account(store, provider, account_id)?;
+    store.delete(&sync_provider_accounts::account_storage_key(
+        &provider,
+        &account_id,
+    ))?;
+    let mut index = read_account_index(store, &provider)?;
+    if index.contains(&account_id) {
+        index.remove(&account_id);
+        write_account_index(store, &provider, &index)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn list_active_statuses(
+    store: &SecureStoreState,
+    providers: Option<Vec<String>>,
+) -> Vec<SyncProviderSecureAuthStatus> {
+    let providers = providers.unwrap_or_else(|| {
+        sync_provider_credential::MANAGED_SYNC_PROVIDERS
+            .iter()
+            .map(|provider| provider.to_string())
+            .collect()
+    });
+    let now = chrono::Utc::now();
+    providers
+        .iter()
+        .map(|provider| match resolve_account(store, provider, None) {
+            Ok((provider, account_id)) => credential_status_for(store, &provider, &account_id, now),
+            Err(error) => sync_provider_credential::credential_status_error(
+                provider,
+                sync_provider_accounts::DEFAULT_SYNC_PROVIDER_ACCOUNT_ID,
+                error,
+            ),
+        })
+        .collect()
+}
+
+pub(crate) fn list_accounts(
+    store: &SecureStoreState,
+    provider: &str,
+) -> Result<SyncProviderAccountList, SoloStackError> {
+    let provider = normalize_sync_provider_identifier(provider)?;
+    let index = read_account_index(store, &provider)?;
+    let now = chrono::Utc::now();
+    let accounts = index
+        .account_ids
+        .iter()
+        .map(|account_id| credential_status_for(store, &provider, account_id, now))
+        .collect();
+    Ok(SyncProviderAccountList {
+        provider,
+        active_account_id: index.active_account_id,
+        accounts,
+    })
+}
+
+pub(crate) fn set_active_account(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: &str,
+) -> Result<SyncProviderAccountList, SoloStackError> {
+    let provider = normalize_sync_provider_identifier(provider)?;
+    let account_id = sync_provider_accounts::normalize_account_id(Some(account_id))?;
+    {
+        let _guard = store.lock_credentials()?;
+        let mut index = read_account_index(store, &provider)?;
+        index.set_active(&account_id)?;
+        write_account_index(store, &provider, &index)?;
+    }
+    list_accounts(store, &provider)
+}
+
+// Returns the new status when the credential was refreshed, or `None` when nothing was due.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub(crate) fn refresh_credential_if_due(
+    store: &SecureStoreState,
+    provider: &str,
+    account_id: &str,
+    config: &crate::sync_provider_token_refresh::TokenRefreshConfig,
+) -> Result<Option<SyncProviderSecureAuthStatus>, SoloStackError> {
+    use crate::sync_provider_token_refresh::{needs_refresh, refresh_credential};
+
+    let Some(record) =
+        read_credential_with(store, SecureStoreState::poll_read, provider, account_id)?
+    else {
+        return Ok(None);
+    };
+    let now = chrono::Utc::now();
+    if !needs_refresh(&record, now, config) {
+        return Ok(None);
+    }
+    let credential = refresh_credential(&record, config, now)?;
+
+    let _guard = store.lock_credentials()?;
+    let current = read_credential(store, provider, account_id)?;
+    if current.as_ref().map(|current| current.secret.as_str()) != Some(record.secret.as_str()) {
+        // Replaced or deleted while the token request was in flight; the newer entry wins.
+        return Ok(None);
+    }
+    write_credential(store, provider, account_id, credential, current.as_ref()).map(Some)
+}
+
+#[tauri::command]
+pub(crate) fn get_sync_provider_secure_auth(
+    state: tauri::State<SecureStoreState>,
+    provider: String,
+    account_id: Option<String>,
+) -> Result<Option<SyncProviderCredentialRecord>, SoloStackError> {
+    get_webview_credential(&state, &provider, account_id.as_deref())
+}
+
+#[tauri::command]
+pub(crate) fn set_sync_provider_secure_auth(
+    state: tauri::State<SecureStoreState>,
+    provider: String,
+    credential: SyncProviderCredentialInput,
+    account_id: Option<String>,
+) -> Result<SyncProviderSecureAuthStatus, SoloStackError> {
+    store_credential(&state, &provider, account_id.as_deref(), credential)
+}
+
+#[tauri::command]
+pub(crate) fn delete_sync_provider_secure_auth(
+    state: tauri::State<SecureStoreState>,
+    provider: String,
+    account_id: Option<String>,
+) -> Result<(), SoloStackError> {
+    delete_credential(&state, &provider, account_id.as_deref())
+}
+
+#[tauri::command]
+pub(crate) fn list_sync_provider_secure_auth_status(
+    state: tauri::State<SecureStoreState>,
+    providers: Option<Vec<String>>,
+) -> Vec<SyncProviderSecureAuthStatus> {
+    list_active_statuses(&state, providers)
+}
+
+#[tauri::command]
+pub(crate) fn list_sync_provider_accounts(
+    state: tauri::State<SecureStoreState>,
+    provider: String,
+) -> Result<SyncProviderAccountList, SoloStackError> {
+    list_accounts(&state, &provider)
+}
+
+#[tauri::command]
+pub(crate) fn set_active_sync_provider_account(
+    state: tauri::State<SecureStoreState>,
+    provider: String,
+    account_id: String,
+) -> Result<SyncProviderAccountList, SoloStackError> {
+    set_active_account(&state, &provider, &account_id)
+}
+
+#[tauri::command]
+pub(crate) fn run_sync_provider_secure_store_self_test(
+    state: tauri::State<SecureStoreState>,
+) -> SyncProviderSecureStoreSelfTestResult {
+    state.self_test()
+}
+
+#[tauri::command]
+pub(crate) fn list_secure_store_audit_records(
+    state: tauri::State<SecureStoreState>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<SecureStoreAuditPage, SoloStackError> {
+    state.audit_page(offset, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        secure_store::InMemorySecureStore, sync_provider_credential::SyncProviderCredentialKind,
+    };
+
+    fn in_memory_store() -> SecureStoreState {
+        SecureStoreState::new(Box::new(InMemorySecureStore::default()))
+    }
+
+    fn access_token(secret: &str) -> SyncProviderCredentialInput {
+        SyncProviderCredentialInput::new(
+            SyncProviderCredentialKind::AccessToken,
+            secret.to_string(),
+            None,
+            Vec::new(),
+        )
+    }
+
+    fn oauth_tokens(token_set: serde_json::Value) -> SyncProviderCredentialInput {
+        SyncProviderCredentialInput::new(
+            SyncProviderCredentialKind::OauthTokens,
+            token_set.to_string(),
+            None,
+            Vec::new(),
+        )
+    }
+
+    fn token_set(record: &SyncProviderCredentialRecord) -> serde_json::Value {
+        serde_json::from_str(&record.secret).unwrap()
+    }
+
+    fn status_json(status: &SyncProviderSecureAuthStatus) -> serde_json::Value {
+        serde_json::to_value(status).unwrap()
+    }
+
+    #[test]
+    fn set_get_delete_roundtrip() {
+        let store = in_memory_store();
+        let status =
+            store_credential(&store, " google_appdata ", None, access_token("token-1")).unwrap();
+        assert_eq!(status_json(&status)["account_id"], "default");
+        assert_eq!(status_json(&status)["expiry"], "no_expiry");
+
+        let record = get_credential(&store, "google_appdata", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.secret, "token-1");
+        assert_eq!(record.rotation, 0);
+        let rotated =
+            store_credential(&store, "google_appdata", None, access_token("token-2")).unwrap();
+        assert_eq!(status_json(&rotated)["rotation"], 1);
+
+        delete_credential(&store, "google_appdata", None).unwrap();
+        assert!(get_credential(&store, "google_appdata", None)
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            status_json(&list_active_statuses(&store, None)[0])["expiry"],
+            "missing"
+        );
+    }
+
+    #[test]
+    fn rejects_missing_provider_and_empty_secret() {
+        let store = in_memory_store();
+        assert_eq!(
+            get_credential(&store, "  ", None).err().unwrap().code,
+            SoloStackErrorCode::ProviderRequired
+        );
+        assert_eq!(
+            store_credential(&store, "google_appdata", None, access_token(" "))
+                .err()
+                .unwrap()
+                .code,
+            SoloStackErrorCode::AuthPayloadRequired
+        );
+    }
+
+    #[test]
+    fn webview_never_receives_the_refresh_token_or_client_secret() {
+        let store = in_memory_store();
+        store_credential(
+            &store,
+            "google_appdata",
+            None,
+            oauth_tokens(serde_json::json!({
+                "access_token": "access-1",
+                "refresh_token": "refresh-1",
+                "client_id": "client",
+                "client_secret": "client-secret",
+                "token_refresh_url": "https://oauth2.example.com/token",
+            })),
+        )
+        .unwrap();
+
+        let redacted = get_webview_credential(&store, "google_appdata", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(token_set(&redacted)["access_token"], "access-1");
+        assert_eq!(token_set(&redacted)["client_id"], "client");
+        let serialized = serde_json::to_string(&redacted).unwrap();
+        assert!(!serialized.contains("refresh-1"));
+        assert!(!serialized.contains("client-secret"));
+
+        let stored = get_credential(&store, "google_appdata", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(token_set(&stored)["refresh_token"], "refresh-1");
+
+        store
+            .write("onedrive_approot", r#"{"version":1,"kind":"refresh_token","secret":"bare-refresh","expires_at":null,"scopes":[],"issued_at":null,"rotation":0}"#)
+            .unwrap();
+        let bare = get_webview_credential(&store, "onedrive_approot", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(bare.secret, "");
+    }
+
+    #[test]
+    fn webview_write_back_keeps_the_withheld_fields() {
+        let store = in_memory_store();
+        store_credential(
+            &store,
+            "google_appdata",
+            None,
+            oauth_tokens(serde_json::json!({
+                "access_token": "access-1",
+                "refresh_token": "refresh-1",
+                "client_id": "client",
+                "client_secret": "client-secret",
+                "token_refresh_url": "https://oauth2.example.com/token",
+            })),
+        )
+        .unwrap();
+
+        let redacted = get_webview_credential(&store, "google_appdata", None)
+            .unwrap()
+            .unwrap();
+        let mut written_back = token_set(&redacted);
+        written_back["refresh_token"] = serde_json::Value::Null;
+        store_credential(&store, "google_appdata", None, oauth_tokens(written_back)).unwrap();
+        let stored = get_credential(&store, "google_appdata", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(token_set(&stored)["refresh_token"], "refresh-1");
+        assert_eq!(token_set(&stored)["client_secret"], "client-secret");
+
+        // A token set for another OAuth client does not inherit the old grant.
+        store_credential(
+            &store,
+            "google_appdata",
+            None,
+            oauth_tokens(serde_json::json!({
+                "access_token": "access-2",
+                "client_id": "other-client",
+                "token_refresh_url": "https://oauth2.example.com/token",
+            })),
+        )
+        .unwrap();
+        let stored = get_credential(&store, "google_appdata", None)
+            .unwrap()
+            .unwrap();
+        assert!(token_set(&stored).get("refresh_token").is_none());
+    }
+
+    #[test]
+    fn reads_entries_written_before_credential_records() {
+        let store = in_memory_store();
+        store
+            .write(
+                "onedrive_approot",
+                r#"{"access_token":"legacy","refresh_token":"refresh"}"#,
+            )
+            .unwrap();
+        let record = get_credential(&store, "onedrive_approot", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.kind, SyncProviderCredentialKind::OauthTokens);
+        let accounts = list_accounts(&store, "onedrive_approot").unwrap();
+        assert_eq!(accounts.active_account_id.as_deref(), Some("default"));
+    }
+
+    #[test]
+    fn switches_between_accounts() {
+        let store = in_memory_store();
+        store_credential(
+            &store,
+            "onedrive_approot",
+            Some("work"),
+            access_token("work-token"),
+        )
+        .unwrap();
+        store_credential(
+            &store,
+            "onedrive_approot",
+            Some("personal"),
+            access_token("personal-token"),
+        )
+        .unwrap();
+        let active = |store: &SecureStoreState| {
+            get_credential(store, "onedrive_approot", None)
+                .unwrap()
+                .unwrap()
+                .secret
+        };
+        assert_eq!(active(&store), "work-token");
+
+        let accounts = set_active_account(&store, "onedrive_approot", "personal").unwrap();
+        assert_eq!(accounts.active_account_id.as_deref(), Some("personal"));
+        assert_eq!(accounts.accounts.len(), 2);
+        assert_eq!(active(&store), "personal-token");
+        assert_eq!(
+            set_active_account(&store, "onedrive_approot", "missing")
+                .err()
+                .unwrap()
+                .code,
+            SoloStackErrorCode::ProviderAccountNotFound
+        );
+
+        delete_credential(&store, "onedrive_approot", None).unwrap();
+        assert_eq!(active(&store), "work-token");
+    }
+
+    // Linux without a Secret Service or keyutils session ends up on the encrypted file backend.
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "ios"
+    ))]
+    #[test]
+    fn encrypted_file_backend_serves_the_commands() {
+        let app_data_dir =
+            std::env::temp_dir().join(format!("solostack-secure-auth-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&app_data_dir).unwrap();
+        std::env::set_var("SOLOSTACK_SECURE_STORE_PASSPHRASE", "test-passphrase");
+        let store = SecureStoreState::new(Box::new(
+            crate::encrypted_file_store::EncryptedFileStore::in_app_data_dir(&app_data_dir),
+        ));
+
+        let self_test = store.self_test();
+        assert!(self_test.roundtrip_ok, "{:?}", self_test.detail);
+        assert_eq!(self_test.backend, "encrypted_file");
+        store_credential(
+            &store,
+            "google_appdata",
+            Some("work"),
+            access_token("token"),
+        )
+        .unwrap();
+        assert_eq!(
+            get_credential(&store, "google_appdata", None)
+                .unwrap()
+                .unwrap()
+                .secret,
+            "token"
+        );
+        delete_credential(&store, "google_appdata", Some("work")).unwrap();
+        assert!(list_accounts(&store, "google_appdata")
+            .unwrap()
+            .accounts
+            .is_empty());
+
+        std::fs::remove_dir_all(&app_data_dir).unwrap();
+    }
+
+    #[test]
+    fn audits_every_secure_store_access() {
+        let app_data_dir =
+            std::env::temp_dir().join(format!("solostack-secure-audit-{}", uuid::Uuid::new_v4()));
+        let store = in_memory_store().with_audit_log(
+            crate::secure_store_audit::SecureStoreAuditLog::in_app_data_dir(&app_data_dir),
+        );
+        store_credential(&store, "google_appdata", None, access_token("secret-token")).unwrap();
+        delete_credential(&store, "google_appdata", None).unwrap();
+
+        let page = store.audit_page(None, Some(100)).unwrap();
+        let operations = page
+            .records
+            .iter()
+            .map(|record| serde_json::to_value(record).unwrap())
+            .map(|record| {
+                format!(
+                    "{}:{}:{}",
+                    record["operation"], record["entry"], record["outcome"]
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(operations.contains(&r#""write":"credential":"ok""#.to_string()));
+        assert!(operations.contains(&r#""delete":"credential":"ok""#.to_string()));
+        assert!(operations.contains(&r#""read":"account_index":"not_found""#.to_string()));
+        assert!(page
+            .records
+            .iter()
+            .all(|record| record.backend == "in_memory"));
+        assert!(!serde_json::to_string(&page.records)
+            .unwrap()
+            .contains("secret-token"));
+        std::fs::remove_dir_all(app_data_dir).unwrap();
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    #[test]
+    fn idle_refresh_pass_writes_no_audit_records() {
+        let app_data_dir =
+            std::env::temp_dir().join(format!("solostack-secure-poll-{}", uuid::Uuid::new_v4()));
+        let store = in_memory_store().with_audit_log(
+            crate::secure_store_audit::SecureStoreAuditLog::in_app_data_dir(&app_data_dir),
+        );
+        store_credential(
+            &store,
+            "google_appdata",
+            Some("work"),
+            access_token("token"),
+        )
+        .unwrap();
+        let audited = store.audit_page(None, Some(1000)).unwrap().records.len();
+
+        let config = crate::sync_provider_token_refresh::TokenRefreshConfig::from_env();
+        for provider in sync_provider_credential::MANAGED_SYNC_PROVIDERS {
+            let index = poll_account_index(&store, provider).unwrap();
+            for account_id in &index.account_ids {
+                assert!(
+                    refresh_credential_if_due(&store, provider, account_id, &config)
+                        .unwrap()
+                        .is_none()
+                );
+            }
+        }
+
+        let page = store.audit_page(None, Some(1000)).unwrap();
+        assert_eq!(page.records.len(), audited);
+        std::fs::remove_dir_all(app_data_dir).unwrap();
+    }
+}
@@ -0,0 +1,393 @@
+// Renews OAuth access tokens held in the secure store before they expire, so a long session does
+// not hit UNAUTHORIZED in the middle of a sync cycle. This module only decides when to refresh and
+// builds the renewed credential; reading and writing the entry goes through
+// `sync_provider_secure_auth::refresh_credential_if_due`, driven by the poll thread in lib.rs.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    sync_provider_credential::{
+        self, SyncProviderCredentialInput, SyncProviderCredentialKind, SyncProviderCredentialRecord,
+    },
+    sync_provider_oauth,
+};
+
+pub(crate) const SYNC_AUTH_REFRESHED_EVENT: &str = "sync-auth:refreshed";
+pub(crate) const SYNC_AUTH_FAILED_EVENT: &str = "sync-auth:failed";
+// Overrides every stored `token_refresh_url`; meant for pointing a dev build at a local stand-in.
+#[cfg(any(test, debug_assertions))]
+const TOKEN_ENDPOINT_OVERRIDE_ENV: &str = "SOLOSTACK_SYNC_TOKEN_ENDPOINT";
+const REFRESH_POLL_INTERVAL: Duration = Duration::from_secs(60);
+const REFRESH_RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+const REFRESH_RETRY_MAX_DELAY: Duration = Duration::from_secs(30 * 60);
+
+pub(crate) struct TokenRefreshConfig {
+    pub(crate) token_endpoint_override: Option<String>,
+    pub(crate) poll_interval: Duration,
+    pub(crate) refresh_ahead: chrono::Duration,
+}
+
+#[cfg(any(test, debug_assertions))]
+fn token_endpoint_override_from_env() -> Option<String> {
+    std::env::var(TOKEN_ENDPOINT_OVERRIDE_ENV)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// The refresh POST carries the refresh token and client secret, so a release build never lets the
+// environment redirect it.
+#[cfg(not(any(test, debug_assertions)))]
+fn token_endpoint_override_from_env() -> Option<String> {
+    None
+}
+
+impl TokenRefreshConfig {
+    pub(crate) fn from_env() -> Self {
+        Self {
+            token_endpoint_override: token_endpoint_override_from_env(),
+            poll_interval: REFRESH_POLL_INTERVAL,
+            // Refreshing as soon as a credential reports `expiring_soon` keeps the status and the
+            // refresh schedule in agreement.
+            refresh_ahead: chrono::Duration::seconds(
+                sync_provider_credential::EXPIRING_SOON_WINDOW_SECONDS,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub(crate) struct SyncAuthRefreshFailedPayload {
+    pub(crate) provider: String,
+    // `None` when the provider's account index itself could not be read.
+    pub(crate) account_id: Option<String>,
+    pub(crate) error: SoloStackError,
+    pub(crate) retry_in_ms: u64,
+}
+
+fn parse_token_set(
+    record: &SyncProviderCredentialRecord,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    if record.kind != SyncProviderCredentialKind::OauthTokens {
+        return None;
+    }
+    match serde_json::from_str::<serde_json::Value>(&record.secret).ok()? {
+        serde_json::Value::Object(token_set) => Some(token_set),
+        _ => None,
+    }
+}
+
+fn token_set_string<'a>(
+    token_set: &'a serde_json::Map<String, serde_json::Value>,
+    field: &str,
+) -> Option<&'a str> {
+    token_set
+        .get(field)
+        .and_then(serde_json::Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+// Credentials without an expiry or without a refresh token are left alone; the sync transport
+// reports UNAUTHORIZED for those and the user signs in again.
+pub(crate) fn needs_refresh(
+    record: &SyncProviderCredentialRecord,
+    now: DateTime<Utc>,
+    config: &TokenRefreshConfig,
+) -> bool {
+    let Some(expires_at) = record
+        .expires_at
+        .as_deref()
+        .and_then(sync_provider_credential::parse_timestamp)
+    else {
+        return false;
+    };
+    expires_at - now <= config.refresh_ahead
+        && parse_token_set(record)
+            .is_some_and(|token_set| token_set_string(&token_set, "refresh_token").is_some())
+}
+
+pub(crate) fn refresh_credential(
+    record: &SyncProviderCredentialRecord,
+    config: &TokenRefreshConfig,
+    now: DateTime<Utc>,
+) -> Result<SyncProviderCredentialInput, SoloStackError> {
+    let invalid = |detail: &str| {
+        SoloStackError::with_detail(SoloStackErrorCode::CredentialInvalid, detail.to_string())
+    };
+    let mut token_set =
+        parse_token_set(record).ok_or_else(|| invalid("credential is not an OAuth token set"))?;
+    let refresh_token = token_set_string(&token_set, "refresh_token")
+        .ok_or_else(|| invalid("refresh_token is required for refresh flow"))?
+        .to_string();
+    let token_endpoint = config
+        .token_endpoint_override
+        .as_deref()
+        .or_else(|| token_set_string(&token_set, "token_refresh_url"))
+        .ok_or_else(|| invalid("token_refresh_url is required for refresh flow"))
+        .and_then(|value| sync_provider_oauth::parse_endpoint(value, "token_refresh_url"))?;
+
+    // Same form as the frontend's `refreshSyncProviderAccessToken`.
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+    ];
+    for field in ["scope", "client_id", "client_secret"] {
+        if let Some(value) = token_set_string(&token_set, field) {
+            form.push((field, value));
+        }
+    }
+    let token_response = sync_provider_oauth::request_tokens(&token_endpoint, &form)?;
+
+    let expires_at = token_response.expires_at(now);
+    token_set.insert(
+        "access_token".to_string(),
+        token_response.access_token.clone().into(),
+    );
+    token_set.insert("expires_at".to_string(), expires_at.clone().into());
+    if let Some(token_type) = token_response.token_type.clone() {
+        token_set.insert("token_type".to_string(), token_type.into());
+    }
+    // Providers that rotate refresh tokens return a new one; the others keep the current one.
+    if let Some(next_refresh_token) = token_response.refresh_token.clone() {
+        token_set.insert("refresh_token".to_string(), next_refresh_token.into());
+    }
+    let scopes = match token_response.scope.clone() {
+        Some(scope) => {
+            let scopes = scope.split_whitespace().map(str::to_string).collect();
+            token_set.insert("scope".to_string(), scope.into());
+            scopes
+        }
+        None => record.scopes.clone(),
+    };
+
+    let secret = serde_json::to_string(&token_set).map_err(|error| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::Internal,
+            format!("serialize token set failed: {error}"),
+        )
+    })?;
+    Ok(SyncProviderCredentialInput::new(
+        SyncProviderCredentialKind::OauthTokens,
+        secret,
+        expires_at,
+        scopes,
+    ))
+}
+
+// Per-provider exponential backoff, so a revoked refresh token or an offline machine does not
+// hit the token endpoint (and emit `sync-auth:failed`) on every poll.
+#[derive(Default)]
+pub(crate) struct RefreshBackoff {
+    failures: HashMap<String, (u32, Instant)>,
+}
+
+impl RefreshBackoff {
+    pub(crate) fn is_due(&self, provider: &str, now: Instant) -> bool {
+        self.failures
+            .get(provider)
+            .is_none_or(|(_, retry_at)| now >= *retry_at)
+    }
+
+    pub(crate) fn record_failure(&mut self, provider: &str, now: Instant) -> Duration {
+        let failure_count = self
+            .failures
+            .get(provider)
+            .map_or(1, |(count, _)| count.saturating_add(1));
+        let delay = REFRESH_RETRY_BASE_DELAY
+            .saturating_mul(2u32.saturating_pow(failure_count - 1))
+            .min(REFRESH_RETRY_MAX_DELAY);
+        self.failures
+            .insert(provider.to_string(), (failure_count, now + delay));
+        delay
+    }
+
+    pub(crate) fn record_success(&mut self, provider: &str) {
+        self.failures.remove(provider);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync_provider_credential::build_credential_record;
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    // Local stand-in for a provider token endpoint: answers one request with `response_body` and
+    // hands the submitted form back to the test.
+    fn spawn_token_endpoint(
+        response_body: &'static str,
+    ) -> (String, mpsc::Receiver<Vec<(String, String)>>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 4096];
+            let body = loop {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let content_length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|value| value.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= content_length {
+                        break body.to_string();
+                    }
+                }
+            };
+            sender
+                .send(
+                    url::form_urlencoded::parse(body.as_bytes())
+                        .into_owned()
+                        .collect(),
+                )
+                .unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response_body}",
+                response_body.len()
+            )
+            .unwrap();
+        });
+        (format!("http://127.0.0.1:{port}/token"), receiver)
+    }
+
+    fn oauth_record(
+        expires_at: DateTime<Utc>,
+        token_refresh_url: &str,
+    ) -> SyncProviderCredentialRecord {
+        let secret = serde_json::json!({
+            "access_token": "access-1",
+            "token_type": "Bearer",
+            "refresh_token": "refresh-1",
+            "token_refresh_url": token_refresh_url,
+            "expires_at": expires_at.to_rfc3339(),
+            "scope": "files",
+            "client_id": "client-1",
+            "client_secret": null,
+        });
+        build_credential_record(
+            SyncProviderCredentialInput::new(
+                SyncProviderCredentialKind::OauthTokens,
+                secret.to_string(),
+                Some(expires_at.to_rfc3339()),
+                vec!["files".to_string()],
+            ),
+            None,
+            Utc::now(),
+        )
+        .unwrap()
+    }
+
+    fn config(token_endpoint_override: Option<String>) -> TokenRefreshConfig {
+        TokenRefreshConfig {
+            token_endpoint_override,
+            poll_interval: REFRESH_POLL_INTERVAL,
+            refresh_ahead: chrono::Duration::minutes(10),
+        }
+    }
+
+    #[test]
+    fn refreshes_only_inside_the_refresh_window() {
+        let now = Utc::now();
+        let config = config(None);
+        let endpoint = "https://example.invalid/token";
+        assert!(!needs_refresh(
+            &oauth_record(now + chrono::Duration::hours(1), endpoint),
+            now,
+            &config
+        ));
+        assert!(needs_refresh(
+            &oauth_record(now + chrono::Duration::minutes(5), endpoint),
+            now,
+            &config
+        ));
+        assert!(needs_refresh(
+            &oauth_record(now - chrono::Duration::minutes(5), endpoint),
+            now,
+            &config
+        ));
+    }
+
+    #[test]
+    fn refreshes_against_configured_endpoint_and_keeps_refresh_token() {
+        let (token_endpoint, token_requests) = spawn_token_endpoint(
+            r#"{"access_token":"access-2","expires_in":3600,"token_type":"Bearer"}"#,
+        );
+        let now = Utc::now();
+        let record = oauth_record(now, "https://example.invalid/token");
+
+        let credential = refresh_credential(&record, &config(Some(token_endpoint)), now).unwrap();
+
+        let form = token_requests.recv().unwrap();
+        assert!(form.contains(&("grant_type".to_string(), "refresh_token".to_string())));
+        assert!(form.contains(&("refresh_token".to_string(), "refresh-1".to_string())));
+        assert!(form.contains(&("client_id".to_string(), "client-1".to_string())));
+        let refreshed = build_credential_record(credential, Some(&record), now).unwrap();
+        assert_eq!(refreshed.rotation, record.rotation + 1);
+        assert_eq!(refreshed.scopes, vec!["files".to_string()]);
+        let token_set: serde_json::Value = serde_json::from_str(&refreshed.secret).unwrap();
+        assert_eq!(token_set["access_token"], "access-2");
+        assert_eq!(token_set["refresh_token"], "refresh-1");
+        assert_eq!(
+            token_set["expires_at"],
+            refreshed.expires_at.unwrap().as_str()
+        );
+    }
+
+    #[test]
+    fn refuses_to_send_the_refresh_token_over_plain_http() {
+        let now = Utc::now();
+        let stored = oauth_record(now, "http://auth.example.com/token");
+        let error = refresh_credential(&stored, &config(None), now)
+            .err()
+            .unwrap();
+        assert_eq!(error.code, SoloStackErrorCode::CredentialInvalid);
+        assert_eq!(
+            error.detail.as_deref(),
+            Some("token_refresh_url must use https")
+        );
+
+        let record = oauth_record(now, "https://example.invalid/token");
+        let overridden = config(Some("http://auth.example.com/token".to_string()));
+        let error = refresh_credential(&record, &overridden, now).err().unwrap();
+        assert_eq!(error.code, SoloStackErrorCode::CredentialInvalid);
+    }
+
+    #[test]
+    fn backs_off_after_failures_until_success() {
+        let mut backoff = RefreshBackoff::default();
+        let now = Instant::now();
+        assert_eq!(
+            backoff.record_failure("google_appdata", now),
+            REFRESH_RETRY_BASE_DELAY
+        );
+        assert_eq!(
+            backoff.record_failure("google_appdata", now),
+            REFRESH_RETRY_BASE_DELAY * 2
+        );
+        assert!(!backoff.is_due("google_appdata", now));
+        assert!(backoff.is_due("onedrive_approot", now));
+        backoff.record_success("google_appdata");
+        assert!(backoff.is_due("google_appdata", now));
+    }
+}
@@ -0,0 +1,721 @@
+// Background sync scheduler. A thread runs `sync_engine` cycles on the interval from the sync
+// runtime settings (`local.sync.auto_interval_seconds` while a window is showing,
+// `local.sync.background_interval_seconds` otherwise), so sync keeps going while the webview is
+// hidden or throttled. Failed cycles back off exponentially with jitter, a RATE_LIMITED
+// `retry_after_ms` is never undercut, and nothing is attempted while the network is down or the
+// system is in battery saver. Each change of state is emitted as `sync:status`.
+// The scheduler is off until the webview turns it on with `configure_native_sync_scheduler`; while
+// it is off the webview keeps its own auto-sync loop.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::{
+    sync::{Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    db::DatabaseState,
+    error::{SoloStackError, SoloStackErrorCode},
+    repository::map_sql_error,
+    secure_store::SecureStoreState,
+    sync_engine::{self, RunSyncCycleOptions, RunSyncCycleSummary},
+    sync_transport::SyncFailure,
+};
+
+pub(crate) const SYNC_STATUS_EVENT: &str = "sync:status";
+const SYNC_SETTINGS_NATIVE_SCHEDULER_KEY: &str = "local.sync.native_scheduler";
+const SYNC_SETTINGS_AUTO_INTERVAL_SECONDS_KEY: &str = "local.sync.auto_interval_seconds";
+const SYNC_SETTINGS_BACKGROUND_INTERVAL_SECONDS_KEY: &str =
+    "local.sync.background_interval_seconds";
+const SYNC_SETTINGS_PUSH_LIMIT_KEY: &str = "local.sync.push_limit";
+const SYNC_SETTINGS_PULL_LIMIT_KEY: &str = "local.sync.pull_limit";
+const SYNC_SETTINGS_MAX_PULL_PAGES_KEY: &str = "local.sync.max_pull_pages";
+// Same bounds and defaults as `getSyncRuntimeSettings` in `src/lib/database.ts`.
+const AUTO_INTERVAL_SECONDS: (i64, i64, i64) = (15, 3600, 60);
+const BACKGROUND_INTERVAL_SECONDS: (i64, i64, i64) = (30, 7200, 300);
+const PUSH_LIMIT: (i64, i64, i64) = (20, 500, 200);
+const PULL_LIMIT: (i64, i64, i64) = (20, 500, 200);
+const MAX_PULL_PAGES: (i64, i64, i64) = (1, 20, 5);
+// Same curve as `calculateSyncBackoffMs` in `use-sync.ts`.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(5);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5 * 60);
+const RETRY_MAX_EXPONENT: u32 = 8;
+// How often a paused or disabled scheduler looks again; network and power changes are not pushed.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum SyncStatus {
+    Synced,
+    Syncing,
+    Offline,
+    Conflict,
+    LocalOnly,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SyncPauseReason {
+    NetworkOffline,
+    BatterySaver,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) struct SyncStatusPayload {
+    pub(crate) status: SyncStatus,
+    pub(crate) paused_reason: Option<SyncPauseReason>,
+    pub(crate) error: Option<SoloStackError>,
+    pub(crate) summary: Option<RunSyncCycleSummary>,
+    pub(crate) next_attempt_in_ms: Option<u64>,
+}
+
+impl SyncStatusPayload {
+    fn new(status: SyncStatus) -> Self {
+        Self {
+            status,
+            paused_reason: None,
+            error: None,
+            summary: None,
+            next_attempt_in_ms: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SyncRuntimeSettings {
+    pub(crate) enabled: bool,
+    pub(crate) auto_interval: Duration,
+    pub(crate) background_interval: Duration,
+    pub(crate) push_limit: i64,
+    pub(crate) pull_limit: i64,
+    pub(crate) max_pull_pages: u32,
+}
+
+impl SyncRuntimeSettings {
+    fn cycle_options(&self) -> RunSyncCycleOptions {
+        RunSyncCycleOptions {
+            push_limit: Some(self.push_limit),
+            pull_limit: Some(self.pull_limit),
+            skip_pull: false,
+            max_pull_pages: Some(self.max_pull_pages),
+        }
+    }
+}
+
+// `asIntegerOrDefault` followed by the range clamp.
+fn runtime_number(value: Option<&str>, (min, max, fallback): (i64, i64, i64)) -> i64 {
+    value
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|value| value.is_finite())
+        .map_or(fallback, |value| value.trunc() as i64)
+        .clamp(min, max)
+}
+
+pub(crate) fn read_runtime_settings(
+    connection: &Connection,
+) -> Result<SyncRuntimeSettings, SoloStackError> {
+    let read = |key: &str| {
+        connection
+            .query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+                row.get::<_, Option<String>>(0)
+            })
+            .optional()
+            .map(Option::flatten)
+            .map_err(map_sql_error("read sync runtime settings"))
+    };
+    let auto_interval_seconds = runtime_number(
+        read(SYNC_SETTINGS_AUTO_INTERVAL_SECONDS_KEY)?.as_deref(),
+        AUTO_INTERVAL_SECONDS,
+    );
+    let background_interval_seconds = runtime_number(
+        read(SYNC_SETTINGS_BACKGROUND_INTERVAL_SECONDS_KEY)?.as_deref(),
+        BACKGROUND_INTERVAL_SECONDS,
+    )
+    .max(auto_interval_seconds);
+    Ok(SyncRuntimeSettings {
+        enabled: read(SYNC_SETTINGS_NATIVE_SCHEDULER_KEY)?.as_deref() == Some("1"),
+        auto_interval: Duration::from_secs(auto_interval_seconds as u64),
+        background_interval: Duration::from_secs(background_interval_seconds as u64),
+        push_limit: runtime_number(read(SYNC_SETTINGS_PUSH_LIMIT_KEY)?.as_deref(), PUSH_LIMIT),
+        pull_limit: runtime_number(read(SYNC_SETTINGS_PULL_LIMIT_KEY)?.as_deref(), PULL_LIMIT),
+        max_pull_pages: runtime_number(
+            read(SYNC_SETTINGS_MAX_PULL_PAGES_KEY)?.as_deref(),
+            MAX_PULL_PAGES,
+        ) as u32,
+    })
+}
+
+// Follows `RefreshBackoff` in `sync_provider_token_refresh`, for the single sync loop. The delay
+// is drawn between half and all of the exponential step so devices that failed together do not
+// retry together; a server-provided `retry_after_ms` is a floor.
+#[derive(Default)]
+pub(crate) struct SyncBackoff {
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl SyncBackoff {
+    pub(crate) fn is_due(&self, now: Instant) -> bool {
+        self.retry_at.is_none_or(|retry_at| now >= retry_at)
+    }
+
+    // `jitter` is a sample from [0, 1).
+    pub(crate) fn record_failure(
+        &mut self,
+        now: Instant,
+        retry_after: Option<Duration>,
+        jitter: f64,
+    ) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        let step = RETRY_BASE_DELAY
+            .saturating_mul(2u32.saturating_pow((self.failures - 1).min(RETRY_MAX_EXPONENT)))
+            .min(RETRY_MAX_DELAY);
+        let delay = (step / 2 + (step / 2).mul_f64(jitter.clamp(0.0, 1.0)))
+            .max(retry_after.unwrap_or_default());
+        self.retry_at = Some(now + delay);
+        delay
+    }
+
+    pub(crate) fn record_success(&mut self) {
+        self.failures = 0;
+        self.retry_at = None;
+    }
+}
+
+fn jitter_sample() -> f64 {
+    f64::from(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .subsec_nanos(),
+    ) / 1_000_000_000.0
+}
+
+// Same rule as `useSync`: rejected pushes, failed applies and pull conflicts need the user.
+pub(crate) fn status_for_summary(summary: &RunSyncCycleSummary) -> SyncStatus {
+    let has_conflict = summary.failed_outbox_changes > 0
+        || summary
+            .pull
+            .as_ref()
+            .is_some_and(|pull| pull.failed > 0 || pull.conflicts > 0);
+    if has_conflict {
+        SyncStatus::Conflict
+    } else {
+        SyncStatus::Synced
+    }
+}
+
+pub(crate) fn status_for_failure(failure: &SyncFailure) -> SyncStatus {
+    match failure.error.code {
+        SoloStackErrorCode::SyncRequestFailed => SyncStatus::Offline,
+        SoloStackErrorCode::SyncTransportUnavailable => SyncStatus::LocalOnly,
+        _ => SyncStatus::Conflict,
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct SyncEnvironment {
+    pub(crate) online: Option<bool>,
+    pub(crate) battery_saver: Option<bool>,
+}
+
+impl SyncEnvironment {
+    // Either source reporting a problem pauses sync; unknown counts as fine.
+    fn pause_reason(self, reported: SyncEnvironment) -> Option<SyncPauseReason> {
+        if self.online == Some(false) || reported.online == Some(false) {
+            Some(SyncPauseReason::NetworkOffline)
+        } else if self.battery_saver == Some(true) || reported.battery_saver == Some(true) {
+            Some(SyncPauseReason::BatterySaver)
+        } else {
+            None
+        }
+    }
+}
+
+// Linux exposes link state and the ACPI platform profile in sysfs. Elsewhere the webview (or the
+// mobile host) reports both through `report_sync_environment`.
+#[cfg(target_os = "linux")]
+fn probe_environment() -> SyncEnvironment {
+    let online = std::fs::read_dir("/sys/class/net").ok().map(|interfaces| {
+        interfaces.flatten().any(|interface| {
+            interface.file_name() != "lo"
+                && std::fs::read_to_string(interface.path().join("operstate"))
+                    .is_ok_and(|state| matches!(state.trim(), "up" | "unknown"))
+        })
+    });
+    let battery_saver = std::fs::read_to_string("/sys/firmware/acpi/platform_profile")
+        .ok()
+        .map(|profile| profile.trim() == "low-power");
+    SyncEnvironment {
+        online,
+        battery_saver,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn probe_environment() -> SyncEnvironment {
+    SyncEnvironment::default()
+}
+
+fn any_window_showing(app: &AppHandle) -> bool {
+    app.webview_windows().values().any(|window| {
+        window.is_visible().unwrap_or(true) && !window.is_minimized().unwrap_or(false)
+    })
+}
+
+struct SchedulerInner {
+    reported: SyncEnvironment,
+    backoff: SyncBackoff,
+    next_run_at: Option<Instant>,
+    last_payload: Option<SyncStatusPayload>,
+    wake_requested: bool,
+}
+
+pub(crate) struct SyncSchedulerState {
+    inner: Mutex<SchedulerInner>,
+    wake: Condvar,
+    // Scheduled and on-demand cycles never overlap.
+    cycle: Mutex<()>,
+}
+
+impl Default for SyncSchedulerState {
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(SchedulerInner {
+                reported: SyncEnvironment::default(),
+                backoff: SyncBackoff::default(),
+                next_run_at: None,
+                last_payload: None,
+                wake_requested: false,
+            }),
+            wake: Condvar::new(),
+            cycle: Mutex::new(()),
+        }
+    }
+}
+
+impl SyncSchedulerState {
+    fn lock_inner(&self) -> Result<MutexGuard<'_, SchedulerInner>, SoloStackError> {
+        self.inner.lock().map_err(|_| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::Internal,
+                "sync scheduler lock is poisoned",
+            )
+        })
+    }
+
+    fn request_wake(&self, run_now: bool) {
+        if let Ok(mut inner) = self.inner.lock() {
+            if run_now {
+                inner.next_run_at = None;
+            }
+            inner.wake_requested = true;
+            self.wake.notify_all();
+        }
+    }
+
+    fn wait_for_wake(&self, timeout: Duration) {
+        let Ok(inner) = self.inner.lock() else {
+            std::thread::sleep(timeout);
+            return;
+        };
+        if let Ok((mut inner, _)) = self
+            .wake
+            .wait_timeout_while(inner, timeout, |inner| !inner.wake_requested)
+        {
+            inner.wake_requested = false;
+        }
+    }
+
+    fn last_payload(&self) -> Option<SyncStatusPayload> {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|inner| inner.last_payload.clone())
+    }
+
+    // Emits only when something the UI shows changed, so idle passes stay quiet.
+    fn publish(&self, app: &AppHandle, payload: SyncStatusPayload) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+        let unchanged = inner.last_payload.as_ref().is_some_and(|last| {
+            last.status == payload.status
+                && last.paused_reason == payload.paused_reason
+                && last.error == payload.error
+                && payload.summary.is_none()
+        });
+        inner.last_payload = Some(payload.clone());
+        drop(inner);
+        if !unchanged {
+            if let Err(error) = app.emit(SYNC_STATUS_EVENT, payload) {
+                eprintln!("Sync scheduler warning: {error}");
+            }
+        }
+    }
+
+    // Cycles started from the webview; a failure there does not push back the schedule.
+    pub(crate) fn run_cycle(
+        &self,
+        app: &AppHandle,
+        options: RunSyncCycleOptions,
+    ) -> Result<RunSyncCycleSummary, SyncFailure> {
+        self.run_cycle_with_schedule(app, options, None).0
+    }
+
+    // With `interval`, also decides when the scheduler runs next and returns that wait.
+    fn run_cycle_with_schedule(
+        &self,
+        app: &AppHandle,
+        options: RunSyncCycleOptions,
+        interval: Option<Duration>,
+    ) -> (Result<RunSyncCycleSummary, SyncFailure>, Duration) {
+        let fallback_wait = interval.unwrap_or(IDLE_POLL_INTERVAL);
+        let _cycle = match self.cycle.lock() {
+            Ok(cycle) => cycle,
+            Err(_) => {
+                let error = SoloStackError::with_detail(
+                    SoloStackErrorCode::Internal,
+                    "sync cycle lock is poisoned",
+                );
+                return (Err(error.into()), fallback_wait);
+            }
+        };
+        self.publish(app, SyncStatusPayload::new(SyncStatus::Syncing));
+        let result = sync_engine::run_sync_cycle_blocking(
+            &app.state::<DatabaseState>(),
+            &app.state::<SecureStoreState>(),
+            options,
+        );
+
+        let Ok(mut inner) = self.lock_inner() else {
+            return (result, fallback_wait);
+        };
+        let now = Instant::now();
+        let (payload, wait) = match &result {
+            Ok(summary) => {
+                inner.backoff.record_success();
+                let payload = SyncStatusPayload {
+                    summary: Some(summary.clone()),
+                    ..SyncStatusPayload::new(status_for_summary(summary))
+                };
+                (payload, interval)
+            }
+            Err(failure) => {
+                let retry_in = interval.map(|interval| {
+                    if failure.error.code == SoloStackErrorCode::SyncTransportUnavailable {
+                        interval
+                    } else {
+                        inner.backoff.record_failure(
+                            now,
+                            failure.retry_after_ms.map(Duration::from_millis),
+                            jitter_sample(),
+                        )
+                    }
+                });
+                let payload = SyncStatusPayload {
+                    error: Some(failure.error.clone()),
+                    next_attempt_in_ms: retry_in.map(|retry_in| retry_in.as_millis() as u64),
+                    ..SyncStatusPayload::new(status_for_failure(failure))
+                };
+                (payload, retry_in)
+            }
+        };
+        if let Some(wait) = wait {
+            inner.next_run_at = Some(now + wait);
+        }
+        drop(inner);
+
+        if result
+            .as_ref()
+            .is_ok_and(|summary| summary.pull.as_ref().is_some_and(|pull| pull.applied > 0))
+        {
+            refresh_local_views(app);
+        }
+        self.publish(app, payload);
+        (result, wait.unwrap_or(fallback_wait))
+    }
+
+    // One scheduling pass: sync if due and return how long to wait before looking again.
+    fn run_pass(&self, app: &AppHandle) -> Duration {
+        // Before startup migration finishes there is nothing to sync.
+        let Ok(connection) = app.state::<DatabaseState>().open() else {
+            return IDLE_POLL_INTERVAL;
+        };
+        let settings = match read_runtime_settings(&connection) {
+            Ok(settings) => settings,
+            Err(error) => {
+                eprintln!("Sync scheduler warning: {error}");
+                return IDLE_POLL_INTERVAL;
+            }
+        };
+        drop(connection);
+        if !settings.enabled {
+            return IDLE_POLL_INTERVAL;
+        }
+
+        let Ok(inner) = self.lock_inner() else {
+            return IDLE_POLL_INTERVAL;
+        };
+        let reported = inner.reported;
+        let now = Instant::now();
+        let backoff_until = inner
+            .backoff
+            .retry_at
+            .filter(|_| !inner.backoff.is_due(now));
+        let wait = [inner.next_run_at, backoff_until]
+            .into_iter()
+            .flatten()
+            .max()
+            .map(|run_at| run_at.saturating_duration_since(now))
+            .filter(|wait| !wait.is_zero());
+        drop(inner);
+
+        if let Some(paused_reason) = probe_environment().pause_reason(reported) {
+            let status = match paused_reason {
+                SyncPauseReason::NetworkOffline => SyncStatus::Offline,
+                // Battery saver holds the last result; before any cycle that reads as offline.
+                SyncPauseReason::BatterySaver => self
+                    .last_payload()
+                    .map_or(SyncStatus::Offline, |payload| payload.status),
+            };
+            self.publish(
+                app,
+                SyncStatusPayload {
+                    paused_reason: Some(paused_reason),
+                    ..SyncStatusPayload::new(status)
+                },
+            );
+            return IDLE_POLL_INTERVAL;
+        }
+        if let Some(wait) = wait {
+            // Still look at the network and power state while waiting out a long interval.
+            return wait.min(IDLE_POLL_INTERVAL);
+        }
+
+        let interval = if any_window_showing(app) {
+            settings.auto_interval
+        } else {
+            settings.background_interval
+        };
+        self.run_cycle_with_schedule(app, settings.cycle_options(), Some(interval))
+            .1
+            .min(IDLE_POLL_INTERVAL)
+    }
+}
+
+// Pulled changes land behind the webview's queries and the desktop tray and reminders.
+fn refresh_local_views(app: &AppHandle) {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        crate::tray::refresh(app);
+        if let Some(scheduler) =
+            app.try_state::<crate::reminder_scheduler::ReminderSchedulerState>()
+        {
+            scheduler.request_resync();
+        }
+    }
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    let _ = app;
+}
+
+pub(crate) fn spawn(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let state = app.state::<SyncSchedulerState>();
+        let wait = state.run_pass(&app);
+        state.wait_for_wake(wait);
+    });
+}
+
+// The webview owns the sync settings screen; turning the scheduler on hands periodic sync to Rust.
+#[tauri::command]
+pub(crate) fn configure_native_sync_scheduler(
+    state: tauri::State<SyncSchedulerState>,
+    database: tauri::State<DatabaseState>,
+    enabled: bool,
+) -> Result<(), SoloStackError> {
+    database
+        .open()?
+        .execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![
+                SYNC_SETTINGS_NATIVE_SCHEDULER_KEY,
+                if enabled { "1" } else { "0" }
+            ],
+        )
+        .map_err(map_sql_error("write sync scheduler setting"))?;
+    state.request_wake(enabled);
+    Ok(())
+}
+
+// `navigator.onLine` from the webview, and battery saver where the host platform knows it. A
+// network that comes back triggers a cycle right away.
+#[tauri::command]
+pub(crate) fn report_sync_environment(
+    state: tauri::State<SyncSchedulerState>,
+    online: Option<bool>,
+    battery_saver: Option<bool>,
+) -> Result<(), SoloStackError> {
+    let reconnected = {
+        let mut inner = state.lock_inner()?;
+        let reconnected = inner.reported.online == Some(false) && online == Some(true);
+        inner.reported = SyncEnvironment {
+            online,
+            battery_saver,
+        };
+        reconnected
+    };
+    state.request_wake(reconnected);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_native_sync_status(
+    state: tauri::State<SyncSchedulerState>,
+) -> Option<SyncStatusPayload> {
+    state.last_payload()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::TestDatabase,
+        sync_engine::{PullConflictEnvelope, PullSummary},
+        sync_transport::api_failure,
+    };
+
+    #[test]
+    fn runtime_settings_follow_the_webview_bounds() {
+        let state = TestDatabase::migrated();
+        let connection = state.open().unwrap();
+        let defaults = read_runtime_settings(&connection).unwrap();
+        assert!(!defaults.enabled);
+        assert_eq!(defaults.auto_interval, Duration::from_secs(60));
+        assert_eq!(defaults.background_interval, Duration::from_secs(300));
+
+        for (key, value) in [
+            (SYNC_SETTINGS_NATIVE_SCHEDULER_KEY, "1"),
+            (SYNC_SETTINGS_AUTO_INTERVAL_SECONDS_KEY, "900.7"),
+            (SYNC_SETTINGS_BACKGROUND_INTERVAL_SECONDS_KEY, "120"),
+            (SYNC_SETTINGS_PUSH_LIMIT_KEY, "5"),
+            (SYNC_SETTINGS_PULL_LIMIT_KEY, "not a number"),
+            (SYNC_SETTINGS_MAX_PULL_PAGES_KEY, "50"),
+        ] {
+            connection
+                .execute(
+                    "INSERT INTO settings (key, value) VALUES (?1, ?2)",
+                    params![key, value],
+                )
+                .unwrap();
+        }
+        assert_eq!(
+            read_runtime_settings(&connection).unwrap(),
+            SyncRuntimeSettings {
+                enabled: true,
+                auto_interval: Duration::from_secs(900),
+                // Never shorter than the foreground interval.
+                background_interval: Duration::from_secs(900),
+                push_limit: 20,
+                pull_limit: 200,
+                max_pull_pages: 20,
+            }
+        );
+    }
+
+    #[test]
+    fn backoff_grows_with_jitter_and_respects_retry_after() {
+        let mut backoff = SyncBackoff::default();
+        let now = Instant::now();
+        assert_eq!(
+            backoff.record_failure(now, None, 0.0),
+            Duration::from_millis(2500)
+        );
+        assert_eq!(
+            backoff.record_failure(now, None, 0.5),
+            Duration::from_millis(7_500)
+        );
+        assert!(!backoff.is_due(now));
+        for _ in 0..10 {
+            backoff.record_failure(now, None, 1.0);
+        }
+        assert_eq!(backoff.record_failure(now, None, 1.0), RETRY_MAX_DELAY);
+
+        let rate_limited = api_failure(
+            429,
+            r#"{"code":"RATE_LIMITED","message":"slow down","retry_after_ms":600000}"#,
+        );
+        assert_eq!(
+            backoff.record_failure(
+                now,
+                rate_limited.retry_after_ms.map(Duration::from_millis),
+                0.5
+            ),
+            Duration::from_secs(600)
+        );
+        assert_eq!(status_for_failure(&rate_limited), SyncStatus::Conflict);
+
+        backoff.record_success();
+        assert!(backoff.is_due(now));
+        assert_eq!(
+            backoff.record_failure(now, None, 0.0),
+            Duration::from_millis(2500)
+        );
+    }
+
+    #[test]
+    fn status_and_pause_reason_match_the_webview() {
+        let mut summary = RunSyncCycleSummary {
+            device_id: "device-1".to_string(),
+            checkpoint_before: None,
+            checkpoint_after: Some("cursor-1".to_string()),
+            prepared_push_changes: 1,
+            skipped_push_changes: 0,
+            removed_outbox_changes: 1,
+            failed_outbox_changes: 0,
+            pending_outbox_changes: 0,
+            pull: Some(PullSummary::default()),
+        };
+        assert_eq!(status_for_summary(&summary), SyncStatus::Synced);
+        summary.pull = Some(PullSummary {
+            conflicts: 1,
+            conflict_envelopes: vec![PullConflictEnvelope {
+                idempotency_key: "remote:1".to_string(),
+                entity_type: crate::repository::SyncEntityType::Task,
+                entity_id: "task-1".to_string(),
+                reason: Some("MISSING_TASK_TITLE".to_string()),
+            }],
+            ..PullSummary::default()
+        });
+        assert_eq!(status_for_summary(&summary), SyncStatus::Conflict);
+        assert_eq!(
+            status_for_failure(&SyncFailure::from(SoloStackError::new(
+                SoloStackErrorCode::SyncRequestFailed
+            ))),
+            SyncStatus::Offline
+        );
+
+        let probed = SyncEnvironment {
+            online: Some(true),
+            battery_saver: None,
+        };
+        assert_eq!(probed.pause_reason(SyncEnvironment::default()), None);
+        assert_eq!(
+            probed.pause_reason(SyncEnvironment {
+                online: Some(false),
+                battery_saver: Some(true),
+            }),
+            Some(SyncPauseReason::NetworkOffline)
+        );
+        assert_eq!(
+            probed.pause_reason(SyncEnvironment {
+                online: None,
+                battery_saver: Some(true),
+            }),
+            Some(SyncPauseReason::BatterySaver)
+        );
+    }
+}
@@ -0,0 +1,271 @@
+// Transports carry schema_version 1 envelopes between the native sync engine and a provider.
+// They return the raw JSON body; the engine parses it with `sync_contract`, exactly like
+// `runSyncCycle` does with the webview transports. Failures keep the server's `retry_after_ms`
+// so callers that schedule retries can honour it.
+
+use rusqlite::{Connection, OptionalExtension};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::{
+    db::DatabaseState,
+    error::{SoloStackError, SoloStackErrorCode},
+    secure_store::SecureStoreState,
+    sync_contract::{self, SyncPullRequest, SyncPushRequest},
+    sync_local_folder::{self, LocalFolderSyncTransport},
+    sync_webdav::{self, WebDavSyncTransport},
+};
+
+const SYNC_SETTINGS_PROVIDER_KEY: &str = "local.sync.provider";
+const SYNC_SETTINGS_PROVIDER_CONFIG_KEY: &str = "local.sync.provider_config";
+const SYNC_SETTINGS_PUSH_URL_KEY: &str = "local.sync.push_url";
+const SYNC_SETTINGS_PULL_URL_KEY: &str = "local.sync.pull_url";
+const PROVIDER_NEUTRAL: &str = "provider_neutral";
+const SYNC_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug)]
+pub(crate) struct SyncFailure {
+    pub(crate) error: SoloStackError,
+    pub(crate) retry_after_ms: Option<u64>,
+}
+
+impl From<SoloStackError> for SyncFailure {
+    fn from(error: SoloStackError) -> Self {
+        Self {
+            error,
+            retry_after_ms: None,
+        }
+    }
+}
+
+// Commands only return the error; the retry hint stays readable in its detail.
+impl From<SyncFailure> for SoloStackError {
+    fn from(failure: SyncFailure) -> Self {
+        match failure.retry_after_ms {
+            Some(retry_after_ms) => Self {
+                detail: Some(format!(
+                    "{} (retry after {retry_after_ms} ms)",
+                    failure.error.detail.unwrap_or_default()
+                )),
+                ..failure.error
+            },
+            None => failure.error,
+        }
+    }
+}
+
+pub(crate) trait SyncTransport {
+    fn push(&self, request: &SyncPushRequest) -> Result<Value, SyncFailure>;
+    fn pull(&self, request: &SyncPullRequest) -> Result<Value, SyncFailure>;
+}
+
+fn api_error_code(code: &str) -> SoloStackErrorCode {
+    match code {
+        "RATE_LIMITED" => SoloStackErrorCode::SyncRateLimited,
+        "UNAUTHORIZED" | "FORBIDDEN" => SoloStackErrorCode::SyncUnauthorized,
+        "SCHEMA_MISMATCH" => SoloStackErrorCode::SyncSchemaMismatch,
+        "INVALID_CURSOR" => SoloStackErrorCode::SyncInvalidCursor,
+        "VALIDATION_ERROR" => SoloStackErrorCode::SyncRequestRejected,
+        _ => SoloStackErrorCode::SyncServerUnavailable,
+    }
+}
+
+// Maps a non-2xx body to the error the webview would show (`[CODE] message`).
+pub(crate) fn api_failure(status: u16, body: &str) -> SyncFailure {
+    let api_error =
+        sync_contract::parse_api_error(&serde_json::from_str::<Value>(body).unwrap_or(Value::Null));
+    SyncFailure {
+        error: SoloStackError::with_detail(
+            api_error_code(&api_error.code),
+            format!("HTTP {status}: [{}] {}", api_error.code, api_error.message),
+        ),
+        retry_after_ms: api_error.retry_after_ms,
+    }
+}
+
+// `provider_neutral` endpoints: the push and pull URLs set in Settings > Sync.
+pub(crate) struct HttpSyncTransport {
+    agent: ureq::Agent,
+    push_url: String,
+    pull_url: String,
+}
+
+impl HttpSyncTransport {
+    pub(crate) fn new(push_url: String, pull_url: String) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(SYNC_REQUEST_TIMEOUT)
+                .build(),
+            push_url,
+            pull_url,
+        }
+    }
+
+    fn post(&self, url: &str, payload: Value) -> Result<Value, SyncFailure> {
+        let response = match self.agent.post(url).send_json(payload) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                return Err(api_failure(
+                    status,
+                    &response.into_string().unwrap_or_default(),
+                ));
+            }
+            Err(error) => {
+                return Err(SoloStackError::with_detail(
+                    SoloStackErrorCode::SyncRequestFailed,
+                    format!("sync request to {url} failed: {error}"),
+                )
+                .into());
+            }
+        };
+        response.into_json::<Value>().map_err(|error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::SyncResponseInvalid,
+                format!("sync response from {url} is not JSON: {error}"),
+            )
+            .into()
+        })
+    }
+}
+
+impl SyncTransport for HttpSyncTransport {
+    fn push(&self, request: &SyncPushRequest) -> Result<Value, SyncFailure> {
+        self.post(&self.push_url, serde_json::json!(request))
+    }
+
+    fn pull(&self, request: &SyncPullRequest) -> Result<Value, SyncFailure> {
+        self.post(&self.pull_url, serde_json::json!(request))
+    }
+}
+
+fn read_setting(connection: &Connection, key: &str) -> Result<Option<String>, SoloStackError> {
+    connection
+        .query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+            row.get::<_, Option<String>>(0)
+        })
+        .optional()
+        .map(|value| {
+            value
+                .flatten()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        })
+        .map_err(|error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::DatabaseQueryFailed,
+                format!("read sync setting failed: {error}"),
+            )
+        })
+}
+
+// Plain http is only good enough for a stub server on this machine, and only in debug builds;
+// anything that sends credentials must use https everywhere else.
+pub(crate) fn allows_plain_http(url: &url::Url) -> bool {
+    let loopback = match url.host() {
+        Some(url::Host::Ipv4(address)) => address.is_loopback(),
+        Some(url::Host::Ipv6(address)) => address.is_loopback(),
+        Some(url::Host::Domain(domain)) => domain == "localhost",
+        None => false,
+    };
+    cfg!(any(test, debug_assertions)) && loopback
+}
+
+fn is_http_url(value: &str) -> bool {
+    url::Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+// A missing or malformed config reads as empty; the provider then reports what it lacks.
+fn read_provider_config(connection: &Connection) -> Result<Map<String, Value>, SoloStackError> {
+    Ok(read_setting(connection, SYNC_SETTINGS_PROVIDER_CONFIG_KEY)?
+        .and_then(|config| serde_json::from_str::<Map<String, Value>>(&config).ok())
+        .unwrap_or_default())
+}
+
+// Cloud providers still sync through the connector adapters in the webview; the provider-neutral
+// HTTP endpoints and the journal-based providers have a native transport.
+pub(crate) fn resolve_transport(
+    connection: &Connection,
+    secure_store: &SecureStoreState,
+) -> Result<Box<dyn SyncTransport>, SoloStackError> {
+    let provider = read_setting(connection, SYNC_SETTINGS_PROVIDER_KEY)?
+        .unwrap_or_else(|| PROVIDER_NEUTRAL.to_string());
+    match provider.as_str() {
+        PROVIDER_NEUTRAL => {}
+        sync_local_folder::LOCAL_FOLDER_PROVIDER => {
+            return Ok(Box::new(LocalFolderSyncTransport::from_provider_config(
+                &read_provider_config(connection)?,
+            )?));
+        }
+        sync_webdav::WEBDAV_PROVIDER => {
+            return Ok(Box::new(WebDavSyncTransport::from_provider_config(
+                &read_provider_config(connection)?,
+                secure_store,
+            )?));
+        }
+        _ => {
+            return Err(SoloStackError::with_detail(
+                SoloStackErrorCode::SyncTransportUnavailable,
+                format!("provider {provider} has no native sync transport"),
+            ));
+        }
+    }
+    let push_url = read_setting(connection, SYNC_SETTINGS_PUSH_URL_KEY)?;
+    let pull_url = read_setting(connection, SYNC_SETTINGS_PULL_URL_KEY)?;
+    match (push_url, pull_url) {
+        (Some(push_url), Some(pull_url)) if is_http_url(&push_url) && is_http_url(&pull_url) => {
+            Ok(Box::new(HttpSyncTransport::new(push_url, pull_url)))
+        }
+        _ => Err(SoloStackError::with_detail(
+            SoloStackErrorCode::SyncTransportUnavailable,
+            "push and pull URLs are not configured",
+        )),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SyncTransportOperation {
+    Push,
+    Pull,
+}
+
+// Lets the webview's `runSyncCycle` use a native-only transport (`local_folder`, `webdav`) the
+// same way it uses an HTTP one: the envelope goes in as built by `sync-contract.ts` and the raw
+// body comes back for it to parse.
+#[tauri::command]
+pub(crate) async fn exchange_native_sync_envelope(
+    app: tauri::AppHandle,
+    operation: SyncTransportOperation,
+    payload: Value,
+) -> Result<Value, SoloStackError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let transport = resolve_transport(
+            &app.state::<DatabaseState>().open()?,
+            &app.state::<SecureStoreState>(),
+        )?;
+        let invalid_envelope = |error: serde_json::Error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::SyncRequestRejected,
+                format!("sync {operation:?} envelope is invalid: {error}"),
+            )
+        };
+        let response = match operation {
+            SyncTransportOperation::Push => {
+                transport.push(&serde_json::from_value(payload).map_err(invalid_envelope)?)
+            }
+            SyncTransportOperation::Pull => {
+                transport.pull(&serde_json::from_value(payload).map_err(invalid_envelope)?)
+            }
+        };
+        response.map_err(SoloStackError::from)
+    })
+    .await
+    .map_err(|error| {
+        SoloStackError::with_detail(
+            SoloStackErrorCode::Internal,
+            format!("sync transport task failed: {error}"),
+        )
+    })?
+}
@@ -0,0 +1,602 @@
+// `webdav` provider: the `sync_journal` journals kept in a collection on any WebDAV server
+// (Nextcloud, ownCloud, Apache mod_dav). Next to `journals/<device_id>.jsonl` the collection holds
+// `index.json`, the last seq of every journal, so a pull only downloads journals that grew since
+// its cursor. WebDAV has no append: every write replaces the whole file with `If-Match` on the
+// ETag it was computed from (`If-None-Match: *` when creating it) and starts over on 412.
+//
+// The URL and user name are provider config; the password is the `webdav` credential in the
+// secure store.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::{
+    error::{SoloStackError, SoloStackErrorCode},
+    repository,
+    secure_store::SecureStoreState,
+    sync_contract::{SyncPullRequest, SyncPushRequest},
+    sync_journal::{self, Journal, JOURNAL_EXTENSION},
+    sync_provider_secure_auth,
+    sync_transport::{self, SyncFailure, SyncTransport},
+};
+
+pub(crate) const WEBDAV_PROVIDER: &str = "webdav";
+const PROVIDER_CONFIG_BASE_URL_KEY: &str = "base_url";
+const PROVIDER_CONFIG_USERNAME_KEY: &str = "username";
+const JOURNALS_COLLECTION: &str = "journals/";
+const INDEX_FILE_NAME: &str = "index.json";
+const JOURNAL_CONTENT_TYPE: &str = "application/x-ndjson";
+const INDEX_CONTENT_TYPE: &str = "application/json";
+const WEBDAV_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+// Other devices only hold a file for one request, so a few re-reads are enough.
+const MAX_WRITE_ATTEMPTS: u32 = 5;
+const DEFAULT_COMPACT_THRESHOLD: usize = 2_000;
+
+#[derive(Default, Serialize, Deserialize)]
+struct JournalIndex {
+    #[serde(default)]
+    devices: BTreeMap<String, JournalIndexEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JournalIndexEntry {
+    last_seq: u64,
+    updated_at: String,
+}
+
+impl JournalIndex {
+    // An unreadable index is rebuilt by the next push of every device.
+    fn parse(content: Option<&str>) -> Self {
+        content
+            .and_then(|content| serde_json::from_str(content).ok())
+            .unwrap_or_default()
+    }
+}
+
+struct RemoteFile {
+    body: String,
+    etag: Option<String>,
+}
+
+enum PutOutcome {
+    Stored,
+    Changed,
+    MissingCollection,
+}
+
+fn not_configured(detail: &str) -> SoloStackError {
+    SoloStackError::with_detail(SoloStackErrorCode::SyncTransportUnavailable, detail)
+}
+
+fn journal_path(device_id: &str) -> String {
+    format!("{JOURNALS_COLLECTION}{device_id}.{JOURNAL_EXTENSION}")
+}
+
+// Details name the file, never the URL: it carries the credentials.
+fn request_failure(path: &str, error: ureq::Error) -> SyncFailure {
+    match error {
+        ureq::Error::Status(status, response) => {
+            let retry_after_ms = response
+                .header("Retry-After")
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(|seconds| seconds * 1_000);
+            let code = match status {
+                401 | 403 => SoloStackErrorCode::SyncUnauthorized,
+                429 => SoloStackErrorCode::SyncRateLimited,
+                500..=599 => SoloStackErrorCode::SyncServerUnavailable,
+                _ => SoloStackErrorCode::SyncRequestRejected,
+            };
+            SyncFailure {
+                error: SoloStackError::with_detail(
+                    code,
+                    format!("WebDAV {path} answered HTTP {status}"),
+                ),
+                retry_after_ms,
+            }
+        }
+        ureq::Error::Transport(transport) => SoloStackError::with_detail(
+            SoloStackErrorCode::SyncRequestFailed,
+            format!(
+                "WebDAV {path} request failed: {} {}",
+                transport.kind(),
+                transport.message().unwrap_or_default()
+            ),
+        )
+        .into(),
+    }
+}
+
+pub(crate) struct WebDavSyncTransport {
+    agent: ureq::Agent,
+    base_url: url::Url,
+    compact_threshold: usize,
+}
+
+impl WebDavSyncTransport {
+    pub(crate) fn new(
+        base_url: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<Self, SoloStackError> {
+        let mut base_url = url::Url::parse(base_url.trim())
+            .ok()
+            .filter(|url| {
+                url.scheme() == "https"
+                    || (url.scheme() == "http" && sync_transport::allows_plain_http(url))
+            })
+            .ok_or_else(|| not_configured("WebDAV URL must use https"))?;
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+        // ureq sends URL credentials as HTTP Basic auth.
+        base_url
+            .set_username(username)
+            .and_then(|_| base_url.set_password(Some(password)))
+            .map_err(|_| not_configured("WebDAV URL cannot carry credentials"))?;
+        Ok(Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout(WEBDAV_REQUEST_TIMEOUT)
+                .build(),
+            base_url,
+            compact_threshold: DEFAULT_COMPACT_THRESHOLD,
+        })
+    }
+
+    pub(crate) fn from_provider_config(
+        provider_config: &Map<String, Value>,
+        secure_store: &SecureStoreState,
+    ) -> Result<Self, SoloStackError> {
+        let config_value = |key: &str| {
+            provider_config
+                .get(key)
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let base_url = config_value(PROVIDER_CONFIG_BASE_URL_KEY)
+            .ok_or_else(|| not_configured("WebDAV URL is not configured"))?;
+        let username = config_value(PROVIDER_CONFIG_USERNAME_KEY)
+            .ok_or_else(|| not_configured("WebDAV user name is not configured"))?;
+        let credential =
+            sync_provider_secure_auth::get_credential(secure_store, WEBDAV_PROVIDER, None)?
+                .ok_or_else(|| {
+                    SoloStackError::with_detail(
+                        SoloStackErrorCode::SyncUnauthorized,
+                        "WebDAV password is not in the secure store",
+                    )
+                })?;
+        Self::new(base_url, username, &credential.secret)
+    }
+
+    fn request(&self, method: &str, path: &str) -> Result<ureq::Request, SyncFailure> {
+        let url = self.base_url.join(path).map_err(|error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::Internal,
+                format!("WebDAV path {path} is invalid: {error}"),
+            )
+        })?;
+        Ok(self.agent.request_url(method, &url))
+    }
+
+    fn get(&self, path: &str) -> Result<Option<RemoteFile>, SyncFailure> {
+        let response = match self.request("GET", path)?.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(error) => return Err(request_failure(path, error)),
+        };
+        let etag = response.header("ETag").map(str::to_string);
+        let body = response.into_string().map_err(|error| {
+            SoloStackError::with_detail(
+                SoloStackErrorCode::SyncResponseInvalid,
+                format!("WebDAV {path} body is unreadable: {error}"),
+            )
+        })?;
+        Ok(Some(RemoteFile { body, etag }))
+    }
+
+    fn put(
+        &self,
+        path: &str,
+        content_type: &str,
+        body: &str,
+        etag: Option<&str>,
+    ) -> Result<PutOutcome, SyncFailure> {
+        let request = self.request("PUT", path)?.set("Content-Type", content_type);
+        let request = match etag {
+            Some(etag) => request.set("If-Match", etag),
+            None => request.set("If-None-Match", "*"),
+        };
+        match request.send_string(body) {
+            Ok(_) => Ok(PutOutcome::Stored),
+            Err(ureq::Error::Status(412, _)) => Ok(PutOutcome::Changed),
+            Err(ureq::Error::Status(409, _)) => Ok(PutOutcome::MissingCollection),
+            Err(error) => Err(request_failure(path, error)),
+        }
+    }
+
+    // 405 means the collection already exists; 409 means its parent, the configured folder, does
+    // not.
+    fn make_collection(&self, path: &str) -> Result<(), SyncFailure> {
+        match self.request("MKCOL", path)?.call() {
+            Ok(_) | Err(ureq::Error::Status(405, _)) => Ok(()),
+            Err(ureq::Error::Status(409, _)) => {
+                Err(not_configured("WebDAV folder does not exist").into())
+            }
+            Err(error) => Err(request_failure(path, error)),
+        }
+    }
+
+    // Recomputes the file from what is on the server until the write lands on that version.
+    // `update` returns None when the current content needs no write.
+    fn write_file(
+        &self,
+        path: &str,
+        content_type: &str,
+        mut update: impl FnMut(Option<&str>) -> Result<Option<String>, SyncFailure>,
+    ) -> Result<(), SyncFailure> {
+        for _ in 0..MAX_WRITE_ATTEMPTS {
+            let current = self.get(path)?;
+            let etag = match &current {
+                Some(RemoteFile { etag: None, .. }) => {
+                    return Err(SoloStackError::with_detail(
+                        SoloStackErrorCode::SyncResponseInvalid,
+                        format!("WebDAV {path} has no ETag to write against"),
+                    )
+                    .into());
+                }
+                Some(RemoteFile { etag, .. }) => etag.as_deref(),
+                None => None,
+            };
+            let Some(body) = update(current.as_ref().map(|file| file.body.as_str()))? else {
+                return Ok(());
+            };
+            match self.put(path, content_type, &body, etag)? {
+                PutOutcome::Stored => return Ok(()),
+                PutOutcome::Changed => continue,
+                PutOutcome::MissingCollection => self.make_collection(JOURNALS_COLLECTION)?,
+            }
+        }
+        Err(SyncFailure {
+            error: SoloStackError::with_detail(
+                SoloStackErrorCode::SyncServerUnavailable,
+                format!("WebDAV {path} kept changing during the write"),
+            ),
+            retry_after_ms: None,
+        })
+    }
+}
+
+impl SyncTransport for WebDavSyncTransport {
+    fn push(&self, request: &SyncPushRequest) -> Result<Value, SyncFailure> {
+        sync_journal::check_schema_version(request.schema_version)?;
+        let device_id = sync_journal::journal_name(&request.device_id)?;
+
+        let mut last_seq = 0;
+        self.write_file(&journal_path(device_id), JOURNAL_CONTENT_TYPE, |current| {
+            let mut journal = Journal::parse(current.unwrap_or_default());
+            let appended = journal.append(&request.changes) < journal.records.len();
+            if appended {
+                journal.compact(self.compact_threshold);
+            }
+            // Read after compaction, so the index never names a seq the journal no longer ends on.
+            last_seq = journal.last_seq();
+            if !appended {
+                return Ok(None);
+            }
+            journal.encode_from(0).map(Some)
+        })?;
+
+        // Also catches up an index entry left behind when a previous push stopped after its
+        // journal write.
+        if last_seq > 0 {
+            self.write_file(INDEX_FILE_NAME, INDEX_CONTENT_TYPE, |current| {
+                let mut index = JournalIndex::parse(current);
+                if index
+                    .devices
+                    .get(device_id)
+                    .is_some_and(|entry| entry.last_seq == last_seq)
+                {
+                    return Ok(None);
+                }
+                index.devices.insert(
+                    device_id.to_string(),
+                    JournalIndexEntry {
+                        last_seq,
+                        updated_at: repository::now_iso(),
+                    },
+                );
+                serde_json::to_string(&index).map(Some).map_err(|error| {
+                    SoloStackError::with_detail(
+                        SoloStackErrorCode::Internal,
+                        format!("encode WebDAV sync index failed: {error}"),
+                    )
+                    .into()
+                })
+            })?;
+        }
+
+        Ok(sync_journal::push_response(WEBDAV_PROVIDER, request))
+    }
+
+    fn pull(&self, request: &SyncPullRequest) -> Result<Value, SyncFailure> {
+        sync_journal::check_schema_version(request.schema_version)?;
+        let device_id = sync_journal::journal_name(&request.device_id)?;
+        let offsets = sync_journal::decode_cursor(WEBDAV_PROVIDER, request.cursor.as_deref());
+
+        let index_file = self.get(INDEX_FILE_NAME)?;
+        let index = JournalIndex::parse(index_file.as_ref().map(|file| file.body.as_str()));
+        let mut journals = Vec::new();
+        for (journal_device_id, entry) in index.devices {
+            let read_up_to = offsets.get(&journal_device_id).copied().unwrap_or(0);
+            if journal_device_id == device_id
+                || entry.last_seq <= read_up_to
+                || sync_journal::journal_name(&journal_device_id).is_err()
+            {
+                continue;
+            }
+            if let Some(file) = self.get(&journal_path(&journal_device_id))? {
+                journals.push((journal_device_id, Journal::parse(&file.body)));
+            }
+        }
+
+        Ok(sync_journal::pull_page(
+            WEBDAV_PROVIDER,
+            journals,
+            offsets,
+            request.limit,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        repository::{SyncEntityType, SyncOperation},
+        sync_contract::{self, SyncPullResponse, SyncPushChange},
+    };
+    use std::{
+        collections::{HashMap, HashSet},
+        io::{Read, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    const COLLECTION_PATH: &str = "/dav/solostack/";
+    // `me:app-password`
+    const EXPECTED_AUTHORIZATION: &str = "Basic bWU6YXBwLXBhc3N3b3Jk";
+
+    #[derive(Default)]
+    struct DavState {
+        files: HashMap<String, (String, u64)>,
+        collections: HashSet<String>,
+        next_etag: u64,
+        // Index writes that lose a race against a device-c push before they are evaluated.
+        raced_index_writes: u32,
+    }
+
+    impl DavState {
+        fn store(&mut self, path: &str, body: String) {
+            self.next_etag += 1;
+            self.files.insert(path.to_string(), (body, self.next_etag));
+        }
+
+        fn handle(
+            &mut self,
+            method: &str,
+            path: &str,
+            headers: &HashMap<String, String>,
+            body: String,
+        ) -> (u16, Option<u64>, String) {
+            if headers.get("authorization").map(String::as_str) != Some(EXPECTED_AUTHORIZATION) {
+                return (401, None, String::new());
+            }
+            let Some(path) = path.strip_prefix(COLLECTION_PATH) else {
+                return (404, None, String::new());
+            };
+            match method {
+                "GET" => match self.files.get(path) {
+                    Some((body, etag)) => (200, Some(*etag), body.clone()),
+                    None => (404, None, String::new()),
+                },
+                "MKCOL" if self.collections.insert(path.to_string()) => (201, None, String::new()),
+                "MKCOL" => (405, None, String::new()),
+                "PUT" => {
+                    if path == INDEX_FILE_NAME && self.raced_index_writes > 0 {
+                        self.raced_index_writes -= 1;
+                        let mut index = JournalIndex::parse(
+                            self.files.get(path).map(|(body, _)| body.as_str()),
+                        );
+                        index.devices.insert(
+                            "device-c".to_string(),
+                            JournalIndexEntry {
+                                last_seq: 1,
+                                updated_at: "2026-01-01T00:00:00.000Z".to_string(),
+                            },
+                        );
+                        self.store(path, serde_json::to_string(&index).unwrap());
+                    }
+                    if let Some((parent, _)) = path.rsplit_once('/') {
+                        if !self.collections.contains(&format!("{parent}/")) {
+                            return (409, None, String::new());
+                        }
+                    }
+                    let current = self.files.get(path).map(|(_, etag)| format!("\"{etag}\""));
+                    let precondition_holds = match headers.get("if-match") {
+                        Some(expected) => current.as_ref() == Some(expected),
+                        None => current.is_none(),
+                    };
+                    if !precondition_holds {
+                        return (412, None, String::new());
+                    }
+                    self.store(path, body);
+                    (201, Some(self.next_etag), String::new())
+                }
+                _ => (405, None, String::new()),
+            }
+        }
+    }
+
+    // In-process stand-in for a WebDAV server: GET, conditional PUT and MKCOL on one collection,
+    // one request per connection.
+    fn spawn_webdav_server(state: Arc<Mutex<DavState>>) -> String {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    break;
+                };
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                let (head, body) = loop {
+                    let read = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let content_length = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|value| value.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= content_length {
+                            break (head.to_string(), body.to_string());
+                        }
+                    }
+                };
+                let mut lines = head.lines();
+                let mut request_line = lines.next().unwrap_or_default().split(' ');
+                let method = request_line.next().unwrap_or_default().to_string();
+                let path = request_line.next().unwrap_or_default().to_string();
+                let headers = lines
+                    .filter_map(|line| line.split_once(':'))
+                    .map(|(name, value)| {
+                        (name.trim().to_ascii_lowercase(), value.trim().to_string())
+                    })
+                    .collect::<HashMap<_, _>>();
+
+                let (status, etag, response_body) =
+                    state.lock().unwrap().handle(&method, &path, &headers, body);
+                let etag_header = etag
+                    .map(|etag| format!("ETag: \"{etag}\"\r\n"))
+                    .unwrap_or_default();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} WebDAV\r\n{etag_header}Content-Length: {}\r\nConnection: close\r\n\r\n{response_body}",
+                    response_body.len()
+                )
+                .unwrap();
+            }
+        });
+        format!("http://127.0.0.1:{port}{COLLECTION_PATH}")
+    }
+
+    fn task_change(device_id: &str, task_id: &str, updated_at: &str, key: &str) -> SyncPushChange {
+        SyncPushChange {
+            entity_type: SyncEntityType::Task,
+            entity_id: task_id.to_string(),
+            operation: SyncOperation::Upsert,
+            updated_at: updated_at.to_string(),
+            updated_by_device: device_id.to_string(),
+            sync_version: 1,
+            payload: Some(Map::new()),
+            idempotency_key: key.to_string(),
+        }
+    }
+
+    fn push(transport: &WebDavSyncTransport, device_id: &str, changes: Vec<SyncPushChange>) {
+        let request = sync_contract::build_push_request(device_id, None, changes);
+        sync_contract::parse_push_response(&transport.push(&request).expect("push"))
+            .expect("push response");
+    }
+
+    fn pull(
+        transport: &WebDavSyncTransport,
+        device_id: &str,
+        cursor: Option<String>,
+        limit: i64,
+    ) -> SyncPullResponse {
+        let request = sync_contract::build_pull_request(device_id, cursor, Some(limit));
+        sync_contract::parse_pull_response(&transport.pull(&request).expect("pull"))
+            .expect("pull response")
+    }
+
+    #[test]
+    fn exchanges_journals_through_conditional_writes() {
+        let state = Arc::new(Mutex::new(DavState::default()));
+        let base_url = spawn_webdav_server(state.clone());
+        let transport = WebDavSyncTransport::new(&base_url, "me", "app-password").unwrap();
+
+        let first = task_change("device-a", "task-1", "2026-01-01T00:00:00.000Z", "a-1");
+        let second = task_change("device-a", "task-2", "2026-01-01T00:01:00.000Z", "a-2");
+        push(&transport, "device-a", vec![first.clone()]);
+        push(&transport, "device-a", vec![first.clone(), second.clone()]);
+        let journal_etag = state.lock().unwrap().files["journals/device-a.jsonl"].1;
+        push(&transport, "device-a", vec![second.clone()]);
+        assert_eq!(
+            state.lock().unwrap().files["journals/device-a.jsonl"].1,
+            journal_etag
+        );
+
+        state.lock().unwrap().raced_index_writes = 1;
+        let from_b = task_change("device-b", "task-3", "2026-01-01T00:02:00.000Z", "b-1");
+        push(&transport, "device-b", vec![from_b.clone()]);
+        let index = JournalIndex::parse(
+            state
+                .lock()
+                .unwrap()
+                .files
+                .get(INDEX_FILE_NAME)
+                .map(|(body, _)| body.as_str()),
+        );
+        assert_eq!(
+            index
+                .devices
+                .iter()
+                .map(|(device_id, entry)| (device_id.as_str(), entry.last_seq))
+                .collect::<Vec<_>>(),
+            vec![("device-a", 2), ("device-b", 1), ("device-c", 1)]
+        );
+
+        let page = pull(&transport, "device-b", None, 1);
+        assert_eq!(page.changes, vec![first]);
+        assert!(page.has_more);
+        let page = pull(&transport, "device-b", Some(page.server_cursor), 10);
+        assert_eq!(page.changes, vec![second]);
+        assert!(!page.has_more);
+        assert!(pull(&transport, "device-b", Some(page.server_cursor), 10)
+            .changes
+            .is_empty());
+        assert_eq!(pull(&transport, "device-a", None, 10).changes, vec![from_b]);
+
+        let wrong_password = WebDavSyncTransport::new(&base_url, "me", "stale").unwrap();
+        let request = sync_contract::build_pull_request("device-a", None, None);
+        let failure = wrong_password.pull(&request).expect_err("rejected");
+        assert_eq!(failure.error.code, SoloStackErrorCode::SyncUnauthorized);
+        assert!(!failure.error.detail.unwrap_or_default().contains("stale"));
+    }
+
+    #[test]
+    fn refuses_plain_http_off_this_machine() {
+        for base_url in ["http://dav.example.com/solostack/", "ftp://127.0.0.1/"] {
+            let error = WebDavSyncTransport::new(base_url, "me", "app-password")
+                .err()
+                .unwrap();
+            assert_eq!(error.code, SoloStackErrorCode::SyncTransportUnavailable);
+            assert_eq!(error.detail.as_deref(), Some("WebDAV URL must use https"));
+        }
+        for base_url in [
+            "https://dav.example.com/solostack/",
+            "http://localhost:8080/",
+        ] {
+            assert!(WebDavSyncTransport::new(base_url, "me", "app-password").is_ok());
+        }
+    }
+}
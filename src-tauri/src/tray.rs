@@ -0,0 +1,592 @@
+// Tray / menu-bar icon. The menu shows how many open tasks are overdue or due today and offers the
+// actions people reach for without the main window. Counts are read straight from the `tasks`
+// table: on every webview refresh request, after quick capture saves, and once a minute so a task
+// moves from "due today" to "overdue" without any write. Tray preferences live in `tray.json` in
+// the app config dir.
+
+use chrono::{DateTime, Local, Utc};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+    time::Duration,
+};
+use tauri::{
+    menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Emitter, Manager, Wry,
+};
+
+use crate::{
+    db::DatabaseState,
+    error::{SoloStackError, SoloStackErrorCode},
+    global_shortcuts::GlobalShortcutAction,
+    repository,
+};
+
+pub(crate) const REMINDERS_PAUSED_EVENT: &str = "reminders:paused-changed";
+const TRAY_ID: &str = "solostack-tray";
+const TRAY_SETTINGS_FILENAME: &str = "tray.json";
+const TRAY_SETTINGS_VERSION: u8 = 1;
+const TRAY_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+// Same key the webview uses for the language setting.
+const APP_LOCALE_SETTING_KEY: &str = "app.locale";
+
+const MENU_ID_OPEN: &str = "tray:open";
+const MENU_ID_OVERDUE: &str = "tray:overdue";
+const MENU_ID_DUE_TODAY: &str = "tray:due-today";
+const MENU_ID_QUICK_CAPTURE: &str = "tray:quick-capture";
+const MENU_ID_OPEN_TODAY: &str = "tray:open-today";
+const MENU_ID_PAUSE_REMINDERS: &str = "tray:pause-reminders";
+const MENU_ID_KEEP_RUNNING: &str = "tray:keep-running";
+const MENU_ID_QUIT: &str = "tray:quit";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TraySettings {
+    // Closing the main window hides it instead of quitting, so reminders keep firing.
+    #[serde(default)]
+    pub(crate) keep_running_in_tray: bool,
+    #[serde(default)]
+    pub(crate) reminders_paused: bool,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct TraySettingsFile {
+    version: u8,
+    #[serde(flatten)]
+    settings: TraySettings,
+}
+
+#[derive(Clone, Serialize)]
+struct RemindersPausedPayload {
+    paused: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct TrayTaskCounts {
+    pub(crate) overdue: u32,
+    pub(crate) due_today: u32,
+}
+
+struct TrayLabels {
+    open: &'static str,
+    overdue: &'static str,
+    due_today: &'static str,
+    quick_capture: &'static str,
+    open_today: &'static str,
+    pause_reminders: &'static str,
+    keep_running: &'static str,
+    quit: &'static str,
+}
+
+const TRAY_LABELS_EN: TrayLabels = TrayLabels {
+    open: "Open SoloStack",
+    overdue: "Overdue",
+    due_today: "Due today",
+    quick_capture: "Quick Capture",
+    open_today: "Open Today",
+    pause_reminders: "Pause reminders",
+    keep_running: "Keep running in tray",
+    quit: "Quit SoloStack",
+};
+
+const TRAY_LABELS_TH: TrayLabels = TrayLabels {
+    open: "เปิด SoloStack",
+    overdue: "เลยกำหนด",
+    due_today: "ครบกำหนดวันนี้",
+    quick_capture: "บันทึกด่วน",
+    open_today: "เปิดหน้าวันนี้",
+    pause_reminders: "หยุดการแจ้งเตือนชั่วคราว",
+    keep_running: "ทำงานต่อใน tray เมื่อปิดหน้าต่าง",
+    quit: "ออกจาก SoloStack",
+};
+
+fn labels_for_locale(locale: Option<&str>) -> &'static TrayLabels {
+    match locale {
+        Some("th") => &TRAY_LABELS_TH,
+        _ => &TRAY_LABELS_EN,
+    }
+}
+
+// `None` counts (database not ready yet) leave the number off rather than showing a zero.
+fn count_label(label: &str, count: Option<u32>) -> String {
+    match count {
+        Some(count) => format!("{label}: {count}"),
+        None => label.to_string(),
+    }
+}
+
+// Matches `getTodayTasks` in the webview: open tasks due before the end of the local day, split at
+// `now` into overdue and due today.
+pub(crate) fn count_due_tasks(
+    connection: &Connection,
+    now: DateTime<Local>,
+) -> rusqlite::Result<TrayTaskCounts> {
+    let day_end = now
+        .date_naive()
+        .succ_opt()
+        .and_then(|tomorrow| tomorrow.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+        .map(|midnight| midnight.with_timezone(&Utc));
+    let now = now.with_timezone(&Utc);
+
+    let mut statement = connection.prepare(
+        "SELECT due_at FROM tasks
+         WHERE status IN ('TODO', 'DOING') AND due_at IS NOT NULL",
+    )?;
+    let mut counts = TrayTaskCounts::default();
+    for due_at in statement.query_map(params![], |row| row.get::<_, String>(0))? {
+        let Some(due_at) = repository::parse_iso_datetime(&due_at?) else {
+            continue;
+        };
+        if due_at < now {
+            counts.overdue += 1;
+        } else if day_end.is_some_and(|day_end| due_at < day_end) {
+            counts.due_today += 1;
+        }
+    }
+    Ok(counts)
+}
+
+fn read_app_locale(connection: &Connection) -> Option<String> {
+    connection
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1 LIMIT 1",
+            params![APP_LOCALE_SETTING_KEY],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+}
+
+fn settings_write_failed(detail: String) -> SoloStackError {
+    SoloStackError::with_detail(SoloStackErrorCode::TraySettingsWriteFailed, detail)
+}
+
+fn read_settings(config_path: &Path) -> TraySettings {
+    match fs::read_to_string(config_path) {
+        Ok(contents) => serde_json::from_str::<TraySettingsFile>(&contents)
+            .map(|file| file.settings)
+            .unwrap_or_else(|error| {
+                eprintln!("Tray settings warning: ignoring unreadable settings: {error}");
+                TraySettings::default()
+            }),
+        Err(_) => TraySettings::default(),
+    }
+}
+
+#[derive(Clone)]
+struct TrayMenuItems {
+    open: MenuItem<Wry>,
+    overdue: MenuItem<Wry>,
+    due_today: MenuItem<Wry>,
+    quick_capture: MenuItem<Wry>,
+    open_today: MenuItem<Wry>,
+    pause_reminders: CheckMenuItem<Wry>,
+    keep_running: CheckMenuItem<Wry>,
+    quit: MenuItem<Wry>,
+}
+
+pub(crate) struct TrayState {
+    config_path: Option<PathBuf>,
+    settings: Mutex<TraySettings>,
+    // Set once the icon is installed; a failed install leaves the settings usable on their own.
+    menu: Mutex<Option<TrayMenuItems>>,
+}
+
+impl TrayState {
+    pub(crate) fn load(app_config_dir: Option<&Path>) -> Self {
+        let config_path = app_config_dir.map(|dir| dir.join(TRAY_SETTINGS_FILENAME));
+        let settings = config_path
+            .as_deref()
+            .map(read_settings)
+            .unwrap_or_default();
+        Self {
+            config_path,
+            settings: Mutex::new(settings),
+            menu: Mutex::new(None),
+        }
+    }
+
+    fn lock_settings(&self) -> Result<MutexGuard<'_, TraySettings>, SoloStackError> {
+        self.settings.lock().map_err(|_| {
+            SoloStackError::with_detail(SoloStackErrorCode::Internal, "tray state lock is poisoned")
+        })
+    }
+
+    pub(crate) fn settings(&self) -> TraySettings {
+        self.lock_settings()
+            .map(|settings| *settings)
+            .unwrap_or_default()
+    }
+
+    // The in-memory value only changes once the file is written, so a failed save leaves the menu
+    // and the next start-up in agreement.
+    fn update(
+        &self,
+        change: impl FnOnce(&mut TraySettings),
+    ) -> Result<TraySettings, SoloStackError> {
+        let mut settings = self.lock_settings()?;
+        let mut next = *settings;
+        change(&mut next);
+        self.save(next)?;
+        *settings = next;
+        Ok(next)
+    }
+
+    fn save(&self, settings: TraySettings) -> Result<(), SoloStackError> {
+        let Some(config_path) = self.config_path.as_ref() else {
+            return Err(SoloStackError::with_detail(
+                SoloStackErrorCode::AppDataDirUnavailable,
+                "tray settings need the app config dir",
+            ));
+        };
+        let file = TraySettingsFile {
+            version: TRAY_SETTINGS_VERSION,
+            settings,
+        };
+        let contents = serde_json::to_string_pretty(&file).map_err(|error| {
+            settings_write_failed(format!("serialize tray settings failed: {error}"))
+        })?;
+        if let Some(parent_dir) = config_path.parent() {
+            fs::create_dir_all(parent_dir).map_err(|error| {
+                settings_write_failed(format!("create config dir failed: {error}"))
+            })?;
+        }
+        let temp_path = config_path.with_extension("json.tmp");
+        fs::File::create(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(contents.as_bytes())?;
+                file.sync_all()
+            })
+            .map_err(|error| {
+                settings_write_failed(format!("write tray settings failed: {error}"))
+            })?;
+        fs::rename(&temp_path, config_path).map_err(|error| {
+            settings_write_failed(format!("replace tray settings failed: {error}"))
+        })
+    }
+
+    // Menu calls from a background thread wait for the main thread, so the items are cloned out
+    // instead of holding the lock across them.
+    fn with_menu(&self, operation: impl FnOnce(&TrayMenuItems) -> tauri::Result<()>) {
+        let Some(items) = self.menu.lock().ok().and_then(|menu| menu.clone()) else {
+            return;
+        };
+        if let Err(error) = operation(&items) {
+            eprintln!("Tray menu warning: {error}");
+        }
+    }
+}
+
+pub(crate) fn install(app: &AppHandle) -> tauri::Result<()> {
+    let state = app.state::<TrayState>();
+    let settings = state.settings();
+    let labels = labels_for_locale(None);
+    let items = TrayMenuItems {
+        open: MenuItem::with_id(app, MENU_ID_OPEN, labels.open, true, None::<&str>)?,
+        overdue: MenuItem::with_id(app, MENU_ID_OVERDUE, labels.overdue, false, None::<&str>)?,
+        due_today: MenuItem::with_id(
+            app,
+            MENU_ID_DUE_TODAY,
+            labels.due_today,
+            false,
+            None::<&str>,
+        )?,
+        quick_capture: MenuItem::with_id(
+            app,
+            MENU_ID_QUICK_CAPTURE,
+            labels.quick_capture,
+            true,
+            None::<&str>,
+        )?,
+        open_today: MenuItem::with_id(
+            app,
+            MENU_ID_OPEN_TODAY,
+            labels.open_today,
+            true,
+            None::<&str>,
+        )?,
+        pause_reminders: CheckMenuItem::with_id(
+            app,
+            MENU_ID_PAUSE_REMINDERS,
+            labels.pause_reminders,
+            true,
+            settings.reminders_paused,
+            None::<&str>,
+        )?,
+        keep_running: CheckMenuItem::with_id(
+            app,
+            MENU_ID_KEEP_RUNNING,
+            labels.keep_running,
+            true,
+            settings.keep_running_in_tray,
+            None::<&str>,
+        )?,
+        quit: MenuItem::with_id(app, MENU_ID_QUIT, labels.quit, true, None::<&str>)?,
+    };
+    let menu = Menu::with_items(
+        app,
+        &[
+            &items.open,
+            &PredefinedMenuItem::separator(app)?,
+            &items.overdue,
+            &items.due_today,
+            &PredefinedMenuItem::separator(app)?,
+            &items.quick_capture,
+            &items.open_today,
+            &PredefinedMenuItem::separator(app)?,
+            &items.pause_reminders,
+            &items.keep_running,
+            &PredefinedMenuItem::separator(app)?,
+            &items.quit,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("SoloStack")
+        .menu(&menu)
+        // Left click opens the app on Windows/Linux; the menu stays on right click.
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                crate::show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    if let Ok(mut menu) = state.menu.lock() {
+        *menu = Some(items);
+    }
+    refresh(app);
+    Ok(())
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        MENU_ID_OPEN => crate::show_main_window(app),
+        MENU_ID_QUICK_CAPTURE => crate::run_app_action(app, GlobalShortcutAction::QuickCapture),
+        MENU_ID_OPEN_TODAY => crate::run_app_action(app, GlobalShortcutAction::OpenToday),
+        MENU_ID_PAUSE_REMINDERS => {
+            let paused = !app.state::<TrayState>().settings().reminders_paused;
+            if let Err(error) = set_reminders_paused_inner(app, paused) {
+                eprintln!("Tray settings warning: {error}");
+            }
+        }
+        MENU_ID_KEEP_RUNNING => {
+            let enabled = !app.state::<TrayState>().settings().keep_running_in_tray;
+            if let Err(error) = set_keep_running_in_tray_inner(app, enabled) {
+                eprintln!("Tray settings warning: {error}");
+            }
+        }
+        MENU_ID_QUIT => app.exit(0),
+        _ => {}
+    }
+}
+
+// Check items flip themselves when clicked; syncing them from the saved settings undoes that flip
+// whenever the save failed.
+fn sync_check_items(state: &TrayState, settings: TraySettings) {
+    state.with_menu(|items| {
+        items
+            .pause_reminders
+            .set_checked(settings.reminders_paused)?;
+        items
+            .keep_running
+            .set_checked(settings.keep_running_in_tray)
+    });
+}
+
+fn set_reminders_paused_inner(
+    app: &AppHandle,
+    paused: bool,
+) -> Result<TraySettings, SoloStackError> {
+    let state = app.state::<TrayState>();
+    let result = state.update(|settings| settings.reminders_paused = paused);
+    let settings = state.settings();
+    sync_check_items(&state, settings);
+    if result.is_ok() {
+        let _ = app.emit(
+            REMINDERS_PAUSED_EVENT,
+            RemindersPausedPayload {
+                paused: settings.reminders_paused,
+            },
+        );
+    }
+    result
+}
+
+fn set_keep_running_in_tray_inner(
+    app: &AppHandle,
+    enabled: bool,
+) -> Result<TraySettings, SoloStackError> {
+    let state = app.state::<TrayState>();
+    let result = state.update(|settings| settings.keep_running_in_tray = enabled);
+    sync_check_items(&state, state.settings());
+    result
+}
+
+pub(crate) fn keeps_running_in_tray(app: &AppHandle) -> bool {
+    app.try_state::<TrayState>()
+        .is_some_and(|state| state.settings().keep_running_in_tray)
+}
+
+pub(crate) fn refresh(app: &AppHandle) {
+    let Some(state) = app.try_state::<TrayState>() else {
+        return;
+    };
+    // Before startup migration finishes there is no database to count from.
+    let (locale, counts) = match app.state::<DatabaseState>().open() {
+        Ok(connection) => {
+            let counts = count_due_tasks(&connection, Local::now())
+                .map_err(|error| eprintln!("Tray count warning: {error}"))
+                .ok();
+            (read_app_locale(&connection), counts)
+        }
+        Err(_) => (None, None),
+    };
+    let labels = labels_for_locale(locale.as_deref());
+    state.with_menu(|items| {
+        items.open.set_text(labels.open)?;
+        items.overdue.set_text(count_label(
+            labels.overdue,
+            counts.map(|counts| counts.overdue),
+        ))?;
+        items.due_today.set_text(count_label(
+            labels.due_today,
+            counts.map(|counts| counts.due_today),
+        ))?;
+        items.quick_capture.set_text(labels.quick_capture)?;
+        items.open_today.set_text(labels.open_today)?;
+        items.pause_reminders.set_text(labels.pause_reminders)?;
+        items.keep_running.set_text(labels.keep_running)?;
+        items.quit.set_text(labels.quit)
+    });
+    if let (Some(tray), Some(counts)) = (app.tray_by_id(TRAY_ID), counts) {
+        let tooltip = format!(
+            "SoloStack — {}, {}",
+            count_label(labels.overdue, Some(counts.overdue)),
+            count_label(labels.due_today, Some(counts.due_today)),
+        );
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+}
+
+pub(crate) fn spawn_refresh_loop(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(TRAY_REFRESH_INTERVAL);
+        refresh(&app);
+    });
+}
+
+#[tauri::command]
+pub(crate) fn get_tray_settings(state: tauri::State<TrayState>) -> TraySettings {
+    state.settings()
+}
+
+#[tauri::command]
+pub(crate) fn set_keep_running_in_tray(
+    app: AppHandle,
+    enabled: bool,
+) -> Result<TraySettings, SoloStackError> {
+    set_keep_running_in_tray_inner(&app, enabled)
+}
+
+#[tauri::command]
+pub(crate) fn set_reminders_paused(
+    app: AppHandle,
+    paused: bool,
+) -> Result<TraySettings, SoloStackError> {
+    set_reminders_paused_inner(&app, paused)
+}
+
+// The webview writes most tasks through the SQL plugin, so it asks for a refresh after each change.
+#[tauri::command]
+pub(crate) fn refresh_tray_task_counts(app: AppHandle) {
+    refresh(&app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn tasks_connection(rows: &[(&str, Option<String>)]) -> Connection {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch("CREATE TABLE tasks (id TEXT PRIMARY KEY, status TEXT, due_at TEXT);")
+            .unwrap();
+        for (index, (status, due_at)) in rows.iter().enumerate() {
+            connection
+                .execute(
+                    "INSERT INTO tasks (id, status, due_at) VALUES (?1, ?2, ?3)",
+                    params![index.to_string(), status, due_at],
+                )
+                .unwrap();
+        }
+        connection
+    }
+
+    fn iso(value: DateTime<Local>) -> Option<String> {
+        Some(value.with_timezone(&Utc).to_rfc3339())
+    }
+
+    #[test]
+    fn counts_open_tasks_overdue_and_due_today() {
+        let now = Local.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let connection = tasks_connection(&[
+            ("TODO", iso(now - chrono::Duration::days(2))),
+            ("DOING", iso(now - chrono::Duration::minutes(5))),
+            ("TODO", iso(now + chrono::Duration::hours(3))),
+            (
+                "TODO",
+                iso(Local.with_ymd_and_hms(2026, 3, 11, 0, 0, 0).unwrap()),
+            ),
+            ("DONE", iso(now - chrono::Duration::hours(1))),
+            ("ARCHIVED", iso(now + chrono::Duration::hours(1))),
+            ("TODO", None),
+            ("TODO", Some("not a date".to_string())),
+        ]);
+
+        assert_eq!(
+            count_due_tasks(&connection, now).unwrap(),
+            TrayTaskCounts {
+                overdue: 2,
+                due_today: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn settings_persist_across_loads() {
+        let config_dir =
+            std::env::temp_dir().join(format!("solostack-tray-{}", uuid::Uuid::new_v4()));
+        let state = TrayState::load(Some(&config_dir));
+        assert_eq!(state.settings(), TraySettings::default());
+
+        state
+            .update(|settings| settings.keep_running_in_tray = true)
+            .unwrap();
+        let reloaded = TrayState::load(Some(&config_dir));
+        assert!(reloaded.settings().keep_running_in_tray);
+        assert!(!reloaded.settings().reminders_paused);
+
+        let unsaved = TrayState::load(None);
+        assert!(unsaved
+            .update(|settings| settings.reminders_paused = true)
+            .is_err());
+        assert!(!unsaved.settings().reminders_paused);
+        fs::remove_dir_all(config_dir).unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 import { QueryClient, QueryClientProvider } from "@tanstack/react-query";
+import { isTauri } from "@tauri-apps/api/core";
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { AppShell } from "./components/AppShell";
 import { TaskBoard } from "./components/TaskBoard";
@@ -23,6 +24,7 @@ import {
   useTodayTasks,
   useUpcomingTasks,
   useCreateTask,
+  useRefreshTaskQueries,
   useUpdateTask,
   useDeleteTask,
   useDeleteProject,
@@ -48,6 +50,12 @@ import {
   useReminderNotifications,
 } from "./hooks/use-reminder-notifications";
 import { useQuickCaptureShortcut } from "./hooks/use-quick-capture-shortcut";
+import { useQuickCaptureTaskCreated } from "./hooks/use-quick-capture-task-created";
+import { useReminderActionApplied } from "./hooks/use-reminder-action-applied";
+import { useGlobalShortcutActions } from "./hooks/use-global-shortcut-actions";
+import { useSyncAuthRefresh } from "./hooks/use-sync-auth-refresh";
+import { useTray } from "./hooks/use-tray";
+import { useNativeReminders } from "./hooks/use-native-reminders";
 import { useTaskFilters } from "./hooks/use-task-filters";
 import { useSync } from "./hooks/use-sync";
 import { useAppStore } from "./store/app-store";
@@ -75,6 +83,10 @@ import {
   getRemindersEnabledPreference,
   setRemindersEnabledPreference,
 } from "./lib/reminder-settings";
+import {
+  getNativeSyncSchedulerPreference,
+  setNativeSyncSchedulerPreference,
+} from "./lib/native-sync";
 import { applyTaskFilters } from "./lib/task-filters";
 import { detectSyncRuntimeProfilePresetWithSource } from "./lib/runtime-platform";
 import { installE2EBridge } from "./lib/e2e-bridge";
@@ -516,6 +528,7 @@ function AppContent() {
     isError: isAllTasksError,
     error: allTasksError,
     refetch: refetchAllTasks,
+    dataUpdatedAt: allTasksUpdatedAt,
   } = useTasks();
   const { data: projects = [] } = useProjects();
   const {
@@ -621,6 +634,8 @@ function AppContent() {
   const [remindersEnabled, setRemindersEnabled] = useState<boolean>(() =>
     getRemindersEnabledPreference(),
   );
+  const [nativeSyncSchedulerEnabled, setNativeSyncSchedulerEnabled] =
+    useState<boolean>(() => getNativeSyncSchedulerPreference());
   const [e2eOpenConflicts, setE2eOpenConflicts] = useState<
     SyncConflictRecord[]
   >([]);
@@ -740,6 +755,7 @@ function AppContent() {
     maxPullPages: effectiveSyncRuntimeSettings.max_pull_pages,
     syncWriteBlocked: effectiveSyncWriteBlocked,
     syncWriteBlockedReason: effectiveSyncWriteBlockedReason,
+    nativeScheduler: nativeSyncSchedulerEnabled,
     locale: appLocale,
   });
   const visibleSyncStatus = e2eBridgeEnabled ? e2eSyncStatus : sync.status;
@@ -1568,6 +1584,16 @@ function AppContent() {
   }, [clearTaskDetailFocus, setEditingTask, setIsCreateOpen]);
 
   useQuickCaptureShortcut(openQuickCapture);
+  const refreshTaskQueries = useRefreshTaskQueries();
+  useQuickCaptureTaskCreated(refreshTaskQueries);
+  useReminderActionApplied(refreshTaskQueries);
+  useSyncAuthRefresh();
+  const { remindersPaused } = useTray(allTasksUpdatedAt);
+  const reminderDeliveryMode = useNativeReminders({
+    enabled: remindersEnabled,
+    locale: appLocale,
+    tasksUpdatedAt: allTasksUpdatedAt,
+  });
 
   const openSettingsWithFocus = useCallback(
     (target: SettingsFocusTarget) => {
@@ -1627,6 +1653,14 @@ function AppContent() {
     setRemindersEnabledPreference(enabled);
   }, []);
 
+  const handleNativeSyncSchedulerEnabledChange = useCallback(
+    (enabled: boolean) => {
+      setNativeSyncSchedulerEnabled(enabled);
+      setNativeSyncSchedulerPreference(enabled);
+    },
+    [],
+  );
+
   const handleE2EResetSyncState = useCallback(() => {
     e2eOpenConflictsRef.current = [];
     setE2eOpenConflicts([]);
@@ -1797,7 +1831,11 @@ function AppContent() {
 
   useReminderNotifications(
     allTasks,
-    remindersEnabled && !isLoadingAllTasks && !isAllTasksError,
+    remindersEnabled &&
+      reminderDeliveryMode === "webview" &&
+      !remindersPaused &&
+      !isLoadingAllTasks &&
+      !isAllTasksError,
     appLocale,
     handleTaskNotificationOpen,
     handleTaskReminderSnooze,
@@ -1988,6 +2026,24 @@ function AppContent() {
     ],
   );
 
+  const openTodayFromShortcut = useCallback(() => {
+    setActiveView("today");
+  }, [setActiveView]);
+
+  // The global shortcut has no task context, so it focuses the first open task due today.
+  const startFocusFromShortcut = useCallback(() => {
+    setActiveView("today");
+    const nextTask = todayTasks.find(
+      (task) => task.status === "TODO" || task.status === "DOING",
+    );
+    if (nextTask) handleStartFocus(nextTask);
+  }, [handleStartFocus, setActiveView, todayTasks]);
+
+  useGlobalShortcutActions({
+    onOpenToday: openTodayFromShortcut,
+    onStartFocusSession: startFocusFromShortcut,
+  });
+
   const handleCreate = async (input: CreateTaskInput | UpdateTaskInput) => {
     setActionError(null);
     try {
@@ -2550,6 +2606,11 @@ function AppContent() {
       syncLastError={visibleSyncLastError}
       syncIsRunning={visibleSyncIsRunning}
       syncHasTransport={visibleSyncHasTransport}
+      syncNativeSchedulerAvailable={!e2eBridgeEnabled && isTauri()}
+      syncNativeSchedulerEnabled={nativeSyncSchedulerEnabled}
+      onSyncNativeSchedulerEnabledChange={
+        handleNativeSyncSchedulerEnabledChange
+      }
       onSyncNow={visibleSyncNow}
       onRetryLastFailedSync={visibleRetryLastFailedSync}
       syncPushUrl={visibleSyncPushUrl}
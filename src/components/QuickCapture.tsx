@@ -7,6 +7,8 @@ interface QuickCaptureProps {
   error: string | null;
   onSubmit: (title: string) => Promise<void>;
   onClose: () => void;
+  // Fills the dedicated capture window instead of dimming the app behind it.
+  standalone?: boolean;
 }
 
 export function QuickCapture({
@@ -14,6 +16,7 @@ export function QuickCapture({
   error,
   onSubmit,
   onClose,
+  standalone = false,
 }: QuickCaptureProps) {
   const { t } = useI18n();
   const [title, setTitle] = useState("");
@@ -38,7 +41,14 @@ export function QuickCapture({
   };
 
   return (
-    <div className="quick-capture-overlay" onClick={onClose}>
+    <div
+      className={
+        standalone
+          ? "quick-capture-overlay quick-capture-standalone"
+          : "quick-capture-overlay"
+      }
+      onClick={onClose}
+    >
       <div
         className="quick-capture-panel animate-scale-in"
         onClick={(event) => event.stopPropagation()}
@@ -232,8 +242,21 @@ export function QuickCapture({
           cursor: not-allowed;
         }
 
+        .quick-capture-standalone {
+          background: transparent;
+          backdrop-filter: none;
+          padding: 0;
+          align-items: stretch;
+        }
+
+        .quick-capture-standalone .quick-capture-panel {
+          width: 100%;
+          border-radius: 0;
+          box-shadow: none;
+        }
+
         @media (max-width: 640px) {
-          .quick-capture-overlay {
+          .quick-capture-overlay:not(.quick-capture-standalone) {
             padding: 64px 12px 12px;
             align-items: flex-start;
           }
@@ -0,0 +1,83 @@
+import { useCallback, useEffect, useState } from "react";
+import { QuickCapture } from "./QuickCapture";
+import { getAppLocaleSetting } from "@/lib/database";
+import { localizeErrorMessage } from "@/lib/error-message";
+import { I18nProvider, detectSystemAppLocale } from "@/lib/i18n";
+import {
+  createQuickCaptureTask,
+  dismissQuickCaptureWindow,
+} from "@/lib/quick-capture-window";
+import type { AppLocale } from "@/lib/types";
+import "../index.css";
+
+// Root of the frameless capture window. The native side hides the window after a save or when it
+// loses focus, so this view only has to reset itself whenever it is shown again.
+export function QuickCaptureWindow() {
+  const [locale, setLocale] = useState<AppLocale>(() =>
+    detectSystemAppLocale(),
+  );
+  const [isSubmitting, setIsSubmitting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  // Remounting the form clears the title and re-focuses the input.
+  const [session, setSession] = useState(0);
+
+  useEffect(() => {
+    let disposed = false;
+    getAppLocaleSetting()
+      .then(({ locale: persistedLocale }) => {
+        if (!disposed && persistedLocale) setLocale(persistedLocale);
+      })
+      .catch(() => {
+        // Keep the system locale when settings are unreadable.
+      });
+    return () => {
+      disposed = true;
+    };
+  }, []);
+
+  useEffect(() => {
+    const handleFocus = () => {
+      setError(null);
+      setSession((current) => current + 1);
+    };
+
+    window.addEventListener("focus", handleFocus);
+    return () => window.removeEventListener("focus", handleFocus);
+  }, []);
+
+  const handleSubmit = useCallback(
+    async (title: string): Promise<void> => {
+      setError(null);
+      setIsSubmitting(true);
+      try {
+        await createQuickCaptureTask(title);
+        setSession((current) => current + 1);
+      } catch (submitError) {
+        setError(
+          localizeErrorMessage(submitError, locale, "app.error.unexpected"),
+        );
+      } finally {
+        setIsSubmitting(false);
+      }
+    },
+    [locale],
+  );
+
+  const handleClose = useCallback(() => {
+    setError(null);
+    void dismissQuickCaptureWindow();
+  }, []);
+
+  return (
+    <I18nProvider locale={locale}>
+      <QuickCapture
+        key={session}
+        standalone
+        isSubmitting={isSubmitting}
+        error={error}
+        onSubmit={handleSubmit}
+        onClose={handleClose}
+      />
+    </I18nProvider>
+  );
+}
@@ -77,7 +77,10 @@ import {
   type SyncProviderManagedConnectorDraft,
 } from "@/lib/sync-provider-adapter-factory";
 import type { SyncProviderAuthState } from "@/lib/sync-provider-auth";
-import { runSyncProviderSecureStoreSelfTest } from "@/lib/sync-provider-secure-store";
+import {
+  runSyncProviderSecureStoreSelfTest,
+  writeSyncProviderSecret,
+} from "@/lib/sync-provider-secure-store";
 import { ManualMergeEditor } from "./ManualMergeEditor";
 
 interface ReminderSettingsProps {
@@ -92,6 +95,9 @@ interface ReminderSettingsProps {
   syncLastError: string | null;
   syncIsRunning: boolean;
   syncHasTransport: boolean;
+  syncNativeSchedulerAvailable: boolean;
+  syncNativeSchedulerEnabled: boolean;
+  onSyncNativeSchedulerEnabledChange: (enabled: boolean) => void;
   onSyncNow: () => Promise<void>;
   onRetryLastFailedSync: () => Promise<boolean>;
   syncProvider: SyncProvider;
@@ -149,6 +155,14 @@ interface SyncProviderCapability {
   warnings: string[];
 }
 
+function extractProviderConfigString(
+  providerConfig: Record<string, unknown> | null | undefined,
+  key: string,
+): string {
+  const value = providerConfig?.[key];
+  return typeof value === "string" ? value : "";
+}
+
 const DESKTOP_RUNTIME_DEFAULTS = {
   auto_sync_interval_seconds: 60,
   background_sync_interval_seconds: 300,
@@ -327,6 +341,50 @@ function getSyncProviderCapabilities(
         ),
       ],
     },
+    local_folder: {
+      label: translate(
+        locale,
+        "settings.sync.provider.capability.local_folder.label",
+      ),
+      summary: translate(
+        locale,
+        "settings.sync.provider.capability.local_folder.summary",
+      ),
+      authRequirement: translate(
+        locale,
+        "settings.sync.provider.capability.local_folder.auth",
+      ),
+      endpointMode: "managed",
+      warnings: [
+        translate(
+          locale,
+          "settings.sync.provider.capability.local_folder.warning1",
+        ),
+        translate(
+          locale,
+          "settings.sync.provider.capability.local_folder.warning2",
+        ),
+      ],
+    },
+    webdav: {
+      label: translate(
+        locale,
+        "settings.sync.provider.capability.webdav.label",
+      ),
+      summary: translate(
+        locale,
+        "settings.sync.provider.capability.webdav.summary",
+      ),
+      authRequirement: translate(
+        locale,
+        "settings.sync.provider.capability.webdav.auth",
+      ),
+      endpointMode: "managed",
+      warnings: [
+        translate(locale, "settings.sync.provider.capability.webdav.warning1"),
+        translate(locale, "settings.sync.provider.capability.webdav.warning2"),
+      ],
+    },
   };
 }
 
@@ -754,6 +812,9 @@ export function ReminderSettings({
   syncLastError,
   syncIsRunning,
   syncHasTransport,
+  syncNativeSchedulerAvailable,
+  syncNativeSchedulerEnabled,
+  onSyncNativeSchedulerEnabledChange,
   onSyncNow,
   onRetryLastFailedSync,
   syncProvider,
@@ -812,6 +873,17 @@ export function ReminderSettings({
     useState<SyncProviderManagedConnectorDraft>(() =>
       extractManagedConnectorDraftFromProviderConfig(syncProviderConfig),
     );
+  const [localFolderDirectoryDraft, setLocalFolderDirectoryDraft] =
+    useState<string>(() =>
+      extractProviderConfigString(syncProviderConfig, "directory"),
+    );
+  const [webdavBaseUrlDraft, setWebdavBaseUrlDraft] = useState<string>(() =>
+    extractProviderConfigString(syncProviderConfig, "base_url"),
+  );
+  const [webdavUsernameDraft, setWebdavUsernameDraft] = useState<string>(
+    () => extractProviderConfigString(syncProviderConfig, "username"),
+  );
+  const [webdavPasswordDraft, setWebdavPasswordDraft] = useState("");
   const [syncServerBaseUrlDraft, setSyncServerBaseUrlDraft] =
     useState<string>("");
   const [syncPushUrlDraft, setSyncPushUrlDraft] = useState<string>("");
@@ -1106,6 +1178,15 @@ export function ReminderSettings({
     setManagedConnectorDraft(
       extractManagedConnectorDraftFromProviderConfig(syncProviderConfig),
     );
+    setLocalFolderDirectoryDraft(
+      extractProviderConfigString(syncProviderConfig, "directory"),
+    );
+    setWebdavBaseUrlDraft(
+      extractProviderConfigString(syncProviderConfig, "base_url"),
+    );
+    setWebdavUsernameDraft(
+      extractProviderConfigString(syncProviderConfig, "username"),
+    );
   }, [syncProviderConfig]);
 
   useEffect(() => {
@@ -1506,8 +1587,28 @@ export function ReminderSettings({
         draft: managedConnectorDraft,
       });
     }
+    if (syncProviderDraft === "local_folder") {
+      nextProviderConfig = {
+        ...nextProviderConfig,
+        directory: localFolderDirectoryDraft.trim(),
+      };
+    }
+    if (syncProviderDraft === "webdav") {
+      nextProviderConfig = {
+        ...nextProviderConfig,
+        base_url: webdavBaseUrlDraft.trim(),
+        username: webdavUsernameDraft.trim(),
+      };
+    }
 
     try {
+      if (syncProviderDraft === "webdav" && webdavPasswordDraft.trim()) {
+        await writeSyncProviderSecret({
+          provider: "webdav",
+          secret: webdavPasswordDraft.trim(),
+        });
+        setWebdavPasswordDraft("");
+      }
       await onSaveSyncProviderSettings({
         provider: syncProviderDraft,
         provider_config: nextProviderConfig,
@@ -1580,6 +1681,19 @@ export function ReminderSettings({
     setSyncProviderSecureStoreTesting(true);
     try {
       const result = await runSyncProviderSecureStoreSelfTest();
+      if (result.roundtrip_ok && result.fallback_reason) {
+        setSyncProviderSecureStoreFeedback(
+          t(
+            "settings.sync.provider.managed.secureStoreTest.feedback.fallback",
+            {
+              backend: result.backend,
+              primary: result.primary_backend,
+              reason: result.fallback_reason,
+            },
+          ),
+        );
+        return;
+      }
       if (result.roundtrip_ok) {
         setSyncProviderSecureStoreFeedback(
           t("settings.sync.provider.managed.secureStoreTest.feedback.success", {
@@ -2662,6 +2776,106 @@ export function ReminderSettings({
             </div>
           )}
 
+          {syncProviderDraft === "local_folder" && (
+            <div className="sync-provider-capability-card">
+              <label className="settings-field">
+                <span className="settings-field-label">
+                  {t("settings.sync.provider.localFolder.directory")}
+                </span>
+                <input
+                  className="settings-input"
+                  type="text"
+                  autoComplete="off"
+                  spellCheck={false}
+                  placeholder={t(
+                    "settings.sync.provider.localFolder.directoryPlaceholder",
+                  )}
+                  value={localFolderDirectoryDraft}
+                  onChange={(event) => {
+                    setSyncProviderFeedback(null);
+                    setSyncProviderError(null);
+                    setLocalFolderDirectoryDraft(event.target.value);
+                  }}
+                  disabled={syncProviderSaving || syncProviderLoading}
+                />
+              </label>
+              <p className="settings-row-subtitle">
+                {t("settings.sync.provider.localFolder.directoryHint")}
+              </p>
+            </div>
+          )}
+
+          {syncProviderDraft === "webdav" && (
+            <div className="sync-provider-capability-card">
+              <div className="sync-provider-managed-grid">
+                <label className="settings-field">
+                  <span className="settings-field-label">
+                    {t("settings.sync.provider.webdav.baseUrl")}
+                  </span>
+                  <input
+                    className="settings-input"
+                    type="url"
+                    inputMode="url"
+                    autoComplete="off"
+                    spellCheck={false}
+                    placeholder={t(
+                      "settings.sync.provider.webdav.baseUrlPlaceholder",
+                    )}
+                    value={webdavBaseUrlDraft}
+                    onChange={(event) => {
+                      setSyncProviderFeedback(null);
+                      setSyncProviderError(null);
+                      setWebdavBaseUrlDraft(event.target.value);
+                    }}
+                    disabled={syncProviderSaving || syncProviderLoading}
+                  />
+                </label>
+                <label className="settings-field">
+                  <span className="settings-field-label">
+                    {t("settings.sync.provider.webdav.username")}
+                  </span>
+                  <input
+                    className="settings-input"
+                    type="text"
+                    autoComplete="off"
+                    spellCheck={false}
+                    value={webdavUsernameDraft}
+                    onChange={(event) => {
+                      setSyncProviderFeedback(null);
+                      setSyncProviderError(null);
+                      setWebdavUsernameDraft(event.target.value);
+                    }}
+                    disabled={syncProviderSaving || syncProviderLoading}
+                  />
+                </label>
+                <label className="settings-field">
+                  <span className="settings-field-label">
+                    {t("settings.sync.provider.webdav.password")}
+                  </span>
+                  <input
+                    className="settings-input"
+                    type="password"
+                    autoComplete="new-password"
+                    spellCheck={false}
+                    placeholder={t(
+                      "settings.sync.provider.webdav.passwordPlaceholder",
+                    )}
+                    value={webdavPasswordDraft}
+                    onChange={(event) => {
+                      setSyncProviderFeedback(null);
+                      setSyncProviderError(null);
+                      setWebdavPasswordDraft(event.target.value);
+                    }}
+                    disabled={syncProviderSaving || syncProviderLoading}
+                  />
+                </label>
+              </div>
+              <p className="settings-row-subtitle">
+                {t("settings.sync.provider.webdav.passwordHint")}
+              </p>
+            </div>
+          )}
+
           <div className="settings-actions">
             <button
               type="button"
@@ -2847,6 +3061,30 @@ export function ReminderSettings({
               {t("settings.sync.runtime.desc")}
             </p>
           </div>
+          {syncNativeSchedulerAvailable && (
+            <div className="settings-row">
+              <div>
+                <p className="settings-row-title">
+                  {t("settings.sync.runtime.nativeScheduler.title")}
+                </p>
+                <p className="settings-row-subtitle">
+                  {t("settings.sync.runtime.nativeScheduler.desc")}
+                </p>
+              </div>
+              <button
+                type="button"
+                className={`toggle-btn${syncNativeSchedulerEnabled ? " enabled" : ""}`}
+                onClick={() =>
+                  onSyncNativeSchedulerEnabledChange(
+                    !syncNativeSchedulerEnabled,
+                  )
+                }
+                aria-pressed={syncNativeSchedulerEnabled}
+              >
+                <span className="toggle-thumb" />
+              </button>
+            </div>
+          )}
           <div className="sync-runtime-grid">
             <label className="settings-field">
               <span className="settings-field-label">
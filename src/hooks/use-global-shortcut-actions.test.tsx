@@ -0,0 +1,60 @@
+import { renderHook } from "@testing-library/react";
+import { useGlobalShortcutActions } from "@/hooks/use-global-shortcut-actions";
+
+const isTauriMock = vi.fn();
+const listenMock = vi.fn();
+
+vi.mock("@tauri-apps/api/core", () => ({
+  isTauri: () => isTauriMock(),
+}));
+
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: (...args: unknown[]) => listenMock(...args),
+}));
+
+describe("useGlobalShortcutActions", () => {
+  beforeEach(() => {
+    isTauriMock.mockReset();
+    listenMock.mockReset();
+  });
+
+  it("does nothing when runtime is not tauri", () => {
+    isTauriMock.mockReturnValue(false);
+
+    renderHook(() =>
+      useGlobalShortcutActions({
+        onOpenToday: vi.fn(),
+        onStartFocusSession: vi.fn(),
+      }),
+    );
+    expect(listenMock).not.toHaveBeenCalled();
+  });
+
+  it("routes each shortcut event to its handler", async () => {
+    isTauriMock.mockReturnValue(true);
+    const onOpenToday = vi.fn();
+    const onStartFocusSession = vi.fn();
+    const detachMock = vi.fn();
+    const handlers = new Map<string, () => void>();
+
+    listenMock.mockImplementation(
+      async (eventName: string, handler: () => void) => {
+        handlers.set(eventName, handler);
+        return detachMock;
+      },
+    );
+
+    const { unmount } = renderHook(() =>
+      useGlobalShortcutActions({ onOpenToday, onStartFocusSession }),
+    );
+    await Promise.resolve();
+
+    handlers.get("today-view:open")?.();
+    handlers.get("focus-session:start")?.();
+    expect(onOpenToday).toHaveBeenCalledTimes(1);
+    expect(onStartFocusSession).toHaveBeenCalledTimes(1);
+
+    unmount();
+    expect(detachMock).toHaveBeenCalledTimes(2);
+  });
+});
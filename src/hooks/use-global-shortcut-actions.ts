@@ -0,0 +1,48 @@
+import { useEffect } from "react";
+import { isTauri } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+const OPEN_TODAY_EVENT = "today-view:open";
+const START_FOCUS_SESSION_EVENT = "focus-session:start";
+
+// Quick capture keeps its own hook; these are the other global shortcut actions.
+export function useGlobalShortcutActions(handlers: {
+  onOpenToday: () => void;
+  onStartFocusSession: () => void;
+}): void {
+  const { onOpenToday, onStartFocusSession } = handlers;
+
+  useEffect(() => {
+    if (typeof window === "undefined" || !isTauri()) return;
+
+    let disposed = false;
+    const unlisteners: Array<() => void> = [];
+
+    const registerListener = async (event: string, handler: () => void) => {
+      try {
+        const detach = await listen(event, () => {
+          handler();
+        });
+
+        if (disposed) {
+          detach();
+          return;
+        }
+
+        unlisteners.push(detach);
+      } catch {
+        // Ignore runtimes where backend event bridge is unavailable.
+      }
+    };
+
+    void registerListener(OPEN_TODAY_EVENT, onOpenToday);
+    void registerListener(START_FOCUS_SESSION_EVENT, onStartFocusSession);
+
+    return () => {
+      disposed = true;
+      for (const unlisten of unlisteners.splice(0)) {
+        unlisten();
+      }
+    };
+  }, [onOpenToday, onStartFocusSession]);
+}
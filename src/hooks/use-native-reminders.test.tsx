@@ -0,0 +1,59 @@
+import { renderHook, waitFor } from "@testing-library/react";
+import { useNativeReminders } from "@/hooks/use-native-reminders";
+
+const isTauriMock = vi.fn();
+const invokeMock = vi.fn();
+
+vi.mock("@tauri-apps/api/core", () => ({
+  isTauri: () => isTauriMock(),
+  invoke: (...args: unknown[]) => invokeMock(...args),
+}));
+
+describe("useNativeReminders", () => {
+  beforeEach(() => {
+    isTauriMock.mockReset();
+    invokeMock.mockReset();
+  });
+
+  it("keeps webview reminders outside tauri", async () => {
+    isTauriMock.mockReturnValue(false);
+
+    const { result } = renderHook(() =>
+      useNativeReminders({ enabled: true, locale: "en", tasksUpdatedAt: 0 }),
+    );
+    expect(result.current).toBe("pending");
+    await waitFor(() => expect(result.current).toBe("webview"));
+    expect(invokeMock).not.toHaveBeenCalled();
+  });
+
+  it("falls back to webview reminders when the scheduler is missing", async () => {
+    isTauriMock.mockReturnValue(true);
+    invokeMock.mockRejectedValue(new Error("command not found"));
+
+    const { result } = renderHook(() =>
+      useNativeReminders({ enabled: true, locale: "en", tasksUpdatedAt: 0 }),
+    );
+    await waitFor(() => expect(result.current).toBe("webview"));
+  });
+
+  it("configures the scheduler and resyncs when tasks change", async () => {
+    isTauriMock.mockReturnValue(true);
+    invokeMock.mockResolvedValue(undefined);
+
+    const { result, rerender } = renderHook(
+      ({ tasksUpdatedAt }) =>
+        useNativeReminders({ enabled: false, locale: "th", tasksUpdatedAt }),
+      { initialProps: { tasksUpdatedAt: 0 } },
+    );
+    await waitFor(() => expect(result.current).toBe("native"));
+    expect(invokeMock).toHaveBeenCalledWith("configure_native_reminders", {
+      enabled: false,
+      locale: "th",
+    });
+
+    rerender({ tasksUpdatedAt: 1_700_000_000_000 });
+    await waitFor(() =>
+      expect(invokeMock).toHaveBeenCalledWith("resync_native_reminders"),
+    );
+  });
+});
@@ -0,0 +1,39 @@
+import { useEffect, useState } from "react";
+import {
+  configureNativeReminders,
+  resyncNativeReminders,
+} from "@/lib/native-reminders";
+import type { AppLocale } from "@/lib/types";
+
+// `pending` until the runtime answers, so the webview loop never fires a reminder the native
+// scheduler is about to fire as well.
+export type ReminderDeliveryMode = "pending" | "native" | "webview";
+
+export function useNativeReminders(input: {
+  enabled: boolean;
+  locale: AppLocale;
+  // The task query's `dataUpdatedAt`; each change asks the scheduler to re-read the tasks.
+  tasksUpdatedAt: number;
+}): ReminderDeliveryMode {
+  const { enabled, locale, tasksUpdatedAt } = input;
+  const [mode, setMode] = useState<ReminderDeliveryMode>("pending");
+
+  useEffect(() => {
+    let disposed = false;
+    void configureNativeReminders({ enabled, locale }).then((isNative) => {
+      if (!disposed) setMode(isNative ? "native" : "webview");
+    });
+    return () => {
+      disposed = true;
+    };
+  }, [enabled, locale]);
+
+  useEffect(() => {
+    if (mode !== "native" || tasksUpdatedAt === 0) return;
+    void resyncNativeReminders().catch(() => {
+      // The scheduler re-reads tasks on its own within a minute.
+    });
+  }, [mode, tasksUpdatedAt]);
+
+  return mode;
+}
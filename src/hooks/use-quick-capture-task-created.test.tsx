@@ -0,0 +1,57 @@
+import { renderHook } from "@testing-library/react";
+import { useQuickCaptureTaskCreated } from "@/hooks/use-quick-capture-task-created";
+
+const isTauriMock = vi.fn();
+const listenMock = vi.fn();
+
+vi.mock("@tauri-apps/api/core", () => ({
+  isTauri: () => isTauriMock(),
+}));
+
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: (...args: unknown[]) => listenMock(...args),
+}));
+
+describe("useQuickCaptureTaskCreated", () => {
+  beforeEach(() => {
+    isTauriMock.mockReset();
+    listenMock.mockReset();
+  });
+
+  it("does nothing when runtime is not tauri", () => {
+    isTauriMock.mockReturnValue(false);
+
+    renderHook(() => useQuickCaptureTaskCreated(vi.fn()));
+    expect(listenMock).not.toHaveBeenCalled();
+  });
+
+  it("calls back when the capture window saves a task", async () => {
+    isTauriMock.mockReturnValue(true);
+    const onCreated = vi.fn();
+    const detachMock = vi.fn();
+    let handlerRef: (() => void) | null = null;
+
+    listenMock.mockImplementation(
+      async (_eventName: string, handler: () => void) => {
+        handlerRef = handler;
+        return detachMock;
+      },
+    );
+
+    const { unmount } = renderHook(() =>
+      useQuickCaptureTaskCreated(onCreated),
+    );
+    await Promise.resolve();
+
+    expect(listenMock).toHaveBeenCalledWith(
+      "quick-capture:task-created",
+      expect.any(Function),
+    );
+
+    handlerRef?.();
+    expect(onCreated).toHaveBeenCalledTimes(1);
+
+    unmount();
+    expect(detachMock).toHaveBeenCalledTimes(1);
+  });
+});
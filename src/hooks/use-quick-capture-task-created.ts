@@ -0,0 +1,43 @@
+import { useEffect } from "react";
+import { isTauri } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+const QUICK_CAPTURE_TASK_CREATED_EVENT = "quick-capture:task-created";
+
+// Tasks saved from the quick capture window are written by the Rust side, so the main window
+// only learns about them through this event.
+export function useQuickCaptureTaskCreated(onCreated: () => void): void {
+  useEffect(() => {
+    if (typeof window === "undefined" || !isTauri()) return;
+
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    const registerListener = async () => {
+      try {
+        const detach = await listen(QUICK_CAPTURE_TASK_CREATED_EVENT, () => {
+          onCreated();
+        });
+
+        if (disposed) {
+          detach();
+          return;
+        }
+
+        unlisten = detach;
+      } catch {
+        // Ignore runtimes where backend event bridge is unavailable.
+      }
+    };
+
+    void registerListener();
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+        unlisten = null;
+      }
+    };
+  }, [onCreated]);
+}
@@ -0,0 +1,55 @@
+import { renderHook } from "@testing-library/react";
+import { useReminderActionApplied } from "@/hooks/use-reminder-action-applied";
+
+const isTauriMock = vi.fn();
+const listenMock = vi.fn();
+
+vi.mock("@tauri-apps/api/core", () => ({
+  isTauri: () => isTauriMock(),
+}));
+
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: (...args: unknown[]) => listenMock(...args),
+}));
+
+describe("useReminderActionApplied", () => {
+  beforeEach(() => {
+    isTauriMock.mockReset();
+    listenMock.mockReset();
+  });
+
+  it("does nothing when runtime is not tauri", () => {
+    isTauriMock.mockReturnValue(false);
+
+    renderHook(() => useReminderActionApplied(vi.fn()));
+    expect(listenMock).not.toHaveBeenCalled();
+  });
+
+  it("calls back when a notification action updates a task", async () => {
+    isTauriMock.mockReturnValue(true);
+    const onApplied = vi.fn();
+    const detachMock = vi.fn();
+    let handlerRef: (() => void) | null = null;
+
+    listenMock.mockImplementation(
+      async (_eventName: string, handler: () => void) => {
+        handlerRef = handler;
+        return detachMock;
+      },
+    );
+
+    const { unmount } = renderHook(() => useReminderActionApplied(onApplied));
+    await Promise.resolve();
+
+    expect(listenMock).toHaveBeenCalledWith(
+      "reminders:action-applied",
+      expect.any(Function),
+    );
+
+    handlerRef?.();
+    expect(onApplied).toHaveBeenCalledTimes(1);
+
+    unmount();
+    expect(detachMock).toHaveBeenCalledTimes(1);
+  });
+});
@@ -0,0 +1,43 @@
+import { useEffect } from "react";
+import { isTauri } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+const REMINDER_ACTION_APPLIED_EVENT = "reminders:action-applied";
+
+// Snooze and done buttons on native reminder notifications write the task from the Rust side,
+// so the main window only learns about the change through this event.
+export function useReminderActionApplied(onApplied: () => void): void {
+  useEffect(() => {
+    if (typeof window === "undefined" || !isTauri()) return;
+
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    const registerListener = async () => {
+      try {
+        const detach = await listen(REMINDER_ACTION_APPLIED_EVENT, () => {
+          onApplied();
+        });
+
+        if (disposed) {
+          detach();
+          return;
+        }
+
+        unlisten = detach;
+      } catch {
+        // Ignore runtimes where backend event bridge is unavailable.
+      }
+    };
+
+    void registerListener();
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+        unlisten = null;
+      }
+    };
+  }, [onApplied]);
+}
@@ -0,0 +1,75 @@
+import { renderHook } from "@testing-library/react";
+import { useSyncAuthRefresh } from "@/hooks/use-sync-auth-refresh";
+import {
+  clearSyncProviderSessionAuthForTests,
+  getSyncProviderSessionAuth,
+  setSyncProviderSessionAuth,
+} from "@/lib/sync-provider-token-policy";
+
+const isTauriMock = vi.fn();
+const listenMock = vi.fn();
+
+vi.mock("@tauri-apps/api/core", () => ({
+  isTauri: () => isTauriMock(),
+}));
+
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: (...args: unknown[]) => listenMock(...args),
+}));
+
+describe("useSyncAuthRefresh", () => {
+  beforeEach(() => {
+    isTauriMock.mockReset();
+    listenMock.mockReset();
+    clearSyncProviderSessionAuthForTests();
+  });
+
+  it("does nothing when runtime is not tauri", () => {
+    isTauriMock.mockReturnValue(false);
+
+    renderHook(() => useSyncAuthRefresh());
+    expect(listenMock).not.toHaveBeenCalled();
+  });
+
+  it("drops the cached session auth when a token is refreshed", async () => {
+    isTauriMock.mockReturnValue(true);
+    const detachMock = vi.fn();
+    let handlerRef: ((event: { payload: unknown }) => void) | null = null;
+
+    listenMock.mockImplementation(
+      async (
+        _eventName: string,
+        handler: (event: { payload: unknown }) => void,
+      ) => {
+        handlerRef = handler;
+        return detachMock;
+      },
+    );
+    setSyncProviderSessionAuth({
+      provider: "google_appdata",
+      auth: {
+        access_token: "access-1",
+        token_type: "Bearer",
+        refresh_token: "refresh-1",
+        token_refresh_url: "https://oauth2.googleapis.com/token",
+        expires_at: null,
+        scope: null,
+        client_id: null,
+        client_secret: null,
+      },
+    });
+
+    const { unmount } = renderHook(() => useSyncAuthRefresh());
+    await Promise.resolve();
+
+    expect(listenMock).toHaveBeenCalledWith(
+      "sync-auth:refreshed",
+      expect.any(Function),
+    );
+    handlerRef?.({ payload: { provider: "google_appdata" } });
+    expect(getSyncProviderSessionAuth("google_appdata")).toBeNull();
+
+    unmount();
+    expect(detachMock).toHaveBeenCalledTimes(1);
+  });
+});
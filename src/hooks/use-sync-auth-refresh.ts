@@ -0,0 +1,57 @@
+import { useEffect } from "react";
+import { isTauri } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { setSyncProviderSessionAuth } from "@/lib/sync-provider-token-policy";
+import type { SyncProvider } from "@/lib/types";
+
+const SYNC_AUTH_REFRESHED_EVENT = "sync-auth:refreshed";
+
+interface SyncAuthRefreshedPayload {
+  provider?: unknown;
+}
+
+// The Rust refresh task renews tokens in the secure store. Dropping the in-memory session copy
+// makes the next sync cycle hydrate the renewed token instead of the expired one.
+export function useSyncAuthRefresh(): void {
+  useEffect(() => {
+    if (typeof window === "undefined" || !isTauri()) return;
+
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    const registerListener = async () => {
+      try {
+        const detach = await listen<SyncAuthRefreshedPayload>(
+          SYNC_AUTH_REFRESHED_EVENT,
+          (event) => {
+            const provider = event.payload?.provider;
+            if (typeof provider !== "string" || !provider) return;
+            setSyncProviderSessionAuth({
+              provider: provider as SyncProvider,
+              auth: null,
+            });
+          },
+        );
+
+        if (disposed) {
+          detach();
+          return;
+        }
+
+        unlisten = detach;
+      } catch {
+        // Ignore runtimes where backend event bridge is unavailable.
+      }
+    };
+
+    void registerListener();
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+        unlisten = null;
+      }
+    };
+  }, []);
+}
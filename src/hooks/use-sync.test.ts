@@ -3,6 +3,7 @@ import {
   appendSyncSessionDiagnostics,
   calculateSyncBackoffMs,
   createInitialSyncSessionDiagnostics,
+  describeNativeSyncStatus,
   getAutoSyncIntervalMsForVisibility,
   normalizeSyncRuntimeProfile,
   normalizeSyncRuntimeProfileWithValidation,
@@ -256,3 +257,72 @@ describe("sync runtime profile validation helpers", () => {
     });
   });
 });
+
+describe("native sync status", () => {
+  const idle = {
+    paused_reason: null,
+    error: null,
+    summary: null,
+    next_attempt_in_ms: null,
+  };
+
+  it("explains why the scheduler paused", () => {
+    expect(
+      describeNativeSyncStatus(
+        { ...idle, status: "OFFLINE", paused_reason: "network_offline" },
+        "en",
+      ),
+    ).toEqual({
+      status: "OFFLINE",
+      isSyncing: false,
+      lastError: "You're offline. Sync will retry when network returns.",
+    });
+    expect(
+      describeNativeSyncStatus(
+        { ...idle, status: "SYNCED", paused_reason: "battery_saver" },
+        "en",
+      ).lastError,
+    ).toBe("Background sync is paused while battery saver is on.");
+  });
+
+  it("surfaces cycle errors and conflicts", () => {
+    expect(
+      describeNativeSyncStatus(
+        {
+          ...idle,
+          status: "CONFLICT",
+          error: {
+            code: "SYNC_UNAUTHORIZED",
+            detail: "sync server rejected the credentials",
+            retryable: false,
+          },
+          next_attempt_in_ms: 5_000,
+        },
+        "en",
+      ).lastError,
+    ).toBe("sync server rejected the credentials");
+
+    const conflict = describeNativeSyncStatus(
+      {
+        ...idle,
+        status: "CONFLICT",
+        summary: {
+          device_id: "device-a",
+          checkpoint_before: null,
+          checkpoint_after: "cursor-1",
+          prepared_push_changes: 1,
+          skipped_push_changes: 0,
+          removed_outbox_changes: 0,
+          failed_outbox_changes: 1,
+          pending_outbox_changes: 1,
+          pull: null,
+        },
+      },
+      "en",
+    );
+    expect(conflict.lastError).toBe("1 outbox change(s) failed.");
+    expect(
+      describeNativeSyncStatus({ ...idle, status: "SYNCING" }, "en"),
+    ).toEqual({ status: "SYNCING", isSyncing: true, lastError: null });
+  });
+});
@@ -1,11 +1,24 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { useQueryClient } from "@tanstack/react-query";
+import { listen } from "@tauri-apps/api/event";
 import {
   appendSyncSessionDiagnosticsSnapshot,
   getSyncCheckpoint,
 } from "@/lib/database";
+import {
+  configureNativeSyncScheduler,
+  getNativeSyncStatus,
+  NATIVE_SYNC_STATUS_EVENT,
+  reportNativeSyncEnvironment,
+  runNativeSyncCycle,
+  watchBatterySaver,
+  type NativeSyncStatusPayload,
+} from "@/lib/native-sync";
 import { runLocalSyncCycle } from "@/lib/sync-service";
-import { resolveSyncTransportConfig } from "@/lib/sync-transport";
+import {
+  resolveSyncTransportConfig,
+  supportsNativeSyncCycle,
+} from "@/lib/sync-transport";
 import { translate } from "@/lib/i18n";
 import { localizeErrorMessage } from "@/lib/error-message";
 import type { RunSyncCycleSummary } from "@/lib/sync-runner";
@@ -26,6 +39,10 @@ const DEFAULT_SYNC_MAX_PULL_PAGES = 5;
 const RETRY_BASE_DELAY_MS = 5_000;
 const RETRY_MAX_DELAY_MS = 300_000;
 
+// `pending` until the runtime answers, so the webview loop never starts a
+// cycle the native scheduler is about to run as well.
+export type SyncDriver = "pending" | "native" | "webview";
+
 interface UseSyncState {
   status: SyncStatus;
   isSyncing: boolean;
@@ -34,6 +51,7 @@ interface UseSyncState {
   lastSyncedAt: string | null;
   lastError: string | null;
   diagnostics: SyncSessionDiagnostics;
+  driver: SyncDriver;
   syncNow: () => Promise<void>;
   retryLastFailedSync: () => Promise<boolean>;
 }
@@ -54,6 +72,8 @@ interface UseSyncOptions {
   pushLimit?: number;
   pullLimit?: number;
   maxPullPages?: number;
+  // Hands periodic sync to the Rust scheduler where runtime and provider allow.
+  nativeScheduler?: boolean;
   locale?: AppLocale;
 }
 
@@ -367,6 +387,44 @@ function buildConflictMessage(
   return `${parts.join(", ")}.`;
 }
 
+export interface NativeSyncStatusView {
+  status: SyncStatus;
+  isSyncing: boolean;
+  lastError: string | null;
+}
+
+export function describeNativeSyncStatus(
+  payload: NativeSyncStatusPayload,
+  locale: AppLocale,
+): NativeSyncStatusView {
+  const view = {
+    status: payload.status,
+    isSyncing: payload.status === "SYNCING",
+  };
+  if (payload.paused_reason === "battery_saver") {
+    return {
+      ...view,
+      lastError: translate(locale, "sync.paused.batterySaver"),
+    };
+  }
+  if (payload.paused_reason === "network_offline") {
+    return {
+      ...view,
+      lastError: translate(locale, "sync.offline.retryNetworkReturn"),
+    };
+  }
+  if (payload.error) {
+    return { ...view, lastError: getErrorMessage(payload.error, locale) };
+  }
+  if (payload.status === "CONFLICT" && payload.summary) {
+    return {
+      ...view,
+      lastError: buildConflictMessage(payload.summary, locale),
+    };
+  }
+  return { ...view, lastError: null };
+}
+
 function buildMigrationSyncWriteBlockedMessage(
   locale: AppLocale,
   reason: string | null | undefined,
@@ -382,6 +440,9 @@ function buildMigrationSyncWriteBlockedMessage(
 
 export function useSync(options: UseSyncOptions): UseSyncState {
   const locale = options.locale ?? "en";
+  const wantsNativeScheduler =
+    (options.nativeScheduler ?? false) &&
+    supportsNativeSyncCycle(options.provider);
   const isConfigReady = options.configReady ?? true;
   const syncWriteBlocked = options.syncWriteBlocked ?? false;
   const syncWriteBlockedMessage = useMemo(
@@ -479,7 +540,10 @@ export function useSync(options: UseSyncOptions): UseSyncState {
   const [diagnostics, setDiagnostics] = useState<SyncSessionDiagnostics>(() =>
     createInitialSyncSessionDiagnostics(),
   );
+  const [driver, setDriver] = useState<SyncDriver>("pending");
+  const [batterySaver, setBatterySaver] = useState<boolean | null>(null);
   const inFlightRef = useRef(false);
+  const nativeCycleStartedAtRef = useRef<number | null>(null);
   const consecutiveFailuresRef = useRef(0);
   const nextAutoAttemptAtRef = useRef(0);
   const lastProviderRef = useRef<SyncProvider | null>(null);
@@ -612,6 +676,21 @@ export function useSync(options: UseSyncOptions): UseSyncState {
         return;
       }
 
+      if (driver === "pending") return;
+      if (driver === "native") {
+        // The scheduler serializes this with its own cycles and reports it
+        // through `sync:status`.
+        inFlightRef.current = true;
+        try {
+          await runNativeSyncCycle({ pushLimit, pullLimit, maxPullPages });
+        } catch (error) {
+          setLastError(getErrorMessage(error, locale));
+        } finally {
+          inFlightRef.current = false;
+        }
+        return;
+      }
+
       inFlightRef.current = true;
       setIsSyncing(true);
       setStatus("SYNCING");
@@ -685,6 +764,7 @@ export function useSync(options: UseSyncOptions): UseSyncState {
       }
     },
     [
+      driver,
       effectiveTransport,
       isConfigReady,
       isOnline,
@@ -712,6 +792,111 @@ export function useSync(options: UseSyncOptions): UseSyncState {
     return true;
   }, [lastError, runSync]);
 
+  useEffect(() => {
+    // The provider is not known until the settings load.
+    if (!isConfigReady) return;
+
+    let disposed = false;
+    setDriver("pending");
+    void configureNativeSyncScheduler(wantsNativeScheduler).then((isNative) => {
+      if (disposed) return;
+      setDriver(wantsNativeScheduler && isNative ? "native" : "webview");
+    });
+    return () => {
+      disposed = true;
+    };
+  }, [isConfigReady, wantsNativeScheduler]);
+
+  const applyNativeSyncStatus = useCallback(
+    (payload: NativeSyncStatusPayload) => {
+      const view = describeNativeSyncStatus(payload, locale);
+      setStatus(view.status);
+      setIsSyncing(view.isSyncing);
+      setLastError(view.lastError);
+      if (view.isSyncing) {
+        nativeCycleStartedAtRef.current = Date.now();
+        return;
+      }
+
+      // Pauses and the replayed last status are not cycles.
+      const startedAt = nativeCycleStartedAtRef.current;
+      nativeCycleStartedAtRef.current = null;
+      if (startedAt === null || payload.paused_reason) return;
+      setDiagnostics((previous) =>
+        appendSyncSessionDiagnostics(previous, {
+          outcome: payload.error ? "failure" : "success",
+          attemptedAt: new Date().toISOString(),
+          durationMs: Date.now() - startedAt,
+          hasConflict: payload.status === "CONFLICT",
+        }),
+      );
+      if (!payload.summary) return;
+      void getSyncCheckpoint()
+        .then((checkpoint) => {
+          setLastSyncedAt(
+            checkpoint.last_synced_at ?? new Date().toISOString(),
+          );
+        })
+        .catch(() => {
+          // The next status carries another chance to read the checkpoint.
+        });
+      void queryClient.invalidateQueries();
+    },
+    [locale, queryClient],
+  );
+
+  useEffect(() => {
+    if (driver !== "native") return;
+
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    const registerListener = async () => {
+      try {
+        const detach = await listen<NativeSyncStatusPayload>(
+          NATIVE_SYNC_STATUS_EVENT,
+          (event) => applyNativeSyncStatus(event.payload),
+        );
+
+        if (disposed) {
+          detach();
+          return;
+        }
+
+        unlisten = detach;
+        const lastStatus = await getNativeSyncStatus();
+        if (!disposed && lastStatus) applyNativeSyncStatus(lastStatus);
+      } catch {
+        // Ignore runtimes where backend event bridge is unavailable.
+      }
+    };
+
+    void registerListener();
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+        unlisten = null;
+      }
+    };
+  }, [applyNativeSyncStatus, driver]);
+
+  useEffect(() => {
+    if (driver !== "native") return;
+    return watchBatterySaver(setBatterySaver);
+  }, [driver]);
+
+  useEffect(() => {
+    if (driver !== "native") return;
+    void reportNativeSyncEnvironment({
+      online: isOnline,
+      batterySaver: batterySaver ?? undefined,
+    }).catch(() => {
+      // Linux probes both itself; elsewhere unknown counts as fine.
+    });
+  }, [batterySaver, driver, isOnline]);
+
   useEffect(() => {
     const handleOnline = () => setIsOnline(true);
     const handleOffline = () => setIsOnline(false);
@@ -780,8 +965,10 @@ export function useSync(options: UseSyncOptions): UseSyncState {
       return;
     }
 
+    if (driver !== "webview") return;
     void runSync(false);
   }, [
+    driver,
     effectiveTransport,
     isConfigReady,
     isOnline,
@@ -794,6 +981,7 @@ export function useSync(options: UseSyncOptions): UseSyncState {
   ]);
 
   useEffect(() => {
+    if (driver !== "webview") return;
     if (!isConfigReady || syncWriteBlocked || !effectiveTransport) return;
     if (resolvedTransportConfig.status === "provider_unavailable") return;
 
@@ -807,6 +995,7 @@ export function useSync(options: UseSyncOptions): UseSyncState {
 
     return () => window.clearInterval(intervalId);
   }, [
+    driver,
     effectiveTransport,
     isConfigReady,
     isDocumentVisible,
@@ -817,12 +1006,14 @@ export function useSync(options: UseSyncOptions): UseSyncState {
   ]);
 
   useEffect(() => {
+    if (driver !== "webview") return;
     if (!isDocumentVisible) return;
     if (!isConfigReady || syncWriteBlocked || !effectiveTransport || !isOnline)
       return;
     if (resolvedTransportConfig.status === "provider_unavailable") return;
     void runSync(false);
   }, [
+    driver,
     effectiveTransport,
     isConfigReady,
     isDocumentVisible,
@@ -840,6 +1031,7 @@ export function useSync(options: UseSyncOptions): UseSyncState {
     lastSyncedAt,
     lastError,
     diagnostics,
+    driver,
     syncNow,
     retryLastFailedSync,
   };
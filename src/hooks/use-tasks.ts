@@ -1,4 +1,10 @@
-import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { useCallback } from "react";
+import {
+  useQuery,
+  useMutation,
+  useQueryClient,
+  type QueryClient,
+} from "@tanstack/react-query";
 import {
   getAppLocaleSetting,
   getMigrationDiagnosticsSetting,
@@ -187,6 +193,17 @@ export function useTaskSubtaskStats(taskIds: string[], enabled = true) {
   });
 }
 
+function invalidateCreatedTaskQueries(queryClient: QueryClient): void {
+  queryClient.invalidateQueries({ queryKey: TASKS_KEY });
+  queryClient.invalidateQueries({ queryKey: TODAY_TASKS_KEY });
+  queryClient.invalidateQueries({ queryKey: UPCOMING_TASKS_KEY });
+  queryClient.invalidateQueries({ queryKey: STATS_KEY });
+  queryClient.invalidateQueries({ queryKey: WEEKLY_REVIEW_KEY });
+  queryClient.invalidateQueries({ queryKey: CHANGELOGS_KEY });
+  queryClient.invalidateQueries({ queryKey: TASK_SUBTASKS_KEY });
+  queryClient.invalidateQueries({ queryKey: TASK_SUBTASK_STATS_KEY });
+}
+
 /** Create a new task */
 export function useCreateTask() {
   const queryClient = useQueryClient();
@@ -194,18 +211,20 @@ export function useCreateTask() {
   return useMutation({
     mutationFn: (input: CreateTaskInput) => createTask(input),
     onSuccess: () => {
-      queryClient.invalidateQueries({ queryKey: TASKS_KEY });
-      queryClient.invalidateQueries({ queryKey: TODAY_TASKS_KEY });
-      queryClient.invalidateQueries({ queryKey: UPCOMING_TASKS_KEY });
-      queryClient.invalidateQueries({ queryKey: STATS_KEY });
-      queryClient.invalidateQueries({ queryKey: WEEKLY_REVIEW_KEY });
-      queryClient.invalidateQueries({ queryKey: CHANGELOGS_KEY });
-      queryClient.invalidateQueries({ queryKey: TASK_SUBTASKS_KEY });
-      queryClient.invalidateQueries({ queryKey: TASK_SUBTASK_STATS_KEY });
+      invalidateCreatedTaskQueries(queryClient);
     },
   });
 }
 
+/** Refresh task queries after tasks were written outside this window */
+export function useRefreshTaskQueries() {
+  const queryClient = useQueryClient();
+
+  return useCallback(() => {
+    invalidateCreatedTaskQueries(queryClient);
+  }, [queryClient]);
+}
+
 /** Create a new project */
 export function useCreateProject() {
   const queryClient = useQueryClient();
@@ -0,0 +1,80 @@
+import { act, renderHook, waitFor } from "@testing-library/react";
+import { useTray } from "@/hooks/use-tray";
+
+const isTauriMock = vi.fn();
+const invokeMock = vi.fn();
+const listenMock = vi.fn();
+
+vi.mock("@tauri-apps/api/core", () => ({
+  isTauri: () => isTauriMock(),
+  invoke: (...args: unknown[]) => invokeMock(...args),
+}));
+
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: (...args: unknown[]) => listenMock(...args),
+}));
+
+describe("useTray", () => {
+  beforeEach(() => {
+    isTauriMock.mockReset();
+    invokeMock.mockReset();
+    listenMock.mockReset();
+  });
+
+  it("does nothing when runtime is not tauri", () => {
+    isTauriMock.mockReturnValue(false);
+
+    const { result } = renderHook(() => useTray(0));
+    expect(result.current.remindersPaused).toBe(false);
+    expect(listenMock).not.toHaveBeenCalled();
+    expect(invokeMock).not.toHaveBeenCalled();
+  });
+
+  it("follows the paused state and refreshes counts when tasks change", async () => {
+    isTauriMock.mockReturnValue(true);
+    invokeMock.mockImplementation(async (command: string) =>
+      command === "get_tray_settings"
+        ? { keep_running_in_tray: false, reminders_paused: true }
+        : undefined,
+    );
+    const detachMock = vi.fn();
+    let handlerRef: ((event: { payload: unknown }) => void) | null = null;
+    listenMock.mockImplementation(
+      async (
+        _eventName: string,
+        handler: (event: { payload: unknown }) => void,
+      ) => {
+        handlerRef = handler;
+        return detachMock;
+      },
+    );
+
+    const { result, rerender, unmount } = renderHook(
+      ({ tasksUpdatedAt }) => useTray(tasksUpdatedAt),
+      { initialProps: { tasksUpdatedAt: 0 } },
+    );
+    await waitFor(() => expect(result.current.remindersPaused).toBe(true));
+    expect(listenMock).toHaveBeenCalledWith(
+      "reminders:paused-changed",
+      expect.any(Function),
+    );
+
+    act(() => {
+      handlerRef?.({ payload: { paused: false } });
+    });
+    expect(result.current.remindersPaused).toBe(false);
+
+    rerender({ tasksUpdatedAt: 1_700_000_000_000 });
+    rerender({ tasksUpdatedAt: 1_700_000_000_000 });
+    await waitFor(() =>
+      expect(
+        invokeMock.mock.calls.filter(
+          ([command]) => command === "refresh_tray_task_counts",
+        ),
+      ).toHaveLength(1),
+    );
+
+    unmount();
+    expect(detachMock).toHaveBeenCalledTimes(1);
+  });
+});
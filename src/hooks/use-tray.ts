@@ -0,0 +1,74 @@
+import { useEffect, useState } from "react";
+import { isTauri } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { getTraySettings, refreshTrayTaskCounts } from "@/lib/tray";
+
+const REMINDERS_PAUSED_EVENT = "reminders:paused-changed";
+
+interface RemindersPausedPayload {
+  paused?: unknown;
+}
+
+// Keeps the tray counts in step with the task list and reports whether reminders were paused
+// from the tray menu. `tasksUpdatedAt` is the task query's `dataUpdatedAt`; it changes after
+// every refetch, which is when the counts may have moved.
+export function useTray(tasksUpdatedAt: number): { remindersPaused: boolean } {
+  const [remindersPaused, setRemindersPaused] = useState(false);
+
+  useEffect(() => {
+    if (typeof window === "undefined" || !isTauri()) return;
+
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+
+    const registerListener = async () => {
+      try {
+        const detach = await listen<RemindersPausedPayload>(
+          REMINDERS_PAUSED_EVENT,
+          (event) => {
+            const paused = event.payload?.paused;
+            if (typeof paused !== "boolean") return;
+            setRemindersPaused(paused);
+          },
+        );
+
+        if (disposed) {
+          detach();
+          return;
+        }
+
+        unlisten = detach;
+      } catch {
+        // Ignore runtimes where backend event bridge is unavailable.
+      }
+    };
+
+    void registerListener();
+    void getTraySettings()
+      .then((settings) => {
+        if (disposed || !settings) return;
+        setRemindersPaused(settings.reminders_paused);
+      })
+      .catch(() => {
+        // Mobile builds have no tray; reminders are never paused there.
+      });
+
+    return () => {
+      disposed = true;
+      if (unlisten) {
+        unlisten();
+        unlisten = null;
+      }
+    };
+  }, []);
+
+  useEffect(() => {
+    if (typeof window === "undefined" || !isTauri()) return;
+    if (tasksUpdatedAt === 0) return;
+    void refreshTrayTaskCounts().catch(() => {
+      // The tray keeps its last counts until the next periodic refresh.
+    });
+  }, [tasksUpdatedAt]);
+
+  return { remindersPaused };
+}
@@ -12,6 +12,16 @@ const TEMP_ROOT = fs.mkdtempSync(
   path.join(os.tmpdir(), "solostack-db-migration-test-"),
 );
 
+const tauriCoreMock = vi.hoisted(() => ({
+  isTauri: vi.fn<() => boolean>(() => false),
+  invoke: vi.fn<(command: string) => Promise<unknown>>(),
+}));
+
+vi.mock("@tauri-apps/api/core", () => ({
+  isTauri: tauriCoreMock.isTauri,
+  invoke: tauriCoreMock.invoke,
+}));
+
 function seedLegacySchema(db: import("node:sqlite").DatabaseSync): void {
   db.exec(`
     CREATE TABLE IF NOT EXISTS settings (
@@ -78,7 +88,9 @@ function seedLegacySchema(db: import("node:sqlite").DatabaseSync): void {
       updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
     );
   `);
+}
 
+function seedLegacyTask(db: import("node:sqlite").DatabaseSync): void {
   db.prepare(
     `INSERT INTO tasks (
         id,
@@ -105,6 +117,7 @@ function seedLegacySchema(db: import("node:sqlite").DatabaseSync): void {
 
 vi.mock("@tauri-apps/plugin-sql", async () => {
   const { DatabaseSync } = await import("node:sqlite");
+  const { applyRustSchema } = await import("@/test/rust-schema");
   const instances = new Map<string, InstanceType<typeof DatabaseSync>>();
 
   class NodeSqliteAdapter {
@@ -115,8 +128,20 @@ vi.mock("@tauri-apps/plugin-sql", async () => {
       this.#db.exec("PRAGMA foreign_keys = ON");
     }
 
-    seedLegacyV0() {
+    // A database from an early build that the Rust schema migrations have not touched.
+    seedUnmigratedV0() {
       seedLegacySchema(this.#db);
+      seedLegacyTask(this.#db);
+    }
+
+    // A database from an early build after the Rust schema migrations upgraded it.
+    seedMigratedV0() {
+      applyRustSchema(this.#db);
+      seedLegacyTask(this.#db);
+    }
+
+    seedFresh() {
+      applyRustSchema(this.#db);
     }
 
     async execute(sql: string, params: unknown[] = []): Promise<void> {
@@ -152,7 +177,11 @@ vi.mock("@tauri-apps/plugin-sql", async () => {
 
         const created = new NodeSqliteAdapter(filePath);
         if (preseedMode === "legacy_v0") {
-          created.seedLegacyV0();
+          created.seedMigratedV0();
+        } else if (preseedMode === "unmigrated_v0") {
+          created.seedUnmigratedV0();
+        } else {
+          created.seedFresh();
         }
         instances.set(filePath, created);
         return created;
@@ -163,7 +192,7 @@ vi.mock("@tauri-apps/plugin-sql", async () => {
 
 async function loadDatabaseModule(input: {
   namespace: string;
-  preseed?: "none" | "legacy_v0";
+  preseed?: "none" | "legacy_v0" | "unmigrated_v0";
 }) {
   process.env.SOLOSTACK_MIGRATION_TEST_NAMESPACE = input.namespace;
   process.env.SOLOSTACK_MIGRATION_TEST_PRESEED = input.preseed ?? "none";
@@ -191,7 +220,7 @@ afterAll(() => {
 });
 
 describe("database migration", () => {
-  it("creates schema and sync metadata on a fresh database", async () => {
+  it("opens a freshly migrated database and seeds sync metadata", async () => {
     const database = await loadDatabaseModule({
       namespace: `fresh-${randomUUID()}`,
       preseed: "none",
@@ -218,7 +247,52 @@ describe("database migration", () => {
     expect(openConflicts).toHaveLength(0);
   });
 
-  it("upgrades legacy schema and keeps existing data compatible", async () => {
+  it("refuses a database the Rust schema migrations have not upgraded", async () => {
+    const database = await loadDatabaseModule({
+      namespace: `unmigrated-${randomUUID()}`,
+      preseed: "unmigrated_v0",
+    });
+
+    await expect(database.getAllTasks()).rejects.toThrow(
+      "DATABASE_UNAVAILABLE",
+    );
+  });
+
+  it("surfaces a failed startup migration report and asks again on the next call", async () => {
+    const commandError = {
+      code: "INTERNAL",
+      detail: "wait for startup migration failed",
+      retryable: false,
+    };
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke
+      .mockRejectedValueOnce(commandError)
+      .mockResolvedValueOnce({
+        legacy_path_detected: false,
+        marker_present: false,
+        migration_attempted: false,
+        migration_completed: false,
+        migration_error: null,
+      });
+    try {
+      const database = await loadDatabaseModule({
+        namespace: `report-failed-${randomUUID()}`,
+        preseed: "none",
+      });
+
+      await expect(database.getAllTasks()).rejects.toEqual(commandError);
+      await expect(database.getAllTasks()).resolves.toEqual([]);
+      expect(tauriCoreMock.invoke).toHaveBeenCalledTimes(2);
+      expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+        "get_startup_migration_report",
+      );
+    } finally {
+      tauriCoreMock.isTauri.mockReturnValue(false);
+      tauriCoreMock.invoke.mockReset();
+    }
+  });
+
+  it("keeps legacy data compatible once Rust has upgraded the schema", async () => {
     const database = await loadDatabaseModule({
       namespace: `legacy-${randomUUID()}`,
       preseed: "legacy_v0",
@@ -8,6 +8,7 @@ vi.mock("@tauri-apps/plugin-sql", async () => {
   const os = await import("node:os");
   const path = await import("node:path");
   const { DatabaseSync } = await import("node:sqlite");
+  const { applyRustSchema } = await import("@/test/rust-schema");
 
   class NodeSqliteAdapter {
     #db: InstanceType<typeof DatabaseSync>;
@@ -15,6 +16,7 @@ vi.mock("@tauri-apps/plugin-sql", async () => {
     constructor(filePath: string) {
       this.#db = new DatabaseSync(filePath);
       this.#db.exec("PRAGMA foreign_keys = ON");
+      applyRustSchema(this.#db);
     }
 
     async execute(sql: string, params: unknown[] = []): Promise<void> {
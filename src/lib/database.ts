@@ -72,6 +72,7 @@ import {
   isTaskProjectNotFoundConflict,
 } from "./sync-conflict-rules";
 import { summarizeBackupPayload } from "./backup-summary";
+import { isCommandError } from "./error-message";
 import {
   getSyncProviderSessionAuth,
   hydrateSyncProviderConfigWithSessionAuth,
@@ -85,6 +86,8 @@ import {
 import type { SyncProviderAuthState } from "./sync-provider-auth";
 
 const DATABASE_NAME = "sqlite:solostack.db";
+// Latest migration in `src-tauri/src/db.rs`; a Rust test keeps the two in step.
+const REQUIRED_SCHEMA_VERSION = 3;
 
 let dbInstance: Database | null = null;
 
@@ -178,7 +181,12 @@ const SYNC_PROVIDERS: SyncProvider[] = [
   "onedrive_approot",
   "icloud_cloudkit",
   "solostack_cloud_aws",
+  "local_folder",
+  "webdav",
 ];
+// The WebDAV password is written to the secure store on its own and read by Rust; it is not an
+// OAuth token set, so the session-auth hydration below must not touch it.
+const NATIVE_CREDENTIAL_SYNC_PROVIDERS: SyncProvider[] = ["webdav"];
 const SYNC_RUNTIME_PROFILE_SETTINGS: SyncRuntimeProfileSetting[] = [
   "desktop",
   "mobile_beta",
@@ -222,6 +230,9 @@ const DB_ERROR_CODES = {
   RESTORE_BLOCKED: "DB_RESTORE_BLOCKED",
   LATEST_BACKUP_NOT_FOUND: "DB_LATEST_BACKUP_NOT_FOUND",
   LATEST_BACKUP_CORRUPTED: "DB_LATEST_BACKUP_CORRUPTED",
+  // Same code the Rust commands return before the schema migration has run.
+  SCHEMA_NOT_MIGRATED: "DATABASE_UNAVAILABLE",
+  STARTUP_MIGRATION_REPORT_UNAVAILABLE: "DATABASE_UNAVAILABLE",
 } as const;
 const SYNC_SETTINGS_DEVICE_ID_KEY = "sync.device_id";
 const APP_LOCALE_KEY = "app.locale";
@@ -299,351 +310,34 @@ let startupMigrationReportPromise: Promise<StartupMigrationReport> | null =
 /** Get or create the database connection singleton */
 async function getDb(): Promise<Database> {
   if (!dbInstance) {
-    dbInstance = await Database.load(DATABASE_NAME);
-    await initSchema(dbInstance);
+    // The Rust startup migration copies the database on a background thread; the report
+    // only resolves once the file is in place.
+    await readStartupMigrationReport();
+    const db = await Database.load(DATABASE_NAME);
+    await ensureSchemaVersion(db);
+    await ensureMigrationDiagnosticsSeeded(db);
+    dbInstance = db;
   }
   return dbInstance;
 }
 
-/** Create tables if they don't exist */
-async function initSchema(db: Database): Promise<void> {
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS settings (
-      key TEXT PRIMARY KEY,
-      value TEXT NOT NULL
-    )
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS projects (
-      id TEXT PRIMARY KEY,
-      name TEXT NOT NULL UNIQUE,
-      description TEXT,
-      color TEXT,
-      status TEXT NOT NULL DEFAULT 'ACTIVE' CHECK(status IN ('ACTIVE', 'COMPLETED', 'ARCHIVED')),
-      created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      sync_version INTEGER NOT NULL DEFAULT 1,
-      updated_by_device TEXT
-    )
-  `);
-
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_projects_status_updated_at
-    ON projects(status, updated_at DESC)
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS tasks (
-      id TEXT PRIMARY KEY,
-      title TEXT NOT NULL,
-      description TEXT,
-      notes_markdown TEXT,
-      project_id TEXT,
-      status TEXT NOT NULL CHECK(status IN ('TODO', 'DOING', 'DONE', 'ARCHIVED')),
-      priority TEXT NOT NULL CHECK(priority IN ('URGENT', 'NORMAL', 'LOW')),
-      is_important BOOLEAN DEFAULT 0,
-      due_at DATETIME,
-      remind_at DATETIME,
-      recurrence TEXT NOT NULL DEFAULT 'NONE' CHECK(recurrence IN ('NONE', 'DAILY', 'WEEKLY', 'MONTHLY')),
-      created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      sync_version INTEGER NOT NULL DEFAULT 1,
-      updated_by_device TEXT,
-      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE SET NULL
-    )
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS sessions (
-      id TEXT PRIMARY KEY,
-      task_id TEXT,
-      duration_minutes INTEGER NOT NULL,
-      completed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      FOREIGN KEY(task_id) REFERENCES tasks(id)
-    )
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS task_changelogs (
-      id TEXT PRIMARY KEY,
-      task_id TEXT NOT NULL,
-      action TEXT NOT NULL CHECK(action IN ('CREATED', 'UPDATED', 'STATUS_CHANGED')),
-      field_name TEXT,
-      old_value TEXT,
-      new_value TEXT,
-      created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
-    )
-  `);
-
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_task_changelogs_task_created_at
-    ON task_changelogs(task_id, created_at DESC)
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS task_templates (
-      id TEXT PRIMARY KEY,
-      name TEXT NOT NULL UNIQUE,
-      title_template TEXT,
-      description TEXT,
-      priority TEXT NOT NULL CHECK(priority IN ('URGENT', 'NORMAL', 'LOW')),
-      is_important BOOLEAN DEFAULT 0,
-      due_offset_minutes INTEGER,
-      remind_offset_minutes INTEGER,
-      recurrence TEXT NOT NULL DEFAULT 'NONE' CHECK(recurrence IN ('NONE', 'DAILY', 'WEEKLY', 'MONTHLY')),
-      created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      sync_version INTEGER NOT NULL DEFAULT 1,
-      updated_by_device TEXT
-    )
-  `);
-
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_task_templates_updated_at
-    ON task_templates(updated_at DESC)
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS task_subtasks (
-      id TEXT PRIMARY KEY,
-      task_id TEXT NOT NULL,
-      title TEXT NOT NULL,
-      is_done BOOLEAN DEFAULT 0,
-      created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      sync_version INTEGER NOT NULL DEFAULT 1,
-      updated_by_device TEXT,
-      FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
-    )
-  `);
-
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_task_subtasks_task_created_at
-    ON task_subtasks(task_id, created_at ASC)
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS sync_checkpoints (
-      id INTEGER PRIMARY KEY CHECK(id = 1),
-      last_sync_cursor TEXT,
-      last_synced_at DATETIME,
-      updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-    )
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS sync_outbox (
-      id TEXT PRIMARY KEY,
-      entity_type TEXT NOT NULL CHECK(entity_type IN ('PROJECT', 'TASK', 'TASK_SUBTASK', 'TASK_TEMPLATE', 'SETTING')),
-      entity_id TEXT NOT NULL,
-      operation TEXT NOT NULL CHECK(operation IN ('UPSERT', 'DELETE')),
-      payload_json TEXT,
-      idempotency_key TEXT NOT NULL UNIQUE,
-      attempts INTEGER NOT NULL DEFAULT 0,
-      last_error TEXT,
-      created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-    )
-  `);
-
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_sync_outbox_created_at
-    ON sync_outbox(created_at ASC)
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS deleted_records (
-      id TEXT PRIMARY KEY,
-      entity_type TEXT NOT NULL CHECK(entity_type IN ('PROJECT', 'TASK', 'TASK_SUBTASK', 'TASK_TEMPLATE', 'SETTING')),
-      entity_id TEXT NOT NULL,
-      deleted_at DATETIME NOT NULL,
-      deleted_by_device TEXT,
-      created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      UNIQUE(entity_type, entity_id)
-    )
-  `);
-
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_deleted_records_deleted_at
-    ON deleted_records(deleted_at DESC)
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS sync_conflicts (
-      id TEXT PRIMARY KEY,
-      incoming_idempotency_key TEXT NOT NULL UNIQUE,
-      entity_type TEXT NOT NULL CHECK(entity_type IN ('PROJECT', 'TASK', 'TASK_SUBTASK', 'TASK_TEMPLATE', 'SETTING')),
-      entity_id TEXT NOT NULL,
-      operation TEXT NOT NULL CHECK(operation IN ('UPSERT', 'DELETE')),
-      conflict_type TEXT NOT NULL CHECK(conflict_type IN ('field_conflict', 'delete_vs_update', 'notes_collision', 'validation_error')),
-      reason_code TEXT NOT NULL,
-      message TEXT NOT NULL,
-      local_payload_json TEXT,
-      remote_payload_json TEXT,
-      base_payload_json TEXT,
-      status TEXT NOT NULL DEFAULT 'open' CHECK(status IN ('open', 'resolved', 'ignored')),
-      resolution_strategy TEXT CHECK(resolution_strategy IN ('keep_local', 'keep_remote', 'manual_merge', 'retry')),
-      resolution_payload_json TEXT,
-      resolved_by_device TEXT,
-      detected_at DATETIME NOT NULL,
-      resolved_at DATETIME,
-      created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-    )
-  `);
-
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_sync_conflicts_status_detected_at
-    ON sync_conflicts(status, detected_at DESC)
-  `);
-
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_sync_conflicts_entity
-    ON sync_conflicts(entity_type, entity_id, detected_at DESC)
-  `);
-
-  await db.execute(`
-    CREATE TABLE IF NOT EXISTS sync_conflict_events (
-      id TEXT PRIMARY KEY,
-      conflict_id TEXT NOT NULL,
-      event_type TEXT NOT NULL CHECK(event_type IN ('detected', 'resolved', 'ignored', 'retried', 'exported')),
-      event_payload_json TEXT,
-      created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-      FOREIGN KEY(conflict_id) REFERENCES sync_conflicts(id) ON DELETE CASCADE
-    )
-  `);
-
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_sync_conflict_events_conflict_created_at
-    ON sync_conflict_events(conflict_id, created_at DESC)
-  `);
-
-  await ensureProjectColumns(db);
-  await ensureTaskColumns(db);
-  await ensureTaskTemplateColumns(db);
-  await ensureTaskSubtaskColumns(db);
-  await ensureSyncTablesReady(db);
-  await ensureMigrationDiagnosticsSeeded(db);
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_tasks_due_at
-    ON tasks(due_at)
-  `);
-  await db.execute(`
-    CREATE INDEX IF NOT EXISTS idx_tasks_project_id
-    ON tasks(project_id)
-  `);
-}
-
-interface SQLiteTableInfoRow {
-  name: string;
-}
-
-async function ensureProjectColumns(db: Database): Promise<void> {
-  const tableInfo = await db.select<SQLiteTableInfoRow[]>(
-    "PRAGMA table_info(projects)",
-  );
-  const existingColumns = new Set(tableInfo.map((column) => column.name));
-
-  if (!existingColumns.has("sync_version")) {
-    await db.execute(
-      "ALTER TABLE projects ADD COLUMN sync_version INTEGER NOT NULL DEFAULT 1",
-    );
-  }
-
-  if (!existingColumns.has("updated_by_device")) {
-    await db.execute("ALTER TABLE projects ADD COLUMN updated_by_device TEXT");
+// Rust owns the schema (`src-tauri/src/db.rs`) and migrates it before the webview opens the
+// database, so the webview only refuses to run against a schema that was not migrated.
+async function ensureSchemaVersion(db: Database): Promise<void> {
+  if ((await readSchemaVersion(db)) < REQUIRED_SCHEMA_VERSION) {
+    throw new Error(DB_ERROR_CODES.SCHEMA_NOT_MIGRATED);
   }
 }
 
-async function ensureTaskColumns(db: Database): Promise<void> {
-  const tableInfo = await db.select<SQLiteTableInfoRow[]>(
-    "PRAGMA table_info(tasks)",
-  );
-  const existingColumns = new Set(tableInfo.map((column) => column.name));
-
-  if (!existingColumns.has("due_at")) {
-    await db.execute("ALTER TABLE tasks ADD COLUMN due_at DATETIME");
-  }
-
-  if (!existingColumns.has("remind_at")) {
-    await db.execute("ALTER TABLE tasks ADD COLUMN remind_at DATETIME");
-  }
-
-  if (!existingColumns.has("recurrence")) {
-    await db.execute(
-      "ALTER TABLE tasks ADD COLUMN recurrence TEXT NOT NULL DEFAULT 'NONE' CHECK(recurrence IN ('NONE', 'DAILY', 'WEEKLY', 'MONTHLY'))",
-    );
-  }
-
-  if (!existingColumns.has("project_id")) {
-    await db.execute("ALTER TABLE tasks ADD COLUMN project_id TEXT");
-  }
-
-  if (!existingColumns.has("notes_markdown")) {
-    await db.execute("ALTER TABLE tasks ADD COLUMN notes_markdown TEXT");
-  }
-
-  if (!existingColumns.has("sync_version")) {
-    await db.execute(
-      "ALTER TABLE tasks ADD COLUMN sync_version INTEGER NOT NULL DEFAULT 1",
-    );
-  }
-
-  if (!existingColumns.has("updated_by_device")) {
-    await db.execute("ALTER TABLE tasks ADD COLUMN updated_by_device TEXT");
-  }
-}
-
-async function ensureTaskTemplateColumns(db: Database): Promise<void> {
-  const tableInfo = await db.select<SQLiteTableInfoRow[]>(
-    "PRAGMA table_info(task_templates)",
-  );
-  const existingColumns = new Set(tableInfo.map((column) => column.name));
-
-  if (!existingColumns.has("due_offset_minutes")) {
-    await db.execute(
-      "ALTER TABLE task_templates ADD COLUMN due_offset_minutes INTEGER",
-    );
-  }
-
-  if (!existingColumns.has("remind_offset_minutes")) {
-    await db.execute(
-      "ALTER TABLE task_templates ADD COLUMN remind_offset_minutes INTEGER",
-    );
-  }
-
-  if (!existingColumns.has("sync_version")) {
-    await db.execute(
-      "ALTER TABLE task_templates ADD COLUMN sync_version INTEGER NOT NULL DEFAULT 1",
-    );
-  }
-
-  if (!existingColumns.has("updated_by_device")) {
-    await db.execute(
-      "ALTER TABLE task_templates ADD COLUMN updated_by_device TEXT",
-    );
-  }
-}
-
-async function ensureTaskSubtaskColumns(db: Database): Promise<void> {
-  const tableInfo = await db.select<SQLiteTableInfoRow[]>(
-    "PRAGMA table_info(task_subtasks)",
-  );
-  const existingColumns = new Set(tableInfo.map((column) => column.name));
-
-  if (!existingColumns.has("sync_version")) {
-    await db.execute(
-      "ALTER TABLE task_subtasks ADD COLUMN sync_version INTEGER NOT NULL DEFAULT 1",
-    );
-  }
-
-  if (!existingColumns.has("updated_by_device")) {
-    await db.execute(
-      "ALTER TABLE task_subtasks ADD COLUMN updated_by_device TEXT",
+async function readSchemaVersion(db: Database): Promise<number> {
+  try {
+    const rows = await db.select<{ version: number }[]>(
+      "SELECT COALESCE(MAX(version), 0) AS version FROM schema_migrations",
     );
+    return Number(rows[0]?.version ?? 0);
+  } catch {
+    // No `schema_migrations` table: the Rust migrations never ran on this file.
+    return 0;
   }
 }
 
@@ -701,16 +395,21 @@ async function readStartupMigrationReport(): Promise<StartupMigrationReport> {
         return { ...DEFAULT_STARTUP_MIGRATION_REPORT };
       }
 
-      try {
-        const { isTauri, invoke } = await import("@tauri-apps/api/core");
-        if (!isTauri()) {
-          return { ...DEFAULT_STARTUP_MIGRATION_REPORT };
-        }
+      const { isTauri, invoke } = await import("@tauri-apps/api/core");
+      if (!isTauri()) {
+        return { ...DEFAULT_STARTUP_MIGRATION_REPORT };
+      }
 
+      // Without the report the copy may still be in flight, so `getDb` fails instead of
+      // opening the database, and the next call asks again.
+      try {
         const report = await invoke<unknown>("get_startup_migration_report");
         return normalizeStartupMigrationReport(report);
-      } catch {
-        return { ...DEFAULT_STARTUP_MIGRATION_REPORT };
+      } catch (error) {
+        startupMigrationReportPromise = null;
+        throw isCommandError(error)
+          ? error
+          : new Error(DB_ERROR_CODES.STARTUP_MIGRATION_REPORT_UNAVAILABLE);
       }
     })();
   }
@@ -1591,10 +1290,12 @@ export async function getSyncProviderSettings(): Promise<SyncProviderSettings> {
     }
   }
 
-  const runtimeSessionAuth = await resolveSyncProviderRuntimeAuth({
-    provider,
-    parsed_session_auth: parsedSessionAuth,
-  });
+  const runtimeSessionAuth = NATIVE_CREDENTIAL_SYNC_PROVIDERS.includes(provider)
+    ? null
+    : await resolveSyncProviderRuntimeAuth({
+        provider,
+        parsed_session_auth: parsedSessionAuth,
+      });
 
   if (providerConfigRedacted) {
     const normalizedProviderConfigJson = providerConfig
@@ -1668,10 +1369,12 @@ export async function updateSyncProviderSettings(
     throw error;
   }
 
-  await writeSyncProviderSecureAuth({
-    provider,
-    auth: normalizedProviderConfig.session_auth,
-  });
+  if (!NATIVE_CREDENTIAL_SYNC_PROVIDERS.includes(provider)) {
+    await writeSyncProviderSecureAuth({
+      provider,
+      auth: normalizedProviderConfig.session_auth,
+    });
+  }
 
   return {
     provider,
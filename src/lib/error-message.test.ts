@@ -27,6 +27,29 @@ describe("localizeErrorMessage", () => {
     );
   });
 
+  it("maps structured command errors by code", () => {
+    expect(
+      localizeErrorMessage(
+        {
+          code: "DATABASE_BUSY",
+          detail: "database is locked",
+          retryable: true,
+        },
+        "en",
+      ),
+    ).toBe("Database is busy. Try again.");
+    expect(
+      localizeErrorMessage(
+        {
+          code: "SECURE_STORE_WRITE_FAILED",
+          detail: "store secure auth failed: denied",
+          retryable: false,
+        },
+        "en",
+      ),
+    ).toBe("store secure auth failed: denied");
+  });
+
   it("falls back to original message when no mapping exists", () => {
     expect(localizeErrorMessage("custom.error.value", "en")).toBe(
       "custom.error.value",
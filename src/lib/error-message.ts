@@ -22,6 +22,7 @@ const ERROR_CODE_TO_TRANSLATION_KEY: Record<string, TranslationKey> = {
   DB_PROJECT_NAME_REQUIRED: "projectView.error.projectNameRequired",
   DB_PROJECT_NAME_EXISTS: "app.error.projectNameExists",
   DB_PROJECT_NOT_FOUND: "app.error.projectNotFound",
+  DB_TASK_TITLE_REQUIRED: "app.error.taskTitleRequired",
   DB_TASK_NOT_FOUND: "app.error.taskNotFound",
   DB_SUBTASK_TITLE_REQUIRED: "app.error.subtaskTitleRequired",
   DB_SUBTASK_NOT_FOUND: "app.error.subtaskNotFound",
@@ -49,6 +50,22 @@ const ERROR_CODE_TO_TRANSLATION_KEY: Record<string, TranslationKey> = {
   SYNC_TRANSPORT_TIMEOUT: "sync.transport.error.timeout",
   SYNC_TRANSPORT_REQUIRE_BOTH_URLS: "sync.transport.error.requireBothUrls",
   SYNC_TRANSPORT_UNEXPECTED: "sync.transport.error.unexpected",
+  PROVIDER_REQUIRED: "app.error.syncProviderRequired",
+  PROVIDER_ACCOUNT_ID_INVALID: "app.error.syncProviderAccountIdInvalid",
+  PROVIDER_ACCOUNT_NOT_FOUND: "app.error.syncProviderAccountNotFound",
+  SECURE_STORE_UNAVAILABLE: "app.error.secureStoreUnavailable",
+  SECURE_STORE_TIMEOUT: "app.error.secureStoreTimeout",
+  OAUTH_CLIENT_ID_REQUIRED: "app.error.oauthClientIdRequired",
+  OAUTH_AUTHORIZATION_DENIED: "app.error.oauthAuthorizationDenied",
+  OAUTH_TIMEOUT: "app.error.oauthTimeout",
+  OAUTH_TOKEN_EXCHANGE_FAILED: "app.error.oauthTokenExchangeFailed",
+  SHORTCUT_INVALID: "app.error.shortcutInvalid",
+  SHORTCUT_CONFLICT: "app.error.shortcutConflict",
+  SHORTCUT_ACTION_BOUND: "app.error.shortcutActionBound",
+  SHORTCUT_UNAVAILABLE: "app.error.shortcutUnavailable",
+  DATABASE_UNAVAILABLE: "app.error.databaseUnavailable",
+  DATABASE_BUSY: "app.error.databaseBusy",
+  LEGACY_CLEANUP_REFUSED: "app.error.legacyCleanupRefused",
 } as const;
 
 const SYNC_API_ERROR_CODE_TO_TRANSLATION_KEY: Record<string, TranslationKey> = {
@@ -62,7 +79,29 @@ const SYNC_API_ERROR_CODE_TO_TRANSLATION_KEY: Record<string, TranslationKey> = {
   UNAVAILABLE: "sync.api.error.unavailable",
 } as const;
 
+export interface CommandError {
+  code: string;
+  detail: string | null;
+  retryable: boolean;
+}
+
+// Rust commands reject with `{ code, detail, retryable }`; `code` is the stable part.
+export function isCommandError(error: unknown): error is CommandError {
+  if (typeof error !== "object" || error === null) return false;
+  const candidate = error as Partial<CommandError>;
+  return (
+    typeof candidate.code === "string" &&
+    typeof candidate.retryable === "boolean"
+  );
+}
+
 function extractErrorMessage(error: unknown): string | null {
+  if (isCommandError(error)) {
+    if (ERROR_CODE_TO_TRANSLATION_KEY[error.code]) {
+      return error.code;
+    }
+    return error.detail?.trim() || error.code;
+  }
   if (error instanceof Error && error.message.trim()) {
     return error.message.trim();
   }
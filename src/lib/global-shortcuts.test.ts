@@ -0,0 +1,64 @@
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+const tauriCoreMock = vi.hoisted(() => ({
+  isTauri: vi.fn<() => boolean>(),
+  invoke:
+    vi.fn<
+      (command: string, args?: Record<string, unknown>) => Promise<unknown>
+    >(),
+}));
+
+vi.mock("@tauri-apps/api/core", () => ({
+  isTauri: tauriCoreMock.isTauri,
+  invoke: tauriCoreMock.invoke,
+}));
+
+import {
+  listGlobalShortcuts,
+  rebindGlobalShortcut,
+  unregisterGlobalShortcut,
+} from "@/lib/global-shortcuts";
+
+describe("global-shortcuts", () => {
+  beforeEach(() => {
+    tauriCoreMock.isTauri.mockReset();
+    tauriCoreMock.invoke.mockReset();
+    tauriCoreMock.isTauri.mockReturnValue(false);
+  });
+
+  it("returns null when runtime is not tauri", async () => {
+    await expect(listGlobalShortcuts()).resolves.toBeNull();
+    expect(tauriCoreMock.invoke).not.toHaveBeenCalled();
+  });
+
+  it("rebinds and unregisters actions via tauri commands", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockResolvedValue([]);
+
+    await rebindGlobalShortcut("open_today", "alt+shift+t");
+    await unregisterGlobalShortcut("start_focus_session");
+
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "rebind_global_shortcut",
+      { action: "open_today", shortcut: "alt+shift+t" },
+    );
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "unregister_global_shortcut",
+      { action: "start_focus_session" },
+    );
+  });
+
+  it("passes structured conflict errors through", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    const conflict = {
+      code: "SHORTCUT_CONFLICT",
+      detail: "shift+control+KeyN is already bound to quick_capture",
+      retryable: false,
+    };
+    tauriCoreMock.invoke.mockRejectedValue(conflict);
+
+    await expect(
+      rebindGlobalShortcut("open_today", "ctrl+shift+n"),
+    ).rejects.toEqual(conflict);
+  });
+});
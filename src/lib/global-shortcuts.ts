@@ -0,0 +1,83 @@
+const TAURI_LIST_COMMAND = "list_global_shortcuts";
+const TAURI_REGISTER_COMMAND = "register_global_shortcut";
+const TAURI_REBIND_COMMAND = "rebind_global_shortcut";
+const TAURI_UNREGISTER_COMMAND = "unregister_global_shortcut";
+
+type TauriInvoke = <T = unknown>(
+  command: string,
+  args?: Record<string, unknown>,
+) => Promise<T>;
+
+export type GlobalShortcutAction =
+  | "quick_capture"
+  | "open_today"
+  | "start_focus_session";
+
+export interface GlobalShortcutBinding {
+  action: GlobalShortcutAction;
+  // Normalized by the native side, e.g. `shift+control+KeyN`.
+  shortcut: string | null;
+  default_shortcut: string | null;
+  registered: boolean;
+  error: { code: string; detail: string | null; retryable: boolean } | null;
+}
+
+async function resolveTauriInvoke(): Promise<TauriInvoke | null> {
+  if (typeof window === "undefined") return null;
+
+  try {
+    const { isTauri, invoke } = await import("@tauri-apps/api/core");
+    if (!isTauri()) return null;
+    return invoke as TauriInvoke;
+  } catch {
+    return null;
+  }
+}
+
+// Global shortcuts are desktop-only; every call resolves to null elsewhere.
+export async function listGlobalShortcuts(): Promise<
+  GlobalShortcutBinding[] | null
+> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<GlobalShortcutBinding[]>(TAURI_LIST_COMMAND);
+}
+
+// Rejects with SHORTCUT_ACTION_BOUND when the action already has a binding.
+export async function registerGlobalShortcut(
+  action: GlobalShortcutAction,
+  shortcut: string,
+): Promise<GlobalShortcutBinding[] | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<GlobalShortcutBinding[]>(TAURI_REGISTER_COMMAND, {
+    action,
+    shortcut,
+  });
+}
+
+// Conflicts reject with SHORTCUT_CONFLICT (another action) or SHORTCUT_UNAVAILABLE (another
+// app or the OS); the previous binding stays active either way.
+export async function rebindGlobalShortcut(
+  action: GlobalShortcutAction,
+  shortcut: string,
+): Promise<GlobalShortcutBinding[] | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<GlobalShortcutBinding[]>(TAURI_REBIND_COMMAND, {
+    action,
+    shortcut,
+  });
+}
+
+export async function unregisterGlobalShortcut(
+  action: GlobalShortcutAction,
+): Promise<GlobalShortcutBinding[] | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<GlobalShortcutBinding[]>(TAURI_UNREGISTER_COMMAND, { action });
+}
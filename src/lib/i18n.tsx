@@ -43,6 +43,8 @@ const TRANSLATIONS = {
     "sync.error.unexpected": "An unexpected sync error occurred.",
     "sync.offline.retryNetworkReturn":
       "You're offline. Sync will retry when network returns.",
+    "sync.paused.batterySaver":
+      "Background sync is paused while battery saver is on.",
     "sync.conflict.part.outboxFailed": "{count} outbox change(s) failed",
     "sync.conflict.part.incomingFailed": "{count} incoming change(s) failed",
     "sync.conflict.part.detected": "{count} conflict(s) detected",
@@ -137,6 +139,33 @@ const TRANSLATIONS = {
       "No latest backup snapshot found. Export backup first.",
     "app.error.latestBackupSnapshotCorrupted":
       "Latest backup snapshot is corrupted.",
+    "app.error.taskTitleRequired": "Task title is required.",
+    "app.error.syncProviderRequired": "Sync provider is required.",
+    "app.error.syncProviderAccountIdInvalid":
+      "Account ID can only use letters, digits and . _ @ -",
+    "app.error.syncProviderAccountNotFound":
+      "No saved sign-in for this account.",
+    "app.error.secureStoreUnavailable": "Secure storage is unavailable.",
+    "app.error.secureStoreTimeout":
+      "Secure storage did not respond in time. Try again.",
+    "app.error.oauthClientIdRequired": "OAuth client ID is required.",
+    "app.error.oauthAuthorizationDenied": "Sign-in was cancelled or denied.",
+    "app.error.oauthTimeout":
+      "Sign-in was not completed in the browser in time. Try again.",
+    "app.error.oauthTokenExchangeFailed":
+      "Could not finish sign-in with the provider.",
+    "app.error.shortcutInvalid":
+      "That key combination is not a valid shortcut.",
+    "app.error.shortcutConflict":
+      "That shortcut is already used by another SoloStack action.",
+    "app.error.shortcutActionBound":
+      "This action already has a shortcut. Change it instead.",
+    "app.error.shortcutUnavailable":
+      "That shortcut is taken by another app or the system.",
+    "app.error.databaseUnavailable": "Database is not ready yet. Try again.",
+    "app.error.databaseBusy": "Database is busy. Try again.",
+    "app.error.legacyCleanupRefused":
+      "Old app data cannot be removed until the migrated database is verified.",
     "common.retry": "Retry",
     "common.error.unableRequest": "Unable to complete the request.",
     "common.never": "Never",
@@ -716,6 +745,8 @@ const TRANSLATIONS = {
       "Verifying Secure Store...",
     "settings.sync.provider.managed.secureStoreTest.feedback.success":
       "Secure store self-test passed ({backend}).",
+    "settings.sync.provider.managed.secureStoreTest.feedback.fallback":
+      "Secure store self-test passed using the {backend} fallback; {primary} is unavailable: {reason}",
     "settings.sync.provider.managed.secureStoreTest.error.unavailable":
       "Secure store is unavailable in this runtime ({backend}).",
     "settings.sync.provider.managed.secureStoreTest.error.failed":
@@ -787,9 +818,45 @@ const TRANSLATIONS = {
       "Availability may vary by region during rollout.",
     "settings.sync.provider.capability.solostack_cloud_aws.warning2":
       "Network outages fall back to local-only retries.",
+    "settings.sync.provider.capability.local_folder.label":
+      "Local folder / file share",
+    "settings.sync.provider.capability.local_folder.summary":
+      "Devices exchange change journals through a shared folder.",
+    "settings.sync.provider.capability.local_folder.auth":
+      "No account; every device needs access to the same folder",
+    "settings.sync.provider.capability.local_folder.warning1":
+      "Available in the desktop and mobile apps only, not in the browser.",
+    "settings.sync.provider.capability.local_folder.warning2":
+      "Sync pauses while the folder or drive is not reachable.",
+    "settings.sync.provider.localFolder.directory": "Sync folder",
+    "settings.sync.provider.localFolder.directoryPlaceholder":
+      "/Users/me/Dropbox/SoloStack",
+    "settings.sync.provider.localFolder.directoryHint":
+      "Use the same folder on every device, e.g. a synced drive or network share.",
+    "settings.sync.provider.capability.webdav.label": "WebDAV (Nextcloud)",
+    "settings.sync.provider.capability.webdav.summary":
+      "Change journals kept in a folder on your own WebDAV server.",
+    "settings.sync.provider.capability.webdav.auth":
+      "WebDAV user name + app password",
+    "settings.sync.provider.capability.webdav.warning1":
+      "Available in the desktop and mobile apps only, not in the browser.",
+    "settings.sync.provider.capability.webdav.warning2":
+      "The folder must already exist on the server.",
+    "settings.sync.provider.webdav.baseUrl": "WebDAV folder URL",
+    "settings.sync.provider.webdav.baseUrlPlaceholder":
+      "https://cloud.example.com/remote.php/dav/files/me/SoloStack/",
+    "settings.sync.provider.webdav.username": "User name",
+    "settings.sync.provider.webdav.password": "App password",
+    "settings.sync.provider.webdav.passwordPlaceholder":
+      "Leave empty to keep the saved password",
+    "settings.sync.provider.webdav.passwordHint":
+      "The password is kept in the OS secure store, never in settings or backups.",
     "settings.sync.runtime.title": "Sync Runtime Profile",
     "settings.sync.runtime.desc":
       "Tune sync behavior for desktop/mobile beta workloads.",
+    "settings.sync.runtime.nativeScheduler.title": "Sync in the background",
+    "settings.sync.runtime.nativeScheduler.desc":
+      "Keep syncing on the intervals above while the window is hidden. Cloud providers still sync from the app window.",
     "settings.sync.runtime.profile.desktop": "Desktop",
     "settings.sync.runtime.profile.mobile": "Mobile Beta",
     "settings.sync.runtime.profile.custom": "Custom",
@@ -1020,6 +1087,8 @@ const TRANSLATIONS = {
     "sync.error.unexpected": "เกิดข้อผิดพลาดการซิงก์ที่ไม่คาดคิด",
     "sync.offline.retryNetworkReturn":
       "คุณออฟไลน์อยู่ ระบบจะลองซิงก์อีกครั้งเมื่อกลับมาออนไลน์",
+    "sync.paused.batterySaver":
+      "หยุดซิงก์เบื้องหลังชั่วคราวขณะเปิดโหมดประหยัดแบตเตอรี่",
     "sync.conflict.part.outboxFailed": "รายการคิวขาออกล้มเหลว {count} รายการ",
     "sync.conflict.part.incomingFailed": "รายการขาเข้าล้มเหลว {count} รายการ",
     "sync.conflict.part.detected": "ตรวจพบคอนฟลิกต์ {count} รายการ",
@@ -1118,6 +1187,29 @@ const TRANSLATIONS = {
     "app.error.noLatestBackupSnapshot":
       "ไม่พบแบ็กอัปล่าสุด กรุณาส่งออกแบ็กอัปก่อน",
     "app.error.latestBackupSnapshotCorrupted": "แบ็กอัปล่าสุดเสียหาย",
+    "app.error.taskTitleRequired": "จำเป็นต้องระบุชื่องาน",
+    "app.error.syncProviderRequired": "จำเป็นต้องระบุผู้ให้บริการซิงก์",
+    "app.error.syncProviderAccountIdInvalid":
+      "Account ID ใช้ได้เฉพาะตัวอักษร ตัวเลข และ . _ @ -",
+    "app.error.syncProviderAccountNotFound":
+      "ไม่พบการลงชื่อเข้าใช้ที่บันทึกไว้สำหรับบัญชีนี้",
+    "app.error.secureStoreUnavailable": "ไม่สามารถใช้ที่เก็บข้อมูลที่ปลอดภัยได้",
+    "app.error.secureStoreTimeout":
+      "ที่เก็บข้อมูลที่ปลอดภัยไม่ตอบสนองภายในเวลาที่กำหนด กรุณาลองอีกครั้ง",
+    "app.error.oauthClientIdRequired": "จำเป็นต้องระบุ OAuth client ID",
+    "app.error.oauthAuthorizationDenied": "การลงชื่อเข้าใช้ถูกยกเลิกหรือถูกปฏิเสธ",
+    "app.error.oauthTimeout":
+      "ไม่ได้ลงชื่อเข้าใช้ในเบราว์เซอร์ภายในเวลาที่กำหนด กรุณาลองอีกครั้ง",
+    "app.error.oauthTokenExchangeFailed":
+      "ไม่สามารถลงชื่อเข้าใช้กับผู้ให้บริการให้เสร็จสมบูรณ์ได้",
+    "app.error.shortcutInvalid": "ชุดปุ่มนี้ใช้เป็นปุ่มลัดไม่ได้",
+    "app.error.shortcutConflict": "ปุ่มลัดนี้ถูกใช้กับคำสั่งอื่นของ SoloStack แล้ว",
+    "app.error.shortcutActionBound": "คำสั่งนี้มีปุ่มลัดอยู่แล้ว ให้เปลี่ยนปุ่มลัดแทน",
+    "app.error.shortcutUnavailable": "ปุ่มลัดนี้ถูกใช้โดยแอปอื่นหรือระบบแล้ว",
+    "app.error.databaseUnavailable": "ฐานข้อมูลยังไม่พร้อม กรุณาลองอีกครั้ง",
+    "app.error.databaseBusy": "ฐานข้อมูลกำลังทำงานอยู่ กรุณาลองอีกครั้ง",
+    "app.error.legacyCleanupRefused":
+      "ยังลบข้อมูลแอปเก่าไม่ได้จนกว่าฐานข้อมูลที่ย้ายมาจะผ่านการตรวจสอบ",
     "common.retry": "ลองอีกครั้ง",
     "common.error.unableRequest": "ไม่สามารถดำเนินการคำขอได้",
     "common.never": "ไม่เคย",
@@ -1690,6 +1782,8 @@ const TRANSLATIONS = {
       "กำลังตรวจสอบ Secure Store...",
     "settings.sync.provider.managed.secureStoreTest.feedback.success":
       "การทดสอบ Secure Store ผ่าน ({backend})",
+    "settings.sync.provider.managed.secureStoreTest.feedback.fallback":
+      "การทดสอบ Secure Store ผ่านโดยใช้ {backend} สำรอง; {primary} ใช้งานไม่ได้: {reason}",
     "settings.sync.provider.managed.secureStoreTest.error.unavailable":
       "Secure Store ไม่พร้อมใช้งานใน runtime นี้ ({backend})",
     "settings.sync.provider.managed.secureStoreTest.error.failed":
@@ -1761,9 +1855,45 @@ const TRANSLATIONS = {
       "ความพร้อมใช้งานอาจต่างกันตามภูมิภาคระหว่างช่วงเปิดใช้งานแบบค่อยเป็นค่อยไป",
     "settings.sync.provider.capability.solostack_cloud_aws.warning2":
       "เมื่อเครือข่ายมีปัญหา ระบบจะถอยกลับเป็นการลองใหม่แบบเฉพาะเครื่อง",
+    "settings.sync.provider.capability.local_folder.label":
+      "โฟลเดอร์ในเครื่อง / แชร์ไฟล์",
+    "settings.sync.provider.capability.local_folder.summary":
+      "อุปกรณ์แลกเปลี่ยนบันทึกการเปลี่ยนแปลงผ่านโฟลเดอร์ที่ใช้ร่วมกัน",
+    "settings.sync.provider.capability.local_folder.auth":
+      "ไม่ต้องใช้บัญชี แต่ทุกอุปกรณ์ต้องเข้าถึงโฟลเดอร์เดียวกันได้",
+    "settings.sync.provider.capability.local_folder.warning1":
+      "ใช้ได้เฉพาะในแอปเดสก์ท็อปและมือถือ ไม่รองรับบนเบราว์เซอร์",
+    "settings.sync.provider.capability.local_folder.warning2":
+      "การซิงก์จะหยุดชั่วคราวเมื่อเข้าถึงโฟลเดอร์หรือไดรฟ์ไม่ได้",
+    "settings.sync.provider.localFolder.directory": "โฟลเดอร์ซิงก์",
+    "settings.sync.provider.localFolder.directoryPlaceholder":
+      "/Users/me/Dropbox/SoloStack",
+    "settings.sync.provider.localFolder.directoryHint":
+      "ใช้โฟลเดอร์เดียวกันในทุกอุปกรณ์ เช่น ไดรฟ์ที่ซิงก์อยู่หรือแชร์ในเครือข่าย",
+    "settings.sync.provider.capability.webdav.label": "WebDAV (Nextcloud)",
+    "settings.sync.provider.capability.webdav.summary":
+      "เก็บบันทึกการเปลี่ยนแปลงไว้ในโฟลเดอร์บนเซิร์ฟเวอร์ WebDAV ของคุณเอง",
+    "settings.sync.provider.capability.webdav.auth":
+      "ต้องใช้ชื่อผู้ใช้ WebDAV และ app password",
+    "settings.sync.provider.capability.webdav.warning1":
+      "ใช้ได้เฉพาะในแอปเดสก์ท็อปและมือถือ ไม่รองรับบนเบราว์เซอร์",
+    "settings.sync.provider.capability.webdav.warning2":
+      "ต้องสร้างโฟลเดอร์บนเซิร์ฟเวอร์ไว้ก่อน",
+    "settings.sync.provider.webdav.baseUrl": "URL ของโฟลเดอร์ WebDAV",
+    "settings.sync.provider.webdav.baseUrlPlaceholder":
+      "https://cloud.example.com/remote.php/dav/files/me/SoloStack/",
+    "settings.sync.provider.webdav.username": "ชื่อผู้ใช้",
+    "settings.sync.provider.webdav.password": "App password",
+    "settings.sync.provider.webdav.passwordPlaceholder":
+      "เว้นว่างไว้เพื่อใช้รหัสผ่านที่บันทึกไว้เดิม",
+    "settings.sync.provider.webdav.passwordHint":
+      "รหัสผ่านเก็บใน secure store ของระบบปฏิบัติการ ไม่อยู่ใน settings หรือไฟล์สำรอง",
     "settings.sync.runtime.title": "โปรไฟล์รันไทม์การซิงก์",
     "settings.sync.runtime.desc":
       "ปรับพฤติกรรมซิงก์ให้เหมาะกับงานบนเดสก์ท็อป/มือถือเบตา",
+    "settings.sync.runtime.nativeScheduler.title": "ซิงก์เบื้องหลัง",
+    "settings.sync.runtime.nativeScheduler.desc":
+      "ซิงก์ต่อตามรอบเวลาด้านบนแม้หน้าต่างถูกซ่อนอยู่ ผู้ให้บริการคลาวด์ยังซิงก์จากหน้าต่างแอปเหมือนเดิม",
     "settings.sync.runtime.profile.desktop": "เดสก์ท็อป",
     "settings.sync.runtime.profile.mobile": "มือถือเบตา",
     "settings.sync.runtime.profile.custom": "กำหนดเอง",
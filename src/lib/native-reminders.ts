@@ -0,0 +1,54 @@
+import type { AppLocale } from "./types";
+
+const TAURI_CONFIGURE_COMMAND = "configure_native_reminders";
+const TAURI_RESYNC_COMMAND = "resync_native_reminders";
+
+type TauriInvoke = <T = unknown>(
+  command: string,
+  args?: Record<string, unknown>,
+) => Promise<T>;
+
+async function resolveTauriInvoke(): Promise<TauriInvoke | null> {
+  if (typeof window === "undefined") return null;
+
+  try {
+    const { isTauri, invoke } = await import("@tauri-apps/api/core");
+    if (!isTauri()) return null;
+    return invoke as TauriInvoke;
+  } catch {
+    return null;
+  }
+}
+
+// Resolves true when the native scheduler took over reminders. Browser and mobile runtimes have
+// no scheduler and keep using the webview reminder loop.
+export async function configureNativeReminders(input: {
+  enabled: boolean;
+  locale: AppLocale;
+}): Promise<boolean> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return false;
+
+  try {
+    await invoke(TAURI_CONFIGURE_COMMAND, input);
+    return true;
+  } catch (error) {
+    // The setting could not be saved, but the scheduler itself is running.
+    if (
+      error &&
+      typeof error === "object" &&
+      (error as { code?: unknown }).code === "REMINDER_STATE_WRITE_FAILED"
+    ) {
+      return true;
+    }
+    return false;
+  }
+}
+
+// Task writes from the webview go through the SQL plugin, which the scheduler cannot observe.
+export async function resyncNativeReminders(): Promise<void> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return;
+
+  await invoke(TAURI_RESYNC_COMMAND);
+}
@@ -0,0 +1,182 @@
+import type {
+  RunSyncCycleOptions,
+  RunSyncCycleSummary,
+  SyncTransport,
+} from "./sync-runner";
+import type { SyncStatus } from "./types";
+
+const TAURI_RUN_SYNC_CYCLE_COMMAND = "run_sync_cycle";
+const TAURI_CONFIGURE_SCHEDULER_COMMAND = "configure_native_sync_scheduler";
+const TAURI_REPORT_ENVIRONMENT_COMMAND = "report_sync_environment";
+const TAURI_GET_STATUS_COMMAND = "get_native_sync_status";
+const TAURI_EXCHANGE_ENVELOPE_COMMAND = "exchange_native_sync_envelope";
+
+export const NATIVE_SYNC_STATUS_EVENT = "sync:status";
+export const NATIVE_SYNC_SCHEDULER_STORAGE_KEY =
+  "solostack.sync.native-scheduler";
+
+const DEFAULT_NATIVE_SYNC_SCHEDULER_ENABLED = true;
+// Windows turns battery saver on at 20% by default.
+const BATTERY_SAVER_LEVEL = 0.2;
+
+type TauriInvoke = <T = unknown>(
+  command: string,
+  args?: Record<string, unknown>,
+) => Promise<T>;
+
+export interface NativeSyncStatusPayload {
+  status: SyncStatus;
+  paused_reason: "network_offline" | "battery_saver" | null;
+  error: { code: string; detail: string | null; retryable: boolean } | null;
+  summary: RunSyncCycleSummary | null;
+  next_attempt_in_ms: number | null;
+}
+
+interface BatteryStatus extends EventTarget {
+  charging: boolean;
+  level: number;
+}
+
+export function getNativeSyncSchedulerPreference(): boolean {
+  if (typeof window === "undefined") {
+    return DEFAULT_NATIVE_SYNC_SCHEDULER_ENABLED;
+  }
+
+  const rawValue = window.localStorage.getItem(
+    NATIVE_SYNC_SCHEDULER_STORAGE_KEY,
+  );
+  if (rawValue === null) return DEFAULT_NATIVE_SYNC_SCHEDULER_ENABLED;
+  return rawValue !== "false";
+}
+
+export function setNativeSyncSchedulerPreference(enabled: boolean): void {
+  if (typeof window === "undefined") return;
+  window.localStorage.setItem(
+    NATIVE_SYNC_SCHEDULER_STORAGE_KEY,
+    enabled ? "true" : "false",
+  );
+}
+
+async function resolveTauriInvoke(): Promise<TauriInvoke | null> {
+  if (typeof window === "undefined") return null;
+
+  try {
+    const { isTauri, invoke } = await import("@tauri-apps/api/core");
+    if (!isTauri()) return null;
+    return invoke as TauriInvoke;
+  } catch {
+    return null;
+  }
+}
+
+// Runs the cycle in Rust against the provider-neutral push/pull URLs or the `local_folder` and
+// `webdav` journals. Resolves null outside Tauri; cloud providers reject with
+// `SYNC_TRANSPORT_UNAVAILABLE` and keep using `runLocalSyncCycle`.
+export async function runNativeSyncCycle(
+  options?: RunSyncCycleOptions,
+): Promise<RunSyncCycleSummary | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<RunSyncCycleSummary>(TAURI_RUN_SYNC_CYCLE_COMMAND, {
+    options: options ?? null,
+  });
+}
+
+// Hands periodic sync to the Rust scheduler, which then emits `sync:status`. Resolves false
+// outside Tauri, or when the setting could not be saved, and the webview keeps its own auto-sync
+// loop.
+export async function configureNativeSyncScheduler(
+  enabled: boolean,
+): Promise<boolean> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return false;
+
+  try {
+    await invoke(TAURI_CONFIGURE_SCHEDULER_COMMAND, { enabled });
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+// The scheduler cannot see `navigator.onLine`, nor battery saver on most platforms.
+export async function reportNativeSyncEnvironment(input: {
+  online?: boolean;
+  batterySaver?: boolean;
+}): Promise<void> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return;
+
+  await invoke(TAURI_REPORT_ENVIRONMENT_COMMAND, {
+    online: input.online ?? null,
+    batterySaver: input.batterySaver ?? null,
+  });
+}
+
+// The webview cannot read the battery saver setting itself. Running on battery at or below the
+// level where Windows turns it on is the closest signal; WebKit has no Battery Status API, so
+// macOS and iOS report unknown. Returns the unsubscribe function.
+export function watchBatterySaver(
+  onChange: (batterySaver: boolean | null) => void,
+): () => void {
+  const getBattery =
+    typeof navigator === "undefined"
+      ? undefined
+      : (navigator as Navigator & { getBattery?: () => Promise<BatteryStatus> })
+          .getBattery;
+  if (!getBattery) {
+    onChange(null);
+    return () => {};
+  }
+
+  let disposed = false;
+  let battery: BatteryStatus | null = null;
+  const report = () => {
+    if (disposed || !battery) return;
+    onChange(!battery.charging && battery.level <= BATTERY_SAVER_LEVEL);
+  };
+  void getBattery
+    .call(navigator)
+    .then((status) => {
+      battery = status;
+      battery.addEventListener("chargingchange", report);
+      battery.addEventListener("levelchange", report);
+      report();
+    })
+    .catch(() => {
+      if (!disposed) onChange(null);
+    });
+
+  return () => {
+    disposed = true;
+    battery?.removeEventListener("chargingchange", report);
+    battery?.removeEventListener("levelchange", report);
+  };
+}
+
+// The last `sync:status` payload, for a window that opens after it was emitted.
+export async function getNativeSyncStatus(): Promise<NativeSyncStatusPayload | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<NativeSyncStatusPayload | null>(TAURI_GET_STATUS_COMMAND);
+}
+
+// Transport for providers that only exist in Rust (`local_folder`, `webdav`). Outside Tauri
+// every call rejects with `SYNC_TRANSPORT_UNAVAILABLE`.
+export function createNativeSyncTransport(): SyncTransport {
+  const exchange = async (operation: "push" | "pull", payload: unknown) => {
+    const invoke = await resolveTauriInvoke();
+    if (!invoke) throw new Error("SYNC_TRANSPORT_UNAVAILABLE");
+    return invoke<unknown>(TAURI_EXCHANGE_ENVELOPE_COMMAND, {
+      operation,
+      payload,
+    });
+  };
+
+  return {
+    push: (payload: unknown) => exchange("push", payload),
+    pull: (payload: unknown) => exchange("pull", payload),
+  };
+}
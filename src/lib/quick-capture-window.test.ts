@@ -0,0 +1,68 @@
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+const tauriCoreMock = vi.hoisted(() => ({
+  isTauri: vi.fn<() => boolean>(),
+  invoke:
+    vi.fn<
+      (command: string, args?: Record<string, unknown>) => Promise<unknown>
+    >(),
+}));
+const tauriWindowMock = vi.hoisted(() => ({
+  label: "main",
+}));
+
+vi.mock("@tauri-apps/api/core", () => ({
+  isTauri: tauriCoreMock.isTauri,
+  invoke: tauriCoreMock.invoke,
+}));
+
+vi.mock("@tauri-apps/api/window", () => ({
+  getCurrentWindow: () => ({ label: tauriWindowMock.label }),
+}));
+
+import {
+  createQuickCaptureTask,
+  dismissQuickCaptureWindow,
+  isQuickCaptureWindow,
+} from "@/lib/quick-capture-window";
+
+describe("quick-capture-window", () => {
+  beforeEach(() => {
+    tauriCoreMock.isTauri.mockReset();
+    tauriCoreMock.invoke.mockReset();
+    tauriCoreMock.isTauri.mockReturnValue(false);
+    tauriWindowMock.label = "main";
+  });
+
+  it("never treats a browser runtime as the capture window", async () => {
+    tauriWindowMock.label = "quick-capture";
+
+    await expect(isQuickCaptureWindow()).resolves.toBe(false);
+    await expect(createQuickCaptureTask("Buy milk")).resolves.toBeNull();
+    expect(tauriCoreMock.invoke).not.toHaveBeenCalled();
+  });
+
+  it("detects the capture window by its label", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+
+    await expect(isQuickCaptureWindow()).resolves.toBe(false);
+    tauriWindowMock.label = "quick-capture";
+    await expect(isQuickCaptureWindow()).resolves.toBe(true);
+  });
+
+  it("creates tasks and dismisses the window via tauri commands", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockResolvedValue(undefined);
+
+    await createQuickCaptureTask("Buy milk");
+    await dismissQuickCaptureWindow();
+
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "create_quick_capture_task",
+      { title: "Buy milk" },
+    );
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "dismiss_quick_capture_window",
+    );
+  });
+});
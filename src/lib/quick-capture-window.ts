@@ -0,0 +1,55 @@
+import type { Task } from "./types";
+
+const QUICK_CAPTURE_WINDOW_LABEL = "quick-capture";
+const TAURI_CREATE_COMMAND = "create_quick_capture_task";
+const TAURI_DISMISS_COMMAND = "dismiss_quick_capture_window";
+
+type TauriInvoke = <T = unknown>(
+  command: string,
+  args?: Record<string, unknown>,
+) => Promise<T>;
+
+async function resolveTauriInvoke(): Promise<TauriInvoke | null> {
+  if (typeof window === "undefined") return null;
+
+  try {
+    const { isTauri, invoke } = await import("@tauri-apps/api/core");
+    if (!isTauri()) return null;
+    return invoke as TauriInvoke;
+  } catch {
+    return null;
+  }
+}
+
+// The native side opens the capture window on the same frontend bundle; the label is the only
+// thing that tells the two apart.
+export async function isQuickCaptureWindow(): Promise<boolean> {
+  if (typeof window === "undefined") return false;
+
+  try {
+    const { isTauri } = await import("@tauri-apps/api/core");
+    if (!isTauri()) return false;
+    const { getCurrentWindow } = await import("@tauri-apps/api/window");
+    return getCurrentWindow().label === QUICK_CAPTURE_WINDOW_LABEL;
+  } catch {
+    return false;
+  }
+}
+
+// Saves the task and hides the capture window; the main window refreshes from the
+// `quick-capture:task-created` event.
+export async function createQuickCaptureTask(
+  title: string,
+): Promise<Task | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<Task>(TAURI_CREATE_COMMAND, { title });
+}
+
+export async function dismissQuickCaptureWindow(): Promise<void> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return;
+
+  await invoke(TAURI_DISMISS_COMMAND);
+}
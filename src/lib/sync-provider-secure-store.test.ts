@@ -14,10 +14,19 @@ vi.mock("@tauri-apps/api/core", () => ({
 }));
 
 import {
+  authorizeSyncProvider,
+  listSecureStoreAuditRecords,
+  listSyncProviderSecureAuthStatus,
   runSyncProviderSecureStoreSelfTest,
   readSyncProviderSecureAuth,
+  setActiveSyncProviderAccount,
   writeSyncProviderSecureAuth,
 } from "@/lib/sync-provider-secure-store";
+import {
+  clearSyncProviderSessionAuthForTests,
+  getSyncProviderSessionAuth,
+  setSyncProviderSessionAuth,
+} from "@/lib/sync-provider-token-policy";
 
 describe("sync-provider-secure-store", () => {
   beforeEach(() => {
@@ -53,6 +62,25 @@ describe("sync-provider-secure-store", () => {
     });
   });
 
+  it("reads the secret from a structured credential record", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockResolvedValueOnce({
+      version: 1,
+      kind: "oauth_tokens",
+      secret: JSON.stringify({
+        access_token: "access-2",
+        token_type: "Bearer",
+      }),
+      expires_at: null,
+      scopes: [],
+      issued_at: "2026-01-01T00:00:00.000Z",
+      rotation: 3,
+    });
+
+    const auth = await readSyncProviderSecureAuth("onedrive_approot");
+    expect(auth).toMatchObject({ access_token: "access-2" });
+  });
+
   it("writes secure auth via tauri command", async () => {
     tauriCoreMock.isTauri.mockReturnValue(true);
     tauriCoreMock.invoke.mockResolvedValue(undefined);
@@ -76,10 +104,83 @@ describe("sync-provider-secure-store", () => {
       "set_sync_provider_secure_auth",
       expect.objectContaining({
         provider: "google_appdata",
+        credential: expect.objectContaining({
+          kind: "oauth_tokens",
+          expires_at: null,
+          scopes: [],
+        }),
       }),
     );
   });
 
+  it("lists secure auth status without secrets", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockResolvedValueOnce([
+      {
+        provider: "google_appdata",
+        expiry: "expiring_soon",
+        kind: "oauth_tokens",
+        expires_at: "2026-01-01T00:05:00.000Z",
+        expires_in_seconds: 300,
+        scopes: ["drive.appdata"],
+        issued_at: "2026-01-01T00:00:00.000Z",
+        rotation: 2,
+        error: null,
+      },
+    ]);
+
+    const statuses = await listSyncProviderSecureAuthStatus(["google_appdata"]);
+
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "list_sync_provider_secure_auth_status",
+      { providers: ["google_appdata"] },
+    );
+    expect(statuses[0]?.expiry).toBe("expiring_soon");
+  });
+
+  it("runs native oauth sign-in and returns only the status", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockResolvedValueOnce({
+      provider: "onedrive_approot",
+      account_id: "default",
+      expiry: "valid",
+      kind: "oauth_tokens",
+      expires_at: "2026-01-01T01:00:00.000Z",
+      expires_in_seconds: 3600,
+      scopes: ["Files.ReadWrite.AppFolder", "offline_access"],
+      issued_at: "2026-01-01T00:00:00.000Z",
+      rotation: 0,
+      error: null,
+    });
+
+    const status = await authorizeSyncProvider({
+      provider: "onedrive_approot",
+      client_id: "client-1",
+    });
+
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "authorize_sync_provider",
+      { request: { provider: "onedrive_approot", client_id: "client-1" } },
+    );
+    expect(status?.kind).toBe("oauth_tokens");
+  });
+
+  it("surfaces oauth sign-in errors", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockRejectedValueOnce({
+      code: "OAUTH_AUTHORIZATION_DENIED",
+      detail: "authorization server returned access_denied: ",
+      retryable: false,
+    });
+
+    await expect(
+      authorizeSyncProvider({
+        provider: "google_appdata",
+        client_id: "client-1",
+      }),
+    ).rejects.toMatchObject({ code: "OAUTH_AUTHORIZATION_DENIED" });
+  });
+
   it("clears secure auth when auth payload is missing", async () => {
     tauriCoreMock.isTauri.mockReturnValue(true);
     tauriCoreMock.invoke.mockResolvedValue(undefined);
@@ -95,6 +196,89 @@ describe("sync-provider-secure-store", () => {
     );
   });
 
+  it("keys secure auth by account id when one is given", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockResolvedValue(undefined);
+
+    await writeSyncProviderSecureAuth({
+      provider: "onedrive_approot",
+      auth: null,
+      account_id: "work",
+    });
+    await readSyncProviderSecureAuth("onedrive_approot", "work");
+
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "delete_sync_provider_secure_auth",
+      { provider: "onedrive_approot", accountId: "work" },
+    );
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "get_sync_provider_secure_auth",
+      { provider: "onedrive_approot", accountId: "work" },
+    );
+  });
+
+  it("drops the session auth when switching the active account", async () => {
+    clearSyncProviderSessionAuthForTests();
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockResolvedValueOnce({
+      provider: "onedrive_approot",
+      active_account_id: "personal",
+      accounts: [],
+    });
+    setSyncProviderSessionAuth({
+      provider: "onedrive_approot",
+      auth: {
+        access_token: "work-access",
+        token_type: "Bearer",
+        refresh_token: null,
+        token_refresh_url: null,
+        expires_at: null,
+        scope: null,
+        client_id: null,
+        client_secret: null,
+      },
+    });
+
+    const accounts = await setActiveSyncProviderAccount(
+      "onedrive_approot",
+      "personal",
+    );
+
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "set_active_sync_provider_account",
+      { provider: "onedrive_approot", accountId: "personal" },
+    );
+    expect(accounts?.active_account_id).toBe("personal");
+    expect(getSyncProviderSessionAuth("onedrive_approot")).toBeNull();
+  });
+
+  it("pages secure store audit records", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockResolvedValueOnce({
+      records: [
+        {
+          timestamp_iso: "2026-03-01T09:00:00.000Z",
+          operation: "delete",
+          entry: "credential",
+          provider: "google_appdata",
+          backend: "keyring",
+          outcome: "failed",
+          error_code: "SECURE_STORE_DELETE_FAILED",
+        },
+      ],
+      next_offset: 20,
+    });
+
+    const page = await listSecureStoreAuditRecords({ offset: 0, limit: 20 });
+
+    expect(tauriCoreMock.invoke).toHaveBeenCalledWith(
+      "list_secure_store_audit_records",
+      { offset: 0, limit: 20 },
+    );
+    expect(page?.records[0]?.error_code).toBe("SECURE_STORE_DELETE_FAILED");
+    expect(page?.next_offset).toBe(20);
+  });
+
   it("returns unavailable self-test result when runtime is not tauri", async () => {
     const result = await runSyncProviderSecureStoreSelfTest();
 
@@ -137,6 +321,31 @@ describe("sync-provider-secure-store", () => {
     });
   });
 
+  it("reports the fallback backend and why the primary failed", async () => {
+    tauriCoreMock.isTauri.mockReturnValue(true);
+    tauriCoreMock.invoke.mockResolvedValueOnce({
+      runtime: "tauri",
+      backend: "encrypted_file",
+      available: true,
+      write_ok: true,
+      read_ok: true,
+      delete_ok: true,
+      roundtrip_ok: true,
+      detail: null,
+      primary_backend: "keyring",
+      fallback_reason: "write failed: Platform secure storage failure",
+    });
+
+    const result = await runSyncProviderSecureStoreSelfTest();
+
+    expect(result).toMatchObject({
+      backend: "encrypted_file",
+      primary_backend: "keyring",
+      fallback_reason: "write failed: Platform secure storage failure",
+      roundtrip_ok: true,
+    });
+  });
+
   it("maps tauri self-test command failures to unavailable result", async () => {
     tauriCoreMock.isTauri.mockReturnValue(true);
     tauriCoreMock.invoke.mockRejectedValueOnce(
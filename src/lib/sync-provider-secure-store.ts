@@ -3,11 +3,17 @@ import {
   parseSyncProviderAuthState,
   type SyncProviderAuthState,
 } from "@/lib/sync-provider-auth";
+import { setSyncProviderSessionAuth } from "@/lib/sync-provider-token-policy";
 
 const TAURI_GET_AUTH_COMMAND = "get_sync_provider_secure_auth";
 const TAURI_SET_AUTH_COMMAND = "set_sync_provider_secure_auth";
 const TAURI_DELETE_AUTH_COMMAND = "delete_sync_provider_secure_auth";
 const TAURI_SELF_TEST_COMMAND = "run_sync_provider_secure_store_self_test";
+const TAURI_LIST_AUTH_STATUS_COMMAND = "list_sync_provider_secure_auth_status";
+const TAURI_AUTHORIZE_COMMAND = "authorize_sync_provider";
+const TAURI_LIST_ACCOUNTS_COMMAND = "list_sync_provider_accounts";
+const TAURI_SET_ACTIVE_ACCOUNT_COMMAND = "set_active_sync_provider_account";
+const TAURI_LIST_AUDIT_RECORDS_COMMAND = "list_secure_store_audit_records";
 
 type TauriInvoke = <T = unknown>(
   command: string,
@@ -23,6 +29,65 @@ export interface SyncProviderSecureStoreSelfTestResult {
   delete_ok: boolean;
   roundtrip_ok: boolean;
   detail: string | null;
+  // Set when the primary backend failed its probe and `backend` is the fallback in use.
+  primary_backend: string;
+  fallback_reason: string | null;
+}
+
+export type SyncProviderCredentialKind =
+  | "oauth_tokens"
+  | "access_token"
+  | "refresh_token"
+  | "api_key"
+  | "opaque";
+
+export type SyncProviderCredentialExpiry =
+  | "missing"
+  | "no_expiry"
+  | "valid"
+  | "expiring_soon"
+  | "expired";
+
+// Mirrors `SyncProviderSecureAuthStatus` in Rust; never carries the secret.
+export interface SyncProviderSecureAuthStatus {
+  provider: string;
+  account_id: string;
+  expiry: SyncProviderCredentialExpiry;
+  kind: SyncProviderCredentialKind | null;
+  expires_at: string | null;
+  expires_in_seconds: number | null;
+  scopes: string[];
+  issued_at: string | null;
+  rotation: number | null;
+  error: { code: string; detail: string | null; retryable: boolean } | null;
+}
+
+export interface SyncProviderAccountList {
+  provider: string;
+  active_account_id: string | null;
+  accounts: SyncProviderSecureAuthStatus[];
+}
+
+// Audit records never carry secrets, account ids or error details.
+export interface SecureStoreAuditRecord {
+  timestamp_iso: string;
+  operation: "read" | "write" | "delete" | "self_test";
+  entry: "credential" | "account_index" | "self_test";
+  provider: string | null;
+  backend: string;
+  outcome: "ok" | "not_found" | "failed";
+  error_code: string | null;
+}
+
+export interface SecureStoreAuditPage {
+  records: SecureStoreAuditRecord[];
+  next_offset: number | null;
+}
+
+// Commands fall back to the provider's active account when no account id is sent.
+function accountArgs(accountId: string | null | undefined) {
+  const normalized = asNullableString(accountId);
+  return normalized ? { accountId: normalized } : {};
 }
 
 function asNullableString(value: unknown): string | null {
@@ -77,6 +142,8 @@ function normalizeSelfTestResult(
       : "tauri";
   const backend = asNullableString(source.backend) ?? "unknown";
   const detail = asNullableString(source.detail);
+  const primaryBackend = asNullableString(source.primary_backend) ?? backend;
+  const fallbackReason = asNullableString(source.fallback_reason);
   const available = asBoolean(source.available);
   const writeOk = asBoolean(source.write_ok);
   const readOk = asBoolean(source.read_ok);
@@ -94,11 +161,14 @@ function normalizeSelfTestResult(
     delete_ok: deleteOk,
     roundtrip_ok: roundtripOk,
     detail,
+    primary_backend: primaryBackend,
+    fallback_reason: fallbackReason,
   };
 }
 
 export async function readSyncProviderSecureAuth(
   provider: SyncProvider,
+  accountId?: string | null,
 ): Promise<SyncProviderAuthState | null> {
   const invoke = await resolveTauriInvoke();
   if (!invoke) return null;
@@ -106,8 +176,15 @@ export async function readSyncProviderSecureAuth(
   try {
     const payload = await invoke<unknown>(TAURI_GET_AUTH_COMMAND, {
       provider,
+      ...accountArgs(accountId),
     });
-    const normalizedPayload = asNullableString(payload) ?? payload;
+    // The command returns a credential record whose `secret` holds the token-set JSON, without
+    // the refresh token and client secret; Rust keeps those when the set is written back.
+    const secret =
+      payload && typeof payload === "object" && "secret" in payload
+        ? (payload as { secret: unknown }).secret
+        : payload;
+    const normalizedPayload = asNullableString(secret) ?? secret;
     return normalizeAuthPayload(normalizedPayload);
   } catch {
     return null;
@@ -117,6 +194,7 @@ export async function readSyncProviderSecureAuth(
 export async function writeSyncProviderSecureAuth(input: {
   provider: SyncProvider;
   auth: SyncProviderAuthState | null | undefined;
+  account_id?: string | null;
 }): Promise<void> {
   const invoke = await resolveTauriInvoke();
   if (!invoke) return;
@@ -129,19 +207,127 @@ export async function writeSyncProviderSecureAuth(input: {
     if (!normalizedAuth) {
       await invoke(TAURI_DELETE_AUTH_COMMAND, {
         provider: input.provider,
+        ...accountArgs(input.account_id),
       });
       return;
     }
 
     await invoke(TAURI_SET_AUTH_COMMAND, {
       provider: input.provider,
-      auth: JSON.stringify(normalizedAuth),
+      credential: {
+        kind: "oauth_tokens",
+        secret: JSON.stringify(normalizedAuth),
+        expires_at: normalizedAuth.expires_at,
+        scopes: normalizedAuth.scope?.split(/\s+/).filter(Boolean) ?? [],
+      },
+      ...accountArgs(input.account_id),
     });
   } catch {
     // Best-effort secure storage only. Runtime in-memory auth remains available.
   }
 }
 
+// Credentials that are not OAuth token sets (the WebDAV app password) are stored as-is for Rust
+// to read when it builds the transport. Unlike token sets, a failed write is surfaced.
+export async function writeSyncProviderSecret(input: {
+  provider: SyncProvider;
+  secret: string;
+  account_id?: string | null;
+}): Promise<boolean> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return false;
+
+  await invoke(TAURI_SET_AUTH_COMMAND, {
+    provider: input.provider,
+    credential: {
+      kind: "api_key",
+      secret: input.secret,
+    },
+    ...accountArgs(input.account_id),
+  });
+  return true;
+}
+
+export async function listSyncProviderSecureAuthStatus(
+  providers?: SyncProvider[],
+): Promise<SyncProviderSecureAuthStatus[]> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return [];
+
+  try {
+    const payload = await invoke<unknown>(TAURI_LIST_AUTH_STATUS_COMMAND, {
+      providers: providers ?? null,
+    });
+    return Array.isArray(payload)
+      ? (payload as SyncProviderSecureAuthStatus[])
+      : [];
+  } catch {
+    return [];
+  }
+}
+
+export interface SyncProviderOAuthRequest {
+  provider: Extract<SyncProvider, "google_appdata" | "onedrive_approot">;
+  account_id?: string | null;
+  client_id: string;
+  client_secret?: string | null;
+  scopes?: string[] | null;
+}
+
+// Unlike the best-effort helpers above, sign-in failures are surfaced to the caller. Tokens are
+// stored natively and only the resulting status comes back.
+export async function authorizeSyncProvider(
+  request: SyncProviderOAuthRequest,
+): Promise<SyncProviderSecureAuthStatus | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<SyncProviderSecureAuthStatus>(TAURI_AUTHORIZE_COMMAND, {
+    request,
+  });
+}
+
+export async function listSyncProviderAccounts(
+  provider: SyncProvider,
+): Promise<SyncProviderAccountList | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<SyncProviderAccountList>(TAURI_LIST_ACCOUNTS_COMMAND, {
+    provider,
+  });
+}
+
+export async function setActiveSyncProviderAccount(
+  provider: SyncProvider,
+  accountId: string,
+): Promise<SyncProviderAccountList | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  const accounts = await invoke<SyncProviderAccountList>(
+    TAURI_SET_ACTIVE_ACCOUNT_COMMAND,
+    { provider, accountId },
+  );
+  // The session copy belongs to the previous account; the next sync cycle hydrates the new one.
+  setSyncProviderSessionAuth({ provider, auth: null });
+  return accounts;
+}
+
+// Newest record first; pass `next_offset` back to read the following page.
+export async function listSecureStoreAuditRecords(input?: {
+  offset?: number;
+  limit?: number;
+}): Promise<SecureStoreAuditPage | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<SecureStoreAuditPage>(TAURI_LIST_AUDIT_RECORDS_COMMAND, {
+    offset: input?.offset ?? null,
+    limit: input?.limit ?? null,
+  });
+}
+
 export async function runSyncProviderSecureStoreSelfTest(): Promise<SyncProviderSecureStoreSelfTestResult> {
   const invoke = await resolveTauriInvoke();
   if (!invoke) {
@@ -154,6 +340,8 @@ export async function runSyncProviderSecureStoreSelfTest(): Promise<SyncProvider
       delete_ok: false,
       roundtrip_ok: false,
       detail: "tauri runtime is not available",
+      primary_backend: "unsupported",
+      fallback_reason: null,
     };
   }
 
@@ -173,6 +361,8 @@ export async function runSyncProviderSecureStoreSelfTest(): Promise<SyncProvider
         error instanceof Error
           ? error.message
           : "secure store self-test command failed",
+      primary_backend: "unknown",
+      fallback_reason: null,
     };
   }
 }
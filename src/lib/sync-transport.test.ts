@@ -173,6 +173,50 @@ describe("sync-transport", () => {
     expect(resolved.warning).toContain("connector is not configured");
   });
 
+  it("resolves local_folder through the native transport once a folder is set", () => {
+    const unconfigured = resolveSyncTransportConfig({
+      provider: "local_folder",
+      pushUrl: "https://sync.example.com/v1/sync/push",
+      pullUrl: "https://sync.example.com/v1/sync/pull",
+    });
+    expect(unconfigured.status).toBe("provider_unavailable");
+    expect(unconfigured.transport).toBeNull();
+    expect(unconfigured.warning).toContain("Local folder");
+
+    const configured = resolveSyncTransportConfig({
+      provider: "local_folder",
+      pushUrl: null,
+      pullUrl: null,
+      providerConfig: { directory: "/Volumes/share/SoloStack" },
+    });
+    expect(configured.status).toBe("ready");
+    expect(configured.transport).toBeTruthy();
+  });
+
+  it("resolves webdav through the native transport once the server is set", () => {
+    const missingUsername = resolveSyncTransportConfig({
+      provider: "webdav",
+      pushUrl: null,
+      pullUrl: null,
+      providerConfig: { base_url: "https://dav.example.com/solostack" },
+    });
+    expect(missingUsername.status).toBe("provider_unavailable");
+    expect(missingUsername.transport).toBeNull();
+    expect(missingUsername.warning).toContain("WebDAV");
+
+    const configured = resolveSyncTransportConfig({
+      provider: "webdav",
+      pushUrl: null,
+      pullUrl: null,
+      providerConfig: {
+        base_url: "https://dav.example.com/solostack",
+        username: "me",
+      },
+    });
+    expect(configured.status).toBe("ready");
+    expect(configured.transport).toBeTruthy();
+  });
+
   it("resolves managed provider as ready via connector settings and executes push/pull RPC", async () => {
     const pushResponsePayload = {
       accepted: [],
@@ -1,6 +1,7 @@
 import { parseSyncApiError } from "./sync-contract";
 import type { SyncTransport } from "./sync-runner";
 import { translate } from "./i18n";
+import { createNativeSyncTransport } from "./native-sync";
 import type { AppLocale, SyncProvider } from "./types";
 import {
   createManagedSyncConnectorAdapterFromSettings,
@@ -69,6 +70,40 @@ function isManagedSyncProvider(provider: SyncProvider): boolean {
   return provider !== "provider_neutral";
 }
 
+// Providers whose transport only exists in Rust; the webview reaches it through
+// `exchange_native_sync_envelope`.
+function isNativeSyncProvider(provider: SyncProvider): boolean {
+  return provider === "local_folder" || provider === "webdav";
+}
+
+// Providers the Rust sync engine can run a whole cycle for; cloud connectors only exist in the
+// webview.
+export function supportsNativeSyncCycle(provider: SyncProvider): boolean {
+  return provider === "provider_neutral" || isNativeSyncProvider(provider);
+}
+
+function hasConfigString(
+  providerConfig: Record<string, unknown> | null | undefined,
+  key: string,
+): boolean {
+  const value = providerConfig?.[key];
+  return typeof value === "string" && value.trim().length > 0;
+}
+
+// The WebDAV password is checked by Rust, which reads it from the secure store.
+function isNativeProviderConfigured(
+  provider: SyncProvider,
+  providerConfig: Record<string, unknown> | null | undefined,
+): boolean {
+  if (provider === "webdav") {
+    return (
+      hasConfigString(providerConfig, "base_url") &&
+      hasConfigString(providerConfig, "username")
+    );
+  }
+  return hasConfigString(providerConfig, "directory");
+}
+
 function normalizeTimeoutMs(value: number | undefined): number {
   if (typeof value !== "number" || !Number.isFinite(value)) {
     return DEFAULT_SYNC_TIMEOUT_MS;
@@ -115,6 +150,15 @@ function getLocalizedProviderLabel(
       "settings.sync.provider.capability.solostack_cloud_aws.label",
     );
   }
+  if (provider === "local_folder") {
+    return translate(
+      locale,
+      "settings.sync.provider.capability.local_folder.label",
+    );
+  }
+  if (provider === "webdav") {
+    return translate(locale, "settings.sync.provider.capability.webdav.label");
+  }
   return translate(
     locale,
     "settings.sync.provider.capability.provider_neutral.label",
@@ -350,6 +394,27 @@ export function resolveSyncTransportConfig(
     };
   }
 
+  if (isNativeSyncProvider(input.provider)) {
+    if (!isNativeProviderConfigured(input.provider, input.providerConfig)) {
+      return {
+        status: "provider_unavailable",
+        provider: input.provider,
+        transport: null,
+        warning: translate(
+          locale,
+          "sync.transport.warning.providerNotConfigured",
+          { provider: providerLabel },
+        ),
+      };
+    }
+    return {
+      status: "ready",
+      provider: input.provider,
+      transport: createNativeSyncTransport(),
+      warning: null,
+    };
+  }
+
   const managedTransport = providerManaged
     ? createManagedConnectorSyncTransport({
         provider: input.provider,
@@ -0,0 +1,61 @@
+const TAURI_GET_SETTINGS_COMMAND = "get_tray_settings";
+const TAURI_SET_KEEP_RUNNING_COMMAND = "set_keep_running_in_tray";
+const TAURI_SET_REMINDERS_PAUSED_COMMAND = "set_reminders_paused";
+const TAURI_REFRESH_COUNTS_COMMAND = "refresh_tray_task_counts";
+
+type TauriInvoke = <T = unknown>(
+  command: string,
+  args?: Record<string, unknown>,
+) => Promise<T>;
+
+export interface TraySettings {
+  keep_running_in_tray: boolean;
+  reminders_paused: boolean;
+}
+
+async function resolveTauriInvoke(): Promise<TauriInvoke | null> {
+  if (typeof window === "undefined") return null;
+
+  try {
+    const { isTauri, invoke } = await import("@tauri-apps/api/core");
+    if (!isTauri()) return null;
+    return invoke as TauriInvoke;
+  } catch {
+    return null;
+  }
+}
+
+// The tray icon is desktop-only; every call resolves to null elsewhere.
+export async function getTraySettings(): Promise<TraySettings | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<TraySettings>(TAURI_GET_SETTINGS_COMMAND);
+}
+
+export async function setKeepRunningInTray(
+  enabled: boolean,
+): Promise<TraySettings | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<TraySettings>(TAURI_SET_KEEP_RUNNING_COMMAND, { enabled });
+}
+
+// Also emitted as `reminders:paused-changed`, so the tray menu and every window stay in step.
+export async function setRemindersPaused(
+  paused: boolean,
+): Promise<TraySettings | null> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return null;
+
+  return invoke<TraySettings>(TAURI_SET_REMINDERS_PAUSED_COMMAND, { paused });
+}
+
+// Task writes from the webview go through the SQL plugin, which the tray cannot observe.
+export async function refreshTrayTaskCounts(): Promise<void> {
+  const invoke = await resolveTauriInvoke();
+  if (!invoke) return;
+
+  await invoke(TAURI_REFRESH_COUNTS_COMMAND);
+}
@@ -349,7 +349,9 @@ export type SyncProvider =
   | "google_appdata"
   | "onedrive_approot"
   | "icloud_cloudkit"
-  | "solostack_cloud_aws";
+  | "solostack_cloud_aws"
+  | "local_folder"
+  | "webdav";
 
 export interface SyncProviderSettings {
   provider: SyncProvider;
@@ -1,9 +1,15 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { QuickCaptureWindow } from "./components/QuickCaptureWindow";
+import { isQuickCaptureWindow } from "./lib/quick-capture-window";
 
-ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
-  <React.StrictMode>
-    <App />
-  </React.StrictMode>,
-);
+const root = ReactDOM.createRoot(document.getElementById("root") as HTMLElement);
+
+void isQuickCaptureWindow().then((isCaptureWindow) => {
+  root.render(
+    <React.StrictMode>
+      {isCaptureWindow ? <QuickCaptureWindow /> : <App />}
+    </React.StrictMode>,
+  );
+});
@@ -0,0 +1,12 @@
+import fs from "node:fs";
+
+// Snapshot of the schema the Rust migrations create; `src-tauri/src/db.rs` tests keep it
+// current. Database tests start from it because the webview no longer creates tables.
+const RUST_SCHEMA_SNAPSHOT_URL = new URL(
+  "./solostack-schema.sql",
+  import.meta.url,
+);
+
+export function applyRustSchema(db: { exec(sql: string): void }): void {
+  db.exec(fs.readFileSync(RUST_SCHEMA_SNAPSHOT_URL, "utf8"));
+}
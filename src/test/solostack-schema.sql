@@ -0,0 +1,190 @@
+-- Generated from the schema migrations in src-tauri/src/db.rs.
+-- Refresh with SOLOSTACK_UPDATE_SCHEMA_SNAPSHOT=1 cargo test schema_snapshot.
+
+CREATE TABLE deleted_records (
+          id TEXT PRIMARY KEY,
+          entity_type TEXT NOT NULL CHECK(entity_type IN ('PROJECT', 'TASK', 'TASK_SUBTASK', 'TASK_TEMPLATE', 'SETTING')),
+          entity_id TEXT NOT NULL,
+          deleted_at DATETIME NOT NULL,
+          deleted_by_device TEXT,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          UNIQUE(entity_type, entity_id)
+        );
+
+CREATE TABLE projects (
+          id TEXT PRIMARY KEY,
+          name TEXT NOT NULL UNIQUE,
+          description TEXT,
+          color TEXT,
+          status TEXT NOT NULL DEFAULT 'ACTIVE' CHECK(status IN ('ACTIVE', 'COMPLETED', 'ARCHIVED')),
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          sync_version INTEGER NOT NULL DEFAULT 1,
+          updated_by_device TEXT
+        );
+
+CREATE TABLE schema_migrations (
+      version INTEGER PRIMARY KEY,
+      name TEXT NOT NULL,
+      applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+
+CREATE TABLE sessions (
+          id TEXT PRIMARY KEY,
+          task_id TEXT,
+          duration_minutes INTEGER NOT NULL,
+          completed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          FOREIGN KEY(task_id) REFERENCES tasks(id)
+        );
+
+CREATE TABLE settings (
+          key TEXT PRIMARY KEY,
+          value TEXT NOT NULL
+        );
+
+CREATE TABLE sync_checkpoints (
+          id INTEGER PRIMARY KEY CHECK(id = 1),
+          last_sync_cursor TEXT,
+          last_synced_at DATETIME,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+CREATE TABLE sync_conflict_events (
+          id TEXT PRIMARY KEY,
+          conflict_id TEXT NOT NULL,
+          event_type TEXT NOT NULL CHECK(event_type IN ('detected', 'resolved', 'ignored', 'retried', 'exported')),
+          event_payload_json TEXT,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          FOREIGN KEY(conflict_id) REFERENCES sync_conflicts(id) ON DELETE CASCADE
+        );
+
+CREATE TABLE sync_conflicts (
+          id TEXT PRIMARY KEY,
+          incoming_idempotency_key TEXT NOT NULL UNIQUE,
+          entity_type TEXT NOT NULL CHECK(entity_type IN ('PROJECT', 'TASK', 'TASK_SUBTASK', 'TASK_TEMPLATE', 'SETTING')),
+          entity_id TEXT NOT NULL,
+          operation TEXT NOT NULL CHECK(operation IN ('UPSERT', 'DELETE')),
+          conflict_type TEXT NOT NULL CHECK(conflict_type IN ('field_conflict', 'delete_vs_update', 'notes_collision', 'validation_error')),
+          reason_code TEXT NOT NULL,
+          message TEXT NOT NULL,
+          local_payload_json TEXT,
+          remote_payload_json TEXT,
+          base_payload_json TEXT,
+          status TEXT NOT NULL DEFAULT 'open' CHECK(status IN ('open', 'resolved', 'ignored')),
+          resolution_strategy TEXT CHECK(resolution_strategy IN ('keep_local', 'keep_remote', 'manual_merge', 'retry')),
+          resolution_payload_json TEXT,
+          resolved_by_device TEXT,
+          detected_at DATETIME NOT NULL,
+          resolved_at DATETIME,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+CREATE TABLE sync_outbox (
+          id TEXT PRIMARY KEY,
+          entity_type TEXT NOT NULL CHECK(entity_type IN ('PROJECT', 'TASK', 'TASK_SUBTASK', 'TASK_TEMPLATE', 'SETTING')),
+          entity_id TEXT NOT NULL,
+          operation TEXT NOT NULL CHECK(operation IN ('UPSERT', 'DELETE')),
+          payload_json TEXT,
+          idempotency_key TEXT NOT NULL UNIQUE,
+          attempts INTEGER NOT NULL DEFAULT 0,
+          last_error TEXT,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+CREATE TABLE task_changelogs (
+          id TEXT PRIMARY KEY,
+          task_id TEXT NOT NULL,
+          action TEXT NOT NULL CHECK(action IN ('CREATED', 'UPDATED', 'STATUS_CHANGED')),
+          field_name TEXT,
+          old_value TEXT,
+          new_value TEXT,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+CREATE TABLE task_subtasks (
+          id TEXT PRIMARY KEY,
+          task_id TEXT NOT NULL,
+          title TEXT NOT NULL,
+          is_done BOOLEAN DEFAULT 0,
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          sync_version INTEGER NOT NULL DEFAULT 1,
+          updated_by_device TEXT,
+          FOREIGN KEY(task_id) REFERENCES tasks(id) ON DELETE CASCADE
+        );
+
+CREATE TABLE task_templates (
+          id TEXT PRIMARY KEY,
+          name TEXT NOT NULL UNIQUE,
+          title_template TEXT,
+          description TEXT,
+          priority TEXT NOT NULL CHECK(priority IN ('URGENT', 'NORMAL', 'LOW')),
+          is_important BOOLEAN DEFAULT 0,
+          due_offset_minutes INTEGER,
+          remind_offset_minutes INTEGER,
+          recurrence TEXT NOT NULL DEFAULT 'NONE' CHECK(recurrence IN ('NONE', 'DAILY', 'WEEKLY', 'MONTHLY')),
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          sync_version INTEGER NOT NULL DEFAULT 1,
+          updated_by_device TEXT
+        );
+
+CREATE TABLE tasks (
+          id TEXT PRIMARY KEY,
+          title TEXT NOT NULL,
+          description TEXT,
+          notes_markdown TEXT,
+          project_id TEXT,
+          status TEXT NOT NULL CHECK(status IN ('TODO', 'DOING', 'DONE', 'ARCHIVED')),
+          priority TEXT NOT NULL CHECK(priority IN ('URGENT', 'NORMAL', 'LOW')),
+          is_important BOOLEAN DEFAULT 0,
+          due_at DATETIME,
+          remind_at DATETIME,
+          recurrence TEXT NOT NULL DEFAULT 'NONE' CHECK(recurrence IN ('NONE', 'DAILY', 'WEEKLY', 'MONTHLY')),
+          created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+          sync_version INTEGER NOT NULL DEFAULT 1,
+          updated_by_device TEXT,
+          FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE SET NULL
+        );
+
+CREATE INDEX idx_deleted_records_deleted_at
+        ON deleted_records(deleted_at DESC);
+
+CREATE INDEX idx_projects_status_updated_at
+        ON projects(status, updated_at DESC);
+
+CREATE INDEX idx_sync_conflict_events_conflict_created_at
+        ON sync_conflict_events(conflict_id, created_at DESC);
+
+CREATE INDEX idx_sync_conflicts_entity
+        ON sync_conflicts(entity_type, entity_id, detected_at DESC);
+
+CREATE INDEX idx_sync_conflicts_status_detected_at
+        ON sync_conflicts(status, detected_at DESC);
+
+CREATE INDEX idx_sync_outbox_created_at
+        ON sync_outbox(created_at ASC);
+
+CREATE INDEX idx_task_changelogs_task_created_at
+        ON task_changelogs(task_id, created_at DESC);
+
+CREATE INDEX idx_task_subtasks_task_created_at
+        ON task_subtasks(task_id, created_at ASC);
+
+CREATE INDEX idx_task_templates_updated_at
+        ON task_templates(updated_at DESC);
+
+CREATE INDEX idx_tasks_due_at
+        ON tasks(due_at);
+
+CREATE INDEX idx_tasks_project_id
+        ON tasks(project_id);
+
+INSERT INTO schema_migrations (version, name) VALUES (1, 'create_base_tables');
+INSERT INTO schema_migrations (version, name) VALUES (2, 'backfill_sync_columns');
+INSERT INTO schema_migrations (version, name) VALUES (3, 'create_task_indexes_and_sync_checkpoint');